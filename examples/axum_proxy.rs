@@ -0,0 +1,71 @@
+//! Internal housing-data API: an Axum service that proxies Parcl Labs.
+//!
+//! Every handler shares one client through `ParclLayer`, and identical GET
+//! requests within five minutes are answered from `ResponseCacheLayer`
+//! without spending credits.
+//!
+//! Usage: cargo run --example axum_proxy --features tower
+//! Then:  curl 'localhost:3000/markets?query=Austin'
+//!        curl localhost:3000/markets/2900187/housing_stock
+
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Extension, Router};
+use parcllabs::middleware::{ParclLayer, ResponseCacheLayer};
+use parcllabs::{MetricsParams, ParclClient, SearchParams};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+type Reply = Result<String, (StatusCode, String)>;
+
+fn upstream_error(err: parcllabs::ParclError) -> (StatusCode, String) {
+    (StatusCode::BAD_GATEWAY, err.to_string())
+}
+
+async fn markets(
+    Extension(client): Extension<Arc<ParclClient>>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Reply {
+    let Some(name) = query.get("query") else {
+        return Err((StatusCode::BAD_REQUEST, "missing ?query=".into()));
+    };
+    let resp = client
+        .search()
+        .markets(SearchParams::new().query(name).limit(10))
+        .await
+        .map_err(upstream_error)?;
+    serde_json::to_string(&resp.items)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn housing_stock(
+    Extension(client): Extension<Arc<ParclClient>>,
+    Path(parcl_id): Path<i64>,
+) -> Reply {
+    let resp = client
+        .market_metrics()
+        .housing_stock(parcl_id, Some(MetricsParams::new().limit(12)))
+        .await
+        .map_err(upstream_error)?;
+    serde_json::to_string(&resp.items)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenvy::dotenv().ok();
+    let client = Arc::new(ParclClient::new()?);
+
+    let app = Router::new()
+        .route("/markets", get(markets))
+        .route("/markets/{parcl_id}/housing_stock", get(housing_stock))
+        .layer(ResponseCacheLayer::new(Duration::from_secs(300)))
+        .layer(ParclLayer::new(client));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
+    println!("Listening on http://127.0.0.1:3000");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -9,8 +9,9 @@
 //! Use Case: Real estate agents identifying buyer's vs seller's markets,
 //! or investors finding markets with motivated sellers (high price drop rates).
 //!
-//! Usage: cargo run --example for_sale_market_analysis
+//! Usage: cargo run --example for_sale_market_analysis --features display
 
+use parcllabs::display::{bar, format_currency, format_number};
 use parcllabs::{ForSaleMetricsParams, ParclClient, PropertyType, SearchParams};
 
 #[tokio::main]
@@ -101,8 +102,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     name: format!("{}, {}", city, state),
                     parcl_id: market.parcl_id,
                     inventory: inv.for_sale_inventory.unwrap_or(0),
-                    pct_price_drop: pc.pct_price_drop.unwrap_or(0.0),
-                    median_days_bt_change: pc.median_days_bt_price_change.unwrap_or(0.0),
+                    pct_price_drop: pc.pct_inventory_price_drop().unwrap_or(0.0),
+                    median_days_bt_change: pc.median_days_bt_change().unwrap_or(0.0),
                     rolling_30_day_listings: roll.rolling_30_day_count.unwrap_or(0),
                     rolling_90_day_listings: roll.rolling_90_day_count.unwrap_or(0),
                 });
@@ -182,7 +183,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("==========================================================\n");
 
         // Get historical price change data
-        let historical = client
+        let mut historical = client
             .for_sale_metrics()
             .for_sale_inventory_price_changes(
                 focus.parcl_id,
@@ -201,14 +202,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
         println!("{}", "-".repeat(55));
 
-        for item in historical.items.iter().rev() {
-            let pct = item.pct_price_drop.unwrap_or(0.0);
+        historical.sort_ascending();
+        for item in &historical.items {
+            let pct = item.pct_inventory_price_drop().unwrap_or(0.0);
             let count = item.count_price_drop.unwrap_or(0);
             let median_change = item.median_price_change.unwrap_or(0.0);
 
             // Visual indicator
-            let bar_len = (pct * 2.0).min(30.0) as usize;
-            let bar = "|".to_string() + &"#".repeat(bar_len);
+            let bar = format!("|{}", bar(pct, 2.0, 30));
 
             println!(
                 "{:<12} {:>10.1}% {:>12} {:>13} {}",
@@ -221,7 +222,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         // Get rolling counts trend
-        let rolling_history = client
+        let mut rolling_history = client
             .for_sale_metrics()
             .new_listings_rolling_counts(
                 focus.parcl_id,
@@ -240,7 +241,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
         println!("{}", "-".repeat(55));
 
-        for item in rolling_history.items.iter().rev() {
+        rolling_history.sort_ascending();
+        for item in &rolling_history.items {
             println!(
                 "{:<12} {:>10} {:>10} {:>10} {:>10}",
                 item.date,
@@ -252,10 +254,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         // Calculate momentum
-        if rolling_history.items.len() >= 2 {
-            let newest = &rolling_history.items[0];
-            let oldest = &rolling_history.items[rolling_history.items.len() - 1];
-
+        if let (Some(oldest), Some(newest), true) = (
+            rolling_history.items.first(),
+            rolling_history.items.last(),
+            rolling_history.items.len() >= 2,
+        ) {
             let new_30 = newest.rolling_30_day_count.unwrap_or(0) as f64;
             let old_30 = oldest.rolling_30_day_count.unwrap_or(1) as f64;
             let change_pct = ((new_30 - old_30) / old_30) * 100.0;
@@ -283,21 +286,3 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
-
-fn format_number(n: i64) -> String {
-    if n >= 1_000_000 {
-        format!("{:.1}M", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.1}K", n as f64 / 1_000.0)
-    } else {
-        n.to_string()
-    }
-}
-
-fn format_currency(n: f64) -> String {
-    if n.abs() >= 1_000.0 {
-        format!("${:.0}K", n / 1_000.0)
-    } else {
-        format!("${:.0}", n)
-    }
-}
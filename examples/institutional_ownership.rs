@@ -9,8 +9,9 @@
 //! Use Case: Homebuyers researching markets with lower investor competition,
 //! or investors identifying underserved markets with growth potential.
 //!
-//! Usage: cargo run --example institutional_ownership
+//! Usage: cargo run --example institutional_ownership --features display
 
+use parcllabs::display::{bar, format_number, sparkline, Trend};
 use parcllabs::{InvestorMetricsParams, MetricsParams, ParclClient, PropertyType, SearchParams};
 
 #[tokio::main]
@@ -58,10 +59,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // Get ownership data
             let ownership = client
                 .investor_metrics()
-                .housing_stock_ownership(
-                    market.parcl_id,
-                    Some(InvestorMetricsParams::new().limit(1)),
-                )
+                .housing_stock_ownership_latest(market.parcl_id)
                 .await?;
 
             // Get pricing data for single family homes
@@ -77,7 +75,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 )
                 .await?;
 
-            if let Some(own) = ownership.items.first() {
+            if let Some(own) = ownership {
                 let mut median_price = 0.0;
                 let mut price_per_sqft = 0.0;
 
@@ -185,7 +183,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("==========================================================\n");
 
         // Get 12-month ownership trend
-        let historical = client
+        let mut historical = client
             .investor_metrics()
             .housing_stock_ownership(
                 focus_market.parcl_id,
@@ -201,23 +199,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("{}", "-".repeat(55));
 
         let mut prev_pct = 0.0;
-        for (i, item) in historical.items.iter().rev().enumerate() {
+        historical.sort_ascending();
+        for (i, item) in historical.items.iter().enumerate() {
             let pct = item.investor_owned_pct.unwrap_or(0.0);
             let count = item.investor_owned_count.unwrap_or(0);
 
             let trend = if i == 0 {
                 "—"
-            } else if pct > prev_pct + 0.1 {
-                "↑"
-            } else if pct < prev_pct - 0.1 {
-                "↓"
             } else {
-                "→"
+                Trend::between(prev_pct, pct, 0.1).arrow()
             };
 
             // Visual bar
-            let bar_len = (pct * 2.0).min(40.0) as usize;
-            let bar = "▓".repeat(bar_len);
+            let bar = bar(pct, 2.0, 40);
 
             println!(
                 "{:<12} {:>10.1}% {:>14}  {} {}",
@@ -232,12 +226,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         // Calculate change
-        if let (Some(oldest), Some(newest)) = (historical.items.last(), historical.items.first()) {
+        if let (Some(oldest), Some(newest)) = (historical.items.first(), historical.items.last()) {
             let old_pct = oldest.investor_owned_pct.unwrap_or(0.0);
             let new_pct = newest.investor_owned_pct.unwrap_or(0.0);
             let change = new_pct - old_pct;
 
-            println!("\n12-Month Change: {:+.2}%", change);
+            let series: Vec<f64> = historical
+                .items
+                .iter()
+                .filter_map(|item| item.investor_owned_pct)
+                .collect();
+            println!("\n12-Month Trend:  {}", sparkline(&series));
+            println!("12-Month Change: {:+.2}%", change);
             if change > 0.5 {
                 println!("📈 Investors are INCREASING their presence in this market");
             } else if change < -0.5 {
@@ -262,10 +262,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     for data in ownership_data.iter().take(5) {
         let events = client
             .investor_metrics()
-            .housing_event_counts(data.parcl_id, Some(InvestorMetricsParams::new().limit(1)))
+            .housing_event_counts_latest(data.parcl_id)
             .await?;
 
-        if let Some(ev) = events.items.first() {
+        if let Some(ev) = events {
             let acq = ev.acquisitions.unwrap_or(0);
             let disp = ev.dispositions.unwrap_or(0);
             let net = acq - disp;
@@ -302,13 +302,3 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
-
-fn format_number(n: i64) -> String {
-    if n >= 1_000_000 {
-        format!("{:.1}M", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.1}K", n as f64 / 1_000.0)
-    } else {
-        n.to_string()
-    }
-}
@@ -9,8 +9,9 @@
 //! Use Case: Real estate analysts tracking where institutional investors
 //! are accumulating or liquidating positions.
 //!
-//! Usage: cargo run --example investor_activity
+//! Usage: cargo run --example investor_activity --features display
 
+use parcllabs::display::{bar, Trend};
 use parcllabs::{InvestorMetricsParams, ParclClient, SearchParams};
 
 #[tokio::main]
@@ -96,13 +97,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "-".repeat(70));
 
     for (name, _parcl_id, ratio, avg_ratio, acquisitions, dispositions, _date) in &market_data {
-        let trend = if *ratio > *avg_ratio {
-            "↑"
-        } else if *ratio < *avg_ratio {
-            "↓"
-        } else {
-            "→"
-        };
+        let trend = Trend::between(*avg_ratio, *ratio, 0.0);
 
         let status = if *ratio > 1.2 {
             "🔥 Strong Buy"
@@ -176,7 +171,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         // Historical trend analysis
-        let historical = client
+        let mut historical = client
             .investor_metrics()
             .purchase_to_sale_ratio(
                 *top_id,
@@ -188,10 +183,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("{:<12} {:>10}  Visual", "Date", "Ratio");
         println!("{}", "-".repeat(50));
 
-        for item in historical.items.iter().rev() {
+        historical.sort_ascending();
+        for item in &historical.items {
             let ratio = item.purchase_to_sale_ratio.unwrap_or(0.0);
-            let bar_len = (ratio * 20.0).min(40.0) as usize;
-            let bar = "█".repeat(bar_len);
+            let bar = bar(ratio, 20.0, 40);
 
             let marker = if ratio > 1.0 { ">" } else { "<" };
 
@@ -0,0 +1,27 @@
+//! Build a `parcl_id` reference index from the live API.
+//!
+//! Writes the index as JSON to the given path (default
+//! `reference_markets.json`); load it with `ReferenceIndex::from_json`.
+//!
+//! Usage: cargo run --example refresh_reference_data -- [path]
+
+use parcllabs::reference::ReferenceIndex;
+use parcllabs::{LocationType, ParclClient};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenvy::dotenv().ok();
+    let client = ParclClient::new()?;
+
+    let index =
+        ReferenceIndex::refresh(&client, &[LocationType::Cbsa, LocationType::City], 200).await?;
+
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "reference_markets.json".to_string());
+    std::fs::write(&path, index.to_json()? + "\n")?;
+
+    println!("Wrote {} markets to {}", index.len(), path);
+    println!("Credits used: {}", client.session_credits_used());
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use super::{fill_parcl_id, sort_rows, year_month, MonthKey};
+use crate::error::Result;
+use crate::metric::Metric;
+use crate::models::{HousingEventCounts, NewListingsRollingCounts};
+use crate::{MetricsParams, ParclClient};
+use std::collections::HashMap;
+
+/// Sales as a share of new for-sale listings for one month.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbsorptionRate {
+    pub parcl_id: Option<i64>,
+    pub date: String,
+    /// Sales in the month (`housing_event_counts.sales`).
+    pub sales: i64,
+    /// 30-day rolling count of new listings at the month's last
+    /// observation.
+    pub new_listings: i64,
+    /// `sales / new_listings`; above 1 means inventory is being drawn down.
+    pub rate: f64,
+}
+
+/// Divides monthly sales by new-listing counts from the same month.
+///
+/// Rolling counts are published more often than monthly event counts, so
+/// each month is matched with the 30-day rolling count of its latest
+/// observation. Months without both values, or with no new listings, are
+/// skipped. Markets are matched by `parcl_id`; output is ordered by market,
+/// then ascending date.
+pub fn absorption_rate(
+    sales: &[HousingEventCounts],
+    new_listings: &[NewListingsRollingCounts],
+) -> Vec<AbsorptionRate> {
+    let mut latest: HashMap<MonthKey, (&str, i64)> = HashMap::new();
+    for item in new_listings {
+        let (Some(month), Some(count)) = (year_month(&item.date), item.rolling_30_day_count) else {
+            continue;
+        };
+        let entry = latest
+            .entry((item.parcl_id, month))
+            .or_insert((&item.date, count));
+        if item.date.as_str() > entry.0 {
+            *entry = (&item.date, count);
+        }
+    }
+
+    let mut rates: Vec<AbsorptionRate> = sales
+        .iter()
+        .filter_map(|item| {
+            let sales = item.sales?;
+            let (_, new_listings) = *latest.get(&(item.parcl_id, year_month(&item.date)?))?;
+            (new_listings != 0).then(|| AbsorptionRate {
+                parcl_id: item.parcl_id,
+                date: item.date.clone(),
+                sales,
+                new_listings,
+                rate: sales as f64 / new_listings as f64,
+            })
+        })
+        .collect();
+    sort_rows(&mut rates);
+    rates
+}
+
+/// Fetches sales counts and new-listing rolling counts for a market
+/// concurrently and computes [`absorption_rate`].
+///
+/// `params` is sent to both endpoints. Rolling counts are weekly, so a
+/// `limit` covers a shorter span of them than of monthly sales; prefer a
+/// date range to line the two windows up.
+pub async fn absorption(
+    client: &ParclClient,
+    parcl_id: i64,
+    params: Option<MetricsParams>,
+) -> Result<Vec<AbsorptionRate>> {
+    let params = params.unwrap_or_default();
+    let (sales, new_listings) = crate::runtime::try_join(
+        client.fetch_endpoint::<HousingEventCounts>(
+            Metric::HousingEventCounts.endpoint(),
+            parcl_id,
+            &params,
+        ),
+        client.fetch_endpoint::<NewListingsRollingCounts>(
+            Metric::NewListingsRollingCounts.endpoint(),
+            parcl_id,
+            &params,
+        ),
+    )
+    .await?;
+    let mut rates = absorption_rate(&sales.items, &new_listings.items);
+    fill_parcl_id(&mut rates, parcl_id);
+    Ok(rates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::fixtures::event_counts;
+    use crate::test_util::{json_response, CannedTransport};
+
+    fn listings(date: &str, rolling_30_day: i64) -> NewListingsRollingCounts {
+        NewListingsRollingCounts {
+            parcl_id: Some(1),
+            date: date.into(),
+            rolling_7_day_count: None,
+            rolling_30_day_count: Some(rolling_30_day),
+            rolling_60_day_count: None,
+            rolling_90_day_count: None,
+        }
+    }
+
+    #[test]
+    fn months_use_their_latest_rolling_count() {
+        let rates = absorption_rate(
+            &[
+                event_counts(1, "2024-03-01", Some(90)),
+                event_counts(1, "2024-02-01", Some(80)),
+                event_counts(1, "2024-01-01", None),
+            ],
+            &[
+                listings("2024-03-25", 100),
+                listings("2024-03-04", 60),
+                listings("2024-02-26", 0),
+                listings("2024-01-29", 50),
+            ],
+        );
+        assert_eq!(
+            rates,
+            [AbsorptionRate {
+                parcl_id: Some(1),
+                date: "2024-03-01".into(),
+                sales: 90,
+                new_listings: 100,
+                rate: 0.9,
+            }]
+        );
+    }
+
+    /// Answers each endpoint with one item for March 2024.
+    fn api() -> CannedTransport {
+        CannedTransport::new(|request| {
+            let item = if request.uri().path().ends_with("/housing_event_counts") {
+                serde_json::json!({"date": "2024-03-01", "sales": 45})
+            } else {
+                serde_json::json!({"date": "2024-03-25", "rolling_30_day": 50})
+            };
+            json_response(
+                200,
+                &serde_json::json!({
+                    "parcl_id": 7, "items": [item], "total": 1, "limit": 1, "offset": 0, "links": {}
+                }),
+            )
+        })
+    }
+
+    #[tokio::test]
+    async fn absorption_fetches_both_series() {
+        let client = ParclClient::with_config("key", "https://example.com").with_transport(api());
+        let rates = absorption(&client, 7, None).await.unwrap();
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].parcl_id, Some(7));
+        assert_eq!(rates[0].rate, 0.9);
+    }
+}
@@ -0,0 +1,91 @@
+use super::{by_month, sort_rows, year_month, MonthKey};
+use crate::models::AllCash;
+use std::collections::HashMap;
+
+/// Year-over-year change in the all-cash share of sales for one month.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CashShareChange {
+    pub parcl_id: Option<i64>,
+    pub date: String,
+    /// All-cash share of arms-length sales this month (`pct_sales`).
+    pub pct_sales: f64,
+    /// The same month's share one year earlier.
+    pub pct_sales_year_ago: f64,
+    /// `pct_sales - pct_sales_year_ago`, in percentage points.
+    pub change: f64,
+}
+
+/// Computes the 12-month change in `pct_sales` for every month that has a
+/// value one year earlier.
+///
+/// Works on single-market or batch results; markets are matched by
+/// `parcl_id`. Output is ordered by market, then ascending date.
+pub fn cash_share_trend(items: &[AllCash]) -> Vec<CashShareChange> {
+    let monthly: HashMap<MonthKey, f64> = by_month(items, |item| item.pct_sales);
+
+    let mut trend: Vec<CashShareChange> = items
+        .iter()
+        .filter_map(|item| {
+            let (year, month) = year_month(&item.date)?;
+            let pct_sales = item.pct_sales?;
+            let pct_sales_year_ago = *monthly.get(&(item.parcl_id, (year - 1, month)))?;
+            Some(CashShareChange {
+                parcl_id: item.parcl_id,
+                date: item.date.clone(),
+                pct_sales,
+                pct_sales_year_ago,
+                change: pct_sales - pct_sales_year_ago,
+            })
+        })
+        .collect();
+    sort_rows(&mut trend);
+    trend
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cash(parcl_id: i64, date: &str, pct_sales: Option<f64>) -> AllCash {
+        AllCash {
+            parcl_id: Some(parcl_id),
+            date: date.into(),
+            count_sales: None,
+            pct_sales,
+            count_transfers: None,
+            pct_transfers: None,
+        }
+    }
+
+    #[test]
+    fn cash_share_trend_matches_year_ago_month() {
+        // Newest first, as the API returns it.
+        let items = vec![
+            cash(1, "2024-02-01", Some(31.0)),
+            cash(1, "2024-01-01", Some(30.0)),
+            cash(1, "2023-02-01", None),
+            cash(1, "2023-01-01", Some(27.5)),
+        ];
+        let trend = cash_share_trend(&items);
+        assert_eq!(trend.len(), 1);
+        assert_eq!(trend[0].date, "2024-01-01");
+        assert_eq!(trend[0].pct_sales_year_ago, 27.5);
+        assert!((trend[0].change - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cash_share_trend_keeps_markets_apart() {
+        let items = vec![
+            cash(2, "2024-01-01", Some(40.0)),
+            cash(1, "2024-01-01", Some(30.0)),
+            cash(2, "2023-01-01", Some(45.0)),
+            cash(1, "2023-01-01", Some(20.0)),
+        ];
+        let trend = cash_share_trend(&items);
+        assert_eq!(trend.len(), 2);
+        assert_eq!(trend[0].parcl_id, Some(1));
+        assert_eq!(trend[0].change, 10.0);
+        assert_eq!(trend[1].parcl_id, Some(2));
+        assert_eq!(trend[1].change, -5.0);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::models::HousingStock;
+use std::collections::BTreeMap;
+
+/// Each property type's share of a market's housing stock, in percent of
+/// `all_properties`. A share is `None` when the API did not report that
+/// type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StockShares {
+    pub single_family: Option<f64>,
+    pub condo: Option<f64>,
+    pub townhouse: Option<f64>,
+    pub other: Option<f64>,
+}
+
+impl StockShares {
+    /// Shares of one observation, or `None` without a positive
+    /// `all_properties`.
+    pub fn of(stock: &HousingStock) -> Option<Self> {
+        let all = stock.all_properties.filter(|all| *all > 0)? as f64;
+        let share = |count: Option<i64>| count.map(|c| c as f64 / all * 100.0);
+        Some(Self {
+            single_family: share(stock.single_family),
+            condo: share(stock.condo),
+            townhouse: share(stock.townhouse),
+            other: share(stock.other),
+        })
+    }
+
+    fn minus(&self, earlier: &Self) -> Self {
+        let diff = |now: Option<f64>, then: Option<f64>| Some(now? - then?);
+        Self {
+            single_family: diff(self.single_family, earlier.single_family),
+            condo: diff(self.condo, earlier.condo),
+            townhouse: diff(self.townhouse, earlier.townhouse),
+            other: diff(self.other, earlier.other),
+        }
+    }
+}
+
+/// How a market's housing stock mix moved between two dates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositionChange {
+    pub parcl_id: Option<i64>,
+    pub start_date: String,
+    pub end_date: String,
+    pub start: StockShares,
+    pub end: StockShares,
+    /// `end - start` for each type, in percentage points.
+    pub change: StockShares,
+}
+
+/// Compares the first and last observations of each market's housing
+/// stock, so the window is whatever range `items` covers.
+///
+/// Works on single-market or batch results; markets are matched by
+/// `parcl_id`. Observations without a positive `all_properties` are
+/// ignored, and markets with fewer than two usable observations are left
+/// out. Output is ordered by market.
+pub fn stock_composition_change(items: &[HousingStock]) -> Vec<CompositionChange> {
+    let mut by_market: BTreeMap<Option<i64>, Vec<(&str, StockShares)>> = BTreeMap::new();
+    for item in items {
+        if let Some(shares) = StockShares::of(item) {
+            by_market
+                .entry(item.parcl_id)
+                .or_default()
+                .push((&item.date, shares));
+        }
+    }
+    by_market
+        .into_iter()
+        .filter_map(|(parcl_id, observations)| {
+            let (start_date, start) = *observations.iter().min_by_key(|(date, _)| *date)?;
+            let (end_date, end) = *observations.iter().max_by_key(|(date, _)| *date)?;
+            (start_date != end_date).then(|| CompositionChange {
+                parcl_id,
+                start_date: start_date.to_string(),
+                end_date: end_date.to_string(),
+                start,
+                end,
+                change: end.minus(&start),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(parcl_id: i64, date: &str, sf: i64, condo: Option<i64>, all: i64) -> HousingStock {
+        HousingStock {
+            parcl_id: Some(parcl_id),
+            date: date.into(),
+            single_family: Some(sf),
+            condo,
+            townhouse: Some(all / 10),
+            other: None,
+            all_properties: Some(all),
+        }
+    }
+
+    #[test]
+    fn compares_first_and_last_observation() {
+        // Newest first, as the API returns it.
+        let items = vec![
+            stock(1, "2024-01-01", 600, Some(300), 1000),
+            stock(1, "2023-01-01", 640, Some(260), 1000),
+            stock(1, "2022-01-01", 700, Some(200), 1000),
+            stock(1, "2021-01-01", 700, Some(200), 0),
+        ];
+        let changes = stock_composition_change(&items);
+        assert_eq!(changes.len(), 1);
+        let change = &changes[0];
+        assert_eq!(
+            (change.start_date.as_str(), change.end_date.as_str()),
+            ("2022-01-01", "2024-01-01")
+        );
+        assert_eq!(change.end.single_family, Some(60.0));
+        assert_eq!(change.change.single_family, Some(-10.0));
+        assert_eq!(change.change.condo, Some(10.0));
+        assert_eq!(change.change.townhouse, Some(0.0));
+        assert_eq!(change.change.other, None);
+    }
+
+    #[test]
+    fn markets_need_two_observations() {
+        let items = vec![
+            stock(2, "2024-01-01", 50, None, 100),
+            stock(2, "2023-01-01", 40, Some(30), 100),
+            stock(3, "2024-01-01", 10, None, 100),
+        ];
+        let changes = stock_composition_change(&items);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].parcl_id, Some(2));
+        assert_eq!(changes[0].change.single_family, Some(10.0));
+        assert_eq!(changes[0].change.condo, None);
+    }
+}
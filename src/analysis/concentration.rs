@@ -0,0 +1,176 @@
+use super::{fill_parcl_id, sort_rows};
+use crate::models::{
+    BatchMetricsResponse, MetricsResponse, PortfolioSize, PortfolioSizeBreakdown,
+    PortfolioSizePctBreakdown, PortfolioStockOwnership,
+};
+
+/// Portfolio size buckets, smallest first.
+const BUCKETS: [PortfolioSize; 4] = [
+    PortfolioSize::Portfolio2To9,
+    PortfolioSize::Portfolio10To99,
+    PortfolioSize::Portfolio100To999,
+    PortfolioSize::Portfolio1000Plus,
+];
+
+/// How portfolio-owned single-family homes split across size buckets on
+/// one date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcentrationPoint {
+    pub parcl_id: Option<i64>,
+    pub date: String,
+    /// Each bucket's share of portfolio-owned homes, from 0 to 1, smallest
+    /// portfolios first.
+    pub shares: [(PortfolioSize, f64); 4],
+    /// Herfindahl index: the sum of squared shares, from 0.25 when homes
+    /// are spread evenly across buckets to 1.0 when one bucket holds them
+    /// all.
+    pub index: f64,
+}
+
+impl ConcentrationPoint {
+    /// The bucket holding the most homes.
+    pub fn largest_bucket(&self) -> PortfolioSize {
+        self.shares
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(PortfolioSize::AllPortfolios, |(size, _)| *size)
+    }
+
+    /// Share held by portfolios of 1,000 or more homes.
+    pub fn institutional_share(&self) -> f64 {
+        self.shares[3].1
+    }
+}
+
+/// A concentration index series built from portfolio stock ownership,
+/// e.g. `PortfolioConcentration::from(&response)`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PortfolioConcentration {
+    points: Vec<ConcentrationPoint>,
+}
+
+impl PortfolioConcentration {
+    /// Computes the index for every item with bucket data.
+    ///
+    /// Bucket counts are used when present, falling back to the percentage
+    /// breakdown. Output is ordered by market, then ascending date.
+    pub fn new(items: &[PortfolioStockOwnership]) -> Self {
+        let mut points: Vec<ConcentrationPoint> = items
+            .iter()
+            .filter_map(|item| {
+                let values = item
+                    .count
+                    .as_ref()
+                    .and_then(counts)
+                    .or_else(|| item.pct_sf_housing_stock.as_ref().and_then(percentages))?;
+                let total: f64 = values.iter().sum();
+                if total <= 0.0 {
+                    return None;
+                }
+                let shares = std::array::from_fn(|i| (BUCKETS[i], values[i] / total));
+                Some(ConcentrationPoint {
+                    parcl_id: item.parcl_id,
+                    date: item.date.clone(),
+                    index: shares.iter().map(|(_, s)| s * s).sum(),
+                    shares,
+                })
+            })
+            .collect();
+        sort_rows(&mut points);
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[ConcentrationPoint] {
+        &self.points
+    }
+
+    /// The most recent point of a single-market series.
+    pub fn latest(&self) -> Option<&ConcentrationPoint> {
+        self.points.last()
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+impl From<&MetricsResponse<PortfolioStockOwnership>> for PortfolioConcentration {
+    fn from(response: &MetricsResponse<PortfolioStockOwnership>) -> Self {
+        let mut concentration = Self::new(&response.items);
+        fill_parcl_id(&mut concentration.points, response.parcl_id);
+        concentration
+    }
+}
+
+impl From<&BatchMetricsResponse<PortfolioStockOwnership>> for PortfolioConcentration {
+    fn from(response: &BatchMetricsResponse<PortfolioStockOwnership>) -> Self {
+        Self::new(&response.items)
+    }
+}
+
+/// Bucket values, or `None` if every bucket is missing.
+fn counts(b: &PortfolioSizeBreakdown) -> Option<[f64; 4]> {
+    let values = [
+        b.portfolio_2_to_9,
+        b.portfolio_10_to_99,
+        b.portfolio_100_to_999,
+        b.portfolio_1000_plus,
+    ];
+    values
+        .iter()
+        .any(Option::is_some)
+        .then(|| values.map(|v| v.unwrap_or(0) as f64))
+}
+
+fn percentages(b: &PortfolioSizePctBreakdown) -> Option<[f64; 4]> {
+    let values = [
+        b.portfolio_2_to_9,
+        b.portfolio_10_to_99,
+        b.portfolio_100_to_999,
+        b.portfolio_1000_plus,
+    ];
+    values
+        .iter()
+        .any(Option::is_some)
+        .then(|| values.map(|v| v.unwrap_or(0.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_from_counts_or_percentages() {
+        let response: MetricsResponse<PortfolioStockOwnership> =
+            serde_json::from_value(serde_json::json!({
+                "parcl_id": 7, "total": 3, "limit": 3, "offset": 0, "links": {},
+                "items": [
+                    {"date": "2024-02-01", "pct_sf_housing_stock": {
+                        "portfolio_2_to_9": 1.0, "portfolio_10_to_99": 1.0,
+                        "portfolio_100_to_999": 1.0, "portfolio_1000_plus": 1.0}},
+                    {"date": "2024-01-01", "count": {
+                        "portfolio_2_to_9": 600, "portfolio_10_to_99": 200,
+                        "portfolio_100_to_999": null, "portfolio_1000_plus": 200,
+                        "all_portfolios": 1000}},
+                    {"date": "2023-12-01", "count": {"all_portfolios": 10}}
+                ]
+            }))
+            .unwrap();
+
+        let concentration = PortfolioConcentration::from(&response);
+        assert_eq!(concentration.len(), 2);
+        let first = &concentration.points()[0];
+        assert_eq!(
+            (first.parcl_id, first.date.as_str()),
+            (Some(7), "2024-01-01")
+        );
+        assert!((first.index - 0.44).abs() < 1e-9);
+        assert_eq!(first.largest_bucket(), PortfolioSize::Portfolio2To9);
+        assert_eq!(first.institutional_share(), 0.2);
+        assert_eq!(concentration.latest().unwrap().index, 0.25);
+    }
+}
@@ -0,0 +1,170 @@
+use super::year_month;
+use crate::error::Result;
+use crate::metric::{Metric, MetricSeries};
+use crate::{MetricsParams, ParclClient};
+use std::collections::HashMap;
+
+/// Fewest shared months a coefficient is computed from.
+const MIN_OBSERVATIONS: usize = 3;
+
+/// Pearson correlation between the [`Headline`](crate::models::Headline)
+/// values of two metrics for one market.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricCorrelation {
+    pub a: Metric,
+    pub b: Metric,
+    /// Months in which both metrics have a value.
+    pub observations: usize,
+    /// From -1 to 1, or `None` with fewer than three shared months or when
+    /// either series is flat.
+    pub coefficient: Option<f64>,
+}
+
+/// Correlates every pair of `series`, which should all describe one market.
+///
+/// Series are lined up by calendar month, each month taking a metric's
+/// latest observation, so weekly rolling counts pair with monthly metrics.
+/// Pairs come in input order: (0, 1), (0, 2), ..., (1, 2), ...
+pub fn correlations(series: &[MetricSeries]) -> Vec<MetricCorrelation> {
+    let monthly: Vec<_> = series
+        .iter()
+        .map(|s| (s.metric(), monthly_values(s)))
+        .collect();
+    let mut pairs = Vec::new();
+    for (i, (a, a_values)) in monthly.iter().enumerate() {
+        for (b, b_values) in &monthly[i + 1..] {
+            let shared: Vec<(f64, f64)> = a_values
+                .iter()
+                .filter_map(|(month, x)| Some((*x, *b_values.get(month)?)))
+                .collect();
+            pairs.push(MetricCorrelation {
+                a: *a,
+                b: *b,
+                observations: shared.len(),
+                coefficient: pearson(&shared),
+            });
+        }
+    }
+    pairs
+}
+
+/// Fetches `metrics` for a market concurrently and computes their pairwise
+/// [`correlations`].
+///
+/// `window` is sent to every endpoint; give it a date range with
+/// `auto_paginate(true)` so each metric covers the same span.
+pub async fn metric_correlations(
+    client: &ParclClient,
+    parcl_id: i64,
+    metrics: &[Metric],
+    window: Option<MetricsParams>,
+) -> Result<Vec<MetricCorrelation>> {
+    let mut fetched = client
+        .fetch_metrics(metrics.iter().map(|&metric| (parcl_id, metric)))
+        .params(window.unwrap_or_default())
+        .collect()
+        .await;
+    // Results arrive in completion order; restore the caller's.
+    fetched.sort_by_key(|(_, metric, _)| metrics.iter().position(|m| m == metric));
+    let series = fetched
+        .into_iter()
+        .map(|(_, _, result)| result)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(correlations(&series))
+}
+
+/// Each month's value from the series' latest observation in that month.
+fn monthly_values(series: &MetricSeries) -> HashMap<(i32, u32), f64> {
+    let mut latest: HashMap<(i32, u32), (&str, f64)> = HashMap::new();
+    for (date, value) in series.headline_values() {
+        let (Some(month), Some(value)) = (year_month(date), value) else {
+            continue;
+        };
+        let entry = latest.entry(month).or_insert((date, value));
+        if date > entry.0 {
+            *entry = (date, value);
+        }
+    }
+    latest
+        .into_iter()
+        .map(|(month, (_, value))| (month, value))
+        .collect()
+}
+
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < MIN_OBSERVATIONS {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    (var_x > 0.0 && var_y > 0.0).then(|| cov / (var_x * var_y).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{json_response, CannedTransport};
+
+    /// Four months in which inventory and price drops rise together while
+    /// yields fall.
+    fn api() -> CannedTransport {
+        CannedTransport::new(|request| {
+            let path = request.uri().path().to_string();
+            let items: Vec<serde_json::Value> = (1..=4)
+                .map(|m| {
+                    let date = format!("2024-0{m}-01");
+                    if path.ends_with("/for_sale_inventory") {
+                        serde_json::json!({"date": date, "for_sale_inventory": 100 * m})
+                    } else if path.ends_with("/for_sale_inventory_price_changes") {
+                        serde_json::json!({"date": date, "pct_inventory_price_drop": 2.0 * m as f64 + 1.0})
+                    } else {
+                        serde_json::json!({"date": date, "gross_yield": 10.0 - m as f64})
+                    }
+                })
+                .collect();
+            json_response(
+                200,
+                &serde_json::json!({
+                    "parcl_id": 7, "items": items, "total": 4, "limit": 4, "offset": 0, "links": {}
+                }),
+            )
+        })
+    }
+
+    #[tokio::test]
+    async fn pairs_in_request_order() {
+        let client = ParclClient::with_config("key", "https://example.com").with_transport(api());
+        let metrics = [
+            Metric::ForSaleInventory,
+            Metric::ForSaleInventoryPriceChanges,
+            Metric::GrossYield,
+        ];
+        let pairs = metric_correlations(&client, 7, &metrics, None)
+            .await
+            .unwrap();
+        let summary: Vec<(Metric, Metric, usize)> =
+            pairs.iter().map(|p| (p.a, p.b, p.observations)).collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    Metric::ForSaleInventory,
+                    Metric::ForSaleInventoryPriceChanges,
+                    4
+                ),
+                (Metric::ForSaleInventory, Metric::GrossYield, 4),
+                (Metric::ForSaleInventoryPriceChanges, Metric::GrossYield, 4),
+            ]
+        );
+        assert!((pairs[0].coefficient.unwrap() - 1.0).abs() < 1e-9);
+        assert!((pairs[1].coefficient.unwrap() + 1.0).abs() < 1e-9);
+        assert_eq!(pearson(&[(1.0, 2.0), (1.0, 3.0), (1.0, 4.0)]), None);
+    }
+}
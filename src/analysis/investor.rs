@@ -0,0 +1,115 @@
+use super::{by_month, fill_parcl_id, sort_rows, year_month};
+use crate::error::Result;
+use crate::metric::Metric;
+use crate::models::{HousingEventCounts, InvestorHousingEventCounts};
+use crate::{MetricsParams, ParclClient};
+
+/// Share of a month's sales bought by investors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvestorShare {
+    pub parcl_id: Option<i64>,
+    pub date: String,
+    /// All sales in the month (`housing_event_counts.sales`).
+    pub sales: i64,
+    /// Investor acquisitions in the month.
+    pub acquisitions: i64,
+    /// `acquisitions / sales`, in percent.
+    pub pct_of_sales: f64,
+}
+
+/// Divides investor acquisitions by market sales for each month present in
+/// both series.
+///
+/// Months with no sales are skipped. Markets are matched by `parcl_id`;
+/// output is ordered by market, then ascending date.
+pub fn investor_share_of_sales(
+    sales: &[HousingEventCounts],
+    investor: &[InvestorHousingEventCounts],
+) -> Vec<InvestorShare> {
+    let acquisitions = by_month(investor, |item| item.acquisitions);
+
+    let mut shares: Vec<InvestorShare> = sales
+        .iter()
+        .filter_map(|item| {
+            let sales = item.sales.filter(|s| *s != 0)?;
+            let acquisitions = *acquisitions.get(&(item.parcl_id, year_month(&item.date)?))?;
+            Some(InvestorShare {
+                parcl_id: item.parcl_id,
+                date: item.date.clone(),
+                sales,
+                acquisitions,
+                pct_of_sales: acquisitions as f64 / sales as f64 * 100.0,
+            })
+        })
+        .collect();
+    sort_rows(&mut shares);
+    shares
+}
+
+/// Fetches market and investor event counts for a market concurrently and
+/// computes [`investor_share_of_sales`].
+pub async fn investor_share(
+    client: &ParclClient,
+    parcl_id: i64,
+    params: Option<MetricsParams>,
+) -> Result<Vec<InvestorShare>> {
+    let params = params.unwrap_or_default();
+    let (sales, investor) = crate::runtime::try_join(
+        client.fetch_endpoint::<HousingEventCounts>(
+            Metric::HousingEventCounts.endpoint(),
+            parcl_id,
+            &params,
+        ),
+        client.fetch_endpoint::<InvestorHousingEventCounts>(
+            Metric::InvestorHousingEventCounts.endpoint(),
+            parcl_id,
+            &params,
+        ),
+    )
+    .await?;
+    let mut shares = investor_share_of_sales(&sales.items, &investor.items);
+    fill_parcl_id(&mut shares, parcl_id);
+    Ok(shares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::fixtures::event_counts;
+
+    fn investor(parcl_id: i64, date: &str, acquisitions: i64) -> InvestorHousingEventCounts {
+        InvestorHousingEventCounts {
+            parcl_id: Some(parcl_id),
+            date: date.into(),
+            acquisitions: Some(acquisitions),
+            dispositions: None,
+            new_listings_for_sale: None,
+            new_rental_listings: None,
+        }
+    }
+
+    #[test]
+    fn aligns_months_per_market() {
+        let shares = investor_share_of_sales(
+            &[
+                event_counts(2, "2024-02-01", 400),
+                event_counts(1, "2024-02-01", 0),
+                event_counts(1, "2024-01-01", 200),
+                event_counts(1, "2023-12-01", 100),
+            ],
+            &[
+                investor(1, "2024-02-01", 10),
+                investor(1, "2024-01-01", 50),
+                investor(2, "2024-02-01", 100),
+            ],
+        );
+        let pct: Vec<(Option<i64>, &str, f64)> = shares
+            .iter()
+            .map(|s| (s.parcl_id, s.date.as_str(), s.pct_of_sales))
+            .collect();
+        assert_eq!(
+            pct,
+            [(Some(1), "2024-01-01", 25.0), (Some(2), "2024-02-01", 25.0)]
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::models::DataPoint;
+use std::ops::Add;
+
+/// How aggregate computations treat [`DataPoint::Missing`] values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MissingPolicy {
+    /// Leave missing values out: a sum or mean covers the values present,
+    /// and is missing only if every value is.
+    #[default]
+    Skip,
+    /// Count missing values as zero, including in a mean's denominator.
+    Zero,
+    /// Any missing value makes the aggregate missing.
+    Propagate,
+}
+
+impl MissingPolicy {
+    /// Applies the policy to one point: `Zero` fills in zero, the others
+    /// leave it as is.
+    pub fn resolve<T: Default>(self, point: DataPoint<T>) -> DataPoint<T> {
+        match (self, point) {
+            (Self::Zero, DataPoint::Missing) => DataPoint::Value(T::default()),
+            (_, point) => point,
+        }
+    }
+
+    /// Sums `points`. An empty input sums to zero under `Zero` and is
+    /// missing otherwise.
+    pub fn sum<T>(self, points: impl IntoIterator<Item = DataPoint<T>>) -> DataPoint<T>
+    where
+        T: Default + Add<Output = T>,
+    {
+        self.fold(points).map(|(total, _)| total)
+    }
+
+    /// Averages `points` as `f64`. Map `i64` counts first, e.g.
+    /// `point.map(|v| v as f64)`.
+    pub fn mean<T>(self, points: impl IntoIterator<Item = DataPoint<T>>) -> DataPoint<f64>
+    where
+        T: Into<f64>,
+    {
+        let points = points.into_iter().map(|point| point.map(Into::into));
+        match self.fold(points) {
+            DataPoint::Value((total, count)) if count > 0 => DataPoint::Value(total / count as f64),
+            _ => DataPoint::Missing,
+        }
+    }
+
+    /// Total and number of values counted, or `Missing` per the policy.
+    fn fold<T>(self, points: impl IntoIterator<Item = DataPoint<T>>) -> DataPoint<(T, usize)>
+    where
+        T: Default + Add<Output = T>,
+    {
+        let mut total = T::default();
+        let mut count = 0;
+        for point in points {
+            match self.resolve(point) {
+                DataPoint::Value(value) => {
+                    total = total + value;
+                    count += 1;
+                }
+                DataPoint::Missing if self == Self::Propagate => return DataPoint::Missing,
+                DataPoint::Missing => {}
+            }
+        }
+        if count == 0 && self != Self::Zero {
+            return DataPoint::Missing;
+        }
+        DataPoint::Value((total, count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HousingEventCounts;
+    use crate::test_util::fixtures::event_counts;
+
+    #[test]
+    fn policies_treat_missing_consistently() {
+        let items = [
+            event_counts(None, "2024-01-01", Some(10)),
+            event_counts(None, "2024-01-01", None),
+            event_counts(None, "2024-01-01", Some(20)),
+        ];
+        let points = || items.iter().map(HousingEventCounts::sales_or_missing);
+        let as_f64 = || points().map(|p| p.map(|v| v as f64));
+
+        assert_eq!(MissingPolicy::Skip.sum(points()), DataPoint::Value(30));
+        assert_eq!(MissingPolicy::Zero.sum(points()), DataPoint::Value(30));
+        assert_eq!(MissingPolicy::Propagate.sum(points()), DataPoint::Missing);
+
+        assert_eq!(MissingPolicy::Skip.mean(as_f64()), DataPoint::Value(15.0));
+        assert_eq!(MissingPolicy::Zero.mean(as_f64()), DataPoint::Value(10.0));
+        assert_eq!(MissingPolicy::Propagate.mean(as_f64()), DataPoint::Missing);
+
+        let none = [event_counts(None, "2024-01-01", None)];
+        let missing = || none.iter().map(HousingEventCounts::sales_or_missing);
+        assert_eq!(MissingPolicy::Skip.sum(missing()), DataPoint::Missing);
+        assert_eq!(MissingPolicy::Zero.sum(missing()), DataPoint::Value(0));
+        assert!(event_counts(None, "2024-01-01", None)
+            .sales_or_missing()
+            .is_missing());
+        assert_eq!(
+            event_counts(None, "2024-01-01", Some(0))
+                .sales_or_missing()
+                .value(),
+            Some(0)
+        );
+    }
+}
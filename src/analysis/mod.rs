@@ -0,0 +1,159 @@
+//! Derived indicators computed from API responses.
+//!
+//! Helpers here are pure functions over response items: they make no
+//! requests and work equally on single-market and batch results. Async
+//! helpers such as [`absorption`] fetch the inputs of a derived series for
+//! one market, then call the matching pure function.
+//!
+//! [`MissingPolicy`] fixes how aggregates over
+//! [`DataPoint`](crate::models::DataPoint)s treat values the API did not
+//! report, rather than each computation picking its own default.
+
+mod absorption;
+mod cash;
+mod composition;
+mod concentration;
+mod correlation;
+mod investor;
+mod missing;
+mod momentum;
+mod new_construction;
+mod per_capita;
+mod price_matrix;
+mod price_series;
+mod rollup;
+pub mod series;
+
+pub use absorption::{absorption, absorption_rate, AbsorptionRate};
+pub use cash::{cash_share_trend, CashShareChange};
+pub use composition::{stock_composition_change, CompositionChange, StockShares};
+pub use concentration::{ConcentrationPoint, PortfolioConcentration};
+pub use correlation::{correlations, metric_correlations, MetricCorrelation};
+pub use investor::{investor_share, investor_share_of_sales, InvestorShare};
+pub use missing::MissingPolicy;
+pub use momentum::{price_cut_momentum, PriceCutMomentum};
+pub use new_construction::{
+    construction_premium, new_construction_premium, NewConstructionPremium,
+};
+pub use per_capita::{per_thousand_homes, per_thousand_residents, PerThousand};
+pub use price_matrix::PriceMatrix;
+pub use price_series::{
+    Bar, BarPeriod, Drawdown, EveryDay, Gap, Interpolation, PricePoint, PriceSeries,
+    TradingCalendar, Weekdays,
+};
+pub use rollup::{rollup, RollUp};
+
+use crate::models::{Dated, HasParclId};
+use std::collections::HashMap;
+
+/// Identifies one market's month when aligning series.
+pub(crate) type MonthKey = (Option<i64>, (i32, u32));
+
+/// Indexes `items` by market and month, keeping those `value` returns a
+/// value for. A later item for the same month replaces an earlier one.
+pub(crate) fn by_month<'a, T, V>(
+    items: &'a [T],
+    value: impl Fn(&'a T) -> Option<V>,
+) -> HashMap<MonthKey, V>
+where
+    T: Dated + HasParclId,
+{
+    items
+        .iter()
+        .filter_map(|item| Some(((item.parcl_id(), year_month(item.date())?), value(item)?)))
+        .collect()
+}
+
+/// A derived row for one market and date.
+pub(crate) trait Row {
+    fn parcl_id_mut(&mut self) -> &mut Option<i64>;
+    fn key(&self) -> (Option<i64>, &str);
+}
+
+macro_rules! impl_row {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl Row for $t {
+                fn parcl_id_mut(&mut self) -> &mut Option<i64> {
+                    &mut self.parcl_id
+                }
+
+                fn key(&self) -> (Option<i64>, &str) {
+                    (self.parcl_id, &self.date)
+                }
+            }
+        )*
+    };
+}
+
+impl_row!(
+    AbsorptionRate,
+    CashShareChange,
+    ConcentrationPoint,
+    InvestorShare,
+    NewConstructionPremium,
+    PerThousand,
+);
+
+/// Orders rows by market, then ascending date.
+pub(crate) fn sort_rows<T: Row>(rows: &mut [T]) {
+    rows.sort_by(|a, b| a.key().cmp(&b.key()));
+}
+
+/// Credits rows computed from a single-market response to `parcl_id`;
+/// single-market items usually omit it.
+pub(crate) fn fill_parcl_id<T: Row>(rows: &mut [T], parcl_id: i64) {
+    for row in rows {
+        row.parcl_id_mut().get_or_insert(parcl_id);
+    }
+}
+
+/// Parses the `(year, month)` of an API date such as `2024-03-01`.
+pub(crate) fn year_month(date: &str) -> Option<(i32, u32)> {
+    let mut parts = date.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.get(..2)?.parse().ok()?;
+    (1..=12).contains(&month).then_some((year, month))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn year_month_parses_api_dates() {
+        assert_eq!(year_month("2024-03-01"), Some((2024, 3)));
+        assert_eq!(year_month("2024-12"), Some((2024, 12)));
+        assert_eq!(year_month("2024-13-01"), None);
+        assert_eq!(year_month("March 2024"), None);
+    }
+
+    #[test]
+    fn rows_sort_by_market_then_date_and_take_the_response_id() {
+        let row = |parcl_id, date: &str| CashShareChange {
+            parcl_id,
+            date: date.into(),
+            pct_sales: 0.0,
+            pct_sales_year_ago: 0.0,
+            change: 0.0,
+        };
+        let mut rows = vec![
+            row(Some(2), "2024-01-01"),
+            row(None, "2024-02-01"),
+            row(Some(1), "2024-02-01"),
+            row(Some(1), "2024-01-01"),
+        ];
+        sort_rows(&mut rows);
+        fill_parcl_id(&mut rows, 9);
+        let keys: Vec<_> = rows.iter().map(Row::key).collect();
+        assert_eq!(
+            keys,
+            [
+                (Some(9), "2024-02-01"),
+                (Some(1), "2024-01-01"),
+                (Some(1), "2024-02-01"),
+                (Some(2), "2024-01-01"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::models::ForSaleInventoryPriceChanges;
+
+/// Week-over-week movement in the share of listings with price cuts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceCutMomentum {
+    pub parcl_id: Option<i64>,
+    pub date: String,
+    /// Share of inventory with a price drop this week, in percent.
+    pub pct_price_drop: f64,
+    /// Change from the previous week, in percentage points.
+    pub change: f64,
+    /// Exponential moving average of `change`; positive while price cuts
+    /// are spreading, negative while they are receding.
+    pub momentum: f64,
+}
+
+/// Computes week-over-week changes in `pct_price_drop` and smooths them
+/// with an exponential moving average over `span` weeks.
+///
+/// Each observation is compared with the market's previous one, so weeks
+/// without a value are bridged rather than breaking the series. Markets are
+/// matched by `parcl_id`; output is ordered by market, then ascending date.
+/// A `span` of 0 or 1 leaves the changes unsmoothed.
+pub fn price_cut_momentum(
+    items: &[ForSaleInventoryPriceChanges],
+    span: usize,
+) -> Vec<PriceCutMomentum> {
+    let alpha = 2.0 / (span.max(1) as f64 + 1.0);
+    let mut points: Vec<(Option<i64>, &str, f64)> = items
+        .iter()
+        .filter_map(|item| {
+            Some((
+                item.parcl_id,
+                item.date.as_str(),
+                item.pct_inventory_price_drop()?,
+            ))
+        })
+        .collect();
+    points.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+
+    let mut series = Vec::new();
+    for pair in points.windows(2) {
+        let ((prev_id, _, previous), (parcl_id, date, pct_price_drop)) = (pair[0], pair[1]);
+        if prev_id != parcl_id {
+            continue;
+        }
+        let change = pct_price_drop - previous;
+        let momentum = match series.last() {
+            Some(PriceCutMomentum {
+                parcl_id: last_id,
+                momentum,
+                ..
+            }) if *last_id == parcl_id => momentum + alpha * (change - momentum),
+            _ => change,
+        };
+        series.push(PriceCutMomentum {
+            parcl_id,
+            date: date.to_string(),
+            pct_price_drop,
+            change,
+            momentum,
+        });
+    }
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn week(
+        parcl_id: i64,
+        date: &str,
+        pct_price_drop: Option<f64>,
+    ) -> ForSaleInventoryPriceChanges {
+        #[allow(deprecated)]
+        ForSaleInventoryPriceChanges {
+            parcl_id: Some(parcl_id),
+            date: date.into(),
+            count_price_change: None,
+            count_price_drop: None,
+            median_days_bt_price_change: None,
+            median_price_change: None,
+            median_pct_price_change: None,
+            pct_price_change: None,
+            pct_price_drop,
+        }
+    }
+
+    #[test]
+    fn momentum_smooths_weekly_changes() {
+        // Newest first, as the API returns it.
+        let items = vec![
+            week(1, "2024-03-22", Some(16.0)),
+            week(1, "2024-03-15", None),
+            week(1, "2024-03-08", Some(12.0)),
+            week(1, "2024-03-01", Some(10.0)),
+            week(2, "2024-03-08", Some(5.0)),
+            week(2, "2024-03-01", Some(6.0)),
+        ];
+        let series = price_cut_momentum(&items, 3);
+        let summary: Vec<(Option<i64>, &str, f64, f64)> = series
+            .iter()
+            .map(|p| (p.parcl_id, p.date.as_str(), p.change, p.momentum))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Some(1), "2024-03-08", 2.0, 2.0),
+                (Some(1), "2024-03-22", 4.0, 3.0),
+                (Some(2), "2024-03-08", -1.0, -1.0),
+            ]
+        );
+        assert_eq!(price_cut_momentum(&items, 0)[1].momentum, 4.0);
+    }
+}
@@ -0,0 +1,129 @@
+use super::{by_month, fill_parcl_id, sort_rows, year_month};
+use crate::error::Result;
+use crate::metric::Metric;
+use crate::models::{HousingEventPrices, PriceStats};
+use crate::{MetricsParams, ParclClient};
+
+/// How much more new builds sold for than the market as a whole in one
+/// period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewConstructionPremium {
+    pub parcl_id: Option<i64>,
+    pub date: String,
+    /// Median sale price across all sales.
+    pub market_median: f64,
+    /// Median sale price of new construction.
+    pub new_construction_median: f64,
+    /// `new_construction_median / market_median - 1`, in percent.
+    pub premium: f64,
+    /// The same premium on median price per square foot, which discounts
+    /// new builds being larger, when both medians are reported.
+    pub premium_per_square_foot: Option<f64>,
+}
+
+/// Compares median sale prices of new construction with the whole market
+/// for each period present in both series.
+///
+/// Periods without both median sale prices are skipped. Markets are matched
+/// by `parcl_id`; output is ordered by market, then ascending date.
+pub fn new_construction_premium(
+    market: &[HousingEventPrices],
+    new_construction: &[HousingEventPrices],
+) -> Vec<NewConstructionPremium> {
+    let new_builds = by_month(new_construction, Some);
+
+    let mut premiums: Vec<NewConstructionPremium> = market
+        .iter()
+        .filter_map(|item| {
+            let new_build = new_builds.get(&(item.parcl_id, year_month(&item.date)?))?;
+            let market_median = median_sale(&item.price)?;
+            let new_construction_median = median_sale(&new_build.price)?;
+            let premium_per_square_foot = median_sale(&item.price_per_square_foot)
+                .zip(median_sale(&new_build.price_per_square_foot))
+                .and_then(|(market, new)| premium(market, new));
+            Some(NewConstructionPremium {
+                parcl_id: item.parcl_id,
+                date: item.date.clone(),
+                market_median,
+                new_construction_median,
+                premium: premium(market_median, new_construction_median)?,
+                premium_per_square_foot,
+            })
+        })
+        .collect();
+    sort_rows(&mut premiums);
+    premiums
+}
+
+/// Fetches market and new-construction event prices for a market
+/// concurrently and computes [`new_construction_premium`].
+///
+/// `params` is sent to both endpoints, so both cover the same window.
+pub async fn construction_premium(
+    client: &ParclClient,
+    parcl_id: i64,
+    params: Option<MetricsParams>,
+) -> Result<Vec<NewConstructionPremium>> {
+    let params = params.unwrap_or_default();
+    let (market, new_construction) = crate::runtime::try_join(
+        client.fetch_endpoint::<HousingEventPrices>(
+            Metric::HousingEventPrices.endpoint(),
+            parcl_id,
+            &params,
+        ),
+        client.fetch_endpoint::<HousingEventPrices>(
+            Metric::NewConstructionHousingEventPrices.endpoint(),
+            parcl_id,
+            &params,
+        ),
+    )
+    .await?;
+    let mut premiums = new_construction_premium(&market.items, &new_construction.items);
+    fill_parcl_id(&mut premiums, parcl_id);
+    Ok(premiums)
+}
+
+fn median_sale(stats: &Option<PriceStats>) -> Option<f64> {
+    stats.as_ref()?.median.as_ref()?.sales
+}
+
+fn premium(market: f64, new_construction: f64) -> Option<f64> {
+    (market != 0.0).then(|| (new_construction / market - 1.0) * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices(date: &str, sales: f64, per_sqft: Option<f64>) -> HousingEventPrices {
+        serde_json::from_value(serde_json::json!({
+            "date": date,
+            "price": {"median": {"sales": sales}},
+            "price_per_square_foot": {"median": {"sales": per_sqft}}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn premium_per_period() {
+        let premiums = new_construction_premium(
+            &[
+                prices("2024-02-01", 400000.0, Some(200.0)),
+                prices("2024-01-01", 380000.0, None),
+                prices("2023-12-01", 390000.0, None),
+            ],
+            &[
+                prices("2024-02-01", 500000.0, Some(250.0)),
+                prices("2024-01-01", 380000.0, Some(190.0)),
+            ],
+        );
+        let summary: Vec<(&str, f64, Option<f64>)> = premiums
+            .iter()
+            .map(|p| (p.date.as_str(), p.premium, p.premium_per_square_foot))
+            .collect();
+        assert_eq!(
+            summary,
+            [("2024-01-01", 0.0, None), ("2024-02-01", 25.0, Some(25.0))]
+        );
+    }
+}
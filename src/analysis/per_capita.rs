@@ -0,0 +1,183 @@
+use super::sort_rows;
+use crate::models::{Dated, HasParclId, HousingStock, Market};
+use std::collections::HashMap;
+
+/// A count scaled to a rate per 1,000 residents or homes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerThousand {
+    pub parcl_id: Option<i64>,
+    pub date: String,
+    /// The count being normalized, e.g. inventory or sales.
+    pub count: i64,
+    /// Residents or homes the count was divided by.
+    pub base: i64,
+    /// `count / base * 1000`.
+    pub rate: f64,
+}
+
+/// Scales a count per 1,000 residents, using each market's
+/// `total_population`.
+///
+/// Items are matched to `markets` by `parcl_id`. Single-market responses
+/// usually omit it, so when `markets` holds one market, items without a
+/// `parcl_id` are credited to it. Items with no count or whose market has
+/// no population are skipped. Output is ordered by market, then ascending
+/// date.
+pub fn per_thousand_residents<T>(
+    items: &[T],
+    count: impl Fn(&T) -> Option<i64>,
+    markets: &[Market],
+) -> Vec<PerThousand>
+where
+    T: Dated + HasParclId,
+{
+    let population: HashMap<i64, i64> = markets
+        .iter()
+        .filter_map(|m| Some((m.parcl_id, m.total_population?)))
+        .collect();
+    let only = match markets {
+        [market] => Some(market.parcl_id),
+        _ => None,
+    };
+    normalize(items, count, |item| {
+        let parcl_id = item.parcl_id().or(only)?;
+        population.get(&parcl_id).copied()
+    })
+}
+
+/// Scales a count per 1,000 homes, using `all_properties` from the housing
+/// stock of the same market.
+///
+/// Each item is divided by the latest stock observation on or before its
+/// date, or by the earliest one if the item predates them all. Stock moves
+/// slowly, so one recent observation can normalize a year of weekly counts.
+/// Items are matched to stock by `parcl_id`, so single-market responses
+/// that both omit it line up. Items with no count or no stock are skipped.
+/// Output is ordered by market, then ascending date.
+pub fn per_thousand_homes<T>(
+    items: &[T],
+    count: impl Fn(&T) -> Option<i64>,
+    stock: &[HousingStock],
+) -> Vec<PerThousand>
+where
+    T: Dated + HasParclId,
+{
+    let mut homes: HashMap<Option<i64>, Vec<(&str, i64)>> = HashMap::new();
+    for item in stock {
+        if let Some(all) = item.all_properties {
+            homes
+                .entry(item.parcl_id)
+                .or_default()
+                .push((&item.date, all));
+        }
+    }
+    for observations in homes.values_mut() {
+        observations.sort_unstable();
+    }
+    normalize(items, count, |item| {
+        let observations = homes.get(&item.parcl_id())?;
+        let on_or_before = observations.partition_point(|(date, _)| *date <= item.date());
+        let (_, all) = observations[on_or_before.saturating_sub(1)];
+        Some(all)
+    })
+}
+
+fn normalize<T>(
+    items: &[T],
+    count: impl Fn(&T) -> Option<i64>,
+    base: impl Fn(&T) -> Option<i64>,
+) -> Vec<PerThousand>
+where
+    T: Dated + HasParclId,
+{
+    let mut rates: Vec<PerThousand> = items
+        .iter()
+        .filter_map(|item| {
+            let count = count(item)?;
+            let base = base(item).filter(|b| *b > 0)?;
+            Some(PerThousand {
+                parcl_id: item.parcl_id(),
+                date: item.date().to_string(),
+                count,
+                base,
+                rate: count as f64 / base as f64 * 1000.0,
+            })
+        })
+        .collect();
+    sort_rows(&mut rates);
+    rates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ForSaleInventory;
+
+    fn inventory(parcl_id: Option<i64>, date: &str, count: i64) -> ForSaleInventory {
+        ForSaleInventory {
+            parcl_id,
+            date: date.into(),
+            for_sale_inventory: Some(count),
+        }
+    }
+
+    fn market(parcl_id: i64, population: Option<i64>) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "parcl_id": parcl_id,
+            "name": format!("Market {parcl_id}"),
+            "location_type": "CITY",
+            "total_population": population
+        }))
+        .unwrap()
+    }
+
+    fn stock(date: &str, all_properties: i64) -> HousingStock {
+        serde_json::from_value(serde_json::json!({
+            "date": date,
+            "all_properties": all_properties
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn residents_are_matched_by_market() {
+        let items = [
+            inventory(Some(2), "2024-01-01", 300),
+            inventory(Some(1), "2024-01-01", 50),
+            inventory(Some(3), "2024-01-01", 10),
+        ];
+        let markets = [
+            market(1, Some(100_000)),
+            market(2, Some(1_000_000)),
+            market(3, None),
+        ];
+        let rates: Vec<(Option<i64>, f64)> =
+            per_thousand_residents(&items, |i| i.for_sale_inventory, &markets)
+                .iter()
+                .map(|r| (r.parcl_id, r.rate))
+                .collect();
+        assert_eq!(rates, [(Some(1), 0.5), (Some(2), 0.3)]);
+
+        let single = per_thousand_residents(
+            &[inventory(None, "2024-01-01", 20)],
+            |i| i.for_sale_inventory,
+            &[market(1, Some(40_000))],
+        );
+        assert_eq!((single[0].base, single[0].rate), (40_000, 0.5));
+    }
+
+    #[test]
+    fn homes_use_latest_stock_on_or_before_each_date() {
+        let items = [
+            inventory(None, "2023-06-01", 10),
+            inventory(None, "2024-02-01", 30),
+            inventory(None, "2024-06-01", 44),
+        ];
+        let stock = [stock("2024-05-01", 22_000), stock("2024-01-01", 20_000)];
+        let rates: Vec<(i64, f64)> = per_thousand_homes(&items, |i| i.for_sale_inventory, &stock)
+            .iter()
+            .map(|r| (r.base, r.rate))
+            .collect();
+        assert_eq!(rates, [(20_000, 0.5), (20_000, 1.5), (22_000, 2.0)]);
+    }
+}
@@ -0,0 +1,176 @@
+use super::price_series::parse_date;
+use crate::models::{BatchMetricsResponse, PriceFeedEntry};
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Price feed history as a wide table: one row per date, one column per
+/// market, the shape backtesting frameworks load directly.
+///
+/// Cells are `None` where a market has no price on a row's date. Build the
+/// matrix from entries of a single feed type, e.g. a daily batch history.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceMatrix {
+    parcl_ids: Vec<i64>,
+    rows: Vec<(NaiveDate, Vec<Option<f64>>)>,
+}
+
+impl PriceMatrix {
+    /// Builds a matrix with markets in ascending `parcl_id` order and dates
+    /// oldest first.
+    ///
+    /// Entries without a `parcl_id` or with unparseable dates are skipped;
+    /// for duplicate market dates the later entry wins.
+    pub fn new(entries: &[PriceFeedEntry]) -> Self {
+        let mut prices: BTreeMap<NaiveDate, BTreeMap<i64, f64>> = BTreeMap::new();
+        let mut parcl_ids = BTreeSet::new();
+        for entry in entries {
+            let (Some(parcl_id), Some(date)) = (entry.parcl_id, parse_date(&entry.date)) else {
+                continue;
+            };
+            parcl_ids.insert(parcl_id);
+            prices
+                .entry(date)
+                .or_default()
+                .insert(parcl_id, entry.price);
+        }
+        let parcl_ids: Vec<i64> = parcl_ids.into_iter().collect();
+        let rows = prices
+            .into_iter()
+            .map(|(date, by_market)| {
+                let cells = parcl_ids.iter().map(|id| by_market.get(id).copied());
+                (date, cells.collect())
+            })
+            .collect();
+        Self { parcl_ids, rows }
+    }
+
+    pub fn from_response(response: &BatchMetricsResponse<PriceFeedEntry>) -> Self {
+        Self::new(&response.items)
+    }
+
+    /// Column headers, in column order.
+    pub fn parcl_ids(&self) -> &[i64] {
+        &self.parcl_ids
+    }
+
+    /// Each date with one cell per market.
+    pub fn rows(&self) -> &[(NaiveDate, Vec<Option<f64>>)] {
+        &self.rows
+    }
+
+    /// One market's column, oldest first.
+    pub fn column(&self, parcl_id: i64) -> Option<Vec<(NaiveDate, Option<f64>)>> {
+        let index = self.parcl_ids.iter().position(|&id| id == parcl_id)?;
+        Some(
+            self.rows
+                .iter()
+                .map(|(date, cells)| (*date, cells[index]))
+                .collect(),
+        )
+    }
+
+    /// Simple returns between consecutive rows, `price / previous - 1`.
+    ///
+    /// The first row is dropped. A cell is `None` unless the market has a
+    /// nonzero price on the previous row and a price on its own.
+    pub fn returns(&self) -> Self {
+        self.changes(|previous, price| price / previous - 1.0)
+    }
+
+    /// Log returns between consecutive rows, `ln(price / previous)`, with
+    /// the same gaps as [`returns`](Self::returns).
+    pub fn log_returns(&self) -> Self {
+        self.changes(|previous, price| (price / previous).ln())
+    }
+
+    fn changes(&self, change: impl Fn(f64, f64) -> f64) -> Self {
+        let rows = self
+            .rows
+            .windows(2)
+            .map(|pair| {
+                let (previous, (date, current)) = (&pair[0].1, &pair[1]);
+                let cells = previous.iter().zip(current).map(|(p, c)| match (p, c) {
+                    (Some(p), Some(c)) if *p != 0.0 => Some(change(*p, *c)),
+                    _ => None,
+                });
+                (*date, cells.collect())
+            })
+            .collect();
+        Self {
+            parcl_ids: self.parcl_ids.clone(),
+            rows,
+        }
+    }
+
+    /// Writes the matrix as CSV: a `date` column followed by one column per
+    /// `parcl_id`, with empty cells where a value is missing.
+    #[cfg(feature = "csv")]
+    pub fn write_csv<W: std::io::Write>(&self, writer: W) -> crate::Result<()> {
+        let mut csv = csv::Writer::from_writer(writer);
+        let mut header = vec!["date".to_string()];
+        header.extend(self.parcl_ids.iter().map(i64::to_string));
+        csv.write_record(&header)?;
+        for (date, cells) in &self.rows {
+            let mut record = vec![date.to_string()];
+            record.extend(
+                cells
+                    .iter()
+                    .map(|c| c.map(|v| v.to_string()).unwrap_or_default()),
+            );
+            csv.write_record(&record)?;
+        }
+        csv.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::fixtures::price_entry;
+
+    fn matrix() -> PriceMatrix {
+        PriceMatrix::new(&[
+            price_entry(20, "2024-03-02", 220.0),
+            price_entry(10, "2024-03-01", 100.0),
+            price_entry(20, "2024-03-01", 200.0),
+            price_entry(10, "2024-03-02", 110.0),
+            price_entry(10, "2024-03-03", 99.0),
+        ])
+    }
+
+    #[test]
+    fn markets_become_columns_and_dates_rows() {
+        let matrix = matrix();
+        assert_eq!(matrix.parcl_ids(), [10, 20]);
+        let dates: Vec<String> = matrix.rows().iter().map(|r| r.0.to_string()).collect();
+        assert_eq!(dates, ["2024-03-01", "2024-03-02", "2024-03-03"]);
+        assert_eq!(matrix.rows()[2].1, [Some(99.0), None]);
+        assert_eq!(matrix.column(20).unwrap()[1].1, Some(220.0));
+        assert!(matrix.column(30).is_none());
+    }
+
+    #[test]
+    fn returns_skip_gaps() {
+        let returns = matrix().returns();
+        assert_eq!(returns.rows().len(), 2);
+        let second = &returns.rows()[0].1;
+        assert!((second[0].unwrap() - 0.1).abs() < 1e-9);
+        assert!((second[1].unwrap() - 0.1).abs() < 1e-9);
+        let third = &returns.rows()[1].1;
+        assert!((third[0].unwrap() + 0.1).abs() < 1e-9);
+        assert_eq!(third[1], None);
+        assert!((matrix().log_returns().rows()[0].1[0].unwrap() - 1.1f64.ln()).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_has_a_column_per_market() {
+        let mut out = Vec::new();
+        matrix().write_csv(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "date,10,20\n2024-03-01,100,200\n2024-03-02,110,220\n2024-03-03,99,\n"
+        );
+    }
+}
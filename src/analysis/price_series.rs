@@ -0,0 +1,586 @@
+use crate::models::{MetricsResponse, PriceFeedEntry};
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::BTreeSet;
+
+/// Decides which dates a daily feed is expected to publish.
+pub trait TradingCalendar {
+    fn is_trading_day(&self, date: NaiveDate) -> bool;
+}
+
+/// A calendar where every date is a trading day.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EveryDay;
+
+impl TradingCalendar for EveryDay {
+    fn is_trading_day(&self, _date: NaiveDate) -> bool {
+        true
+    }
+}
+
+/// Monday to Friday, minus any listed holidays.
+#[derive(Debug, Clone, Default)]
+pub struct Weekdays {
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl Weekdays {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds dates on which no data is expected.
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+}
+
+impl TradingCalendar for Weekdays {
+    fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+}
+
+/// How [`PriceSeries::fill`] prices a missing trading day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Repeat the last published price.
+    ForwardFill,
+    /// Interpolate linearly by calendar day between the surrounding prices.
+    Linear,
+}
+
+/// One dated price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PricePoint {
+    pub date: NaiveDate,
+    pub price: f64,
+    /// True if the point was added by [`PriceSeries::fill`].
+    pub interpolated: bool,
+}
+
+/// A run of consecutive missing trading days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gap {
+    pub first_missing: NaiveDate,
+    pub last_missing: NaiveDate,
+    /// Trading days missing in the run.
+    pub missing: usize,
+}
+
+/// Period covered by each [`Bar`] from [`PriceSeries::resample`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarPeriod {
+    /// ISO weeks, Monday to Sunday.
+    Week,
+    Month,
+}
+
+impl BarPeriod {
+    /// First day of the period containing `date`.
+    fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Week => date - chrono::Days::new(date.weekday().num_days_from_monday().into()),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+/// Open, high, low, close and mean of the prices in one period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bar {
+    /// First day of the period, e.g. the Monday of a weekly bar.
+    pub start: NaiveDate,
+    /// Date of the first price in the period.
+    pub first: NaiveDate,
+    /// Date of the last price in the period.
+    pub last: NaiveDate,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub mean: f64,
+    /// Prices in the period.
+    pub count: usize,
+}
+
+/// A fall from a peak to a trough, and the recovery back to the peak
+/// price if there was one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drawdown {
+    pub peak: PricePoint,
+    /// The lowest price before recovery, or so far if still open.
+    pub trough: PricePoint,
+    /// First date the price was back at or above the peak; `None` while
+    /// the drawdown is still open.
+    pub recovery: Option<NaiveDate>,
+    /// Fall from peak to trough, in percent of the peak (positive).
+    pub depth: f64,
+    /// Calendar days from peak to trough.
+    pub days_to_trough: i64,
+    /// Calendar days from trough to recovery.
+    pub days_to_recovery: Option<i64>,
+}
+
+impl Drawdown {
+    /// Calendar days from peak to recovery: the full length of the
+    /// correction.
+    pub fn duration(&self) -> Option<i64> {
+        Some((self.recovery? - self.peak.date).num_days())
+    }
+
+    fn new(peak: PricePoint, trough: PricePoint, recovery: Option<NaiveDate>) -> Self {
+        Self {
+            peak,
+            trough,
+            recovery,
+            depth: (peak.price - trough.price) / peak.price * 100.0,
+            days_to_trough: (trough.date - peak.date).num_days(),
+            days_to_recovery: recovery.map(|date| (date - trough.date).num_days()),
+        }
+    }
+}
+
+/// A daily price feed ordered by date, with calendar-aware gap checks.
+///
+/// Weekends and holidays are normal gaps in a daily feed; a missing trading
+/// day is not. Comparing the series against a [`TradingCalendar`] tells the
+/// two apart, and [`is_halted`](Self::is_halted) flags a feed that has
+/// stopped publishing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceSeries {
+    points: Vec<PricePoint>,
+}
+
+impl PriceSeries {
+    /// Builds a series from price feed entries of a single feed type.
+    ///
+    /// Entries with unparseable dates are skipped; for duplicate dates the
+    /// later entry wins.
+    pub fn new(entries: &[PriceFeedEntry]) -> Self {
+        Self::from_points(
+            entries
+                .iter()
+                .filter_map(|entry| Some((parse_date(&entry.date)?, entry.price))),
+        )
+    }
+
+    pub fn from_response(response: &MetricsResponse<PriceFeedEntry>) -> Self {
+        Self::new(&response.items)
+    }
+
+    /// Builds a series from any dated values, e.g. weekly for-sale
+    /// inventory. Same ordering and duplicate rules as [`new`](Self::new).
+    pub fn from_points(points: impl IntoIterator<Item = (NaiveDate, f64)>) -> Self {
+        let mut points: Vec<PricePoint> = points
+            .into_iter()
+            .map(|(date, price)| PricePoint {
+                date,
+                price,
+                interpolated: false,
+            })
+            .collect();
+        points.reverse();
+        points.sort_by_key(|p| p.date);
+        points.dedup_by_key(|p| p.date);
+        Self { points }
+    }
+
+    pub fn points(&self) -> &[PricePoint] {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The most recent point.
+    pub fn last(&self) -> Option<&PricePoint> {
+        self.points.last()
+    }
+
+    /// Trading days between the first and last point that have no price.
+    pub fn missing_dates<C>(&self, calendar: &C) -> Vec<NaiveDate>
+    where
+        C: TradingCalendar + ?Sized,
+    {
+        self.points
+            .windows(2)
+            .flat_map(|pair| between(pair[0].date, pair[1].date))
+            .filter(|date| calendar.is_trading_day(*date))
+            .collect()
+    }
+
+    /// [`missing_dates`](Self::missing_dates) grouped into runs, where a
+    /// run spans every missing trading day between two published prices.
+    pub fn gaps<C>(&self, calendar: &C) -> Vec<Gap>
+    where
+        C: TradingCalendar + ?Sized,
+    {
+        self.points
+            .windows(2)
+            .filter_map(|pair| {
+                let missing: Vec<NaiveDate> = between(pair[0].date, pair[1].date)
+                    .filter(|date| calendar.is_trading_day(*date))
+                    .collect();
+                Some(Gap {
+                    first_missing: *missing.first()?,
+                    last_missing: *missing.last()?,
+                    missing: missing.len(),
+                })
+            })
+            .collect()
+    }
+
+    /// Trading days after the last point up to and including `as_of`.
+    pub fn trading_days_since_last<C>(&self, calendar: &C, as_of: NaiveDate) -> usize
+    where
+        C: TradingCalendar + ?Sized,
+    {
+        match self.last() {
+            Some(last) => between(last.date, as_of.succ_opt().unwrap_or(as_of))
+                .filter(|date| calendar.is_trading_day(*date))
+                .count(),
+            None => 0,
+        }
+    }
+
+    /// Returns true if more than `tolerance` trading days have passed
+    /// without a price, i.e. the feed looks halted rather than between
+    /// normal publication dates.
+    pub fn is_halted<C>(&self, calendar: &C, as_of: NaiveDate, tolerance: usize) -> bool
+    where
+        C: TradingCalendar + ?Sized,
+    {
+        self.trading_days_since_last(calendar, as_of) > tolerance
+    }
+
+    /// The series from `date` on, indexed to 100 at `date`.
+    ///
+    /// The base is the last price on or before `date`, so a weekend date
+    /// rebases on Friday's close. Returns `None` if the series starts after
+    /// `date` or the base price is zero.
+    pub fn rebase(&self, date: NaiveDate) -> Option<Self> {
+        self.relative_to(date, |ratio| ratio * 100.0)
+    }
+
+    /// The series from `date` on, as the percent change from the price at
+    /// `date`: 0 at the base, 12.5 for a price 12.5% higher. Same base
+    /// rules as [`rebase`](Self::rebase).
+    pub fn pct_change_since(&self, date: NaiveDate) -> Option<Self> {
+        self.relative_to(date, |ratio| (ratio - 1.0) * 100.0)
+    }
+
+    fn relative_to(&self, date: NaiveDate, scale: impl Fn(f64) -> f64) -> Option<Self> {
+        let base_index = self
+            .points
+            .partition_point(|p| p.date <= date)
+            .checked_sub(1)?;
+        let base = self.points[base_index].price;
+        if base == 0.0 {
+            return None;
+        }
+        let points = self.points[base_index..]
+            .iter()
+            .map(|p| PricePoint {
+                price: scale(p.price / base),
+                ..*p
+            })
+            .collect();
+        Some(Self { points })
+    }
+
+    /// Every stretch below a previous peak, oldest first.
+    ///
+    /// A drawdown starts when the price falls below the highest price so
+    /// far and ends when it gets back to it; a drawdown still open at the
+    /// end of the series has no recovery. Sort by
+    /// [`depth`](Drawdown::depth) to find the worst corrections.
+    pub fn drawdowns(&self) -> Vec<Drawdown> {
+        let mut drawdowns = Vec::new();
+        let Some(&first) = self.points.first() else {
+            return drawdowns;
+        };
+        let (mut peak, mut trough) = (first, None::<PricePoint>);
+        for &point in &self.points[1..] {
+            if point.price >= peak.price {
+                if let Some(low) = trough.take() {
+                    drawdowns.push(Drawdown::new(peak, low, Some(point.date)));
+                }
+                peak = point;
+            } else if trough.is_none_or(|low| point.price < low.price) {
+                trough = Some(point);
+            }
+        }
+        drawdowns.extend(trough.map(|low| Drawdown::new(peak, low, None)));
+        drawdowns
+    }
+
+    /// Collapses the series into one bar per week or month that has at
+    /// least one price, oldest first.
+    ///
+    /// Every point counts, including interpolated ones, so call
+    /// [`fill`](Self::fill) first to weight each trading day equally.
+    pub fn resample(&self, period: BarPeriod) -> Vec<Bar> {
+        let mut bars: Vec<Bar> = Vec::new();
+        for point in &self.points {
+            let start = period.start_of(point.date);
+            match bars.last_mut() {
+                Some(bar) if bar.start == start => {
+                    bar.last = point.date;
+                    bar.high = bar.high.max(point.price);
+                    bar.low = bar.low.min(point.price);
+                    bar.close = point.price;
+                    // Running total until the period is complete.
+                    bar.mean += point.price;
+                    bar.count += 1;
+                }
+                _ => bars.push(Bar {
+                    start,
+                    first: point.date,
+                    last: point.date,
+                    open: point.price,
+                    high: point.price,
+                    low: point.price,
+                    close: point.price,
+                    mean: point.price,
+                    count: 1,
+                }),
+            }
+        }
+        for bar in &mut bars {
+            bar.mean /= bar.count as f64;
+        }
+        bars
+    }
+
+    /// Adds a point for every missing trading day between the first and last
+    /// point, marked as interpolated.
+    pub fn fill<C>(&self, calendar: &C, method: Interpolation) -> Self
+    where
+        C: TradingCalendar + ?Sized,
+    {
+        let mut points = Vec::with_capacity(self.points.len());
+        for pair in self.points.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            points.push(from);
+            let span = (to.date - from.date).num_days() as f64;
+            for date in between(from.date, to.date).filter(|d| calendar.is_trading_day(*d)) {
+                let price = match method {
+                    Interpolation::ForwardFill => from.price,
+                    Interpolation::Linear => {
+                        let t = (date - from.date).num_days() as f64 / span;
+                        from.price + (to.price - from.price) * t
+                    }
+                };
+                points.push(PricePoint {
+                    date,
+                    price,
+                    interpolated: true,
+                });
+            }
+        }
+        points.extend(self.points.last().copied());
+        Self { points }
+    }
+}
+
+/// Parses an API date such as `2024-03-01`.
+pub(super) fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Dates strictly between `from` and `to`.
+fn between(from: NaiveDate, to: NaiveDate) -> impl Iterator<Item = NaiveDate> {
+    from.iter_days().skip(1).take_while(move |date| *date < to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::fixtures::price_entry;
+
+    fn date(s: &str) -> NaiveDate {
+        parse_date(s).unwrap()
+    }
+
+    fn series(points: &[(&str, f64)]) -> PriceSeries {
+        let entries: Vec<PriceFeedEntry> = points
+            .iter()
+            .map(|(d, price)| price_entry(1, d, *price))
+            .collect();
+        PriceSeries::new(&entries)
+    }
+
+    // 2024-03-01 is a Friday.
+    fn feed() -> PriceSeries {
+        series(&[
+            ("2024-03-07", 107.0),
+            ("2024-03-01", 101.0),
+            ("2024-03-04", 104.0),
+        ])
+    }
+
+    #[test]
+    fn weekends_are_not_missing_on_a_weekday_calendar() {
+        let feed = feed();
+        assert_eq!(feed.points()[0].date, date("2024-03-01"));
+        assert_eq!(
+            feed.missing_dates(&Weekdays::new()),
+            [date("2024-03-05"), date("2024-03-06")]
+        );
+        assert_eq!(feed.missing_dates(&EveryDay).len(), 4);
+
+        let holidays = Weekdays::new().with_holidays([date("2024-03-05")]);
+        assert_eq!(
+            feed.gaps(&holidays),
+            [Gap {
+                first_missing: date("2024-03-06"),
+                last_missing: date("2024-03-06"),
+                missing: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn halted_feed_is_told_apart_from_a_weekend() {
+        let feed = feed();
+        // Thursday's price, checked on Sunday: only Friday is missing.
+        assert_eq!(
+            feed.trading_days_since_last(&Weekdays::new(), date("2024-03-10")),
+            1
+        );
+        assert!(!feed.is_halted(&Weekdays::new(), date("2024-03-10"), 1));
+        assert!(feed.is_halted(&Weekdays::new(), date("2024-03-13"), 1));
+        assert!(!PriceSeries::default().is_halted(&EveryDay, date("2024-03-13"), 0));
+    }
+
+    #[test]
+    fn fill_interpolates_missing_trading_days() {
+        let filled = feed().fill(&Weekdays::new(), Interpolation::Linear);
+        let prices: Vec<(NaiveDate, f64, bool)> = filled
+            .points()
+            .iter()
+            .map(|p| (p.date, p.price, p.interpolated))
+            .collect();
+        assert_eq!(
+            prices,
+            [
+                (date("2024-03-01"), 101.0, false),
+                (date("2024-03-04"), 104.0, false),
+                (date("2024-03-05"), 105.0, true),
+                (date("2024-03-06"), 106.0, true),
+                (date("2024-03-07"), 107.0, false),
+            ]
+        );
+
+        let filled = feed().fill(&Weekdays::new(), Interpolation::ForwardFill);
+        assert_eq!(filled.points()[3].price, 104.0);
+        assert!(filled.missing_dates(&Weekdays::new()).is_empty());
+    }
+
+    #[test]
+    fn resample_builds_weekly_and_monthly_bars() {
+        // Friday, then Monday to Wednesday of the next week, then April.
+        let feed = series(&[
+            ("2024-03-01", 100.0),
+            ("2024-03-04", 104.0),
+            ("2024-03-05", 99.0),
+            ("2024-03-06", 103.0),
+            ("2024-04-01", 110.0),
+        ]);
+        let weeks = feed.resample(BarPeriod::Week);
+        assert_eq!(weeks.len(), 3);
+        assert_eq!(weeks[0].start, date("2024-02-26"));
+        let week = weeks[1];
+        assert_eq!(
+            (week.start, week.first, week.last),
+            (date("2024-03-04"), date("2024-03-04"), date("2024-03-06"))
+        );
+        assert_eq!(
+            (week.open, week.high, week.low, week.close),
+            (104.0, 104.0, 99.0, 103.0)
+        );
+        assert!((week.mean - 102.0).abs() < 1e-9);
+        assert_eq!(week.count, 3);
+
+        let months = feed.resample(BarPeriod::Month);
+        assert_eq!(months.len(), 2);
+        assert_eq!((months[0].start, months[0].count), (date("2024-03-01"), 4));
+        assert_eq!((months[0].open, months[0].close), (100.0, 103.0));
+        assert_eq!(
+            (months[1].start, months[1].mean),
+            (date("2024-04-01"), 110.0)
+        );
+        assert!(PriceSeries::default().resample(BarPeriod::Week).is_empty());
+    }
+
+    #[test]
+    fn rebase_indexes_from_last_price_on_or_before_date() {
+        let feed = feed();
+        // Sunday the 3rd rebases on Friday the 1st.
+        let rebased = feed.rebase(date("2024-03-03")).unwrap();
+        let levels: Vec<f64> = rebased.points().iter().map(|p| p.price).collect();
+        assert_eq!(rebased.points()[0].date, date("2024-03-01"));
+        assert!((levels[2] - 107.0 / 101.0 * 100.0).abs() < 1e-9);
+
+        let since = feed.pct_change_since(date("2024-03-04")).unwrap();
+        let changes: Vec<f64> = since.points().iter().map(|p| p.price).collect();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0], 0.0);
+        assert!((changes[1] - 300.0 / 104.0).abs() < 1e-9);
+
+        assert!(feed.rebase(date("2024-02-29")).is_none());
+        assert!(series(&[("2024-03-01", 0.0)])
+            .rebase(date("2024-03-01"))
+            .is_none());
+    }
+
+    #[test]
+    fn drawdowns_track_peak_trough_and_recovery() {
+        let feed = series(&[
+            ("2024-01-01", 100.0),
+            ("2024-01-02", 90.0),
+            ("2024-01-03", 80.0),
+            ("2024-01-04", 95.0),
+            ("2024-01-05", 100.0),
+            ("2024-01-06", 120.0),
+            ("2024-01-08", 108.0),
+        ]);
+        let drawdowns = feed.drawdowns();
+        assert_eq!(drawdowns.len(), 2);
+
+        let first = drawdowns[0];
+        assert_eq!(
+            (first.peak.date, first.trough.date, first.recovery),
+            (
+                date("2024-01-01"),
+                date("2024-01-03"),
+                Some(date("2024-01-05"))
+            )
+        );
+        assert_eq!(first.depth, 20.0);
+        assert_eq!((first.days_to_trough, first.days_to_recovery), (2, Some(2)));
+        assert_eq!(first.duration(), Some(4));
+
+        let open = drawdowns[1];
+        assert_eq!(open.peak.price, 120.0);
+        assert!((open.depth - 10.0).abs() < 1e-9);
+        assert_eq!((open.recovery, open.duration()), (None, None));
+
+        assert!(series(&[("2024-01-01", 1.0), ("2024-01-02", 2.0)])
+            .drawdowns()
+            .is_empty());
+    }
+
+    #[test]
+    fn later_duplicates_win() {
+        let feed = series(&[("2024-03-01", 1.0), ("2024-03-01", 2.0), ("bad", 3.0)]);
+        assert_eq!(feed.len(), 1);
+        assert_eq!(feed.points()[0].price, 2.0);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::models::{
+    AllCash, Dated, EventPrices, ForSaleInventory, ForSaleInventoryPriceChanges, GrossYield,
+    HasParclId, HousingEventCounts, HousingEventPrices, HousingStock, InvestorHousingEventCounts,
+    InvestorHousingStockOwnership, NewListingsRollingCounts, PriceStats,
+    RentalNewListingsRollingCounts, RentalPrice, RentalUnitsConcentration,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Item types that can be combined across markets into one custom region.
+///
+/// Counts are summed, each scaled by its market's weight, so a weight of
+/// 0.5 counts half a ZIP that straddles the region's border. Prices,
+/// percentages, yields and medians are weight-averaged over the markets
+/// that report them; for medians and percentiles this approximates the
+/// region's true statistic.
+pub trait RollUp: Sized {
+    /// Combines one date's items, each paired with its market's weight.
+    fn roll_up(date: &str, parts: &[(&Self, f64)]) -> Self;
+}
+
+/// Aggregates ZIP-level (or any market-level) items into a region defined
+/// by `weights`, a `parcl_id` → weight map.
+///
+/// Items from markets not in `weights` are ignored. Returns one item per
+/// date, oldest first, with `parcl_id` set to `None`. A field is `None` on
+/// dates where no member market reports it.
+pub fn rollup<T>(items: &[T], weights: &HashMap<i64, f64>) -> Vec<T>
+where
+    T: RollUp + Dated + HasParclId,
+{
+    let mut by_date: BTreeMap<&str, Vec<(&T, f64)>> = BTreeMap::new();
+    for item in items {
+        if let Some(&weight) = item.parcl_id().and_then(|id| weights.get(&id)) {
+            by_date.entry(item.date()).or_default().push((item, weight));
+        }
+    }
+    by_date
+        .into_iter()
+        .map(|(date, parts)| T::roll_up(date, &parts))
+        .collect()
+}
+
+/// Weighted sum of the counts present, rounded to a whole count.
+fn sum<T>(parts: &[(&T, f64)], field: impl Fn(&T) -> Option<i64>) -> Option<i64> {
+    let mut total = None;
+    for (item, weight) in parts {
+        if let Some(value) = field(item) {
+            *total.get_or_insert(0.0) += value as f64 * weight;
+        }
+    }
+    total.map(|t: f64| t.round() as i64)
+}
+
+/// Weighted mean of the values present.
+fn mean<T>(parts: &[(&T, f64)], field: impl Fn(&T) -> Option<f64>) -> Option<f64> {
+    let (mut total, mut weights) = (0.0, 0.0);
+    for (item, weight) in parts {
+        if let Some(value) = field(item) {
+            total += value * weight;
+            weights += weight;
+        }
+    }
+    (weights > 0.0).then(|| total / weights)
+}
+
+macro_rules! impl_roll_up {
+    ($($t:ty { sum: [$($s:ident),*], mean: [$($m:ident),*] })*) => {
+        $(
+            impl RollUp for $t {
+                // Reads fields kept under deprecated names until 0.2.
+                #[allow(deprecated)]
+                fn roll_up(date: &str, parts: &[(&Self, f64)]) -> Self {
+                    Self {
+                        parcl_id: None,
+                        date: date.to_string(),
+                        $($s: sum(parts, |i| i.$s),)*
+                        $($m: mean(parts, |i| i.$m),)*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_roll_up! {
+    HousingEventCounts { sum: [sales, new_listings_for_sale, new_rental_listings], mean: [] }
+    HousingStock { sum: [single_family, condo, townhouse, other, all_properties], mean: [] }
+    AllCash { sum: [count_sales, count_transfers], mean: [pct_sales, pct_transfers] }
+    InvestorHousingEventCounts {
+        sum: [acquisitions, dispositions, new_listings_for_sale, new_rental_listings],
+        mean: []
+    }
+    InvestorHousingStockOwnership { sum: [investor_owned_count], mean: [investor_owned_pct] }
+    ForSaleInventory { sum: [for_sale_inventory], mean: [] }
+    ForSaleInventoryPriceChanges {
+        sum: [count_price_change, count_price_drop],
+        mean: [
+            median_days_bt_price_change,
+            median_price_change,
+            median_pct_price_change,
+            pct_price_change,
+            pct_price_drop
+        ]
+    }
+    NewListingsRollingCounts {
+        sum: [rolling_7_day_count, rolling_30_day_count, rolling_60_day_count, rolling_90_day_count],
+        mean: []
+    }
+    RentalNewListingsRollingCounts {
+        sum: [rolling_7_day_count, rolling_30_day_count, rolling_60_day_count, rolling_90_day_count],
+        mean: []
+    }
+    GrossYield { sum: [], mean: [gross_yield] }
+    RentalUnitsConcentration { sum: [], mean: [rental_units_concentration] }
+    RentalPrice {
+        sum: [],
+        mean: [median, standard_deviation, percentile_20th, percentile_80th, median_per_square_foot]
+    }
+}
+
+impl RollUp for HousingEventPrices {
+    fn roll_up(date: &str, parts: &[(&Self, f64)]) -> Self {
+        Self {
+            parcl_id: None,
+            date: date.to_string(),
+            price: roll_up_stats(parts, |i| i.price.as_ref()),
+            price_per_square_foot: roll_up_stats(parts, |i| i.price_per_square_foot.as_ref()),
+        }
+    }
+}
+
+fn roll_up_stats<T>(
+    parts: &[(&T, f64)],
+    stats: impl Fn(&T) -> Option<&PriceStats>,
+) -> Option<PriceStats> {
+    let present: Vec<(&PriceStats, f64)> = parts
+        .iter()
+        .filter_map(|(item, weight)| Some((stats(item)?, *weight)))
+        .collect();
+    if present.is_empty() {
+        return None;
+    }
+    let prices = |pick: fn(&PriceStats) -> Option<&EventPrices>| {
+        let parts: Vec<(&EventPrices, f64)> = present
+            .iter()
+            .filter_map(|(s, weight)| Some((pick(s)?, *weight)))
+            .collect();
+        (!parts.is_empty()).then(|| EventPrices {
+            sales: mean(&parts, |p| p.sales),
+            new_listings_for_sale: mean(&parts, |p| p.new_listings_for_sale),
+            new_rental_listings: mean(&parts, |p| p.new_rental_listings),
+        })
+    };
+    Some(PriceStats {
+        median: prices(|s| s.median.as_ref()),
+        standard_deviation: prices(|s| s.standard_deviation.as_ref()),
+        percentile_20th: prices(|s| s.percentile_20th.as_ref()),
+        percentile_80th: prices(|s| s.percentile_80th.as_ref()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::fixtures::event_counts;
+
+    fn prices(parcl_id: i64, median_sale: f64) -> HousingEventPrices {
+        serde_json::from_value(serde_json::json!({
+            "parcl_id": parcl_id,
+            "date": "2024-01-01",
+            "price": {"median": {"sales": median_sale}}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn counts_are_summed_by_date_with_weights() {
+        let items = vec![
+            event_counts(1, "2024-02-01", Some(10)),
+            event_counts(2, "2024-02-01", Some(20)),
+            event_counts(1, "2024-01-01", Some(8)),
+            event_counts(2, "2024-01-01", None),
+            event_counts(3, "2024-01-01", Some(1000)),
+        ];
+        let weights = HashMap::from([(1, 1.0), (2, 0.5)]);
+        let region = rollup(&items, &weights);
+        let rows: Vec<(&str, Option<i64>)> =
+            region.iter().map(|i| (i.date.as_str(), i.sales)).collect();
+        assert_eq!(rows, [("2024-01-01", Some(8)), ("2024-02-01", Some(20))]);
+        assert!(region.iter().all(|i| i.parcl_id.is_none()));
+        assert_eq!(region[0].new_rental_listings, None);
+    }
+
+    #[test]
+    fn prices_are_weight_averaged() {
+        let items = vec![prices(1, 100_000.0), prices(2, 400_000.0)];
+        let weights = HashMap::from([(1, 2.0), (2, 1.0)]);
+        let region = rollup(&items, &weights);
+        let median = region[0].price.as_ref().unwrap().median.as_ref().unwrap();
+        assert_eq!(median.sales, Some(200_000.0));
+        assert_eq!(median.new_rental_listings, None);
+        assert!(region[0].price_per_square_foot.is_none());
+    }
+}
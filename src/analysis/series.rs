@@ -0,0 +1,109 @@
+//! Generic helpers over any [`Dated`] metric series.
+//!
+//! These treat their input as one market's series; filter batch items by
+//! `parcl_id` first.
+
+use super::year_month;
+use crate::models::Dated;
+use std::collections::HashMap;
+
+/// Sorts items oldest first. The sort is stable.
+pub fn sort_by_date<T: Dated>(items: &mut [T]) {
+    items.sort_by(|a, b| a.date().cmp(b.date()));
+}
+
+/// The most recent item, whatever order the API returned.
+pub fn latest<T: Dated>(items: &[T]) -> Option<&T> {
+    items.iter().max_by(|a, b| a.date().cmp(b.date()))
+}
+
+/// Pairs items from two series observed on the same date, oldest first.
+///
+/// Dates present in only one series are dropped. If a series repeats a
+/// date, its first item for that date is used.
+pub fn align<'a, A: Dated, B: Dated>(a: &'a [A], b: &'a [B]) -> Vec<(&'a A, &'a B)> {
+    let mut by_date: HashMap<&str, &B> = HashMap::with_capacity(b.len());
+    for item in b {
+        by_date.entry(item.date()).or_insert(item);
+    }
+    let mut pairs: Vec<(&A, &B)> = Vec::new();
+    for item in a {
+        // Taking the entry out keeps a repeated date in `a` from pairing twice.
+        if let Some(other) = by_date.remove(item.date()) {
+            pairs.push((item, other));
+        }
+    }
+    pairs.sort_by(|x, y| x.0.date().cmp(y.0.date()));
+    pairs
+}
+
+/// Downsamples to one item per calendar month, keeping each month's latest
+/// observation, oldest month first.
+///
+/// Useful for lining weekly series such as rolling counts up with monthly
+/// ones. Items with unparseable dates are skipped.
+pub fn resample_monthly<T: Dated>(items: &[T]) -> Vec<&T> {
+    let mut months: HashMap<(i32, u32), &T> = HashMap::new();
+    for item in items {
+        let Some(month) = year_month(item.date()) else {
+            continue;
+        };
+        let entry = months.entry(month).or_insert(item);
+        if item.date() > entry.date() {
+            *entry = item;
+        }
+    }
+    let mut resampled: Vec<&T> = months.into_values().collect();
+    resampled.sort_by(|a, b| a.date().cmp(b.date()));
+    resampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{GrossYield, NewListingsRollingCounts};
+
+    fn listings(date: &str, count: i64) -> NewListingsRollingCounts {
+        NewListingsRollingCounts {
+            parcl_id: None,
+            date: date.into(),
+            rolling_7_day_count: Some(count),
+            rolling_30_day_count: None,
+            rolling_60_day_count: None,
+            rolling_90_day_count: None,
+        }
+    }
+
+    fn yields(date: &str) -> GrossYield {
+        serde_json::from_value(serde_json::json!({"date": date})).unwrap()
+    }
+
+    #[test]
+    fn works_across_metric_types() {
+        let weekly = [
+            listings("2024-02-26", 4),
+            listings("2024-02-05", 3),
+            listings("2024-01-29", 2),
+            listings("2024-01-08", 1),
+        ];
+        let monthly = [yields("2024-02-01"), yields("2024-01-29")];
+
+        assert_eq!(latest(&weekly).unwrap().date(), "2024-02-26");
+        let counts: Vec<Option<i64>> = resample_monthly(&weekly)
+            .iter()
+            .map(|i| i.rolling_7_day_count)
+            .collect();
+        assert_eq!(counts, [Some(2), Some(4)]);
+
+        let pairs = align(&weekly, &monthly);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].1.date(), "2024-01-29");
+
+        let mut sorted = weekly.clone();
+        sort_by_date(&mut sorted);
+        assert_eq!(
+            sorted[0].naive_date(),
+            chrono::NaiveDate::from_ymd_opt(2024, 1, 8)
+        );
+    }
+}
@@ -0,0 +1,468 @@
+//! Append-only audit log of API calls, for data-governance records of
+//! third-party data usage.
+//!
+//! Install an [`AuditLog`] with `ParclClient::with_audit_log`. Every HTTP
+//! request the client sends, including 429 retries and pagination
+//! follow-ups, is then appended as one JSON line ([`AuditEntry`]). Query
+//! strings and bodies are not stored, only a hash of them, and headers are
+//! never read, so the API key stays out of the log.
+//!
+//! [`AuditReport`] reads a log back to break credit usage down by endpoint
+//! and to estimate what caching or batching would have saved.
+
+use crate::error::Result;
+use crate::models::AccountInfo;
+use crate::protocol::{HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// One API call as recorded in the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub method: String,
+    /// Request path with numeric segments replaced by `{id}`, e.g.
+    /// `/v1/market_metrics/{id}/housing_stock`.
+    pub endpoint: String,
+    /// The first numeric path segment, usually the market requested.
+    pub parcl_id: Option<i64>,
+    /// FNV-1a hash of the query string and request body, in hex. Equal
+    /// hashes on the same endpoint mean the same request.
+    pub params_hash: String,
+    pub status: u16,
+    /// `account.est_credits_used` reported by the response, if any.
+    pub credits: Option<i64>,
+    /// Response body size.
+    pub bytes: u64,
+}
+
+impl AuditEntry {
+    /// Captures the request side; the status is filled in by
+    /// [`with_response`](Self::with_response).
+    pub(crate) fn for_request(request: &HttpRequest) -> Self {
+        let uri = request.uri();
+        let mut parcl_id = None;
+        let endpoint = uri
+            .path()
+            .split('/')
+            .map(|segment| match segment.parse::<i64>() {
+                Ok(id) => {
+                    parcl_id.get_or_insert(id);
+                    "{id}"
+                }
+                Err(_) => segment,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        let mut hash = Fnv1a::default();
+        hash.write(uri.query().unwrap_or_default().as_bytes());
+        hash.write(&[0]);
+        hash.write(request.body());
+        Self {
+            timestamp: Utc::now(),
+            method: request.method().to_string(),
+            endpoint,
+            parcl_id,
+            params_hash: format!("{:016x}", hash.0),
+            status: 0,
+            credits: None,
+            bytes: 0,
+        }
+    }
+
+    pub(crate) fn with_response(mut self, response: &HttpResponse) -> Self {
+        #[derive(Deserialize)]
+        struct Account {
+            account: Option<AccountInfo>,
+        }
+        self.status = response.status().as_u16();
+        self.bytes = response.body().len() as u64;
+        self.credits = serde_json::from_slice::<Account>(response.body())
+            .ok()
+            .and_then(|a| a.account?.est_credits_used);
+        self
+    }
+}
+
+/// Destination for [`AuditEntry`] lines.
+///
+/// Clones share the same writer, so one log can serve several clients.
+/// Each entry is written and flushed as a whole line; if writing fails, the
+/// request that produced it fails with `ParclError::Io` rather than going
+/// unrecorded.
+///
+/// ```no_run
+/// use parcllabs::audit::AuditLog;
+/// use parcllabs::ParclClient;
+///
+/// # fn example() -> parcllabs::Result<()> {
+/// let client = ParclClient::new()?.with_audit_log(AuditLog::append("parcl-audit.jsonl")?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct AuditLog {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl AuditLog {
+    /// Writes entries to `writer`.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+        }
+    }
+
+    /// Appends entries to the file at `path`, creating it if needed.
+    pub fn append(path: impl AsRef<Path>) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        Ok(Self::new(file))
+    }
+
+    /// Writes one entry as a JSON line.
+    pub fn record(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writer.write_all(&line)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditLog").finish_non_exhaustive()
+    }
+}
+
+/// Requests, credits and bytes recorded for one endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointUsage {
+    pub endpoint: String,
+    pub requests: u64,
+    /// Requests answered with a non-2xx status, including 429 retries.
+    pub failed: u64,
+    pub credits: i64,
+    pub bytes: u64,
+}
+
+/// A what-if strategy for [`AuditReport::simulate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Repeats of a successful request (same endpoint, market and params)
+    /// within `ttl` of it are served from a cache, costing nothing.
+    Cache { ttl: Duration },
+    /// Single-market GETs to the same endpoint with the same params, issued
+    /// within `window` of each other, are merged into batch requests of up
+    /// to `max_markets` markets. Credits are billed per result, so only the
+    /// request count changes.
+    Batch {
+        window: Duration,
+        max_markets: usize,
+    },
+}
+
+/// Outcome of replaying a log under a [`Strategy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Simulation {
+    pub baseline_requests: u64,
+    pub baseline_credits: i64,
+    pub requests: u64,
+    pub credits: i64,
+}
+
+impl Simulation {
+    pub fn requests_saved(&self) -> u64 {
+        self.baseline_requests - self.requests
+    }
+
+    pub fn credits_saved(&self) -> i64 {
+        self.baseline_credits - self.credits
+    }
+}
+
+/// A recorded audit log, read back for cost analysis.
+///
+/// ```no_run
+/// use parcllabs::audit::{AuditReport, Strategy};
+/// use std::time::Duration;
+///
+/// # fn example() -> parcllabs::Result<()> {
+/// let report = AuditReport::load("parcl-audit.jsonl")?;
+/// for usage in report.by_endpoint() {
+///     println!("{}: {} credits over {} requests", usage.endpoint, usage.credits, usage.requests);
+/// }
+/// let cached = report.simulate(Strategy::Cache { ttl: Duration::from_secs(86_400) });
+/// println!("a daily cache would save {} credits", cached.credits_saved());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditReport {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditReport {
+    /// Orders `entries` by timestamp.
+    pub fn new(mut entries: Vec<AuditEntry>) -> Self {
+        entries.sort_by_key(|e| e.timestamp);
+        Self { entries }
+    }
+
+    /// Parses a JSON-lines log, skipping blank lines.
+    pub fn read(reader: impl BufRead) -> Result<Self> {
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self::new(entries))
+    }
+
+    /// Reads the log file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::read(std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    pub fn total_requests(&self) -> u64 {
+        self.entries.len() as u64
+    }
+
+    pub fn total_credits(&self) -> i64 {
+        self.entries.iter().filter_map(|e| e.credits).sum()
+    }
+
+    /// Usage per endpoint, most credits first.
+    pub fn by_endpoint(&self) -> Vec<EndpointUsage> {
+        let mut usage: HashMap<&str, EndpointUsage> = HashMap::new();
+        for entry in &self.entries {
+            let row = usage
+                .entry(&entry.endpoint)
+                .or_insert_with(|| EndpointUsage {
+                    endpoint: entry.endpoint.clone(),
+                    ..Default::default()
+                });
+            row.requests += 1;
+            row.failed += u64::from(!(200..300).contains(&entry.status));
+            row.credits += entry.credits.unwrap_or(0);
+            row.bytes += entry.bytes;
+        }
+        let mut usage: Vec<EndpointUsage> = usage.into_values().collect();
+        usage.sort_by(|a, b| {
+            b.credits
+                .cmp(&a.credits)
+                .then_with(|| a.endpoint.cmp(&b.endpoint))
+        });
+        usage
+    }
+
+    /// Replays the log under `strategy` and compares it with what was spent.
+    pub fn simulate(&self, strategy: Strategy) -> Simulation {
+        let mut requests = 0;
+        let mut credits = 0;
+        match strategy {
+            Strategy::Cache { ttl } => {
+                let mut cached: HashMap<(&str, Option<i64>, &str), DateTime<Utc>> = HashMap::new();
+                for entry in &self.entries {
+                    let key = (
+                        entry.endpoint.as_str(),
+                        entry.parcl_id,
+                        entry.params_hash.as_str(),
+                    );
+                    if cached
+                        .get(&key)
+                        .is_some_and(|at| elapsed(*at, entry.timestamp) <= ttl)
+                    {
+                        continue;
+                    }
+                    requests += 1;
+                    credits += entry.credits.unwrap_or(0);
+                    if (200..300).contains(&entry.status) {
+                        cached.insert(key, entry.timestamp);
+                    }
+                }
+            }
+            Strategy::Batch {
+                window,
+                max_markets,
+            } => {
+                // Open batch per (endpoint, params): start time and size.
+                let mut open: HashMap<(&str, &str), (DateTime<Utc>, usize)> = HashMap::new();
+                for entry in &self.entries {
+                    credits += entry.credits.unwrap_or(0);
+                    if entry.method != "GET" || entry.parcl_id.is_none() {
+                        requests += 1;
+                        continue;
+                    }
+                    let key = (entry.endpoint.as_str(), entry.params_hash.as_str());
+                    match open.get_mut(&key) {
+                        Some((start, size))
+                            if elapsed(*start, entry.timestamp) <= window
+                                && *size < max_markets.max(1) =>
+                        {
+                            *size += 1;
+                        }
+                        _ => {
+                            requests += 1;
+                            open.insert(key, (entry.timestamp, 1));
+                        }
+                    }
+                }
+            }
+        }
+        Simulation {
+            baseline_requests: self.total_requests(),
+            baseline_credits: self.total_credits(),
+            requests,
+            credits,
+        }
+    }
+}
+
+fn elapsed(from: DateTime<Utc>, to: DateTime<Utc>) -> Duration {
+    (to - from).to_std().unwrap_or_default()
+}
+
+/// 64-bit FNV-1a, stable across Rust versions unlike `DefaultHasher`.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{json_response, CannedTransport};
+    use crate::ParclClient;
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Rate-limits the first request, then answers with one item.
+    fn limited_once() -> CannedTransport {
+        let body = serde_json::json!({
+            "parcl_id": 2900187, "items": [], "total": 0, "limit": 1, "offset": 0,
+            "links": {}, "account": {"est_credits_used": 3, "est_remaining_credits": 97}
+        });
+        CannedTransport::sequence(vec![json_response(429, &body), json_response(200, &body)])
+    }
+
+    fn entry(secs: i64, parcl_id: i64, status: u16, credits: i64) -> AuditEntry {
+        AuditEntry {
+            timestamp: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            method: "GET".into(),
+            endpoint: "/v1/market_metrics/{id}/housing_stock".into(),
+            parcl_id: Some(parcl_id),
+            params_hash: "00".into(),
+            status,
+            credits: Some(credits),
+            bytes: 100,
+        }
+    }
+
+    #[test]
+    fn report_breaks_down_and_simulates_costs() {
+        let mut post = entry(5, 0, 200, 4);
+        post.method = "POST".into();
+        post.endpoint = "/v1/market_metrics/housing_stock".into();
+        post.parcl_id = None;
+        let log: String = [
+            entry(0, 1, 429, 0),
+            entry(1, 1, 200, 2),
+            entry(2, 2, 200, 2),
+            post,
+            entry(60, 1, 200, 2),
+            entry(7200, 1, 200, 2),
+        ]
+        .iter()
+        .map(|e| serde_json::to_string(e).unwrap() + "\n")
+        .collect();
+        let report = AuditReport::read(log.as_bytes()).unwrap();
+
+        assert_eq!((report.total_requests(), report.total_credits()), (6, 12));
+        let usage = report.by_endpoint();
+        assert_eq!(usage[0].endpoint, "/v1/market_metrics/{id}/housing_stock");
+        assert_eq!(
+            (usage[0].requests, usage[0].failed, usage[0].credits),
+            (5, 1, 8)
+        );
+
+        let cached = report.simulate(Strategy::Cache {
+            ttl: Duration::from_secs(3600),
+        });
+        assert_eq!((cached.requests_saved(), cached.credits_saved()), (1, 2));
+
+        let batched = report.simulate(Strategy::Batch {
+            window: Duration::from_secs(10),
+            max_markets: 100,
+        });
+        assert_eq!((batched.requests, batched.credits_saved()), (4, 0));
+    }
+
+    #[tokio::test]
+    async fn records_every_attempt_as_a_json_line() {
+        let out = Shared::default();
+        let client = ParclClient::with_config("secret-key", "https://example.com")
+            .with_transport(limited_once())
+            .with_timer(crate::runtime::MockClock::new())
+            .with_audit_log(AuditLog::new(out.clone()));
+        let params = crate::MetricsParams::new().limit(1);
+        client
+            .market_metrics()
+            .housing_stock(2900187, Some(params))
+            .await
+            .unwrap();
+
+        let text = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+        assert!(!text.contains("secret-key"));
+        let entries: Vec<AuditEntry> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        let (retried, ok) = (&entries[0], &entries[1]);
+        assert_eq!(retried.status, 429);
+        assert_eq!(ok.status, 200);
+        assert_eq!(ok.method, "GET");
+        assert_eq!(ok.endpoint, "/v1/market_metrics/{id}/housing_stock");
+        assert_eq!(ok.parcl_id, Some(2900187));
+        assert_eq!(ok.credits, Some(3));
+        assert_eq!(ok.params_hash, retried.params_hash);
+    }
+}
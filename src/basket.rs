@@ -0,0 +1,200 @@
+//! Named, weighted sets of markets that can be saved and reused.
+//!
+//! A [`Basket`] pins down a coverage universe, such as a fund's target
+//! metros or a sales territory's ZIPs, so every job fetches the same
+//! markets with the same weights.
+//!
+//! ```no_run
+//! use parcllabs::{Basket, ParclClient};
+//!
+//! # async fn example() -> parcllabs::Result<()> {
+//! let client = ParclClient::new()?;
+//! let sun_belt = Basket::new("sun belt")
+//!     .member(2900187, 2.0)
+//!     .member(2899845, 1.0)
+//!     .member(2900245, 1.0);
+//! sun_belt.save("sun_belt.json")?;
+//!
+//! let index = sun_belt.price_index(&client, None).await?;
+//! let inventory = sun_belt.inventory(&client, None).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::analysis::{rollup, PriceMatrix, PriceSeries};
+use crate::error::Result;
+use crate::models::ForSaleInventory;
+use crate::{ForSaleMetricsParams, MetricsParams, ParclClient};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Value a [`Basket::price_index`] starts from.
+const INDEX_BASE: f64 = 100.0;
+
+/// A named list of markets with weights.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Basket {
+    pub name: String,
+    pub members: Vec<BasketMember>,
+}
+
+/// One market in a [`Basket`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BasketMember {
+    pub parcl_id: i64,
+    /// Relative weight; defaults to 1 when omitted from a saved basket.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+impl Basket {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            members: Vec::new(),
+        }
+    }
+
+    /// Adds a market, replacing its weight if it is already a member.
+    pub fn member(mut self, parcl_id: i64, weight: f64) -> Self {
+        match self.members.iter_mut().find(|m| m.parcl_id == parcl_id) {
+            Some(member) => member.weight = weight,
+            None => self.members.push(BasketMember { parcl_id, weight }),
+        }
+        self
+    }
+
+    /// Adds markets with a weight of 1 each.
+    pub fn members(self, parcl_ids: impl IntoIterator<Item = i64>) -> Self {
+        parcl_ids
+            .into_iter()
+            .fold(self, |basket, parcl_id| basket.member(parcl_id, 1.0))
+    }
+
+    pub fn parcl_ids(&self) -> Vec<i64> {
+        self.members.iter().map(|m| m.parcl_id).collect()
+    }
+
+    /// Weights keyed by `parcl_id`, the shape [`rollup`] takes.
+    pub fn weights(&self) -> HashMap<i64, f64> {
+        self.members
+            .iter()
+            .map(|m| (m.parcl_id, m.weight))
+            .collect()
+    }
+
+    /// Reads a basket written by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    /// Writes the basket to `path` as pretty-printed JSON, so definitions
+    /// can be reviewed and versioned alongside code.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// A weighted price index of the members' price feeds, starting at 100.
+    ///
+    /// Each step moves by the weighted mean of the members' returns on that
+    /// date, so members joining or pausing mid-history shift the weights
+    /// rather than the level. Members without a price feed are left out.
+    pub async fn price_index(
+        &self,
+        client: &ParclClient,
+        params: Option<MetricsParams>,
+    ) -> Result<PriceSeries> {
+        let feed = client
+            .price_feed()
+            .batch_history(self.parcl_ids(), params)
+            .await?;
+        Ok(self.index(&PriceMatrix::from_response(&feed)))
+    }
+
+    /// The members' for-sale inventory combined with [`rollup`]: one item
+    /// per date with counts summed in proportion to each weight.
+    pub async fn inventory(
+        &self,
+        client: &ParclClient,
+        params: Option<ForSaleMetricsParams>,
+    ) -> Result<Vec<ForSaleInventory>> {
+        let inventory = client
+            .for_sale_metrics()
+            .batch_for_sale_inventory(self.parcl_ids(), params)
+            .await?;
+        Ok(rollup(&inventory.items, &self.weights()))
+    }
+
+    fn index(&self, prices: &PriceMatrix) -> PriceSeries {
+        let weights = self.weights();
+        let column_weights: Vec<f64> = prices
+            .parcl_ids()
+            .iter()
+            .map(|id| weights.get(id).copied().unwrap_or(0.0))
+            .collect();
+        let mut level = INDEX_BASE;
+        let first = prices.rows().first().map(|(date, _)| (*date, level));
+        let returns = prices.returns();
+        let steps = returns.rows().iter().map(|(date, returns)| {
+            let (mut total, mut weight) = (0.0, 0.0);
+            for (r, w) in returns.iter().zip(&column_weights) {
+                if let Some(r) = r {
+                    total += r * w;
+                    weight += w;
+                }
+            }
+            if weight > 0.0 {
+                level *= 1.0 + total / weight;
+            }
+            (*date, level)
+        });
+        PriceSeries::from_points(first.into_iter().chain(steps).collect::<Vec<_>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::fixtures::price_entry;
+
+    #[test]
+    fn round_trips_through_json_with_default_weights() {
+        let basket = Basket::new("metros").member(1, 2.0).members([2, 1]);
+        assert_eq!(basket.parcl_ids(), [1, 2]);
+        assert_eq!(basket.weights()[&1], 1.0);
+
+        let json = serde_json::to_string(&basket).unwrap();
+        assert_eq!(serde_json::from_str::<Basket>(&json).unwrap(), basket);
+        let saved: Basket =
+            serde_json::from_str(r#"{"name": "zips", "members": [{"parcl_id": 5}]}"#).unwrap();
+        assert_eq!(saved.members[0].weight, 1.0);
+    }
+
+    #[test]
+    fn index_chains_weighted_returns() {
+        let basket = Basket::new("pair").member(1, 3.0).member(2, 1.0);
+        let prices = PriceMatrix::new(&[
+            price_entry(1, "2024-03-01", 100.0),
+            price_entry(2, "2024-03-01", 50.0),
+            price_entry(1, "2024-03-02", 110.0),
+            price_entry(2, "2024-03-02", 45.0),
+            price_entry(1, "2024-03-03", 121.0),
+        ]);
+        let levels: Vec<f64> = basket
+            .index(&prices)
+            .points()
+            .iter()
+            .map(|p| p.price)
+            .collect();
+        // Day 2: 0.75 * 10% + 0.25 * -10% = +5%. Day 3: only member 1, +10%.
+        assert_eq!(levels.len(), 3);
+        assert!((levels[1] - 105.0).abs() < 1e-9);
+        assert!((levels[2] - 115.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,618 @@
+//! In-memory caches that avoid spending credits on repeat lookups.
+
+use crate::error::Result;
+use crate::models::{HasParclId, Market, PaginatedResponse};
+use crate::protocol::{HttpRequest, HttpResponse};
+use crate::{ParclClient, SearchParams};
+use chrono::{DateTime, Utc};
+use http::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Entries kept by [`ConditionalCache`] unless
+/// [`max_entries`](ConditionalCache::max_entries) is set.
+const DEFAULT_MAX_ENTRIES: usize = 1024;
+
+/// A value paired with the market metadata of the `parcl_id` it belongs to.
+#[derive(Debug, Clone)]
+pub struct Labeled<T> {
+    /// The resolved market, or `None` if the `parcl_id` could not be found.
+    pub market: Option<Market>,
+    pub item: T,
+}
+
+/// The name, state and population of a market record, for name lookups.
+pub(crate) trait NamedMarket {
+    fn name(&self) -> &str;
+    fn state_abbreviation(&self) -> Option<&str>;
+    fn total_population(&self) -> Option<i64>;
+}
+
+impl NamedMarket for Market {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn state_abbreviation(&self) -> Option<&str> {
+        self.state_abbreviation.as_deref()
+    }
+
+    fn total_population(&self) -> Option<i64> {
+        self.total_population
+    }
+}
+
+/// The most populous of `markets` named `name` (case-insensitive),
+/// optionally within `state`.
+pub(crate) fn most_populous_named<'m, M: NamedMarket + 'm>(
+    markets: impl IntoIterator<Item = &'m M>,
+    name: &str,
+    state: Option<&str>,
+) -> Option<&'m M> {
+    let (name, state) = (name.trim(), state.map(str::trim));
+    markets
+        .into_iter()
+        .filter(|m| m.name().eq_ignore_ascii_case(name))
+        .filter(|m| {
+            state.is_none_or(|s| {
+                m.state_abbreviation()
+                    .is_some_and(|ms| ms.eq_ignore_ascii_case(s))
+            })
+        })
+        .max_by_key(|m| m.total_population().unwrap_or(0))
+}
+
+/// Thread-safe cache of `Market` records keyed by `parcl_id`.
+///
+/// Every `ParclClient` owns one, seeded automatically from market search
+/// results, so labeling metrics with market names rarely needs extra requests.
+#[derive(Debug, Default)]
+pub struct MarketCache {
+    markets: RwLock<HashMap<i64, Market>>,
+}
+
+impl MarketCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached market for `parcl_id`, if present.
+    pub fn get(&self, parcl_id: i64) -> Option<Market> {
+        self.read().get(&parcl_id).cloned()
+    }
+
+    /// Adds or replaces a market record.
+    pub fn insert(&self, market: Market) {
+        self.write().insert(market.parcl_id, market);
+    }
+
+    /// Adds or replaces several market records.
+    pub fn extend(&self, markets: impl IntoIterator<Item = Market>) {
+        let mut map = self.write();
+        for market in markets {
+            map.insert(market.parcl_id, market);
+        }
+    }
+
+    /// Returns true if `parcl_id` is cached.
+    pub fn contains(&self, parcl_id: i64) -> bool {
+        self.read().contains_key(&parcl_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Finds a cached market by exact (case-insensitive) name, optionally
+    /// within a state. The most populous match wins.
+    pub fn find_by_name(&self, name: &str, state: Option<&str>) -> Option<Market> {
+        most_populous_named(self.read().values(), name, state).cloned()
+    }
+
+    /// Removes all cached markets.
+    pub fn clear(&self) {
+        self.write().clear();
+    }
+
+    /// Returns the cached market for `parcl_id`, searching the API on a miss.
+    pub async fn resolve(&self, client: &ParclClient, parcl_id: i64) -> Result<Option<Market>> {
+        if let Some(market) = self.get(parcl_id) {
+            return Ok(Some(market));
+        }
+        let params = SearchParams::new().parcl_id(parcl_id).limit(1);
+        let resp = client.search().markets(params).await?;
+        let market = resp.items.into_iter().find(|m| m.parcl_id == parcl_id);
+        if let Some(ref m) = market {
+            self.insert(m.clone());
+        }
+        Ok(market)
+    }
+
+    /// Resolves several `parcl_id`s, searching only for those not yet cached.
+    ///
+    /// IDs that the API does not know about are omitted from the result.
+    pub async fn resolve_all(
+        &self,
+        client: &ParclClient,
+        parcl_ids: &[i64],
+    ) -> Result<HashMap<i64, Market>> {
+        let mut resolved = HashMap::new();
+        for &id in parcl_ids {
+            if resolved.contains_key(&id) {
+                continue;
+            }
+            if let Some(market) = self.resolve(client, id).await? {
+                resolved.insert(id, market);
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Labels items with their cached market without making any requests.
+    pub fn label<T: HasParclId>(&self, items: Vec<T>) -> Vec<Labeled<T>> {
+        let map = self.read();
+        items
+            .into_iter()
+            .map(|item| Labeled {
+                market: item.parcl_id().and_then(|id| map.get(&id).cloned()),
+                item,
+            })
+            .collect()
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<i64, Market>> {
+        self.markets.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<i64, Market>> {
+        self.markets.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Search responses keyed by normalized query and filters.
+///
+/// Used by [`SearchClient::cached`](crate::endpoints::SearchClient::cached).
+/// Market metadata changes rarely, so entries live for a day by default,
+/// and the cache can be saved to disk to skip repeat searches across runs.
+#[derive(Debug)]
+pub struct SearchCache {
+    ttl: Duration,
+    entries: RwLock<HashMap<String, CachedSearch>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedSearch {
+    stored_at: DateTime<Utc>,
+    response: PaginatedResponse<Market>,
+}
+
+impl Default for SearchCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(24 * 60 * 60))
+    }
+}
+
+impl SearchCache {
+    /// Creates an empty cache whose entries expire after `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Loads a cache written by [`save`](Self::save). Expired entries are
+    /// dropped; a missing file gives an empty cache.
+    pub fn load(path: impl AsRef<Path>, ttl: Duration) -> Result<Self> {
+        let cache = Self::new(ttl);
+        let json = match std::fs::read(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(cache),
+            Err(e) => return Err(e.into()),
+        };
+        let entries: HashMap<String, CachedSearch> = serde_json::from_slice(&json)?;
+        let now = Utc::now();
+        cache.write().extend(
+            entries
+                .into_iter()
+                .filter(|(_, entry)| cache.is_fresh(entry, now)),
+        );
+        Ok(cache)
+    }
+
+    /// Writes unexpired entries to `path` as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let now = Utc::now();
+        let stored = self.read_entries();
+        let entries: HashMap<&String, &CachedSearch> = stored
+            .iter()
+            .filter(|(_, entry)| self.is_fresh(entry, now))
+            .collect();
+        std::fs::write(path, serde_json::to_vec(&entries)?)?;
+        Ok(())
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The cached response for `params`, if stored within the TTL.
+    pub fn get(&self, params: &SearchParams) -> Option<PaginatedResponse<Market>> {
+        self.get_at(params, Utc::now())
+    }
+
+    /// Stores `response` as the result of searching with `params`.
+    pub fn insert(&self, params: &SearchParams, response: PaginatedResponse<Market>) {
+        self.insert_at(params, response, Utc::now());
+    }
+
+    /// Number of stored entries, including expired ones not yet evicted.
+    pub fn len(&self) -> usize {
+        self.read_entries().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read_entries().is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.write().clear();
+    }
+
+    fn get_at(
+        &self,
+        params: &SearchParams,
+        now: DateTime<Utc>,
+    ) -> Option<PaginatedResponse<Market>> {
+        self.read_entries()
+            .get(&params.cache_key())
+            .filter(|entry| self.is_fresh(entry, now))
+            .map(|entry| entry.response.clone())
+    }
+
+    fn insert_at(
+        &self,
+        params: &SearchParams,
+        response: PaginatedResponse<Market>,
+        stored_at: DateTime<Utc>,
+    ) {
+        self.write().insert(
+            params.cache_key(),
+            CachedSearch {
+                stored_at,
+                response,
+            },
+        );
+    }
+
+    fn is_fresh(&self, entry: &CachedSearch, now: DateTime<Utc>) -> bool {
+        (now - entry.stored_at)
+            .to_std()
+            .map_or(true, |age| age < self.ttl)
+    }
+
+    fn read_entries(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, CachedSearch>> {
+        self.entries.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, CachedSearch>> {
+        self.entries.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// GET response bodies stored with their `ETag` and `Last-Modified`
+/// validators, for conditional requests.
+///
+/// Installed with [`ParclClient::with_conditional_cache`]. Repeat GETs send
+/// `If-None-Match` / `If-Modified-Since`, and a `304 Not Modified` reply is
+/// served from the stored body, saving the transfer and, where the API does
+/// not bill revalidations, the credits. Responses without validators are
+/// not stored. When the cache is full, storing a new response evicts the
+/// least recently used one.
+#[derive(Debug)]
+pub struct ConditionalCache {
+    max_entries: usize,
+    entries: RwLock<HashMap<String, Validated>>,
+    /// Incremented on every store or hit, to order entries by last use.
+    clock: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+struct Validated {
+    headers: HeaderMap,
+    body: Vec<u8>,
+    last_used: u64,
+}
+
+/// Counters reported by [`ConditionalCache::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConditionalStats {
+    /// `304` responses answered from a stored body.
+    pub hits: u64,
+    /// Full responses received for requests that could have been cached.
+    pub misses: u64,
+}
+
+impl ConditionalStats {
+    /// Share of cacheable requests answered by a `304`, or 0 before any.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+impl Default for ConditionalCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConditionalCache {
+    pub fn new() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_ENTRIES,
+            entries: RwLock::new(HashMap::new()),
+            clock: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Caps the number of stored responses (default 1024). Past the cap, the
+    /// least recently stored or revalidated response is evicted.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    pub fn stats(&self) -> ConditionalStats {
+        ConditionalStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Number of stored responses.
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Drops every stored response; the stats are kept.
+    pub fn clear(&self) {
+        self.write().clear();
+    }
+
+    /// Adds the stored validators for `url` to a GET request.
+    pub(crate) fn prepare(&self, url: &str, request: &mut HttpRequest) {
+        let entries = self.read();
+        let Some(entry) = entries.get(url) else {
+            return;
+        };
+        let headers = request.headers_mut();
+        if let Some(etag) = entry.headers.get(ETAG) {
+            headers.insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(modified) = entry.headers.get(LAST_MODIFIED) {
+            headers.insert(IF_MODIFIED_SINCE, modified.clone());
+        }
+    }
+
+    /// Replaces a `304` with the stored response and stores successful
+    /// responses that carry validators.
+    pub(crate) fn complete(&self, url: &str, response: HttpResponse) -> HttpResponse {
+        if response.status() == StatusCode::NOT_MODIFIED {
+            let Some(entry) = self.write().get_mut(url).map(|entry| {
+                entry.last_used = self.tick();
+                entry.clone()
+            }) else {
+                return response;
+            };
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            let mut cached = http::Response::new(entry.body);
+            *cached.headers_mut() = entry.headers;
+            // Rate-limit and credit headers describe this request, not the stored one.
+            for (name, value) in response.headers() {
+                cached.headers_mut().insert(name, value.clone());
+            }
+            return cached;
+        }
+        let headers = response.headers();
+        if !response.status().is_success()
+            || !(headers.contains_key(ETAG) || headers.contains_key(LAST_MODIFIED))
+        {
+            return response;
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let mut entries = self.write();
+        if entries.len() >= self.max_entries && !entries.contains_key(url) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        if self.max_entries > 0 {
+            entries.insert(
+                url.to_string(),
+                Validated {
+                    headers: headers.clone(),
+                    body: response.body().clone(),
+                    last_used: self.tick(),
+                },
+            );
+        }
+        response
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, Validated>> {
+        self.entries.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, Validated>> {
+        self.entries.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::fixtures::event_counts;
+
+    fn market(parcl_id: i64, name: &str) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "parcl_id": parcl_id,
+            "name": name,
+            "location_type": "CITY"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn market_cache_insert_and_get() {
+        let cache = MarketCache::new();
+        assert!(cache.is_empty());
+        cache.insert(market(1, "Denver"));
+        cache.extend(vec![market(2, "Austin"), market(1, "Denver City")]);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains(2));
+        assert_eq!(cache.get(1).unwrap().name, "Denver City");
+        assert!(cache.get(3).is_none());
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn market_cache_label_items() {
+        let cache = MarketCache::new();
+        cache.insert(market(1, "Denver"));
+        let labeled = cache.label(vec![
+            event_counts(Some(1), "2024-01-01", 10),
+            event_counts(Some(2), "2024-01-01", 10),
+            event_counts(None, "2024-01-01", 10),
+        ]);
+        assert_eq!(labeled.len(), 3);
+        assert_eq!(labeled[0].market.as_ref().unwrap().name, "Denver");
+        assert!(labeled[1].market.is_none());
+        assert!(labeled[2].market.is_none());
+        assert_eq!(labeled[0].item.sales, Some(10));
+    }
+
+    #[test]
+    fn search_cache_expires_and_round_trips() {
+        let cache = SearchCache::new(Duration::from_secs(60));
+        let params = SearchParams::new().query("Denver");
+        let response: PaginatedResponse<Market> = serde_json::from_value(serde_json::json!({
+            "items": [{"parcl_id": 1, "name": "Denver", "location_type": "CITY"}],
+            "total": 1, "limit": 1, "offset": 0, "links": {}
+        }))
+        .unwrap();
+        let now = Utc::now();
+        cache.insert_at(&params, response, now);
+        assert!(cache
+            .get_at(&params, now + chrono::Duration::seconds(59))
+            .is_some());
+        assert!(cache
+            .get_at(&params, now + chrono::Duration::seconds(60))
+            .is_none());
+
+        let path =
+            std::env::temp_dir().join(format!("parcllabs-search-{}.json", std::process::id()));
+        cache.save(&path).unwrap();
+        let loaded = SearchCache::load(&path, Duration::from_secs(60)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.get(&params).unwrap().items[0].name, "Denver");
+        assert!(SearchCache::load(&path, Duration::from_secs(60))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn market_cache_resolve_hit_skips_request() {
+        // Unroutable base URL: a cache miss would fail, a hit must not touch the network.
+        let client = ParclClient::with_config("test", "http://127.0.0.1:9");
+        let cache = MarketCache::new();
+        cache.insert(market(5, "Miami"));
+        let resolved = cache.resolve(&client, 5).await.unwrap();
+        assert_eq!(resolved.unwrap().name, "Miami");
+    }
+
+    /// Sends an `ETag` with the body, then answers matching revalidations
+    /// with an empty `304`.
+    fn revalidating() -> crate::test_util::CannedTransport {
+        crate::test_util::CannedTransport::new(|request| {
+            let response = if request.headers().get(IF_NONE_MATCH).is_some() {
+                http::Response::builder().status(304).body(Vec::new())
+            } else {
+                http::Response::builder().header(ETAG, "\"v1\"").body(
+                    br#"{"parcl_id":7,"items":[{"date":"2024-01-01","sales":3}],"total":1,"limit":1,"offset":0,"links":{}}"#
+                        .to_vec(),
+                )
+            };
+            response.unwrap()
+        })
+    }
+
+    #[tokio::test]
+    async fn not_modified_is_served_from_the_conditional_cache() {
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(revalidating())
+            .with_conditional_cache(ConditionalCache::new());
+        for _ in 0..2 {
+            let resp = client
+                .market_metrics()
+                .housing_event_counts(7, None)
+                .await
+                .unwrap();
+            assert_eq!(resp.items[0].sales, Some(3));
+        }
+        let stats = client.conditional_stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert_eq!(stats.hit_rate(), 0.5);
+        assert_eq!(client.conditional_cache().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn full_conditional_cache_evicts_the_least_recently_used() {
+        let cache = ConditionalCache::new().max_entries(2);
+        let stored = |tag: &str| {
+            http::Response::builder()
+                .header(ETAG, tag)
+                .body(tag.as_bytes().to_vec())
+                .unwrap()
+        };
+        cache.complete("a", stored("\"a\""));
+        cache.complete("b", stored("\"b\""));
+        let not_modified = http::Response::builder().status(304).body(Vec::new());
+        let hit = cache.complete("a", not_modified.unwrap());
+        assert_eq!(hit.body(), b"\"a\"");
+
+        cache.complete("c", stored("\"c\""));
+        assert_eq!(cache.len(), 2);
+        let entries = cache.read();
+        assert!(entries.contains_key("a") && entries.contains_key("c"));
+        assert!(!entries.contains_key("b"));
+    }
+}
@@ -0,0 +1,310 @@
+//! Per-market capability discovery, so batch jobs can skip markets that have
+//! no data for a metric family.
+
+use crate::error::{ParclError, Result};
+use crate::models::{Availability, Market};
+use crate::{
+    ForSaleMetricsParams, InvestorMetricsParams, MetricsParams, NewConstructionMetricsParams,
+    ParclClient, PortfolioMetricsParams, RentalMetricsParams,
+};
+use std::sync::Arc;
+
+/// A group of related API endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetricFamily {
+    MarketMetrics,
+    InvestorMetrics,
+    ForSaleMetrics,
+    RentalMetrics,
+    NewConstructionMetrics,
+    PortfolioMetrics,
+    PriceFeed,
+}
+
+impl MetricFamily {
+    /// Every metric family, in probe order.
+    pub const ALL: [MetricFamily; 7] = [
+        Self::MarketMetrics,
+        Self::InvestorMetrics,
+        Self::ForSaleMetrics,
+        Self::RentalMetrics,
+        Self::NewConstructionMetrics,
+        Self::PortfolioMetrics,
+        Self::PriceFeed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MarketMetrics => "market_metrics",
+            Self::InvestorMetrics => "investor_metrics",
+            Self::ForSaleMetrics => "for_sale_metrics",
+            Self::RentalMetrics => "rental_metrics",
+            Self::NewConstructionMetrics => "new_construction_metrics",
+            Self::PortfolioMetrics => "portfolio_metrics",
+            Self::PriceFeed => "price_feed",
+        }
+    }
+}
+
+impl std::fmt::Display for MetricFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The outcome of probing one metric family.
+#[derive(Debug, Clone)]
+pub enum Probe {
+    /// The family returned data.
+    Available,
+    /// The family has no data for the market.
+    NoData,
+    /// The key's plan does not include the family.
+    NotInPlan,
+    /// The probe failed, e.g. on a 5xx or a rate limit, so whether the
+    /// family has data is unknown.
+    Failed(Arc<ParclError>),
+}
+
+impl Probe {
+    fn from_result(result: Result<Availability>) -> Self {
+        match result {
+            Ok(Availability::Available) => Self::Available,
+            Ok(Availability::NoData) => Self::NoData,
+            Err(ParclError::NotInPlan { .. }) => Self::NotInPlan,
+            Err(err) => Self::Failed(Arc::new(err)),
+        }
+    }
+}
+
+/// What data the API offers for a single market.
+#[derive(Debug, Clone)]
+pub struct MarketCapabilities {
+    pub parcl_id: i64,
+    /// The market record, or `None` if search did not return it.
+    pub market: Option<Market>,
+    /// Whether the market has price feed data.
+    pub has_price_feed: bool,
+    /// Whether the market is tradeable on the Parcl exchange.
+    pub is_exchange_market: bool,
+    /// Probe results per family. Empty unless probing was requested.
+    pub probed: Vec<(MetricFamily, Probe)>,
+}
+
+impl MarketCapabilities {
+    /// Returns whether `family` has data and is in the key's plan, or
+    /// `None` if it was not probed or its probe failed.
+    ///
+    /// The price feed is known from market flags even without probing.
+    pub fn supports(&self, family: MetricFamily) -> Option<bool> {
+        match self.probed.iter().find(|(f, _)| *f == family) {
+            Some((_, Probe::Available)) => return Some(true),
+            Some((_, Probe::NoData | Probe::NotInPlan)) => return Some(false),
+            Some((_, Probe::Failed(_))) => return None,
+            None => {}
+        }
+        match family {
+            MetricFamily::PriceFeed if self.market.is_some() => Some(self.has_price_feed),
+            _ => None,
+        }
+    }
+
+    /// Families whose probe failed, with the error.
+    pub fn failed_probes(&self) -> Vec<(MetricFamily, &ParclError)> {
+        self.probed
+            .iter()
+            .filter_map(|(family, probe)| match probe {
+                Probe::Failed(err) => Some((*family, err.as_ref())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the families confirmed to have data.
+    pub fn available_families(&self) -> Vec<MetricFamily> {
+        MetricFamily::ALL
+            .into_iter()
+            .filter(|f| self.supports(*f) == Some(true))
+            .collect()
+    }
+}
+
+impl ParclClient {
+    /// Reports a market's capabilities from its metadata flags.
+    ///
+    /// Costs at most one search request (none if the market is cached).
+    pub async fn capabilities(&self, parcl_id: i64) -> Result<MarketCapabilities> {
+        let market = self.resolve_market(parcl_id).await?;
+        Ok(MarketCapabilities {
+            parcl_id,
+            has_price_feed: market.as_ref().is_some_and(Market::has_price_feed),
+            is_exchange_market: market.as_ref().is_some_and(Market::is_exchange_market),
+            market,
+            probed: Vec::new(),
+        })
+    }
+
+    /// Reports a market's capabilities, probing each metric family with a
+    /// `limit=1` request to confirm it has data.
+    ///
+    /// The price feed is only probed when the market flags advertise one. A
+    /// failed probe is recorded as [`Probe::Failed`] and the remaining
+    /// families are still probed; only the market lookup itself can fail
+    /// the call.
+    pub async fn probe_capabilities(&self, parcl_id: i64) -> Result<MarketCapabilities> {
+        let mut caps = self.capabilities(parcl_id).await?;
+
+        for family in MetricFamily::ALL {
+            let result = match family {
+                MetricFamily::MarketMetrics => self
+                    .market_metrics()
+                    .housing_event_counts(parcl_id, Some(MetricsParams::new().limit(1)))
+                    .await
+                    .map(|r| r.availability()),
+                MetricFamily::InvestorMetrics => self
+                    .investor_metrics()
+                    .housing_stock_ownership(parcl_id, Some(InvestorMetricsParams::new().limit(1)))
+                    .await
+                    .map(|r| r.availability()),
+                MetricFamily::ForSaleMetrics => self
+                    .for_sale_metrics()
+                    .for_sale_inventory(parcl_id, Some(ForSaleMetricsParams::new().limit(1)))
+                    .await
+                    .map(|r| r.availability()),
+                MetricFamily::RentalMetrics => self
+                    .rental_metrics()
+                    .gross_yield(parcl_id, Some(RentalMetricsParams::new().limit(1)))
+                    .await
+                    .map(|r| r.availability()),
+                MetricFamily::NewConstructionMetrics => self
+                    .new_construction_metrics()
+                    .housing_event_counts(
+                        parcl_id,
+                        Some(NewConstructionMetricsParams::new().limit(1)),
+                    )
+                    .await
+                    .map(|r| r.availability()),
+                MetricFamily::PortfolioMetrics => self
+                    .portfolio_metrics()
+                    .sf_housing_stock_ownership(
+                        parcl_id,
+                        Some(PortfolioMetricsParams::new().limit(1)),
+                    )
+                    .await
+                    .map(|r| r.availability()),
+                MetricFamily::PriceFeed if caps.has_price_feed => self
+                    .price_feed()
+                    .history(parcl_id, Some(MetricsParams::new().limit(1)))
+                    .await
+                    .map(|r| r.availability()),
+                MetricFamily::PriceFeed => Ok(Availability::NoData),
+            };
+            caps.probed.push((family, Probe::from_result(result)));
+        }
+
+        Ok(caps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{json_response, CannedTransport};
+
+    fn market(pricefeed: i32, exchange: i32) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "parcl_id": 7,
+            "name": "Test",
+            "location_type": "CITY",
+            "pricefeed_market": pricefeed,
+            "parcl_exchange_market": exchange
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn metric_family_as_str() {
+        assert_eq!(MetricFamily::RentalMetrics.as_str(), "rental_metrics");
+        assert_eq!(format!("{}", MetricFamily::PriceFeed), "price_feed");
+    }
+
+    #[tokio::test]
+    async fn capabilities_from_cached_flags() {
+        let client = ParclClient::with_config("test", "http://127.0.0.1:9");
+        client.market_cache().insert(market(1, 0));
+        let caps = client.capabilities(7).await.unwrap();
+        assert!(caps.has_price_feed);
+        assert!(!caps.is_exchange_market);
+        assert_eq!(caps.supports(MetricFamily::PriceFeed), Some(true));
+        assert_eq!(caps.supports(MetricFamily::RentalMetrics), None);
+        assert_eq!(caps.available_families(), vec![MetricFamily::PriceFeed]);
+    }
+
+    #[test]
+    fn capabilities_probed_results_take_precedence() {
+        let caps = MarketCapabilities {
+            parcl_id: 7,
+            market: Some(market(1, 1)),
+            has_price_feed: true,
+            is_exchange_market: true,
+            probed: vec![
+                (MetricFamily::MarketMetrics, Probe::Available),
+                (MetricFamily::PriceFeed, Probe::NoData),
+            ],
+        };
+        assert_eq!(caps.supports(MetricFamily::MarketMetrics), Some(true));
+        assert_eq!(caps.supports(MetricFamily::PriceFeed), Some(false));
+        assert_eq!(caps.available_families(), vec![MetricFamily::MarketMetrics]);
+    }
+
+    /// Serves data for market metrics, a 404 for investor metrics, a plan
+    /// 403 for for-sale metrics, a 500 for rental metrics and empty pages
+    /// for the rest.
+    fn mixed_families() -> CannedTransport {
+        CannedTransport::new(|request| {
+            let path = request.uri().path();
+            let items = if path.starts_with("/v1/market_metrics/") {
+                serde_json::json!([{"date": "2024-01-01", "sales": 3}])
+            } else if path.starts_with("/v1/investor_metrics/") {
+                return json_response(404, &serde_json::json!({"detail": "Not found"}));
+            } else if path.starts_with("/v1/for_sale_market_metrics/") {
+                let detail = serde_json::json!({"detail": "Not available on your plan"});
+                return json_response(403, &detail);
+            } else if path.starts_with("/v1/rental_market_metrics/") {
+                return json_response(500, &serde_json::json!({"detail": "Internal error"}));
+            } else {
+                serde_json::json!([])
+            };
+            json_response(
+                200,
+                &serde_json::json!({
+                    "parcl_id": 7, "items": items, "total": 1, "limit": 1, "offset": 0, "links": {}
+                }),
+            )
+        })
+    }
+
+    #[tokio::test]
+    async fn failed_probes_do_not_stop_the_others() {
+        let transport = mixed_families();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(transport.clone());
+        client.market_cache().insert(market(0, 0));
+
+        let caps = client.probe_capabilities(7).await.unwrap();
+        assert_eq!(caps.supports(MetricFamily::MarketMetrics), Some(true));
+        assert_eq!(caps.supports(MetricFamily::InvestorMetrics), Some(false));
+        assert!(matches!(caps.probed[1].1, Probe::NoData));
+        assert_eq!(caps.supports(MetricFamily::ForSaleMetrics), Some(false));
+        assert!(matches!(caps.probed[2].1, Probe::NotInPlan));
+        assert_eq!(caps.supports(MetricFamily::RentalMetrics), None);
+        let failed = caps.failed_probes();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, MetricFamily::RentalMetrics);
+        assert_eq!(failed[0].1.status(), Some(500));
+        assert_eq!(caps.supports(MetricFamily::PortfolioMetrics), Some(false));
+        assert_eq!(caps.available_families(), vec![MetricFamily::MarketMetrics]);
+        // Six families probed; the price feed is skipped by its flag.
+        assert_eq!(transport.request_count(), 6);
+    }
+}
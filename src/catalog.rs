@@ -0,0 +1,222 @@
+//! Timestamped snapshots of market search metadata.
+//!
+//! Parcl revises market metadata (populations, incomes, price feed coverage)
+//! and adds new markets over time. Storing a [`MarketCatalog`] per pull and
+//! diffing two of them shows what changed in between.
+//!
+//! ```no_run
+//! use parcllabs::{LocationType, MarketCatalog, ParclClient, SearchParams};
+//!
+//! # async fn example(previous: MarketCatalog) -> parcllabs::Result<()> {
+//! let client = ParclClient::new()?;
+//! let params = SearchParams::new()
+//!     .location_type(LocationType::Cbsa)
+//!     .auto_paginate(true);
+//! let current = MarketCatalog::new(client.search().markets(params).await?.items);
+//!
+//! let diff = previous.diff(&current);
+//! for market in &diff.added {
+//!     println!("new market: {} ({})", market.name, market.parcl_id);
+//! }
+//! for change in &diff.changed {
+//!     for field in &change.fields {
+//!         println!("{} {}: {} -> {}", change.name, field.field, field.before, field.after);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::models::Market;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Market rows keyed by `parcl_id`, with the time they were pulled.
+///
+/// Serializable, so snapshots can be written to disk between runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketCatalog {
+    pub taken_at: DateTime<Utc>,
+    markets: BTreeMap<i64, Market>,
+}
+
+impl MarketCatalog {
+    /// Snapshots `markets` as of now. A later duplicate `parcl_id` wins.
+    pub fn new(markets: impl IntoIterator<Item = Market>) -> Self {
+        Self::at(Utc::now(), markets)
+    }
+
+    /// Snapshots `markets` as of `taken_at`.
+    pub fn at(taken_at: DateTime<Utc>, markets: impl IntoIterator<Item = Market>) -> Self {
+        Self {
+            taken_at,
+            markets: markets.into_iter().map(|m| (m.parcl_id, m)).collect(),
+        }
+    }
+
+    pub fn get(&self, parcl_id: i64) -> Option<&Market> {
+        self.markets.get(&parcl_id)
+    }
+
+    /// Markets in `parcl_id` order.
+    pub fn markets(&self) -> impl Iterator<Item = &Market> {
+        self.markets.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.markets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.markets.is_empty()
+    }
+
+    /// What changed between this snapshot and a `later` one.
+    pub fn diff(&self, later: &MarketCatalog) -> MarketCatalogDiff {
+        let added = later
+            .markets()
+            .filter(|m| !self.markets.contains_key(&m.parcl_id))
+            .cloned()
+            .collect();
+        let removed = self
+            .markets()
+            .filter(|m| !later.markets.contains_key(&m.parcl_id))
+            .cloned()
+            .collect();
+        let changed = self
+            .markets()
+            .filter_map(|before| {
+                let after = later.get(before.parcl_id)?;
+                let fields = field_changes(before, after);
+                (!fields.is_empty()).then(|| MarketChange {
+                    parcl_id: after.parcl_id,
+                    name: after.name.clone(),
+                    fields,
+                })
+            })
+            .collect();
+        MarketCatalogDiff {
+            from: self.taken_at,
+            to: later.taken_at,
+            added,
+            removed,
+            changed,
+        }
+    }
+}
+
+/// Differences between two [`MarketCatalog`] snapshots.
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketCatalogDiff {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Markets only in the later snapshot.
+    pub added: Vec<Market>,
+    /// Markets only in the earlier snapshot.
+    pub removed: Vec<Market>,
+    /// Markets in both whose metadata was revised.
+    pub changed: Vec<MarketChange>,
+}
+
+impl MarketCatalogDiff {
+    /// Returns true if nothing was added, removed or revised.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Revised metadata for one market.
+#[derive(Debug, Clone, Serialize)]
+pub struct MarketChange {
+    pub parcl_id: i64,
+    /// The market name in the later snapshot.
+    pub name: String,
+    pub fields: Vec<FieldChange>,
+}
+
+impl MarketChange {
+    /// The change to `field`, e.g. `"total_population"`, if it was revised.
+    pub fn field(&self, field: &str) -> Option<&FieldChange> {
+        self.fields.iter().find(|f| f.field == field)
+    }
+}
+
+/// One revised `Market` field, as JSON values.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// Compares markets field by field through their serialized form, so
+/// fields added to `Market` later are diffed without changes here.
+fn field_changes(before: &Market, after: &Market) -> Vec<FieldChange> {
+    let (Ok(Value::Object(before)), Ok(Value::Object(mut after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+    before
+        .into_iter()
+        .filter_map(|(field, before)| {
+            let after = after.remove(&field).unwrap_or(Value::Null);
+            (before != after).then_some(FieldChange {
+                field,
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(parcl_id: i64, name: &str, population: i64) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "parcl_id": parcl_id,
+            "name": name,
+            "location_type": "CBSA",
+            "total_population": population,
+            "median_income": 70000,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_revised_markets() {
+        let jan = MarketCatalog::new([market(1, "Austin", 100), market(2, "Denver", 200)]);
+        let feb = MarketCatalog::new([market(1, "Austin", 110), market(3, "Tampa", 300)]);
+
+        let diff = jan.diff(&feb);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].parcl_id, 3);
+        assert_eq!(diff.removed[0].name, "Denver");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.changed[0].field("total_population"),
+            Some(&FieldChange {
+                field: "total_population".into(),
+                before: 100.into(),
+                after: 110.into(),
+            })
+        );
+        assert_eq!(diff.changed[0].fields.len(), 1);
+
+        assert!(feb.diff(&feb).is_empty());
+    }
+
+    #[test]
+    fn snapshots_round_trip_through_json() {
+        let catalog = MarketCatalog::new([market(1, "Austin", 100)]);
+        let json = serde_json::to_string(&catalog).unwrap();
+        let restored: MarketCatalog = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.taken_at, catalog.taken_at);
+        assert!(catalog.diff(&restored).is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+//! PNG line charts of price feeds and other dated series.
+//!
+//! ```no_run
+//! use parcllabs::analysis::PriceSeries;
+//! use parcllabs::{charts, ParclClient};
+//!
+//! # async fn example() -> parcllabs::Result<()> {
+//! let client = ParclClient::new()?;
+//! let austin = client.price_feed().history(2900078, None).await?;
+//! let denver = client.price_feed().history(2900187, None).await?;
+//! let austin = PriceSeries::from_response(&austin);
+//! let denver = PriceSeries::from_response(&denver);
+//!
+//! charts::line(&austin, "austin.png")?;
+//! charts::compare(&[("Austin", &austin), ("Denver", &denver)], "compare.png")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Other metrics can be charted by building a series with
+//! [`PriceSeries::from_points`].
+
+use crate::analysis::PriceSeries;
+use crate::error::{ParclError, Result};
+use chrono::NaiveDate;
+use plotters::prelude::*;
+use std::path::Path;
+
+/// Width and height of rendered charts, in pixels.
+const SIZE: (u32, u32) = (1024, 640);
+
+/// Draws `series` as a single line. The image format follows the file
+/// extension of `path`, e.g. `.png`.
+pub fn line(series: &PriceSeries, path: impl AsRef<Path>) -> Result<()> {
+    draw(&[("", series)], path.as_ref(), false)
+}
+
+/// Draws one labeled line per series on shared axes, with a legend.
+pub fn compare(series: &[(&str, &PriceSeries)], path: impl AsRef<Path>) -> Result<()> {
+    draw(series, path.as_ref(), true)
+}
+
+fn draw(series: &[(&str, &PriceSeries)], path: &Path, legend: bool) -> Result<()> {
+    let points = || series.iter().flat_map(|(_, s)| s.points());
+    let (Some(first), Some(last)) = (
+        points().map(|p| p.date).min(),
+        points().map(|p| p.date).max(),
+    ) else {
+        return Err(ParclError::Validation(
+            "cannot chart a series with no points".into(),
+        ));
+    };
+    let (low, high) = points().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+        (lo.min(p.price), hi.max(p.price))
+    });
+    let pad = match (high - low) * 0.05 {
+        pad if pad > 0.0 => pad,
+        _ => low.abs().max(1.0) * 0.05,
+    };
+    // A one-day range keeps a single-point chart drawable.
+    let last = last.max(first.succ_opt().unwrap_or(first));
+
+    let root = BitMapBackend::new(path, SIZE).into_drawing_area();
+    root.fill(&WHITE).map_err(chart_error)?;
+    let mut chart = ChartBuilder::on(&root)
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(80)
+        .build_cartesian_2d(first..last, (low - pad)..(high + pad))
+        .map_err(chart_error)?;
+    chart
+        .configure_mesh()
+        .x_labels(8)
+        .x_label_formatter(&|date: &NaiveDate| date.format("%Y-%m-%d").to_string())
+        .y_label_formatter(&|value: &f64| format!("{value:.0}"))
+        .draw()
+        .map_err(chart_error)?;
+
+    for (i, (name, s)) in series.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        let drawn = chart
+            .draw_series(LineSeries::new(
+                s.points().iter().map(|p| (p.date, p.price)),
+                color.stroke_width(2),
+            ))
+            .map_err(chart_error)?;
+        if legend {
+            drawn.label(*name).legend(move |(x, y)| {
+                PathElement::new([(x, y), (x + 20, y)], color.stroke_width(2))
+            });
+        }
+    }
+    if legend {
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()
+            .map_err(chart_error)?;
+    }
+    root.present().map_err(chart_error)
+}
+
+fn chart_error<E: std::error::Error + Send + Sync + 'static>(err: E) -> ParclError {
+    ParclError::Chart(Box::new(err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(start: f64) -> PriceSeries {
+        let first = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        PriceSeries::from_points(
+            first
+                .iter_days()
+                .take(30)
+                .enumerate()
+                .map(|(i, date)| (date, start + i as f64)),
+        )
+    }
+
+    #[test]
+    fn writes_png_files() {
+        let dir = std::env::temp_dir();
+        let single = dir.join(format!("parcllabs-chart-line-{}.png", std::process::id()));
+        let both = dir.join(format!(
+            "parcllabs-chart-compare-{}.png",
+            std::process::id()
+        ));
+
+        line(&series(100.0), &single).unwrap();
+        compare(&[("a", &series(100.0)), ("b", &series(140.0))], &both).unwrap();
+        for path in [single, both] {
+            let bytes = std::fs::read(&path).unwrap();
+            assert!(bytes.starts_with(b"\x89PNG"));
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn empty_series_is_rejected() {
+        let err = line(&PriceSeries::default(), "unused.png").unwrap_err();
+        assert!(matches!(err, ParclError::Validation(_)));
+    }
+}
@@ -0,0 +1,196 @@
+//! Redacted, truncated dumps of responses for bug reports.
+//!
+//! Response types have a `debug_dump(max_items)` method that pretty-prints
+//! the payload with long arrays cut to `max_items`, account details and
+//! credential-like fields masked, and credentials stripped from URLs.
+//!
+//! Setting `PARCL_LABS_DUMP_FAILED` makes the client write the raw body of
+//! any response that fails to deserialize to a file, and log its path to
+//! stderr. Use `1` for the system temp directory or a directory path.
+
+use crate::models::{
+    BatchMetricsResponse, MetricsResponse, PaginatedResponse, PropertyEventHistoryResponse,
+    PropertySearchResponse, PropertyV2SearchResponse,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Environment variable enabling dumps of undecodable payloads.
+pub const DUMP_FAILED_ENV: &str = "PARCL_LABS_DUMP_FAILED";
+
+const REDACTED: &str = "[redacted]";
+
+/// Field names whose values are always masked.
+const SENSITIVE_KEYS: &[&str] = &["account", "api_key", "apikey", "authorization", "token"];
+
+/// Pretty-prints `value` with arrays truncated to `max_items` and sensitive
+/// values redacted.
+pub fn dump(value: &impl Serialize, max_items: usize) -> String {
+    let mut json = serde_json::to_value(value).unwrap_or(Value::Null);
+    scrub(&mut json, max_items);
+    serde_json::to_string_pretty(&json).unwrap_or_default()
+}
+
+fn scrub(value: &mut Value, max_items: usize) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SENSITIVE_KEYS.iter().any(|s| key.contains(s)) && !field.is_null() {
+                    *field = Value::String(REDACTED.into());
+                } else {
+                    scrub(field, max_items);
+                }
+            }
+        }
+        Value::Array(items) => {
+            let hidden = items.len().saturating_sub(max_items);
+            items.truncate(max_items);
+            for item in items.iter_mut() {
+                scrub(item, max_items);
+            }
+            if hidden > 0 {
+                items.push(Value::String(format!("... {hidden} more items")));
+            }
+        }
+        Value::String(s) => {
+            if let Some(clean) = redact_url_credentials(s) {
+                *s = clean;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Masks credential query parameters such as `api_key=...` in a URL.
+fn redact_url_credentials(s: &str) -> Option<String> {
+    let (base, query) = s.split_once('?')?;
+    let mut changed = false;
+    let params: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _))
+                if SENSITIVE_KEYS
+                    .iter()
+                    .any(|k| name.to_ascii_lowercase().contains(k)) =>
+            {
+                changed = true;
+                format!("{name}={REDACTED}")
+            }
+            _ => pair.to_string(),
+        })
+        .collect();
+    changed.then(|| format!("{base}?{}", params.join("&")))
+}
+
+/// Writes an undecodable payload to disk if `PARCL_LABS_DUMP_FAILED` is set.
+pub(crate) fn dump_failed_payload(endpoint: &str, body: &[u8]) {
+    if let Some(dir) = dump_dir(std::env::var(DUMP_FAILED_ENV).ok()) {
+        write_payload(&dir, endpoint, body);
+    }
+}
+
+fn write_payload(dir: &std::path::Path, endpoint: &str, body: &[u8]) {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let name: String = endpoint
+        .trim_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let path = dir.join(format!("parcllabs-{name}-{stamp}.json"));
+    match std::fs::write(&path, body) {
+        Ok(()) => eprintln!("parcllabs: wrote undecodable payload to {}", path.display()),
+        Err(e) => eprintln!("parcllabs: could not write {}: {e}", path.display()),
+    }
+}
+
+fn dump_dir(setting: Option<String>) -> Option<PathBuf> {
+    match setting?.trim() {
+        "" | "0" | "false" => None,
+        "1" | "true" => Some(std::env::temp_dir()),
+        dir => Some(PathBuf::from(dir)),
+    }
+}
+
+macro_rules! impl_debug_dump {
+    ($($ty:ident$(<$t:ident>)?),* $(,)?) => {
+        $(
+            impl$(<$t: Serialize>)? $ty$(<$t>)? {
+                /// Pretty JSON for bug reports: arrays cut to `max_items`,
+                /// account details and credentials redacted.
+                pub fn debug_dump(&self, max_items: usize) -> String {
+                    dump(self, max_items)
+                }
+            }
+        )*
+    };
+}
+
+impl_debug_dump!(
+    PaginatedResponse<T>,
+    MetricsResponse<T>,
+    BatchMetricsResponse<T>,
+    PropertySearchResponse,
+    PropertyEventHistoryResponse,
+    PropertyV2SearchResponse,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AccountInfo;
+
+    #[test]
+    fn debug_dump_truncates_and_redacts() {
+        let mut resp = MetricsResponse::<i64>::empty(7);
+        resp.items = vec![1, 2, 3, 4];
+        resp.links.next = Some("https://api.example.com/v1/x?offset=2&api_key=secret".into());
+        resp.account = Some(AccountInfo {
+            est_credits_used: Some(4),
+            est_remaining_credits: Some(996),
+        });
+
+        let dump = resp.debug_dump(2);
+        let json: Value = serde_json::from_str(&dump).unwrap();
+        assert_eq!(json["items"], serde_json::json!([1, 2, "... 2 more items"]));
+        assert_eq!(json["account"], REDACTED);
+        assert_eq!(
+            json["links"]["next"],
+            "https://api.example.com/v1/x?offset=2&api_key=[redacted]"
+        );
+        assert!(!dump.contains("secret"));
+        assert!(!dump.contains("996"));
+    }
+
+    #[test]
+    fn dump_dir_reads_setting() {
+        assert_eq!(dump_dir(None), None);
+        assert_eq!(dump_dir(Some("0".into())), None);
+        assert_eq!(dump_dir(Some("1".into())), Some(std::env::temp_dir()));
+        assert_eq!(
+            dump_dir(Some("/tmp/dumps".into())),
+            Some(PathBuf::from("/tmp/dumps"))
+        );
+    }
+
+    #[test]
+    fn write_payload_names_file_after_endpoint() {
+        let dir = std::env::temp_dir().join(format!("parcllabs-dump-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write_payload(&dir, "/v1/test/endpoint", b"{\"items\": [oops]}");
+
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().flatten().collect();
+        assert_eq!(files.len(), 1);
+        let name = files[0].file_name().into_string().unwrap();
+        assert!(name.starts_with("parcllabs-v1_test_endpoint-"));
+        assert_eq!(
+            std::fs::read(files[0].path()).unwrap(),
+            b"{\"items\": [oops]}"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,381 @@
+//! Terminal formatting helpers for dashboards and command-line tools.
+//!
+//! Enabled with the `display` feature. Everything here returns plain
+//! `String`s, so output can be printed, logged or embedded in tables.
+
+use crate::models::Market;
+use crate::pipeline::MarketSnapshot;
+use std::fmt;
+
+#[cfg(feature = "locale")]
+pub use num_format::Locale;
+#[cfg(feature = "locale")]
+use num_format::ToFormattedString;
+
+/// Formats a count compactly: `950`, `12.3K`, `4.5M`.
+pub fn format_number(n: i64) -> String {
+    let abs = n.unsigned_abs() as f64;
+    let sign = if n < 0 { "-" } else { "" };
+    if abs >= 1_000_000.0 {
+        format!("{sign}{:.1}M", abs / 1_000_000.0)
+    } else if abs >= 1_000.0 {
+        format!("{sign}{:.1}K", abs / 1_000.0)
+    } else {
+        n.to_string()
+    }
+}
+
+/// Formats a dollar amount compactly: `$850`, `$425K`, `$1.2M`.
+pub fn format_currency(amount: f64) -> String {
+    let sign = if amount < 0.0 { "-" } else { "" };
+    let abs = amount.abs();
+    if abs >= 1_000_000.0 {
+        format!("{sign}${:.1}M", abs / 1_000_000.0)
+    } else if abs >= 1_000.0 {
+        format!("{sign}${:.0}K", abs / 1_000.0)
+    } else {
+        format!("{sign}${abs:.0}")
+    }
+}
+
+/// Formats a count in full with `locale`'s digit grouping and minus sign:
+/// `1,234,567` in `en`, `1.234.567` in `de`, `12,34,567` in `en_IN`.
+#[cfg(feature = "locale")]
+pub fn format_number_in(n: i64, locale: &Locale) -> String {
+    n.to_formatted_string(locale)
+}
+
+/// Currency formatting for a locale: digit grouping and decimal mark from
+/// the [`Locale`], plus a currency symbol and where it goes.
+///
+/// Locales do not carry a currency, so the symbol and its placement are
+/// set here: `$1,250` is [`new`](Self::new)`(Locale::en, "$")`, `1.250 €`
+/// adds [`symbol_after`](Self::symbol_after).
+#[cfg(feature = "locale")]
+#[derive(Debug, Clone)]
+pub struct CurrencyFormat {
+    locale: Locale,
+    symbol: String,
+    symbol_after: bool,
+    decimals: usize,
+}
+
+#[cfg(feature = "locale")]
+impl CurrencyFormat {
+    /// Whole units with the symbol in front.
+    pub fn new(locale: Locale, symbol: impl Into<String>) -> Self {
+        Self {
+            locale,
+            symbol: symbol.into(),
+            symbol_after: false,
+            decimals: 0,
+        }
+    }
+
+    /// Writes the symbol after the amount, separated by a no-break space.
+    pub fn symbol_after(mut self) -> Self {
+        self.symbol_after = true;
+        self
+    }
+
+    /// Digits after the decimal mark, e.g. 2 for prices per square foot.
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Formats `amount` rounded to the configured decimals.
+    pub fn format(&self, amount: f64) -> String {
+        let scale = 10u64.pow(self.decimals as u32);
+        let scaled = (amount.abs() * scale as f64).round() as u64;
+        let mut number = (scaled / scale).to_formatted_string(&self.locale);
+        if self.decimals > 0 {
+            let fraction = scaled % scale;
+            number.push_str(self.locale.decimal());
+            number.push_str(&format!("{fraction:0width$}", width = self.decimals));
+        }
+        let sign = if amount < 0.0 && scaled > 0 {
+            self.locale.minus_sign()
+        } else {
+            ""
+        };
+        if self.symbol_after {
+            format!("{sign}{number}\u{a0}{}", self.symbol)
+        } else {
+            format!("{sign}{}{number}", self.symbol)
+        }
+    }
+}
+
+/// Renders `value` as a horizontal bar of `█`, `scale` cells per unit and
+/// at most `max_width` cells. Negative and non-finite values render empty.
+pub fn bar(value: f64, scale: f64, max_width: usize) -> String {
+    let cells = value * scale;
+    let len = if cells.is_finite() && cells > 0.0 {
+        (cells as usize).min(max_width)
+    } else {
+        0
+    };
+    "█".repeat(len)
+}
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Renders `values` as a one-line sparkline, e.g. `▁▃▅█▆`.
+///
+/// Values are scaled between the series minimum and maximum; non-finite
+/// values render as spaces. A flat series renders at mid height.
+pub fn sparkline(values: &[f64]) -> String {
+    let finite = values.iter().copied().filter(|v| v.is_finite());
+    let min = finite.clone().fold(f64::INFINITY, f64::min);
+    let max = finite.fold(f64::NEG_INFINITY, f64::max);
+    let top = SPARK_LEVELS.len() - 1;
+    values
+        .iter()
+        .map(|&v| {
+            if !v.is_finite() {
+                ' '
+            } else if max > min {
+                SPARK_LEVELS[(((v - min) / (max - min)) * top as f64).round() as usize]
+            } else {
+                SPARK_LEVELS[top / 2]
+            }
+        })
+        .collect()
+}
+
+/// Renders labelled values as a horizontal bar chart, one line per entry.
+///
+/// The largest value spans `width` cells and the rest scale against it.
+/// Labels are left-aligned to the longest one and each line ends with the
+/// value.
+///
+/// ```
+/// use parcllabs::display::bar_chart;
+///
+/// let chart = bar_chart(&[("Austin", 4.0), ("Denver", 2.0)], 8);
+/// assert_eq!(chart, "Austin ████████ 4\nDenver ████     2");
+/// ```
+pub fn bar_chart<L: AsRef<str>>(series: &[(L, f64)], width: usize) -> String {
+    let label_width = series
+        .iter()
+        .map(|(label, _)| label.as_ref().chars().count())
+        .max()
+        .unwrap_or(0);
+    let max = series
+        .iter()
+        .map(|&(_, v)| v)
+        .filter(|v| v.is_finite())
+        .fold(0.0, f64::max);
+    let scale = if max > 0.0 { width as f64 / max } else { 0.0 };
+    series
+        .iter()
+        .map(|(label, value)| {
+            format!(
+                "{:<label_width$} {:<width$} {value}",
+                label.as_ref(),
+                bar(*value, scale, width),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Direction of change between two consecutive values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Up,
+    Down,
+    Flat,
+}
+
+impl Trend {
+    /// Compares `current` with `previous`, treating moves within
+    /// `tolerance` as flat.
+    pub fn between(previous: f64, current: f64, tolerance: f64) -> Self {
+        if current > previous + tolerance {
+            Self::Up
+        } else if current < previous - tolerance {
+            Self::Down
+        } else {
+            Self::Flat
+        }
+    }
+
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            Self::Up => "↑",
+            Self::Down => "↓",
+            Self::Flat => "→",
+        }
+    }
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.arrow())
+    }
+}
+
+/// `Austin, TX (CITY, parcl_id 2900078)`
+impl fmt::Display for Market {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(state) = &self.state_abbreviation {
+            write!(f, ", {state}")?;
+        }
+        write!(f, " ({}, parcl_id {})", self.location_type, self.parcl_id)
+    }
+}
+
+/// A short multi-line summary of the latest data point of each metric.
+impl fmt::Display for MarketSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let missing = || "n/a".to_string();
+        writeln!(f, "{}", self.market)?;
+
+        match self.event_counts.items.first() {
+            Some(c) => writeln!(
+                f,
+                "  {}  sales {}  new listings {}  new rentals {}",
+                c.date,
+                c.sales.map(format_number).unwrap_or_else(missing),
+                c.new_listings_for_sale
+                    .map(format_number)
+                    .unwrap_or_else(missing),
+                c.new_rental_listings
+                    .map(format_number)
+                    .unwrap_or_else(missing),
+            )?,
+            None => writeln!(f, "  event counts: no data")?,
+        }
+
+        match self.event_prices.items.first() {
+            Some(p) => {
+                let median_sale = p
+                    .price
+                    .as_ref()
+                    .and_then(|s| s.median.as_ref())
+                    .and_then(|m| m.sales);
+                writeln!(
+                    f,
+                    "  {}  median sale price {}",
+                    p.date,
+                    median_sale.map(format_currency).unwrap_or_else(missing),
+                )?
+            }
+            None => writeln!(f, "  event prices: no data")?,
+        }
+
+        match self.housing_stock.items.first() {
+            Some(s) => write!(
+                f,
+                "  {}  housing stock {}",
+                s.date,
+                s.all_properties.map(format_number).unwrap_or_else(missing),
+            ),
+            None => write!(f, "  housing stock: no data"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MetricsResponse;
+
+    #[test]
+    fn format_number_compacts() {
+        assert_eq!(format_number(950), "950");
+        assert_eq!(format_number(12_345), "12.3K");
+        assert_eq!(format_number(4_500_000), "4.5M");
+        assert_eq!(format_number(-2_000), "-2.0K");
+    }
+
+    #[test]
+    fn format_currency_compacts() {
+        assert_eq!(format_currency(850.0), "$850");
+        assert_eq!(format_currency(425_000.0), "$425K");
+        assert_eq!(format_currency(1_250_000.0), "$1.2M");
+        assert_eq!(format_currency(-12_000.0), "-$12K");
+    }
+
+    #[cfg(feature = "locale")]
+    #[test]
+    fn locale_formats_group_digits_and_place_symbols() {
+        assert_eq!(format_number_in(1_234_567, &Locale::en), "1,234,567");
+        assert_eq!(format_number_in(-1_234_567, &Locale::de), "-1.234.567");
+        assert_eq!(format_number_in(1_234_567, &Locale::en_IN), "12,34,567");
+
+        let usd = CurrencyFormat::new(Locale::en, "$");
+        assert_eq!(usd.format(425_000.4), "$425,000");
+        assert_eq!(usd.format(-12_000.0), "-$12,000");
+        assert_eq!(usd.format(-0.2), "$0");
+        let eur = CurrencyFormat::new(Locale::de, "€")
+            .symbol_after()
+            .decimals(2);
+        assert_eq!(eur.format(1_250.5), "1.250,50\u{a0}€");
+        assert_eq!(eur.format(0.05), "0,05\u{a0}€");
+    }
+
+    #[test]
+    fn bar_scales_and_caps() {
+        assert_eq!(bar(2.5, 2.0, 10), "█████");
+        assert_eq!(bar(100.0, 2.0, 3), "███");
+        assert_eq!(bar(-1.0, 2.0, 10), "");
+        assert_eq!(bar(f64::NAN, 2.0, 10), "");
+    }
+
+    #[test]
+    fn sparkline_scales_between_min_and_max() {
+        assert_eq!(sparkline(&[1.0, 2.0, 3.0, 8.0]), "▁▂▃█");
+        assert_eq!(sparkline(&[5.0, 5.0]), "▄▄");
+        assert_eq!(sparkline(&[0.0, f64::NAN, 1.0]), "▁ █");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn bar_chart_scales_to_largest_value() {
+        let chart = bar_chart(&[("a", 1.0), ("long", 4.0), ("neg", -1.0)], 4);
+        assert_eq!(chart, "a    █    1\nlong ████ 4\nneg       -1");
+        assert_eq!(bar_chart::<&str>(&[], 10), "");
+    }
+
+    #[test]
+    fn trend_between_uses_tolerance() {
+        assert_eq!(Trend::between(1.0, 1.05, 0.1), Trend::Flat);
+        assert_eq!(Trend::between(1.0, 1.2, 0.1), Trend::Up);
+        assert_eq!(Trend::between(1.0, 0.8, 0.1).to_string(), "↓");
+    }
+
+    #[test]
+    fn snapshot_display_summarizes_latest_points() {
+        let market: Market = serde_json::from_value(serde_json::json!({
+            "parcl_id": 7,
+            "name": "Austin",
+            "state_abbreviation": "TX",
+            "location_type": "CITY"
+        }))
+        .unwrap();
+        let mut event_counts = MetricsResponse::empty(7);
+        event_counts.items = vec![serde_json::from_value(serde_json::json!({
+            "date": "2024-01-01", "sales": 1500, "new_listings_for_sale": null,
+            "new_rental_listings": 900
+        }))
+        .unwrap()];
+        let snapshot = MarketSnapshot {
+            market,
+            event_counts,
+            event_prices: MetricsResponse::empty(7),
+            housing_stock: MetricsResponse::empty(7),
+        };
+        let text = snapshot.to_string();
+        assert_eq!(
+            text,
+            "Austin, TX (CITY, parcl_id 7)\n\
+             \x20 2024-01-01  sales 1.5K  new listings n/a  new rentals 900\n\
+             \x20 event prices: no data\n\
+             \x20 housing stock: no data"
+        );
+    }
+}
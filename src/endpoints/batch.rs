@@ -0,0 +1,84 @@
+//! Typed JSON bodies for the batch POST metric endpoints.
+
+use crate::models::{PortfolioSize, PropertyType};
+use serde::{Serialize, Serializer};
+use std::fmt::Display;
+
+/// Body of a batch POST request: the market IDs plus endpoint filters.
+///
+/// Unset filters are left out of the body rather than sent as `null`.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct BatchRequest<'a, P> {
+    pub parcl_id: &'a [i64],
+    #[serde(flatten)]
+    pub filters: P,
+}
+
+/// Filters accepted by the market, investor, for-sale, rental and new
+/// construction batch endpoints.
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct MetricFilters<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "as_api_str")]
+    pub property_type: Option<PropertyType>,
+}
+
+/// Filters accepted by the portfolio batch endpoints.
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct PortfolioFilters<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "as_api_str")]
+    pub portfolio_size: Option<PortfolioSize>,
+}
+
+/// Writes a filter enum as its API string, e.g. `SINGLE_FAMILY`.
+fn as_api_str<S: Serializer, T: Display>(value: &Option<T>, s: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => s.collect_str(value),
+        None => s.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn omits_unset_filters() {
+        let body = BatchRequest {
+            parcl_id: &[1, 2],
+            filters: MetricFilters {
+                limit: Some(10),
+                property_type: Some(PropertyType::SingleFamily),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({"parcl_id": [1, 2], "limit": 10, "property_type": "SINGLE_FAMILY"})
+        );
+
+        let body = BatchRequest {
+            parcl_id: &[3],
+            filters: PortfolioFilters::default(),
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({"parcl_id": [3]})
+        );
+    }
+}
@@ -1,181 +1,474 @@
 //! Shared fetch helpers with retry logic for GET and batch POST endpoints.
 
+use crate::audit::AuditEntry;
+use crate::endpoints::price_feed;
 use crate::error::{ParclError, Result};
-use crate::models::{BatchMetricsResponse, MetricsResponse};
-use crate::RetryConfig;
-use reqwest::Client;
+use crate::limits;
+use crate::models::{
+    AccountInfo, BatchMetricsResponse, ContentHash, MetricsResponse, RawPayload, ResponseMeta,
+};
+use crate::plan;
+use crate::protocol::{self, HttpResponse, RequestContext, StreamingResponse};
+use crate::quota::QuotaSignal;
+use crate::runtime;
+use crate::{CancellationToken, ParclClient, RetryConfig};
 use serde::de::DeserializeOwned;
-use std::time::Duration;
+use serde::Serialize;
+use std::time::Instant;
 
-/// Executes a single GET request with retry on 429.
-pub(crate) async fn get_page<T: DeserializeOwned>(
-    http: &Client,
-    api_key: &str,
+/// Retry, cancellation and deadline settings carried by the metric and
+/// search params builders, usually set through their `retry_config`,
+/// `cancel_token` and `deadline` setters.
+#[derive(Debug, Clone, Default)]
+pub struct FetchOptions {
+    /// Copied from the params' `auto_paginate` when the request is made.
+    pub(crate) auto_paginate: bool,
+    /// Overrides the client's `RetryConfig` for this request.
+    pub retry: Option<RetryConfig>,
+    /// Checked between pages when auto-paginating.
+    pub cancel: Option<CancellationToken>,
+    /// Checked between pages when auto-paginating.
+    pub deadline: Option<Instant>,
+}
+
+impl FetchOptions {
+    /// Returns true, flagging `meta`, once the caller has cancelled or the
+    /// deadline has passed.
+    pub(crate) fn should_stop(&self, client: &ParclClient, meta: &mut ResponseMeta) -> bool {
+        if self
+            .cancel
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            meta.cancelled = true;
+        } else if self.deadline.is_some_and(|d| client.timer.now() >= d) {
+            meta.deadline_exceeded = true;
+        }
+        meta.is_partial()
+    }
+}
+
+/// Sends a GET (or POST, when `body` is set) request, retrying on 429 and
+/// transient network failures with exponential backoff.
+///
+/// Returns the first non-429 response, or the last 429 once retries are
+/// exhausted, together with retry telemetry. `retry` overrides the client's
+/// retry configuration. With a quota guard installed, waits while the quota
+/// breaker is open and returns quota rejections without retrying. Requests
+/// outside the key's plan fail with `NotInPlan`, before sending if known.
+pub(crate) async fn send(
+    client: &ParclClient,
     url: &str,
-    retry_config: &RetryConfig,
-) -> Result<MetricsResponse<T>> {
-    for attempt in 0..=retry_config.max_retries {
-        let response = http
-            .get(url)
-            .header("Authorization", api_key)
-            .send()
-            .await?;
-
-        let status = response.status();
-        if status.as_u16() == 429 && attempt < retry_config.max_retries {
-            let backoff = retry_config.initial_backoff_ms * 2u64.pow(attempt);
-            tokio::time::sleep(Duration::from_millis(backoff)).await;
-            continue;
+    body: Option<&serde_json::Value>,
+    retry: Option<&RetryConfig>,
+) -> Result<(HttpResponse, ResponseMeta)> {
+    match exchange(client, url, body, retry, false).await? {
+        (Received::Buffered(response), meta) => Ok((response, meta)),
+        (Received::Streaming(_), _) => unreachable!("streaming was not requested"),
+    }
+}
+
+/// A response from [`send_streaming`].
+pub(crate) enum Received {
+    /// An error response, or any response when streaming was not possible.
+    Buffered(HttpResponse),
+    /// A successful response whose body is still arriving.
+    Streaming(StreamingResponse),
+}
+
+/// Like [`send`], but hands over a successful body as it arrives.
+///
+/// Error responses are buffered, retried and reported as in [`send`]. The
+/// body is buffered anyway when the client audits requests, keeps raw
+/// payloads or hashes content, since those need all of it; `bytes_received`
+/// then covers only buffered bodies.
+pub(crate) async fn send_streaming(
+    client: &ParclClient,
+    url: &str,
+    body: Option<&serde_json::Value>,
+    retry: Option<&RetryConfig>,
+) -> Result<(Received, ResponseMeta)> {
+    let stream = client.audit.is_none() && !client.keep_raw && !client.hash_content;
+    exchange(client, url, body, retry, stream).await
+}
+
+async fn exchange(
+    client: &ParclClient,
+    url: &str,
+    body: Option<&serde_json::Value>,
+    retry: Option<&RetryConfig>,
+    stream: bool,
+) -> Result<(Received, ResponseMeta)> {
+    let retry = retry.unwrap_or(&client.retry_config);
+    let path = plan::path_of(url);
+    client.plan.check(&path, client.timer.now())?;
+    let mut meta = ResponseMeta::default();
+    let mut attempt = 0;
+
+    loop {
+        if let Some(quota) = &client.quota {
+            quota.wait(client.timer.as_ref()).await;
+        }
+        let mut request = protocol::build_request(&client.auth, &client.api_key, url, body)?;
+        if client.sdk_header {
+            request.headers_mut().insert(
+                protocol::SDK_HEADER,
+                http::HeaderValue::from_static(protocol::USER_AGENT),
+            );
         }
+        let conditional = client.conditional_cache().filter(|_| body.is_none());
+        if let Some(cache) = conditional {
+            cache.prepare(url, &mut request);
+        }
+        let audit = client
+            .audit
+            .as_ref()
+            .map(|log| (log, AuditEntry::for_request(&request)));
+        let sent = if stream {
+            match client.transport.execute_streaming(request).await {
+                Ok(response) if response.status().is_success() => {
+                    meta.attempts_made += 1;
+                    client.record_rate_limit(&response);
+                    return Ok((Received::Streaming(response), meta));
+                }
+                Ok(response) => protocol::buffer(response).await,
+                Err(e) => Err(e),
+            }
+        } else {
+            client.transport.execute(request).await
+        };
+        let mut response = match sent {
+            Ok(response) => response,
+            Err(e)
+                if e.network_kind().is_some_and(|k| k.is_transient())
+                    && attempt < retry.max_retries =>
+            {
+                let backoff = protocol::backoff(retry, attempt);
+                meta.attempts_made += 1;
+                meta.retries += 1;
+                meta.total_backoff += backoff;
+                client.timer.sleep(backoff).await;
+                attempt += 1;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some((log, entry)) = audit {
+            log.record(&entry.with_response(&response))?;
+        }
+        meta.attempts_made += 1;
+        meta.bytes_received += response.body().len() as u64;
+        if let Some(cache) = conditional {
+            response = cache.complete(url, response);
+        }
+        client.record_rate_limit(&response);
 
-        if !status.is_success() {
-            let message = response.text().await.unwrap_or_default();
-            if status.as_u16() == 429 {
-                return Err(ParclError::RateLimited {
-                    attempts: attempt + 1,
-                    message,
-                });
+        if let Some(message) = plan::plan_denial(&response) {
+            return Err(client.plan.deny(&path, Some(message), client.timer.now()));
+        }
+
+        if let Some(quota) = &client.quota {
+            if protocol::is_quota_exhausted(&response) {
+                quota.trip(
+                    QuotaSignal::Rejected {
+                        status: response.status().as_u16(),
+                    },
+                    client.timer.now(),
+                );
+                return Ok((Received::Buffered(response), meta));
             }
-            return Err(ParclError::ApiError {
-                status: status.as_u16(),
-                message,
-            });
         }
 
-        let data: MetricsResponse<T> = response.json().await?;
-        return Ok(data);
+        match protocol::retry_delay(retry, &response, attempt) {
+            Some(backoff) => {
+                meta.retries += 1;
+                meta.total_backoff += backoff;
+                client.timer.sleep(backoff).await;
+                attempt += 1;
+            }
+            None => {
+                if client.keep_raw {
+                    meta.raw.push(RawPayload::new(response.body().clone()));
+                }
+                if client.hash_content {
+                    meta.content_hash = Some(ContentHash::of(response.body()));
+                }
+                return Ok((Received::Buffered(response), meta));
+            }
+        }
     }
+}
 
-    unreachable!()
+/// Decodes a successful JSON response, mapping error statuses to `ParclError`.
+pub(crate) fn decode<T: DeserializeOwned>(
+    client: &ParclClient,
+    response: &HttpResponse,
+    meta: &ResponseMeta,
+    context: RequestContext,
+) -> Result<T> {
+    client
+        .response_limits
+        .check(context.endpoint, meta.bytes_received, 0)?;
+    #[cfg(feature = "schema-validation")]
+    report_schema_drift(client, response, context);
+    #[cfg(not(feature = "schema-validation"))]
+    let _ = client;
+
+    let result = protocol::parse_response(response, context, meta.attempts_made);
+    if let Err(ParclError::Decode { .. }) = &result {
+        crate::debug::dump_failed_payload(context.endpoint, response.body());
+    }
+    result
+}
+
+/// Passes any schema discrepancies in a successful response to the client's handler.
+#[cfg(feature = "schema-validation")]
+fn report_schema_drift(client: &ParclClient, response: &HttpResponse, context: RequestContext) {
+    if !response.status().is_success() || !crate::schema::has_schema(context.endpoint) {
+        return;
+    }
+    let Ok(body) = serde_json::from_slice(response.body()) else {
+        return;
+    };
+    for drift in crate::schema::check(context.endpoint, &body) {
+        match &client.schema_drift_handler {
+            Some(handler) => handler(&drift),
+            None => crate::schema::log_drift(&drift),
+        }
+    }
+}
+
+/// Endpoint families that answer 404 when a metric has no data for the
+/// requested markets, e.g. a small ZIP.
+const NO_DATA_FAMILIES: [&str; 6] = [
+    "/v1/market_metrics/",
+    "/v1/for_sale_market_metrics/",
+    "/v1/rental_market_metrics/",
+    "/v1/investor_metrics/",
+    "/v1/new_construction_metrics/",
+    "/v1/portfolio_metrics/",
+];
+
+/// Whether `response` is a 404 that `endpoint` uses to report no data.
+///
+/// Other endpoints' 404s stay errors: a price feed 404 means the market
+/// has none, which callers see as [`ParclError::NoPriceFeed`].
+fn is_no_data(endpoint: &str, response: &HttpResponse) -> bool {
+    protocol::is_no_data(response)
+        && NO_DATA_FAMILIES
+            .iter()
+            .any(|family| endpoint.starts_with(family))
+}
+
+/// Executes a single GET request with retry on 429.
+///
+/// A 404 from a metrics family that uses it for "no data" yields an empty
+/// response.
+pub(crate) async fn get_page<T: DeserializeOwned>(
+    client: &ParclClient,
+    endpoint: &'static str,
+    url: &str,
+    parcl_id: i64,
+    retry: Option<&RetryConfig>,
+) -> Result<MetricsResponse<T>> {
+    let (response, meta) = send(client, url, None, retry).await?;
+
+    let mut data = if is_no_data(endpoint, &response) {
+        MetricsResponse::empty(parcl_id)
+    } else {
+        decode::<MetricsResponse<T>>(
+            client,
+            &response,
+            &meta,
+            RequestContext::new(endpoint).parcl_id(parcl_id),
+        )?
+    };
+    data.meta = meta;
+    Ok(data)
 }
 
 /// GETs the initial page, then auto-paginates if enabled.
 pub(crate) async fn get_with_pagination<T: DeserializeOwned>(
-    http: &Client,
-    api_key: &str,
+    client: &ParclClient,
+    endpoint: &'static str,
     url: &str,
-    auto_paginate: bool,
-    retry_config: &RetryConfig,
+    parcl_id: i64,
+    options: FetchOptions,
 ) -> Result<MetricsResponse<T>> {
-    let mut response = get_page(http, api_key, url, retry_config).await?;
+    let retry = options.retry.as_ref();
+    let url = limits::apply_to_url(client.limit_policy, endpoint, url)?;
+    let is_price_feed = endpoint.starts_with("/v1/price_feed/");
+    if is_price_feed && client.price_feed_check {
+        price_feed::ensure_feed(client, parcl_id).await?;
+    }
+    let mut response = match get_page(client, endpoint, &url, parcl_id, retry).await {
+        Err(err) if is_price_feed => return Err(price_feed::explain(client, parcl_id, err).await),
+        result => result?,
+    };
+    check_total(client, endpoint, &response.meta, response.items.len())?;
 
-    if auto_paginate {
+    if options.auto_paginate {
         while let Some(ref next_url) = response.links.next {
+            if options.should_stop(client, &mut response.meta) {
+                break;
+            }
             let next_page: MetricsResponse<T> =
-                get_page(http, api_key, next_url, retry_config).await?;
+                get_page(client, endpoint, next_url, parcl_id, retry).await?;
             response.items.extend(next_page.items);
             response.links = next_page.links;
+            response.meta.absorb(&next_page.meta);
+            check_total(client, endpoint, &response.meta, response.items.len())?;
         }
     }
 
     Ok(response)
 }
 
-/// Executes a single POST request with retry on 429.
-pub(crate) async fn post_page<T: DeserializeOwned>(
-    http: &Client,
-    api_key: &str,
+/// Checks the pages accumulated so far against the client's response limits.
+pub(crate) fn check_total(
+    client: &ParclClient,
+    endpoint: &'static str,
+    meta: &ResponseMeta,
+    items: usize,
+) -> Result<()> {
+    client
+        .response_limits
+        .check(endpoint, meta.bytes_received, items)
+}
+
+/// Fetches one batch page via POST (initial request) or GET (pagination links).
+///
+/// A 404 from a metrics family that uses it for "no data" means none of the
+/// requested markets have data and yields an empty response.
+async fn batch_page<T: DeserializeOwned>(
+    client: &ParclClient,
+    endpoint: &'static str,
     url: &str,
-    body: &serde_json::Value,
-    retry_config: &RetryConfig,
+    body: Option<&serde_json::Value>,
+    retry: Option<&RetryConfig>,
 ) -> Result<BatchMetricsResponse<T>> {
-    for attempt in 0..=retry_config.max_retries {
-        let response = http
-            .post(url)
-            .header("Authorization", api_key)
-            .json(body)
-            .send()
-            .await?;
-
-        let status = response.status();
-        if status.as_u16() == 429 && attempt < retry_config.max_retries {
-            let backoff = retry_config.initial_backoff_ms * 2u64.pow(attempt);
-            tokio::time::sleep(Duration::from_millis(backoff)).await;
-            continue;
-        }
-
-        if !status.is_success() {
-            let message = response.text().await.unwrap_or_default();
-            if status.as_u16() == 429 {
-                return Err(ParclError::RateLimited {
-                    attempts: attempt + 1,
-                    message,
-                });
-            }
-            return Err(ParclError::ApiError {
-                status: status.as_u16(),
-                message,
-            });
-        }
+    let (response, meta) = send(client, url, body, retry).await?;
 
-        let data: BatchMetricsResponse<T> = response.json().await?;
-        return Ok(data);
-    }
-
-    unreachable!()
+    let mut data = if is_no_data(endpoint, &response) {
+        BatchMetricsResponse::empty()
+    } else {
+        decode::<BatchMetricsResponse<T>>(client, &response, &meta, RequestContext::new(endpoint))?
+    };
+    data.meta = meta;
+    Ok(data)
 }
 
-/// Fetches a follow-up page via GET for batch pagination links (returns BatchMetricsResponse).
-async fn batch_get_page<T: DeserializeOwned>(
-    http: &Client,
-    api_key: &str,
+/// Executes a single POST request with retry on 429.
+pub(crate) async fn post_page<T: DeserializeOwned>(
+    client: &ParclClient,
+    endpoint: &'static str,
     url: &str,
-    retry_config: &RetryConfig,
+    body: &serde_json::Value,
+    retry: Option<&RetryConfig>,
 ) -> Result<BatchMetricsResponse<T>> {
-    for attempt in 0..=retry_config.max_retries {
-        let response = http
-            .get(url)
-            .header("Authorization", api_key)
-            .send()
-            .await?;
-
-        let status = response.status();
-        if status.as_u16() == 429 && attempt < retry_config.max_retries {
-            let backoff = retry_config.initial_backoff_ms * 2u64.pow(attempt);
-            tokio::time::sleep(Duration::from_millis(backoff)).await;
-            continue;
-        }
-
-        if !status.is_success() {
-            let message = response.text().await.unwrap_or_default();
-            if status.as_u16() == 429 {
-                return Err(ParclError::RateLimited {
-                    attempts: attempt + 1,
-                    message,
-                });
-            }
-            return Err(ParclError::ApiError {
-                status: status.as_u16(),
-                message,
-            });
-        }
-
-        let data: BatchMetricsResponse<T> = response.json().await?;
-        return Ok(data);
-    }
-
-    unreachable!()
+    batch_page(client, endpoint, url, Some(body), retry).await
 }
 
 /// POSTs the initial request, then auto-paginates via GET if enabled.
 pub(crate) async fn post_with_pagination<T: DeserializeOwned>(
-    http: &Client,
-    api_key: &str,
+    client: &ParclClient,
+    endpoint: &'static str,
     url: &str,
-    body: &serde_json::Value,
-    auto_paginate: bool,
-    retry_config: &RetryConfig,
+    body: &impl Serialize,
+    options: FetchOptions,
 ) -> Result<BatchMetricsResponse<T>> {
-    let mut response = post_page(http, api_key, url, body, retry_config).await?;
+    let retry = options.retry.as_ref();
+    let mut body = serde_json::to_value(body)?;
+    limits::apply_to_body(client.limit_policy, endpoint, &mut body)?;
+    let mut response = post_page(client, endpoint, url, &body, retry).await?;
+    check_total(client, endpoint, &response.meta, response.items.len())?;
 
-    if auto_paginate {
+    if options.auto_paginate {
         while let Some(ref next_url) = response.links.next {
+            if options.should_stop(client, &mut response.meta) {
+                break;
+            }
             let next_page: BatchMetricsResponse<T> =
-                batch_get_page(http, api_key, next_url, retry_config).await?;
+                batch_page(client, endpoint, next_url, None, retry).await?;
             response.items.extend(next_page.items);
             response.links = next_page.links;
+            response.meta.absorb(&next_page.meta);
+            check_total(client, endpoint, &response.meta, response.items.len())?;
         }
     }
 
     Ok(response)
 }
+
+/// Runs a batch request, splitting `parcl_ids` into chunks of
+/// [`limits::MAX_BATCH_PARCL_IDS`] when there are more.
+///
+/// Chunks run `client.batch_concurrency` at a time, each through
+/// [`post_with_pagination`], and are merged in chunk order: items
+/// concatenated, totals and credits summed, telemetry absorbed. A merged
+/// response has no pagination links, so set `auto_paginate` to get every
+/// page of every chunk. The first failing chunk fails the whole call.
+pub(crate) async fn post_batched<T: DeserializeOwned>(
+    client: &ParclClient,
+    endpoint: &'static str,
+    url: &str,
+    parcl_ids: &[i64],
+    body: impl Fn(&[i64]) -> serde_json::Result<serde_json::Value>,
+    options: FetchOptions,
+) -> Result<BatchMetricsResponse<T>> {
+    if parcl_ids.len() <= limits::MAX_BATCH_PARCL_IDS {
+        return post_with_pagination(client, endpoint, url, &body(parcl_ids)?, options).await;
+    }
+    let bodies = parcl_ids
+        .chunks(limits::MAX_BATCH_PARCL_IDS)
+        .map(body)
+        .collect::<serde_json::Result<Vec<_>>>()?;
+
+    let mut chunks: Vec<Option<BatchMetricsResponse<T>>> =
+        std::iter::repeat_with(|| None).take(bodies.len()).collect();
+    runtime::for_each_concurrent(
+        bodies.iter().enumerate(),
+        client.batch_concurrency,
+        |(index, body)| {
+            let options = options.clone();
+            async move {
+                let result = post_with_pagination(client, endpoint, url, body, options).await;
+                (index, result)
+            }
+        },
+        |(index, result)| {
+            chunks[index] = Some(result?);
+            Ok::<_, ParclError>(())
+        },
+    )
+    .await?;
+
+    let mut merged = BatchMetricsResponse::empty();
+    for chunk in chunks.into_iter().flatten() {
+        merged.items.extend(chunk.items);
+        merged.total += chunk.total;
+        merged.limit = merged.limit.max(chunk.limit);
+        merged.account = merge_accounts(merged.account, chunk.account);
+        merged.meta.absorb(&chunk.meta);
+    }
+    check_total(client, endpoint, &merged.meta, merged.items.len())?;
+    Ok(merged)
+}
+
+/// Sums credits used; keeps the lowest remaining balance reported.
+fn merge_accounts(a: Option<AccountInfo>, b: Option<AccountInfo>) -> Option<AccountInfo> {
+    let sum = |x: Option<i64>, y: Option<i64>| match (x, y) {
+        (Some(x), Some(y)) => Some(x + y),
+        (x, y) => x.or(y),
+    };
+    match (a, b) {
+        (Some(a), Some(b)) => Some(AccountInfo {
+            est_credits_used: sum(a.est_credits_used, b.est_credits_used),
+            est_remaining_credits: match (a.est_remaining_credits, b.est_remaining_credits) {
+                (Some(x), Some(y)) => Some(x.min(y)),
+                (x, y) => x.or(y),
+            },
+        }),
+        (a, b) => a.or(b),
+    }
+}
@@ -1,15 +1,17 @@
 //! For-sale market metrics endpoints for tracking inventory and listings.
 
-use crate::error::Result;
+use super::batch::{BatchRequest, MetricFilters};
+use super::common::FetchOptions;
+use super::query::Query;
 use crate::models::{
-    BatchMetricsResponse, ForSaleInventory, ForSaleInventoryPriceChanges, MetricsResponse,
-    NewListingsRollingCounts, PropertyType,
+    ForSaleInventory, ForSaleInventoryPriceChanges, NewListingsRollingCounts, PropertyType,
 };
 use crate::ParclClient;
+use std::borrow::Cow;
 
 /// Client for for-sale market metrics API endpoints.
 pub struct ForSaleMetricsClient<'a> {
-    client: &'a ParclClient,
+    client: Cow<'a, ParclClient>,
 }
 
 /// Query parameters for for-sale metrics requests.
@@ -21,6 +23,8 @@ pub struct ForSaleMetricsParams {
     pub end_date: Option<String>,
     pub property_type: Option<PropertyType>,
     pub auto_paginate: bool,
+    /// Retry policy, cancellation token and deadline for this request.
+    pub fetch: FetchOptions,
 }
 
 impl ForSaleMetricsParams {
@@ -64,219 +68,124 @@ impl ForSaleMetricsParams {
         self
     }
 
+    fetch_option_setters!();
+
     pub(crate) fn to_query_string(&self) -> String {
-        let mut params = Vec::new();
+        let mut query = Query::new();
 
         if let Some(l) = self.limit {
-            params.push(format!("limit={}", l));
+            query.push("limit", l);
         }
         if let Some(o) = self.offset {
-            params.push(format!("offset={}", o));
+            query.push("offset", o);
         }
         if let Some(ref s) = self.start_date {
-            params.push(format!("start_date={}", s));
+            query.push("start_date", s);
         }
         if let Some(ref e) = self.end_date {
-            params.push(format!("end_date={}", e));
+            query.push("end_date", e);
         }
         if let Some(pt) = self.property_type {
-            params.push(format!("property_type={}", pt.as_str()));
+            query.push("property_type", pt.as_str());
         }
 
-        if params.is_empty() {
-            String::new()
-        } else {
-            format!("?{}", params.join("&"))
-        }
+        query.finish()
     }
 
-    pub(crate) fn to_batch_body(&self, parcl_ids: &[i64]) -> serde_json::Value {
-        let mut body = serde_json::json!({ "parcl_id": parcl_ids });
-        let obj = body.as_object_mut().unwrap();
-        if let Some(l) = self.limit {
-            obj.insert("limit".into(), serde_json::json!(l));
-        }
-        if let Some(o) = self.offset {
-            obj.insert("offset".into(), serde_json::json!(o));
-        }
-        if let Some(ref s) = self.start_date {
-            obj.insert("start_date".into(), serde_json::json!(s));
-        }
-        if let Some(ref e) = self.end_date {
-            obj.insert("end_date".into(), serde_json::json!(e));
-        }
-        if let Some(pt) = self.property_type {
-            obj.insert("property_type".into(), serde_json::json!(pt.as_str()));
+    pub(crate) fn to_batch_body<'a>(
+        &'a self,
+        parcl_ids: &'a [i64],
+    ) -> BatchRequest<'a, MetricFilters<'a>> {
+        BatchRequest {
+            parcl_id: parcl_ids,
+            filters: MetricFilters {
+                limit: self.limit,
+                offset: self.offset,
+                start_date: self.start_date.as_deref(),
+                end_date: self.end_date.as_deref(),
+                property_type: self.property_type,
+            },
         }
-        body
     }
 }
 
 impl<'a> ForSaleMetricsClient<'a> {
-    pub(crate) fn new(client: &'a ParclClient) -> Self {
+    pub(crate) fn new(client: Cow<'a, ParclClient>) -> Self {
         Self { client }
     }
 
-    /// Retrieves current for-sale inventory counts.
-    ///
-    /// Returns the total count of properties currently listed for sale in the market.
-    /// Data series begins on September 1, 2022.
-    pub async fn for_sale_inventory(
-        &self,
-        parcl_id: i64,
-        params: Option<ForSaleMetricsParams>,
-    ) -> Result<MetricsResponse<ForSaleInventory>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/for_sale_market_metrics/{}/for_sale_inventory{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Retrieves for-sale inventory price change metrics.
-    ///
-    /// Returns metrics on price behavior including price changes, price drops,
-    /// median days between changes, and percentage of inventory affected.
-    /// Data series begins on September 1, 2022.
-    pub async fn for_sale_inventory_price_changes(
-        &self,
-        parcl_id: i64,
-        params: Option<ForSaleMetricsParams>,
-    ) -> Result<MetricsResponse<ForSaleInventoryPriceChanges>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/for_sale_market_metrics/{}/for_sale_inventory_price_changes{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Retrieves rolling counts of new for-sale listings.
-    ///
-    /// Returns rolling counts over 7, 30, 60, and 90 day periods
-    /// for newly listed properties in the market.
-    pub async fn new_listings_rolling_counts(
-        &self,
-        parcl_id: i64,
-        params: Option<ForSaleMetricsParams>,
-    ) -> Result<MetricsResponse<NewListingsRollingCounts>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/for_sale_market_metrics/{}/new_listings_rolling_counts{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    // --- Batch POST methods ---
-
-    /// Batch retrieves for-sale inventory for multiple markets.
-    pub async fn batch_for_sale_inventory(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<ForSaleMetricsParams>,
-    ) -> Result<BatchMetricsResponse<ForSaleInventory>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/for_sale_market_metrics/for_sale_inventory",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Batch retrieves for-sale inventory price changes for multiple markets.
-    pub async fn batch_for_sale_inventory_price_changes(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<ForSaleMetricsParams>,
-    ) -> Result<BatchMetricsResponse<ForSaleInventoryPriceChanges>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/for_sale_market_metrics/for_sale_inventory_price_changes",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Batch retrieves new listings rolling counts for multiple markets.
-    pub async fn batch_new_listings_rolling_counts(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<ForSaleMetricsParams>,
-    ) -> Result<BatchMetricsResponse<NewListingsRollingCounts>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/for_sale_market_metrics/new_listings_rolling_counts",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
+    define_endpoints! {
+        params = ForSaleMetricsParams;
+
+        /// Retrieves current for-sale inventory counts.
+        ///
+        /// Returns the total count of properties currently listed for sale in the market.
+        /// Data series begins on September 1, 2022.
+        get for_sale_inventory(
+            "/v1/for_sale_market_metrics",
+            "for_sale_inventory"
+        ) -> ForSaleInventory;
+
+        /// Retrieves for-sale inventory price change metrics.
+        ///
+        /// Returns metrics on price behavior including price changes, price drops,
+        /// median days between changes, and percentage of inventory affected.
+        /// Data series begins on September 1, 2022.
+        get for_sale_inventory_price_changes(
+            "/v1/for_sale_market_metrics",
+            "for_sale_inventory_price_changes"
+        ) -> ForSaleInventoryPriceChanges;
+
+        /// Retrieves rolling counts of new for-sale listings.
+        ///
+        /// Returns rolling counts over 7, 30, 60, and 90 day periods
+        /// for newly listed properties in the market.
+        get new_listings_rolling_counts(
+            "/v1/for_sale_market_metrics",
+            "new_listings_rolling_counts"
+        ) -> NewListingsRollingCounts;
+
+        // --- Latest-value methods ---
+
+        /// Most recent `for_sale_inventory` item for a market.
+        latest for_sale_inventory_latest(for_sale_inventory) -> ForSaleInventory;
+
+        /// Most recent `for_sale_inventory_price_changes` item for a market.
+        latest for_sale_inventory_price_changes_latest(for_sale_inventory_price_changes) -> ForSaleInventoryPriceChanges;
+
+        /// Most recent `new_listings_rolling_counts` item for a market.
+        latest new_listings_rolling_counts_latest(new_listings_rolling_counts) -> NewListingsRollingCounts;
+
+        // --- By-name methods ---
+
+        /// `for_sale_inventory` for a market given by name.
+        by_name for_sale_inventory_by_name(for_sale_inventory) -> ForSaleInventory;
+
+        /// `for_sale_inventory_price_changes` for a market given by name.
+        by_name for_sale_inventory_price_changes_by_name(for_sale_inventory_price_changes) -> ForSaleInventoryPriceChanges;
+
+        /// `new_listings_rolling_counts` for a market given by name.
+        by_name new_listings_rolling_counts_by_name(new_listings_rolling_counts) -> NewListingsRollingCounts;
+
+        // --- Batch POST methods ---
+
+        /// Batch retrieves for-sale inventory for multiple markets.
+        batch batch_for_sale_inventory(
+            "/v1/for_sale_market_metrics",
+            "for_sale_inventory"
+        ) -> ForSaleInventory;
+
+        /// Batch retrieves for-sale inventory price changes for multiple markets.
+        batch batch_for_sale_inventory_price_changes(
+            "/v1/for_sale_market_metrics",
+            "for_sale_inventory_price_changes"
+        ) -> ForSaleInventoryPriceChanges;
+
+        /// Batch retrieves new listings rolling counts for multiple markets.
+        batch batch_new_listings_rolling_counts(
+            "/v1/for_sale_market_metrics",
+            "new_listings_rolling_counts"
+        ) -> NewListingsRollingCounts;
     }
 }
 
@@ -354,7 +263,7 @@ mod tests {
     #[test]
     fn for_sale_params_batch_body_minimal() {
         let params = ForSaleMetricsParams::new();
-        let body = params.to_batch_body(&[100, 200]);
+        let body = serde_json::to_value(params.to_batch_body(&[100, 200])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100, 200]));
         assert!(!obj.contains_key("limit"));
@@ -368,7 +277,7 @@ mod tests {
             .start_date("2024-01-01")
             .end_date("2024-12-31")
             .property_type(PropertyType::Condo);
-        let body = params.to_batch_body(&[100]);
+        let body = serde_json::to_value(params.to_batch_body(&[100])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100]));
         assert_eq!(obj["limit"], 10);
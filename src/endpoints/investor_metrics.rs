@@ -1,16 +1,18 @@
 //! Investor metrics endpoints for tracking institutional activity.
 
-use crate::error::Result;
+use super::batch::{BatchRequest, MetricFilters};
+use super::common::FetchOptions;
+use super::query::Query;
 use crate::models::{
-    BatchMetricsResponse, HousingEventPrices, InvestorHousingEventCounts,
-    InvestorHousingStockOwnership, InvestorNewListingsRollingCounts, InvestorPurchaseToSaleRatio,
-    MetricsResponse, PropertyType,
+    HousingEventPrices, InvestorHousingEventCounts, InvestorHousingStockOwnership,
+    InvestorNewListingsRollingCounts, InvestorPurchaseToSaleRatio, PropertyType,
 };
 use crate::ParclClient;
+use std::borrow::Cow;
 
 /// Client for investor metrics API endpoints.
 pub struct InvestorMetricsClient<'a> {
-    client: &'a ParclClient,
+    client: Cow<'a, ParclClient>,
 }
 
 /// Query parameters for investor metrics requests.
@@ -22,6 +24,8 @@ pub struct InvestorMetricsParams {
     pub end_date: Option<String>,
     pub property_type: Option<PropertyType>,
     pub auto_paginate: bool,
+    /// Retry policy, cancellation token and deadline for this request.
+    pub fetch: FetchOptions,
 }
 
 impl InvestorMetricsParams {
@@ -65,309 +69,150 @@ impl InvestorMetricsParams {
         self
     }
 
+    fetch_option_setters!();
+
     pub(crate) fn to_query_string(&self) -> String {
-        let mut params = Vec::new();
+        let mut query = Query::new();
 
         if let Some(l) = self.limit {
-            params.push(format!("limit={}", l));
+            query.push("limit", l);
         }
         if let Some(o) = self.offset {
-            params.push(format!("offset={}", o));
+            query.push("offset", o);
         }
         if let Some(ref s) = self.start_date {
-            params.push(format!("start_date={}", s));
+            query.push("start_date", s);
         }
         if let Some(ref e) = self.end_date {
-            params.push(format!("end_date={}", e));
+            query.push("end_date", e);
         }
         if let Some(pt) = self.property_type {
-            params.push(format!("property_type={}", pt.as_str()));
+            query.push("property_type", pt.as_str());
         }
 
-        if params.is_empty() {
-            String::new()
-        } else {
-            format!("?{}", params.join("&"))
-        }
+        query.finish()
     }
 
-    pub(crate) fn to_batch_body(&self, parcl_ids: &[i64]) -> serde_json::Value {
-        let mut body = serde_json::json!({ "parcl_id": parcl_ids });
-        let obj = body.as_object_mut().unwrap();
-        if let Some(l) = self.limit {
-            obj.insert("limit".into(), serde_json::json!(l));
-        }
-        if let Some(o) = self.offset {
-            obj.insert("offset".into(), serde_json::json!(o));
-        }
-        if let Some(ref s) = self.start_date {
-            obj.insert("start_date".into(), serde_json::json!(s));
-        }
-        if let Some(ref e) = self.end_date {
-            obj.insert("end_date".into(), serde_json::json!(e));
+    pub(crate) fn to_batch_body<'a>(
+        &'a self,
+        parcl_ids: &'a [i64],
+    ) -> BatchRequest<'a, MetricFilters<'a>> {
+        BatchRequest {
+            parcl_id: parcl_ids,
+            filters: MetricFilters {
+                limit: self.limit,
+                offset: self.offset,
+                start_date: self.start_date.as_deref(),
+                end_date: self.end_date.as_deref(),
+                property_type: self.property_type,
+            },
         }
-        if let Some(pt) = self.property_type {
-            obj.insert("property_type".into(), serde_json::json!(pt.as_str()));
-        }
-        body
     }
 }
 
 impl<'a> InvestorMetricsClient<'a> {
-    pub(crate) fn new(client: &'a ParclClient) -> Self {
+    pub(crate) fn new(client: Cow<'a, ParclClient>) -> Self {
         Self { client }
     }
 
-    /// Retrieves investor housing stock ownership data.
-    pub async fn housing_stock_ownership(
-        &self,
-        parcl_id: i64,
-        params: Option<InvestorMetricsParams>,
-    ) -> Result<MetricsResponse<InvestorHousingStockOwnership>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/investor_metrics/{}/housing_stock_ownership{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+    define_endpoints! {
+        params = InvestorMetricsParams;
 
-    /// Retrieves investor purchase-to-sale ratio.
-    pub async fn purchase_to_sale_ratio(
-        &self,
-        parcl_id: i64,
-        params: Option<InvestorMetricsParams>,
-    ) -> Result<MetricsResponse<InvestorPurchaseToSaleRatio>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/investor_metrics/{}/purchase_to_sale_ratio{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves investor housing stock ownership data.
+        get housing_stock_ownership(
+            "/v1/investor_metrics",
+            "housing_stock_ownership"
+        ) -> InvestorHousingStockOwnership;
 
-    /// Retrieves investor housing event counts.
-    pub async fn housing_event_counts(
-        &self,
-        parcl_id: i64,
-        params: Option<InvestorMetricsParams>,
-    ) -> Result<MetricsResponse<InvestorHousingEventCounts>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/investor_metrics/{}/housing_event_counts{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves investor purchase-to-sale ratio.
+        get purchase_to_sale_ratio(
+            "/v1/investor_metrics",
+            "purchase_to_sale_ratio"
+        ) -> InvestorPurchaseToSaleRatio;
 
-    /// Retrieves investor housing event prices.
-    pub async fn housing_event_prices(
-        &self,
-        parcl_id: i64,
-        params: Option<InvestorMetricsParams>,
-    ) -> Result<MetricsResponse<HousingEventPrices>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/investor_metrics/{}/housing_event_prices{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves investor housing event counts.
+        get housing_event_counts(
+            "/v1/investor_metrics",
+            "housing_event_counts"
+        ) -> InvestorHousingEventCounts;
 
-    /// Retrieves rolling counts of investor new listings for sale.
-    pub async fn new_listings_for_sale_rolling_counts(
-        &self,
-        parcl_id: i64,
-        params: Option<InvestorMetricsParams>,
-    ) -> Result<MetricsResponse<InvestorNewListingsRollingCounts>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/investor_metrics/{}/new_listings_for_sale_rolling_counts{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves investor housing event prices.
+        get housing_event_prices(
+            "/v1/investor_metrics",
+            "housing_event_prices"
+        ) -> HousingEventPrices;
 
-    // --- Batch POST methods ---
-
-    /// Batch retrieves housing stock ownership for multiple markets.
-    pub async fn batch_housing_stock_ownership(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<InvestorMetricsParams>,
-    ) -> Result<BatchMetricsResponse<InvestorHousingStockOwnership>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/investor_metrics/housing_stock_ownership",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves rolling counts of investor new listings for sale.
+        get new_listings_for_sale_rolling_counts(
+            "/v1/investor_metrics",
+            "new_listings_for_sale_rolling_counts"
+        ) -> InvestorNewListingsRollingCounts;
 
-    /// Batch retrieves purchase-to-sale ratio for multiple markets.
-    pub async fn batch_purchase_to_sale_ratio(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<InvestorMetricsParams>,
-    ) -> Result<BatchMetricsResponse<InvestorPurchaseToSaleRatio>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/investor_metrics/purchase_to_sale_ratio",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        // --- Latest-value methods ---
 
-    /// Batch retrieves housing event counts for multiple markets.
-    pub async fn batch_housing_event_counts(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<InvestorMetricsParams>,
-    ) -> Result<BatchMetricsResponse<InvestorHousingEventCounts>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/investor_metrics/housing_event_counts",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Most recent `housing_stock_ownership` item for a market.
+        latest housing_stock_ownership_latest(housing_stock_ownership) -> InvestorHousingStockOwnership;
 
-    /// Batch retrieves housing event prices for multiple markets.
-    pub async fn batch_housing_event_prices(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<InvestorMetricsParams>,
-    ) -> Result<BatchMetricsResponse<HousingEventPrices>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/investor_metrics/housing_event_prices",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Most recent `purchase_to_sale_ratio` item for a market.
+        latest purchase_to_sale_ratio_latest(purchase_to_sale_ratio) -> InvestorPurchaseToSaleRatio;
+
+        /// Most recent `housing_event_counts` item for a market.
+        latest housing_event_counts_latest(housing_event_counts) -> InvestorHousingEventCounts;
+
+        /// Most recent `housing_event_prices` item for a market.
+        latest housing_event_prices_latest(housing_event_prices) -> HousingEventPrices;
+
+        /// Most recent `new_listings_for_sale_rolling_counts` item for a market.
+        latest new_listings_for_sale_rolling_counts_latest(new_listings_for_sale_rolling_counts) -> InvestorNewListingsRollingCounts;
+
+        // --- By-name methods ---
+
+        /// `housing_stock_ownership` for a market given by name.
+        by_name housing_stock_ownership_by_name(housing_stock_ownership) -> InvestorHousingStockOwnership;
+
+        /// `purchase_to_sale_ratio` for a market given by name.
+        by_name purchase_to_sale_ratio_by_name(purchase_to_sale_ratio) -> InvestorPurchaseToSaleRatio;
+
+        /// `housing_event_counts` for a market given by name.
+        by_name housing_event_counts_by_name(housing_event_counts) -> InvestorHousingEventCounts;
+
+        /// `housing_event_prices` for a market given by name.
+        by_name housing_event_prices_by_name(housing_event_prices) -> HousingEventPrices;
+
+        /// `new_listings_for_sale_rolling_counts` for a market given by name.
+        by_name new_listings_for_sale_rolling_counts_by_name(new_listings_for_sale_rolling_counts) -> InvestorNewListingsRollingCounts;
+
+        // --- Batch POST methods ---
+
+        /// Batch retrieves housing stock ownership for multiple markets.
+        batch batch_housing_stock_ownership(
+            "/v1/investor_metrics",
+            "housing_stock_ownership"
+        ) -> InvestorHousingStockOwnership;
+
+        /// Batch retrieves purchase-to-sale ratio for multiple markets.
+        batch batch_purchase_to_sale_ratio(
+            "/v1/investor_metrics",
+            "purchase_to_sale_ratio"
+        ) -> InvestorPurchaseToSaleRatio;
+
+        /// Batch retrieves housing event counts for multiple markets.
+        batch batch_housing_event_counts(
+            "/v1/investor_metrics",
+            "housing_event_counts"
+        ) -> InvestorHousingEventCounts;
+
+        /// Batch retrieves housing event prices for multiple markets.
+        batch batch_housing_event_prices(
+            "/v1/investor_metrics",
+            "housing_event_prices"
+        ) -> HousingEventPrices;
 
-    /// Batch retrieves new listings for sale rolling counts for multiple markets.
-    pub async fn batch_new_listings_for_sale_rolling_counts(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<InvestorMetricsParams>,
-    ) -> Result<BatchMetricsResponse<InvestorNewListingsRollingCounts>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/investor_metrics/new_listings_for_sale_rolling_counts",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
+        /// Batch retrieves new listings for sale rolling counts for multiple markets.
+        batch batch_new_listings_for_sale_rolling_counts(
+            "/v1/investor_metrics",
+            "new_listings_for_sale_rolling_counts"
+        ) -> InvestorNewListingsRollingCounts;
     }
 }
 
@@ -431,7 +276,7 @@ mod tests {
     #[test]
     fn investor_params_batch_body_minimal() {
         let params = InvestorMetricsParams::new();
-        let body = params.to_batch_body(&[100, 200]);
+        let body = serde_json::to_value(params.to_batch_body(&[100, 200])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100, 200]));
         assert!(!obj.contains_key("limit"));
@@ -445,7 +290,7 @@ mod tests {
             .start_date("2024-01-01")
             .end_date("2024-12-31")
             .property_type(PropertyType::SingleFamily);
-        let body = params.to_batch_body(&[100]);
+        let body = serde_json::to_value(params.to_batch_body(&[100])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100]));
         assert_eq!(obj["limit"], 10);
@@ -0,0 +1,187 @@
+//! Generates the paginated metric endpoint methods.
+
+/// Defines GET and batch-POST metric methods on an endpoint client.
+///
+/// Each entry names the method, the service path and the metric, and the
+/// item type. `get` methods take a `parcl_id` and call
+/// `GET {service}/{parcl_id}/{metric}`; `batch` methods take a list of IDs
+/// and call `POST {service}/{metric}`, split into several requests when the
+/// list exceeds the API maximum. Both go through the shared
+/// pagination, retry and credit tracking in `common`. `latest` methods wrap
+/// a `get` method, requesting a single item and returning it unwrapped;
+/// `by_name` methods wrap one, resolving a market name to its `parcl_id`.
+///
+/// ```text
+/// impl<'a> MarketMetricsClient<'a> {
+///     define_endpoints! {
+///         params = MetricsParams;
+///
+///         /// Retrieves housing stock data for a market.
+///         get housing_stock("/v1/market_metrics", "housing_stock") -> HousingStock;
+///         /// Most recent housing stock observation for a market.
+///         latest housing_stock_latest(housing_stock) -> HousingStock;
+///         /// Batch retrieves housing stock data for multiple markets.
+///         batch batch_housing_stock("/v1/market_metrics", "housing_stock") -> HousingStock;
+///     }
+/// }
+/// ```
+///
+/// The enclosing client needs a `client: Cow<'_, ParclClient>` field,
+/// borrowed or owned, and the params type `Default`, `limit`,
+/// `to_query_string`, `to_batch_body` and `fetch_options`.
+macro_rules! define_endpoints {
+    (params = $params:ty; $($entries:tt)*) => {
+        define_endpoints!(@entries $params; $($entries)*);
+    };
+    (@entries $params:ty;) => {};
+    (
+        @entries $params:ty;
+        $(#[$meta:meta])*
+        get $name:ident($service:literal, $metric:literal) -> $item:ty;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        pub async fn $name(
+            &self,
+            parcl_id: i64,
+            params: Option<$params>,
+        ) -> $crate::error::Result<$crate::models::MetricsResponse<$item>> {
+            let params = params.unwrap_or_default();
+            let url = format!(
+                concat!("{}", $service, "/{}/", $metric, "{}"),
+                self.client.base_url,
+                parcl_id,
+                params.to_query_string()
+            );
+            let resp = $crate::endpoints::common::get_with_pagination(
+                &self.client,
+                concat!($service, "/", $metric),
+                &url,
+                parcl_id,
+                params.fetch_options(),
+            )
+            .await?;
+            self.client.update_credits(&resp.account);
+            Ok(resp)
+        }
+
+        define_endpoints!(@entries $params; $($rest)*);
+    };
+    (
+        @entries $params:ty;
+        $(#[$meta:meta])*
+        latest $name:ident($get:ident) -> $item:ty;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        ///
+        /// Requests a single item, the newest the API serves, and returns
+        /// `None` when the market has no data.
+        pub async fn $name(&self, parcl_id: i64) -> $crate::error::Result<Option<$item>> {
+            let params = <$params>::default().limit(1);
+            let resp = self.$get(parcl_id, Some(params)).await?;
+            Ok(resp.items.into_iter().next())
+        }
+
+        define_endpoints!(@entries $params; $($rest)*);
+    };
+    (
+        @entries $params:ty;
+        $(#[$meta:meta])*
+        by_name $name:ident($get:ident) -> $item:ty;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        ///
+        /// Resolves `name` (e.g. `"Denver, CO"`) with
+        /// [`ParclClient::market_by_name`](crate::ParclClient::market_by_name)
+        /// first; the lookup is cached, so repeat calls cost one request.
+        pub async fn $name(
+            &self,
+            name: &str,
+            params: Option<$params>,
+        ) -> $crate::error::Result<$crate::models::MetricsResponse<$item>> {
+            let market = self.client.market_by_name(name).await?;
+            self.$get(market.parcl_id, params).await
+        }
+
+        define_endpoints!(@entries $params; $($rest)*);
+    };
+    (
+        @entries $params:ty;
+        $(#[$meta:meta])*
+        batch $name:ident($service:literal, $metric:literal) -> $item:ty;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        pub async fn $name(
+            &self,
+            parcl_ids: Vec<i64>,
+            params: Option<$params>,
+        ) -> $crate::error::Result<$crate::models::BatchMetricsResponse<$item>> {
+            let params = params.unwrap_or_default();
+            let url = format!(concat!("{}", $service, "/", $metric), self.client.base_url);
+            let resp = $crate::endpoints::common::post_batched(
+                &self.client,
+                concat!($service, "/", $metric),
+                &url,
+                &parcl_ids,
+                |ids| serde_json::to_value(params.to_batch_body(ids)),
+                params.fetch_options(),
+            )
+            .await?;
+            self.client.update_credits(&resp.account);
+            Ok(resp)
+        }
+
+        define_endpoints!(@entries $params; $($rest)*);
+    };
+}
+
+/// Defines the retry, cancellation and deadline setters on a params type.
+///
+/// The params type needs an `auto_paginate: bool` field and a
+/// `fetch: FetchOptions` field, and gains `fetch_options` for the shared
+/// request code in `common`.
+macro_rules! fetch_option_setters {
+    () => {
+        /// Overrides the client's retry policy for this request.
+        pub fn retry_config(mut self, config: $crate::RetryConfig) -> Self {
+            self.fetch.retry = Some(config);
+            self
+        }
+
+        /// Fails immediately on 429 instead of retrying, e.g. for interactive use.
+        pub fn no_retry(self) -> Self {
+            self.retry_config($crate::RetryConfig::none())
+        }
+
+        /// Stops auto-pagination once `token` is cancelled.
+        ///
+        /// The token is checked between pages; the pages fetched so far are
+        /// returned with `meta.cancelled` set.
+        pub fn cancel_token(mut self, token: $crate::CancellationToken) -> Self {
+            self.fetch.cancel = Some(token);
+            self
+        }
+
+        /// Bounds the whole auto-paginated fetch, not just each request.
+        ///
+        /// Checked before each follow-up page against the client's
+        /// [`Timer`](crate::Timer); a page already in flight is allowed to
+        /// finish. Pages fetched by then are returned with
+        /// `meta.deadline_exceeded` set; resume with
+        /// `.offset(response.next_offset())`.
+        pub fn deadline(mut self, deadline: std::time::Instant) -> Self {
+            self.fetch.deadline = Some(deadline);
+            self
+        }
+
+        pub(crate) fn fetch_options(&self) -> $crate::endpoints::common::FetchOptions {
+            $crate::endpoints::common::FetchOptions {
+                auto_paginate: self.auto_paginate,
+                ..self.fetch.clone()
+            }
+        }
+    };
+}
@@ -1,15 +1,18 @@
 //! Market metrics endpoints for housing data retrieval.
 
-use crate::error::Result;
+use super::batch::{BatchRequest, MetricFilters};
+use super::common::FetchOptions;
+use super::query::Query;
 use crate::models::{
-    AllCash, BatchMetricsResponse, HousingEventCounts, HousingEventPrices,
-    HousingEventPropertyAttributes, HousingStock, MetricsResponse, PropertyType,
+    AllCash, HousingEventCounts, HousingEventPrices, HousingEventPropertyAttributes, HousingStock,
+    PropertyType,
 };
 use crate::ParclClient;
+use std::borrow::Cow;
 
 /// Client for market metrics API endpoints.
 pub struct MarketMetricsClient<'a> {
-    client: &'a ParclClient,
+    client: Cow<'a, ParclClient>,
 }
 
 /// Query parameters for paginated metrics requests.
@@ -21,6 +24,8 @@ pub struct MetricsParams {
     pub end_date: Option<String>,
     pub property_type: Option<PropertyType>,
     pub auto_paginate: bool,
+    /// Retry policy, cancellation token and deadline for this request.
+    pub fetch: FetchOptions,
 }
 
 impl MetricsParams {
@@ -64,303 +69,138 @@ impl MetricsParams {
         self
     }
 
+    fetch_option_setters!();
+
     pub(crate) fn to_query_string(&self) -> String {
-        let mut params = Vec::new();
+        let mut query = Query::new();
 
         if let Some(l) = self.limit {
-            params.push(format!("limit={}", l));
+            query.push("limit", l);
         }
         if let Some(o) = self.offset {
-            params.push(format!("offset={}", o));
+            query.push("offset", o);
         }
         if let Some(ref s) = self.start_date {
-            params.push(format!("start_date={}", s));
+            query.push("start_date", s);
         }
         if let Some(ref e) = self.end_date {
-            params.push(format!("end_date={}", e));
+            query.push("end_date", e);
         }
         if let Some(pt) = self.property_type {
-            params.push(format!("property_type={}", pt.as_str()));
+            query.push("property_type", pt.as_str());
         }
 
-        if params.is_empty() {
-            String::new()
-        } else {
-            format!("?{}", params.join("&"))
-        }
+        query.finish()
     }
 
-    pub(crate) fn to_batch_body(&self, parcl_ids: &[i64]) -> serde_json::Value {
-        let mut body = serde_json::json!({ "parcl_id": parcl_ids });
-        let obj = body.as_object_mut().unwrap();
-        if let Some(l) = self.limit {
-            obj.insert("limit".into(), serde_json::json!(l));
-        }
-        if let Some(o) = self.offset {
-            obj.insert("offset".into(), serde_json::json!(o));
+    pub(crate) fn to_batch_body<'a>(
+        &'a self,
+        parcl_ids: &'a [i64],
+    ) -> BatchRequest<'a, MetricFilters<'a>> {
+        BatchRequest {
+            parcl_id: parcl_ids,
+            filters: MetricFilters {
+                limit: self.limit,
+                offset: self.offset,
+                start_date: self.start_date.as_deref(),
+                end_date: self.end_date.as_deref(),
+                property_type: self.property_type,
+            },
         }
-        if let Some(ref s) = self.start_date {
-            obj.insert("start_date".into(), serde_json::json!(s));
-        }
-        if let Some(ref e) = self.end_date {
-            obj.insert("end_date".into(), serde_json::json!(e));
-        }
-        if let Some(pt) = self.property_type {
-            obj.insert("property_type".into(), serde_json::json!(pt.as_str()));
-        }
-        body
     }
 }
 
 impl<'a> MarketMetricsClient<'a> {
-    pub(crate) fn new(client: &'a ParclClient) -> Self {
+    pub(crate) fn new(client: Cow<'a, ParclClient>) -> Self {
         Self { client }
     }
 
-    /// Retrieves housing event counts (sales, listings) for a market.
-    pub async fn housing_event_counts(
-        &self,
-        parcl_id: i64,
-        params: Option<MetricsParams>,
-    ) -> Result<MetricsResponse<HousingEventCounts>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/market_metrics/{}/housing_event_counts{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Retrieves housing stock data (single-family, condo, townhouse counts).
-    pub async fn housing_stock(
-        &self,
-        parcl_id: i64,
-        params: Option<MetricsParams>,
-    ) -> Result<MetricsResponse<HousingStock>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/market_metrics/{}/housing_stock{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Retrieves housing event prices (median sale, list, rental prices).
-    pub async fn housing_event_prices(
-        &self,
-        parcl_id: i64,
-        params: Option<MetricsParams>,
-    ) -> Result<MetricsResponse<HousingEventPrices>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/market_metrics/{}/housing_event_prices{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Retrieves all-cash transaction counts and percentages.
-    pub async fn all_cash(
-        &self,
-        parcl_id: i64,
-        params: Option<MetricsParams>,
-    ) -> Result<MetricsResponse<AllCash>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/market_metrics/{}/all_cash{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Retrieves physical attributes of properties in housing events.
-    pub async fn housing_event_property_attributes(
-        &self,
-        parcl_id: i64,
-        params: Option<MetricsParams>,
-    ) -> Result<MetricsResponse<HousingEventPropertyAttributes>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/market_metrics/{}/housing_event_property_attributes{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    // --- Batch POST methods ---
-
-    /// Batch retrieves housing event counts for multiple markets.
-    pub async fn batch_housing_event_counts(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<MetricsParams>,
-    ) -> Result<BatchMetricsResponse<HousingEventCounts>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/market_metrics/housing_event_counts",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Batch retrieves housing stock data for multiple markets.
-    pub async fn batch_housing_stock(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<MetricsParams>,
-    ) -> Result<BatchMetricsResponse<HousingStock>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!("{}/v1/market_metrics/housing_stock", self.client.base_url);
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Batch retrieves housing event prices for multiple markets.
-    pub async fn batch_housing_event_prices(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<MetricsParams>,
-    ) -> Result<BatchMetricsResponse<HousingEventPrices>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/market_metrics/housing_event_prices",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Batch retrieves all-cash transaction data for multiple markets.
-    pub async fn batch_all_cash(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<MetricsParams>,
-    ) -> Result<BatchMetricsResponse<AllCash>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!("{}/v1/market_metrics/all_cash", self.client.base_url);
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Batch retrieves housing event property attributes for multiple markets.
-    pub async fn batch_housing_event_property_attributes(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<MetricsParams>,
-    ) -> Result<BatchMetricsResponse<HousingEventPropertyAttributes>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/market_metrics/housing_event_property_attributes",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
+    define_endpoints! {
+        params = MetricsParams;
+
+        /// Retrieves housing event counts (sales, listings) for a market.
+        get housing_event_counts(
+            "/v1/market_metrics",
+            "housing_event_counts"
+        ) -> HousingEventCounts;
+
+        /// Retrieves housing stock data (single-family, condo, townhouse counts).
+        get housing_stock("/v1/market_metrics", "housing_stock") -> HousingStock;
+
+        /// Retrieves housing event prices (median sale, list, rental prices).
+        get housing_event_prices(
+            "/v1/market_metrics",
+            "housing_event_prices"
+        ) -> HousingEventPrices;
+
+        /// Retrieves all-cash transaction counts and percentages.
+        get all_cash("/v1/market_metrics", "all_cash") -> AllCash;
+
+        /// Retrieves physical attributes of properties in housing events.
+        get housing_event_property_attributes(
+            "/v1/market_metrics",
+            "housing_event_property_attributes"
+        ) -> HousingEventPropertyAttributes;
+
+        // --- Latest-value methods ---
+
+        /// Most recent `housing_event_counts` item for a market.
+        latest housing_event_counts_latest(housing_event_counts) -> HousingEventCounts;
+
+        /// Most recent `housing_stock` item for a market.
+        latest housing_stock_latest(housing_stock) -> HousingStock;
+
+        /// Most recent `housing_event_prices` item for a market.
+        latest housing_event_prices_latest(housing_event_prices) -> HousingEventPrices;
+
+        /// Most recent `all_cash` item for a market.
+        latest all_cash_latest(all_cash) -> AllCash;
+
+        /// Most recent `housing_event_property_attributes` item for a market.
+        latest housing_event_property_attributes_latest(housing_event_property_attributes) -> HousingEventPropertyAttributes;
+
+        // --- By-name methods ---
+
+        /// `housing_event_counts` for a market given by name.
+        by_name housing_event_counts_by_name(housing_event_counts) -> HousingEventCounts;
+
+        /// `housing_stock` for a market given by name.
+        by_name housing_stock_by_name(housing_stock) -> HousingStock;
+
+        /// `housing_event_prices` for a market given by name.
+        by_name housing_event_prices_by_name(housing_event_prices) -> HousingEventPrices;
+
+        /// `all_cash` for a market given by name.
+        by_name all_cash_by_name(all_cash) -> AllCash;
+
+        /// `housing_event_property_attributes` for a market given by name.
+        by_name housing_event_property_attributes_by_name(housing_event_property_attributes) -> HousingEventPropertyAttributes;
+
+        // --- Batch POST methods ---
+
+        /// Batch retrieves housing event counts for multiple markets.
+        batch batch_housing_event_counts(
+            "/v1/market_metrics",
+            "housing_event_counts"
+        ) -> HousingEventCounts;
+
+        /// Batch retrieves housing stock data for multiple markets.
+        batch batch_housing_stock("/v1/market_metrics", "housing_stock") -> HousingStock;
+
+        /// Batch retrieves housing event prices for multiple markets.
+        batch batch_housing_event_prices(
+            "/v1/market_metrics",
+            "housing_event_prices"
+        ) -> HousingEventPrices;
+
+        /// Batch retrieves all-cash transaction data for multiple markets.
+        batch batch_all_cash("/v1/market_metrics", "all_cash") -> AllCash;
+
+        /// Batch retrieves housing event property attributes for multiple markets.
+        batch batch_housing_event_property_attributes(
+            "/v1/market_metrics",
+            "housing_event_property_attributes"
+        ) -> HousingEventPropertyAttributes;
     }
 }
 
@@ -368,6 +208,193 @@ impl<'a> MarketMetricsClient<'a> {
 mod tests {
     use super::*;
     use crate::models::PropertyType;
+    use crate::test_util::{json_response, CannedTransport};
+    use crate::{CancellationToken, RetryConfig};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn metrics_params_retry_override() {
+        let params = MetricsParams::new().auto_paginate(true).no_retry();
+        let options = params.fetch_options();
+        assert!(options.auto_paginate);
+        assert_eq!(options.retry, Some(RetryConfig::none()));
+        assert!(MetricsParams::new().fetch_options().retry.is_none());
+    }
+
+    /// Serves endless pages, cancelling `token` once two have been sent.
+    fn cancel_after_two(token: CancellationToken) -> CannedTransport {
+        let pages = AtomicU32::new(0);
+        CannedTransport::new(move |_| {
+            let page = pages.fetch_add(1, Ordering::SeqCst);
+            if page == 1 {
+                token.cancel();
+            }
+            json_response(
+                200,
+                &serde_json::json!({
+                    "parcl_id": 1, "total": 100, "limit": 1, "offset": page,
+                    "items": [{"date": "2024-01-01", "sales": page}],
+                    "links": {"next": format!("https://example.com/next?offset={}", page + 1)}
+                }),
+            )
+        })
+    }
+
+    #[tokio::test]
+    async fn cancelled_pagination_returns_pages_so_far() {
+        let token = CancellationToken::new();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(cancel_after_two(token.clone()));
+        let params = MetricsParams::new().auto_paginate(true).cancel_token(token);
+        let resp = client
+            .market_metrics()
+            .housing_event_counts(1, Some(params))
+            .await
+            .unwrap();
+        assert_eq!(resp.items.len(), 2);
+        assert!(resp.meta.cancelled);
+        assert_eq!(
+            resp.links.next.as_deref(),
+            Some("https://example.com/next?offset=2")
+        );
+    }
+
+    #[tokio::test]
+    async fn raw_mode_keeps_each_page_body() {
+        let token = CancellationToken::new();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(cancel_after_two(token.clone()))
+            .with_raw(true);
+        let params = MetricsParams::new().auto_paginate(true).cancel_token(token);
+        let resp = client
+            .market_metrics()
+            .housing_event_counts(1, Some(params))
+            .await
+            .unwrap();
+        let pages = resp.meta.raw_json().unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[1]["items"][0]["sales"], 1);
+        assert_eq!(resp.items[1].sales, Some(1));
+        assert!(format!("{:?}", resp.meta.raw[0]).ends_with(" bytes)"));
+    }
+
+    #[tokio::test]
+    async fn content_hash_covers_every_page() {
+        let token = CancellationToken::new();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(cancel_after_two(token.clone()))
+            .with_content_hash(true);
+        let params = MetricsParams::new().auto_paginate(true).cancel_token(token);
+        let resp = client
+            .market_metrics()
+            .housing_event_counts(1, Some(params))
+            .await
+            .unwrap();
+        let first = resp.meta.content_hash.unwrap();
+        assert!(resp.meta.raw.is_empty());
+
+        let token = CancellationToken::new();
+        let client = client.with_transport(cancel_after_two(token.clone()));
+        let params = MetricsParams::new().auto_paginate(true).cancel_token(token);
+        let again = client
+            .market_metrics()
+            .housing_event_counts(1, Some(params))
+            .await
+            .unwrap();
+        assert_eq!(again.meta.content_hash, Some(first));
+    }
+
+    /// Serves endless pages, each taking a virtual second on `clock`.
+    fn slow_pages(clock: crate::runtime::MockClock) -> CannedTransport {
+        CannedTransport::new(move |request| {
+            clock.advance(std::time::Duration::from_secs(1));
+            let offset: u32 = request
+                .uri()
+                .query()
+                .and_then(|q| q.strip_prefix("offset="))
+                .map_or(0, |o| o.parse().unwrap());
+            json_response(
+                200,
+                &serde_json::json!({
+                    "parcl_id": 1, "total": 100, "limit": 1, "offset": offset,
+                    "items": [{"date": "2024-01-01", "sales": offset}],
+                    "links": {"next": format!("https://example.com/next?offset={}", offset + 1)}
+                }),
+            )
+        })
+    }
+
+    #[tokio::test]
+    async fn deadline_bounds_the_whole_fetch() {
+        let clock = crate::runtime::MockClock::new();
+        let deadline = crate::Timer::now(&clock) + std::time::Duration::from_millis(2500);
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(slow_pages(clock.clone()))
+            .with_timer(clock);
+        let params = MetricsParams::new().auto_paginate(true).deadline(deadline);
+        let resp = client
+            .market_metrics()
+            .housing_event_counts(1, Some(params))
+            .await
+            .unwrap();
+        assert_eq!(resp.items.len(), 3);
+        assert!(resp.meta.deadline_exceeded && resp.meta.is_partial());
+        assert_eq!(resp.next_offset(), Some(3));
+    }
+
+    /// The `parcl_id`s in a batch POST body.
+    fn batch_ids(request: &crate::protocol::HttpRequest) -> Vec<i64> {
+        let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+        serde_json::from_value(body["parcl_id"].clone()).unwrap()
+    }
+
+    /// Answers a batch POST with one item per requested market, charging a
+    /// credit per market.
+    fn echo_batch() -> CannedTransport {
+        CannedTransport::new(|request| {
+            let ids = batch_ids(request);
+            let items: Vec<_> = ids
+                .iter()
+                .map(|id| serde_json::json!({"parcl_id": id, "date": "2024-01-01"}))
+                .collect();
+            json_response(
+                200,
+                &serde_json::json!({
+                    "items": items, "total": ids.len(), "limit": ids.len(), "offset": 0,
+                    "links": {}, "account": {"est_credits_used": ids.len()}
+                }),
+            )
+        })
+    }
+
+    #[tokio::test]
+    async fn oversized_batches_are_split_and_merged() {
+        let transport = echo_batch();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(transport.clone())
+            .with_batch_concurrency(2);
+        let resp = client
+            .market_metrics()
+            .batch_housing_event_counts((0..2500).collect(), None)
+            .await
+            .unwrap();
+
+        let mut sizes: Vec<usize> = transport
+            .requests()
+            .iter()
+            .map(|r| batch_ids(r).len())
+            .collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, [500, 1000, 1000]);
+        assert_eq!((resp.items.len(), resp.total), (2500, 2500));
+        assert!(resp
+            .items
+            .iter()
+            .zip(0..)
+            .all(|(item, id)| item.parcl_id == Some(id)));
+        assert_eq!(resp.meta.attempts_made, 3);
+        assert_eq!(client.session_credits_used(), 2500);
+    }
 
     #[test]
     fn metrics_params_default() {
@@ -427,6 +454,15 @@ mod tests {
         assert!(qs.contains("end_date=2024-06-30"));
     }
 
+    #[test]
+    fn metrics_params_query_values_are_encoded() {
+        let params = MetricsParams::new().start_date("2024-01-01&limit=1000");
+        assert_eq!(
+            params.to_query_string(),
+            "?start_date=2024-01-01%26limit%3D1000"
+        );
+    }
+
     #[test]
     fn metrics_params_query_string_property_type() {
         let params = MetricsParams::new().property_type(PropertyType::Condo);
@@ -462,7 +498,7 @@ mod tests {
     #[test]
     fn metrics_params_batch_body_minimal() {
         let params = MetricsParams::new();
-        let body = params.to_batch_body(&[100, 200]);
+        let body = serde_json::to_value(params.to_batch_body(&[100, 200])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100, 200]));
         assert!(!obj.contains_key("limit"));
@@ -477,7 +513,7 @@ mod tests {
             .start_date("2024-01-01")
             .end_date("2024-12-31")
             .property_type(PropertyType::SingleFamily);
-        let body = params.to_batch_body(&[100]);
+        let body = serde_json::to_value(params.to_batch_body(&[100])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100]));
         assert_eq!(obj["limit"], 10);
@@ -486,4 +522,55 @@ mod tests {
         assert_eq!(obj["end_date"], "2024-12-31");
         assert_eq!(obj["property_type"], "SINGLE_FAMILY");
     }
+
+    /// Answers market searches with two markets named Denver and a
+    /// near miss, and metrics with one item.
+    fn denvers() -> CannedTransport {
+        CannedTransport::new(|request| {
+            let body = if request.uri().path().ends_with("/v1/search/markets") {
+                let market = |id: i64, name: &str, pop: i64| {
+                    serde_json::json!({
+                        "parcl_id": id, "name": name, "state_abbreviation": "CO",
+                        "location_type": "CITY", "total_population": pop
+                    })
+                };
+                serde_json::json!({
+                    "items": [market(1, "Denverton", 900_000), market(2, "Denver", 700_000), market(3, "DENVER", 10)],
+                    "total": 3, "limit": 50, "offset": 0, "links": {}
+                })
+            } else {
+                assert!(request.uri().path().contains("/2/"));
+                serde_json::json!({
+                    "parcl_id": 2, "items": [{"date": "2024-01-01", "sales": 9}],
+                    "total": 1, "limit": 1, "offset": 0, "links": {}
+                })
+            };
+            json_response(200, &body)
+        })
+    }
+
+    #[tokio::test]
+    async fn by_name_resolves_once_then_hits_the_cache() {
+        let transport = denvers();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(transport.clone());
+        for name in ["Denver, CO", "denver"] {
+            let resp = client
+                .market_metrics()
+                .housing_event_counts_by_name(name, None)
+                .await
+                .unwrap();
+            assert_eq!(resp.items[0].sales, Some(9));
+        }
+        let searches = transport.uris();
+        assert_eq!(
+            searches
+                .iter()
+                .filter(|uri| uri.contains("/v1/search/markets"))
+                .count(),
+            1
+        );
+        let err = client.market_by_name("Boulder, CO").await.unwrap_err();
+        assert!(matches!(err, crate::ParclError::MarketNotFound { .. }));
+    }
 }
@@ -1,3 +1,7 @@
+#[macro_use]
+mod macros;
+
+mod batch;
 pub(crate) mod common;
 pub mod for_sale_metrics;
 pub mod investor_metrics;
@@ -6,8 +10,11 @@ pub mod new_construction_metrics;
 pub mod portfolio_metrics;
 pub mod price_feed;
 pub mod property;
+mod query;
 pub mod rental_metrics;
 pub mod search;
+mod stream;
+pub mod sweep;
 
 pub use for_sale_metrics::ForSaleMetricsClient;
 pub use investor_metrics::InvestorMetricsClient;
@@ -18,3 +25,4 @@ pub use price_feed::PriceFeedClient;
 pub use property::PropertyClient;
 pub use rental_metrics::RentalMetricsClient;
 pub use search::{SearchClient, SearchParams};
+pub use sweep::{Sweep, SweepCheckpoint, SweepPage};
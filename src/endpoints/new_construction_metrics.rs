@@ -1,14 +1,15 @@
 //! New construction metrics endpoints for tracking new-build housing data.
 
-use crate::error::Result;
-use crate::models::{
-    BatchMetricsResponse, HousingEventCounts, HousingEventPrices, MetricsResponse, PropertyType,
-};
+use super::batch::{BatchRequest, MetricFilters};
+use super::common::FetchOptions;
+use super::query::Query;
+use crate::models::{HousingEventCounts, HousingEventPrices, PropertyType};
 use crate::ParclClient;
+use std::borrow::Cow;
 
 /// Client for new construction metrics API endpoints.
 pub struct NewConstructionMetricsClient<'a> {
-    client: &'a ParclClient,
+    client: Cow<'a, ParclClient>,
 }
 
 /// Query parameters for new construction metrics requests.
@@ -20,6 +21,8 @@ pub struct NewConstructionMetricsParams {
     pub end_date: Option<String>,
     pub property_type: Option<PropertyType>,
     pub auto_paginate: bool,
+    /// Retry policy, cancellation token and deadline for this request.
+    pub fetch: FetchOptions,
 }
 
 impl NewConstructionMetricsParams {
@@ -63,159 +66,96 @@ impl NewConstructionMetricsParams {
         self
     }
 
+    fetch_option_setters!();
+
     pub(crate) fn to_query_string(&self) -> String {
-        let mut params = Vec::new();
+        let mut query = Query::new();
 
         if let Some(l) = self.limit {
-            params.push(format!("limit={}", l));
+            query.push("limit", l);
         }
         if let Some(o) = self.offset {
-            params.push(format!("offset={}", o));
+            query.push("offset", o);
         }
         if let Some(ref s) = self.start_date {
-            params.push(format!("start_date={}", s));
+            query.push("start_date", s);
         }
         if let Some(ref e) = self.end_date {
-            params.push(format!("end_date={}", e));
+            query.push("end_date", e);
         }
         if let Some(pt) = self.property_type {
-            params.push(format!("property_type={}", pt.as_str()));
+            query.push("property_type", pt.as_str());
         }
 
-        if params.is_empty() {
-            String::new()
-        } else {
-            format!("?{}", params.join("&"))
-        }
+        query.finish()
     }
 
-    pub(crate) fn to_batch_body(&self, parcl_ids: &[i64]) -> serde_json::Value {
-        let mut body = serde_json::json!({ "parcl_id": parcl_ids });
-        let obj = body.as_object_mut().unwrap();
-        if let Some(l) = self.limit {
-            obj.insert("limit".into(), serde_json::json!(l));
-        }
-        if let Some(o) = self.offset {
-            obj.insert("offset".into(), serde_json::json!(o));
-        }
-        if let Some(ref s) = self.start_date {
-            obj.insert("start_date".into(), serde_json::json!(s));
-        }
-        if let Some(ref e) = self.end_date {
-            obj.insert("end_date".into(), serde_json::json!(e));
-        }
-        if let Some(pt) = self.property_type {
-            obj.insert("property_type".into(), serde_json::json!(pt.as_str()));
+    pub(crate) fn to_batch_body<'a>(
+        &'a self,
+        parcl_ids: &'a [i64],
+    ) -> BatchRequest<'a, MetricFilters<'a>> {
+        BatchRequest {
+            parcl_id: parcl_ids,
+            filters: MetricFilters {
+                limit: self.limit,
+                offset: self.offset,
+                start_date: self.start_date.as_deref(),
+                end_date: self.end_date.as_deref(),
+                property_type: self.property_type,
+            },
         }
-        body
     }
 }
 
 impl<'a> NewConstructionMetricsClient<'a> {
-    pub(crate) fn new(client: &'a ParclClient) -> Self {
+    pub(crate) fn new(client: Cow<'a, ParclClient>) -> Self {
         Self { client }
     }
 
-    /// Retrieves new construction housing event counts.
-    pub async fn housing_event_counts(
-        &self,
-        parcl_id: i64,
-        params: Option<NewConstructionMetricsParams>,
-    ) -> Result<MetricsResponse<HousingEventCounts>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/new_construction_metrics/{}/housing_event_counts{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+    define_endpoints! {
+        params = NewConstructionMetricsParams;
 
-    /// Retrieves new construction housing event prices.
-    pub async fn housing_event_prices(
-        &self,
-        parcl_id: i64,
-        params: Option<NewConstructionMetricsParams>,
-    ) -> Result<MetricsResponse<HousingEventPrices>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/new_construction_metrics/{}/housing_event_prices{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves new construction housing event counts.
+        get housing_event_counts(
+            "/v1/new_construction_metrics",
+            "housing_event_counts"
+        ) -> HousingEventCounts;
 
-    // --- Batch POST methods ---
-
-    /// Batch retrieves housing event counts for multiple markets.
-    pub async fn batch_housing_event_counts(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<NewConstructionMetricsParams>,
-    ) -> Result<BatchMetricsResponse<HousingEventCounts>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/new_construction_metrics/housing_event_counts",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves new construction housing event prices.
+        get housing_event_prices(
+            "/v1/new_construction_metrics",
+            "housing_event_prices"
+        ) -> HousingEventPrices;
+
+        // --- Latest-value methods ---
+
+        /// Most recent `housing_event_counts` item for a market.
+        latest housing_event_counts_latest(housing_event_counts) -> HousingEventCounts;
+
+        /// Most recent `housing_event_prices` item for a market.
+        latest housing_event_prices_latest(housing_event_prices) -> HousingEventPrices;
+
+        // --- By-name methods ---
+
+        /// `housing_event_counts` for a market given by name.
+        by_name housing_event_counts_by_name(housing_event_counts) -> HousingEventCounts;
+
+        /// `housing_event_prices` for a market given by name.
+        by_name housing_event_prices_by_name(housing_event_prices) -> HousingEventPrices;
+
+        // --- Batch POST methods ---
+
+        /// Batch retrieves housing event counts for multiple markets.
+        batch batch_housing_event_counts(
+            "/v1/new_construction_metrics",
+            "housing_event_counts"
+        ) -> HousingEventCounts;
 
-    /// Batch retrieves housing event prices for multiple markets.
-    pub async fn batch_housing_event_prices(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<NewConstructionMetricsParams>,
-    ) -> Result<BatchMetricsResponse<HousingEventPrices>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/new_construction_metrics/housing_event_prices",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
+        /// Batch retrieves housing event prices for multiple markets.
+        batch batch_housing_event_prices(
+            "/v1/new_construction_metrics",
+            "housing_event_prices"
+        ) -> HousingEventPrices;
     }
 }
 
@@ -290,7 +230,7 @@ mod tests {
     #[test]
     fn new_construction_params_batch_body_minimal() {
         let params = NewConstructionMetricsParams::new();
-        let body = params.to_batch_body(&[100, 200]);
+        let body = serde_json::to_value(params.to_batch_body(&[100, 200])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100, 200]));
         assert!(!obj.contains_key("limit"));
@@ -304,7 +244,7 @@ mod tests {
             .start_date("2024-01-01")
             .end_date("2024-12-31")
             .property_type(PropertyType::Condo);
-        let body = params.to_batch_body(&[100]);
+        let body = serde_json::to_value(params.to_batch_body(&[100])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100]));
         assert_eq!(obj["limit"], 10);
@@ -1,16 +1,23 @@
 //! Portfolio metrics endpoints for tracking portfolio-level housing data.
 
-use crate::error::Result;
+use super::batch::{BatchRequest, PortfolioFilters};
+use super::common::FetchOptions;
+use super::query::Query;
 use crate::models::{
-    BatchMetricsResponse, MetricsResponse, PortfolioHousingEventCounts,
-    PortfolioNewListingsRollingCounts, PortfolioRentalListingsRollingCounts, PortfolioSize,
-    PortfolioStockOwnership,
+    PortfolioHousingEventCounts, PortfolioNewListingsRollingCounts,
+    PortfolioRentalListingsRollingCounts, PortfolioSize, PortfolioStockOwnership,
 };
 use crate::ParclClient;
+use std::borrow::Cow;
 
 /// Client for portfolio metrics API endpoints.
+///
+/// The API aggregates these series by portfolio size only; it has no
+/// per-entity (`entity_owner_name`) variant. For a single operator's
+/// activity, filter property search by
+/// [`PropertySearchParams::current_entity_owner_name`](crate::PropertySearchParams::current_entity_owner_name).
 pub struct PortfolioMetricsClient<'a> {
-    client: &'a ParclClient,
+    client: Cow<'a, ParclClient>,
 }
 
 /// Query parameters for portfolio metrics requests.
@@ -22,6 +29,8 @@ pub struct PortfolioMetricsParams {
     pub end_date: Option<String>,
     pub portfolio_size: Option<PortfolioSize>,
     pub auto_paginate: bool,
+    /// Retry policy, cancellation token and deadline for this request.
+    pub fetch: FetchOptions,
 }
 
 impl PortfolioMetricsParams {
@@ -65,259 +74,132 @@ impl PortfolioMetricsParams {
         self
     }
 
+    fetch_option_setters!();
+
     pub(crate) fn to_query_string(&self) -> String {
-        let mut params = Vec::new();
+        let mut query = Query::new();
 
         if let Some(l) = self.limit {
-            params.push(format!("limit={}", l));
+            query.push("limit", l);
         }
         if let Some(o) = self.offset {
-            params.push(format!("offset={}", o));
+            query.push("offset", o);
         }
         if let Some(ref s) = self.start_date {
-            params.push(format!("start_date={}", s));
+            query.push("start_date", s);
         }
         if let Some(ref e) = self.end_date {
-            params.push(format!("end_date={}", e));
+            query.push("end_date", e);
         }
         if let Some(ps) = self.portfolio_size {
-            params.push(format!("portfolio_size={}", ps.as_str()));
+            query.push("portfolio_size", ps.as_str());
         }
 
-        if params.is_empty() {
-            String::new()
-        } else {
-            format!("?{}", params.join("&"))
-        }
+        query.finish()
     }
 
-    pub(crate) fn to_batch_body(&self, parcl_ids: &[i64]) -> serde_json::Value {
-        let mut body = serde_json::json!({ "parcl_id": parcl_ids });
-        let obj = body.as_object_mut().unwrap();
-        if let Some(l) = self.limit {
-            obj.insert("limit".into(), serde_json::json!(l));
-        }
-        if let Some(o) = self.offset {
-            obj.insert("offset".into(), serde_json::json!(o));
-        }
-        if let Some(ref s) = self.start_date {
-            obj.insert("start_date".into(), serde_json::json!(s));
+    pub(crate) fn to_batch_body<'a>(
+        &'a self,
+        parcl_ids: &'a [i64],
+    ) -> BatchRequest<'a, PortfolioFilters<'a>> {
+        BatchRequest {
+            parcl_id: parcl_ids,
+            filters: PortfolioFilters {
+                limit: self.limit,
+                offset: self.offset,
+                start_date: self.start_date.as_deref(),
+                end_date: self.end_date.as_deref(),
+                portfolio_size: self.portfolio_size,
+            },
         }
-        if let Some(ref e) = self.end_date {
-            obj.insert("end_date".into(), serde_json::json!(e));
-        }
-        if let Some(ps) = self.portfolio_size {
-            obj.insert("portfolio_size".into(), serde_json::json!(ps.as_str()));
-        }
-        body
     }
 }
 
 impl<'a> PortfolioMetricsClient<'a> {
-    pub(crate) fn new(client: &'a ParclClient) -> Self {
+    pub(crate) fn new(client: Cow<'a, ParclClient>) -> Self {
         Self { client }
     }
 
-    /// Retrieves single-family housing stock ownership by portfolio holders.
-    pub async fn sf_housing_stock_ownership(
-        &self,
-        parcl_id: i64,
-        params: Option<PortfolioMetricsParams>,
-    ) -> Result<MetricsResponse<PortfolioStockOwnership>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/portfolio_metrics/{}/sf_housing_stock_ownership{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+    define_endpoints! {
+        params = PortfolioMetricsParams;
 
-    /// Retrieves single-family housing event counts by portfolio holders.
-    pub async fn sf_housing_event_counts(
-        &self,
-        parcl_id: i64,
-        params: Option<PortfolioMetricsParams>,
-    ) -> Result<MetricsResponse<PortfolioHousingEventCounts>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/portfolio_metrics/{}/sf_housing_event_counts{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves single-family housing stock ownership by portfolio holders.
+        get sf_housing_stock_ownership(
+            "/v1/portfolio_metrics",
+            "sf_housing_stock_ownership"
+        ) -> PortfolioStockOwnership;
 
-    /// Retrieves rolling counts of new for-sale listings by portfolio holders.
-    pub async fn sf_new_listings_for_sale_rolling_counts(
-        &self,
-        parcl_id: i64,
-        params: Option<PortfolioMetricsParams>,
-    ) -> Result<MetricsResponse<PortfolioNewListingsRollingCounts>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/portfolio_metrics/{}/sf_new_listings_for_sale_rolling_counts{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves single-family housing event counts by portfolio holders.
+        get sf_housing_event_counts(
+            "/v1/portfolio_metrics",
+            "sf_housing_event_counts"
+        ) -> PortfolioHousingEventCounts;
 
-    /// Retrieves rolling counts of new rental listings by portfolio holders.
-    pub async fn sf_new_listings_for_rent_rolling_counts(
-        &self,
-        parcl_id: i64,
-        params: Option<PortfolioMetricsParams>,
-    ) -> Result<MetricsResponse<PortfolioRentalListingsRollingCounts>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/portfolio_metrics/{}/sf_new_listings_for_rent_rolling_counts{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves rolling counts of new for-sale listings by portfolio holders.
+        get sf_new_listings_for_sale_rolling_counts(
+            "/v1/portfolio_metrics",
+            "sf_new_listings_for_sale_rolling_counts"
+        ) -> PortfolioNewListingsRollingCounts;
 
-    // --- Batch POST methods ---
-
-    /// Batch retrieves single-family housing stock ownership for multiple markets.
-    pub async fn batch_sf_housing_stock_ownership(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<PortfolioMetricsParams>,
-    ) -> Result<BatchMetricsResponse<PortfolioStockOwnership>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/portfolio_metrics/sf_housing_stock_ownership",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Retrieves rolling counts of new rental listings by portfolio holders.
+        get sf_new_listings_for_rent_rolling_counts(
+            "/v1/portfolio_metrics",
+            "sf_new_listings_for_rent_rolling_counts"
+        ) -> PortfolioRentalListingsRollingCounts;
 
-    /// Batch retrieves single-family housing event counts for multiple markets.
-    pub async fn batch_sf_housing_event_counts(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<PortfolioMetricsParams>,
-    ) -> Result<BatchMetricsResponse<PortfolioHousingEventCounts>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/portfolio_metrics/sf_housing_event_counts",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        // --- Latest-value methods ---
 
-    /// Batch retrieves new for-sale listing rolling counts for multiple markets.
-    pub async fn batch_sf_new_listings_for_sale_rolling_counts(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<PortfolioMetricsParams>,
-    ) -> Result<BatchMetricsResponse<PortfolioNewListingsRollingCounts>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/portfolio_metrics/sf_new_listings_for_sale_rolling_counts",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+        /// Most recent `sf_housing_stock_ownership` item for a market.
+        latest sf_housing_stock_ownership_latest(sf_housing_stock_ownership) -> PortfolioStockOwnership;
+
+        /// Most recent `sf_housing_event_counts` item for a market.
+        latest sf_housing_event_counts_latest(sf_housing_event_counts) -> PortfolioHousingEventCounts;
+
+        /// Most recent `sf_new_listings_for_sale_rolling_counts` item for a market.
+        latest sf_new_listings_for_sale_rolling_counts_latest(sf_new_listings_for_sale_rolling_counts) -> PortfolioNewListingsRollingCounts;
+
+        /// Most recent `sf_new_listings_for_rent_rolling_counts` item for a market.
+        latest sf_new_listings_for_rent_rolling_counts_latest(sf_new_listings_for_rent_rolling_counts) -> PortfolioRentalListingsRollingCounts;
+
+        // --- By-name methods ---
+
+        /// `sf_housing_stock_ownership` for a market given by name.
+        by_name sf_housing_stock_ownership_by_name(sf_housing_stock_ownership) -> PortfolioStockOwnership;
+
+        /// `sf_housing_event_counts` for a market given by name.
+        by_name sf_housing_event_counts_by_name(sf_housing_event_counts) -> PortfolioHousingEventCounts;
+
+        /// `sf_new_listings_for_sale_rolling_counts` for a market given by name.
+        by_name sf_new_listings_for_sale_rolling_counts_by_name(sf_new_listings_for_sale_rolling_counts) -> PortfolioNewListingsRollingCounts;
+
+        /// `sf_new_listings_for_rent_rolling_counts` for a market given by name.
+        by_name sf_new_listings_for_rent_rolling_counts_by_name(sf_new_listings_for_rent_rolling_counts) -> PortfolioRentalListingsRollingCounts;
+
+        // --- Batch POST methods ---
+
+        /// Batch retrieves single-family housing stock ownership for multiple markets.
+        batch batch_sf_housing_stock_ownership(
+            "/v1/portfolio_metrics",
+            "sf_housing_stock_ownership"
+        ) -> PortfolioStockOwnership;
+
+        /// Batch retrieves single-family housing event counts for multiple markets.
+        batch batch_sf_housing_event_counts(
+            "/v1/portfolio_metrics",
+            "sf_housing_event_counts"
+        ) -> PortfolioHousingEventCounts;
+
+        /// Batch retrieves new for-sale listing rolling counts for multiple markets.
+        batch batch_sf_new_listings_for_sale_rolling_counts(
+            "/v1/portfolio_metrics",
+            "sf_new_listings_for_sale_rolling_counts"
+        ) -> PortfolioNewListingsRollingCounts;
 
-    /// Batch retrieves new rental listing rolling counts for multiple markets.
-    pub async fn batch_sf_new_listings_for_rent_rolling_counts(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<PortfolioMetricsParams>,
-    ) -> Result<BatchMetricsResponse<PortfolioRentalListingsRollingCounts>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/portfolio_metrics/sf_new_listings_for_rent_rolling_counts",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
+        /// Batch retrieves new rental listing rolling counts for multiple markets.
+        batch batch_sf_new_listings_for_rent_rolling_counts(
+            "/v1/portfolio_metrics",
+            "sf_new_listings_for_rent_rolling_counts"
+        ) -> PortfolioRentalListingsRollingCounts;
     }
 }
 
@@ -396,7 +278,7 @@ mod tests {
     #[test]
     fn portfolio_params_batch_body_minimal() {
         let params = PortfolioMetricsParams::new();
-        let body = params.to_batch_body(&[100, 200]);
+        let body = serde_json::to_value(params.to_batch_body(&[100, 200])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100, 200]));
         assert!(!obj.contains_key("limit"));
@@ -411,7 +293,7 @@ mod tests {
             .start_date("2024-01-01")
             .end_date("2024-12-31")
             .portfolio_size(PortfolioSize::Portfolio10To99);
-        let body = params.to_batch_body(&[100]);
+        let body = serde_json::to_value(params.to_batch_body(&[100])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100]));
         assert_eq!(obj["limit"], 10);
@@ -1,113 +1,160 @@
 //! Price feed endpoints for Parcl exchange trading data.
+//!
+//! Only some markets have a price feed. Requesting one for any other market
+//! fails with [`ParclError::NoPriceFeed`] rather than the API's bare 4xx, so
+//! multi-market loops can skip it. With
+//! [`ParclClient::with_price_feed_check`], the market is looked up first and
+//! the request is not sent at all.
 
 use crate::endpoints::market_metrics::MetricsParams;
-use crate::error::Result;
-use crate::models::{BatchMetricsResponse, MetricsResponse, PriceFeedEntry};
+use crate::error::{ParclError, Result};
+use crate::models::PriceFeedEntry;
 use crate::ParclClient;
+use std::borrow::Cow;
 
 /// Client for price feed API endpoints.
+///
+/// The API does not filter by feed type; each entry reports its series in
+/// [`PriceFeedEntry::price_feed_type`].
 pub struct PriceFeedClient<'a> {
-    client: &'a ParclClient,
+    client: Cow<'a, ParclClient>,
+}
+
+/// Fails with `NoPriceFeed` if the market is known to have no price feed.
+///
+/// Costs a market search unless the market is cached.
+pub(crate) async fn ensure_feed(client: &ParclClient, parcl_id: i64) -> Result<()> {
+    match client.resolve_market(parcl_id).await? {
+        Some(market) if !market.has_price_feed() => Err(ParclError::NoPriceFeed { parcl_id }),
+        _ => Ok(()),
+    }
+}
+
+/// Replaces a 4xx API error with `NoPriceFeed` when the market turns out to
+/// have no price feed; otherwise returns `err` unchanged.
+pub(crate) async fn explain(client: &ParclClient, parcl_id: i64, err: ParclError) -> ParclError {
+    if !matches!(
+        err,
+        ParclError::ApiError {
+            status: 400..=499,
+            ..
+        }
+    ) {
+        return err;
+    }
+    match ensure_feed(client, parcl_id).await {
+        Err(no_feed @ ParclError::NoPriceFeed { .. }) => no_feed,
+        _ => err,
+    }
 }
 
 impl<'a> PriceFeedClient<'a> {
-    pub(crate) fn new(client: &'a ParclClient) -> Self {
+    pub(crate) fn new(client: Cow<'a, ParclClient>) -> Self {
         Self { client }
     }
 
-    /// Retrieves historical price feed data for a market.
-    pub async fn history(
-        &self,
-        parcl_id: i64,
-        params: Option<MetricsParams>,
-    ) -> Result<MetricsResponse<PriceFeedEntry>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/price_feed/{}/history{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
+    define_endpoints! {
+        params = MetricsParams;
+
+        /// Retrieves historical price feed data for a market.
+        get history("/v1/price_feed", "history") -> PriceFeedEntry;
+
+        /// Retrieves historical rental price feed data for a market.
+        get rental_history("/v1/price_feed", "rental_price_feed") -> PriceFeedEntry;
+
+        // --- Latest-value methods ---
+
+        /// Most recent `history` item for a market.
+        latest history_latest(history) -> PriceFeedEntry;
+
+        /// Most recent `rental_history` item for a market.
+        latest rental_history_latest(rental_history) -> PriceFeedEntry;
+
+        // --- By-name methods ---
+
+        /// `history` for a market given by name.
+        by_name history_by_name(history) -> PriceFeedEntry;
+
+        /// `rental_history` for a market given by name.
+        by_name rental_history_by_name(rental_history) -> PriceFeedEntry;
+
+        // --- Batch POST methods ---
+
+        /// Batch retrieves historical price feed data for multiple markets.
+        batch batch_history("/v1/price_feed", "history") -> PriceFeedEntry;
+
+        /// Batch retrieves historical rental price feed data for multiple markets.
+        batch batch_rental_history("/v1/price_feed", "rental_price_feed") -> PriceFeedEntry;
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Market;
+    use crate::test_util::CannedTransport;
 
-    /// Retrieves historical rental price feed data for a market.
-    pub async fn rental_history(
-        &self,
-        parcl_id: i64,
-        params: Option<MetricsParams>,
-    ) -> Result<MetricsResponse<PriceFeedEntry>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/price_feed/{}/rental_price_feed{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
+    fn market(parcl_id: i64, pricefeed: i32) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "parcl_id": parcl_id,
+            "name": "Test",
+            "location_type": "CITY",
+            "pricefeed_market": pricefeed
+        }))
+        .unwrap()
     }
 
-    // --- Batch POST methods ---
-
-    /// Batch retrieves historical price feed data for multiple markets.
-    pub async fn batch_history(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<MetricsParams>,
-    ) -> Result<BatchMetricsResponse<PriceFeedEntry>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!("{}/v1/price_feed/history", self.client.base_url);
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
+    #[tokio::test]
+    async fn markets_without_a_feed_fail_with_no_price_feed() {
+        let sent = CannedTransport::json(422, serde_json::json!({"detail": "Invalid parcl_id"}));
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(sent.clone());
+        client.market_cache().insert(market(1, 0));
+        client.market_cache().insert(market(2, 1));
+
+        let err = client.price_feed().history(1, None).await.unwrap_err();
+        assert!(matches!(err, ParclError::NoPriceFeed { parcl_id: 1 }));
+        let err = client.price_feed().history(2, None).await.unwrap_err();
+        assert!(matches!(err, ParclError::ApiError { status: 422, .. }));
+        assert_eq!(sent.request_count(), 2);
+
+        let client = client.with_price_feed_check(true);
+        let err = client
+            .price_feed()
+            .rental_history(1, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ParclError::NoPriceFeed { parcl_id: 1 }));
+        assert_eq!(sent.request_count(), 2);
     }
 
-    /// Batch retrieves historical rental price feed data for multiple markets.
-    pub async fn batch_rental_history(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<MetricsParams>,
-    ) -> Result<BatchMetricsResponse<PriceFeedEntry>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!("{}/v1/price_feed/rental_price_feed", self.client.base_url);
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
+    #[tokio::test]
+    async fn a_404_is_no_data_for_metrics_but_not_for_price_feeds() {
+        let sent = CannedTransport::json(404, serde_json::json!({"detail": "Not found"}));
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(sent.clone());
+        client.market_cache().insert(market(1, 0));
+        client.market_cache().insert(market(2, 1));
+
+        let rental = client.rental_metrics().gross_yield(1, None).await.unwrap();
+        assert_eq!(rental.availability(), crate::Availability::NoData);
+        let batch = client
+            .market_metrics()
+            .batch_housing_stock(vec![1, 2], None)
+            .await
+            .unwrap();
+        assert!(batch.is_empty());
+
+        let err = client.price_feed().history(1, None).await.unwrap_err();
+        assert!(matches!(err, ParclError::NoPriceFeed { parcl_id: 1 }));
+        let err = client.price_feed().history(2, None).await.unwrap_err();
+        assert!(matches!(err, ParclError::ApiError { status: 404, .. }));
+        let err = client
+            .price_feed()
+            .batch_history(vec![1, 2], None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ParclError::ApiError { status: 404, .. }));
+        assert_eq!(sent.request_count(), 5);
     }
 }
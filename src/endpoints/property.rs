@@ -1,17 +1,40 @@
 //! Property API endpoints for searching properties and retrieving event history.
 
+use super::common::Received;
+use super::query::Query;
+use super::stream::ArrayItems;
 use crate::error::{ParclError, Result};
+#[cfg(feature = "csv")]
+use crate::models::Property;
 use crate::models::{
-    AddressSearchRequest, EntityOwnerName, EventType, PropertyEventHistoryResponse,
-    PropertySearchResponse, PropertyType, PropertyV2SearchRequest, PropertyV2SearchResponse,
+    AccountInfo, AddressSearchRequest, EntityOwnerName, EventType, PropertyEventHistoryResponse,
+    PropertySearchResponse, PropertySortBy, PropertyType, PropertyV2, PropertyV2SearchRequest,
+    PropertyV2SearchResponse, ResponseMeta, SortOrder,
 };
-use crate::ParclClient;
-use serde::de::DeserializeOwned;
-use serde::Serialize;
+use crate::protocol::{BodyStream, HttpResponse, RequestContext};
+#[cfg(feature = "csv")]
+use crate::CancellationToken;
+use crate::{ParclClient, RetryConfig};
+use chrono::{Months, NaiveDate, Utc};
+use serde::de::{DeserializeOwned, DeserializeSeed};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::marker::PhantomData;
+#[cfg(feature = "csv")]
+use std::time::Instant;
+
+/// Property search response decoded without its items, for counting.
+#[derive(Deserialize)]
+struct PropertyCountResponse {
+    #[serde(default)]
+    total: Option<u64>,
+    #[serde(default)]
+    account: Option<AccountInfo>,
+}
 
 /// Client for property API endpoints.
 pub struct PropertyClient<'a> {
-    client: &'a ParclClient,
+    client: Cow<'a, ParclClient>,
 }
 
 /// Query parameters for the `GET /v1/property/search` endpoint.
@@ -40,6 +63,10 @@ pub struct PropertySearchParams {
     pub current_on_market_rental_flag: Option<bool>,
     pub record_added_date_start: Option<String>,
     pub record_added_date_end: Option<String>,
+    pub sort_by: Option<PropertySortBy>,
+    pub sort_order: Option<SortOrder>,
+    /// Overrides the client's retry policy for this request.
+    pub retry: Option<RetryConfig>,
 }
 
 impl PropertySearchParams {
@@ -69,9 +96,23 @@ impl PropertySearchParams {
             current_on_market_rental_flag: None,
             record_added_date_start: None,
             record_added_date_end: None,
+            sort_by: None,
+            sort_order: None,
+            retry: None,
         }
     }
 
+    /// Overrides the client's retry policy for this request.
+    pub fn retry_config(mut self, config: RetryConfig) -> Self {
+        self.retry = Some(config);
+        self
+    }
+
+    /// Fails immediately on 429 instead of retrying, e.g. for interactive use.
+    pub fn no_retry(self) -> Self {
+        self.retry_config(RetryConfig::none())
+    }
+
     /// Maximum number of results per page.
     pub fn limit(mut self, limit: u32) -> Self {
         self.limit = Some(limit);
@@ -179,77 +220,95 @@ impl PropertySearchParams {
         self
     }
 
+    /// Orders results on the server, so a `limit` returns e.g. the largest
+    /// or newest properties rather than needing to over-fetch and sort.
+    pub fn sort_by(mut self, sort_by: PropertySortBy) -> Self {
+        self.sort_by = Some(sort_by);
+        self
+    }
+
+    pub fn sort_order(mut self, order: SortOrder) -> Self {
+        self.sort_order = Some(order);
+        self
+    }
+
     pub(crate) fn to_query_string(&self) -> String {
-        let mut params = Vec::new();
+        let mut query = Query::new();
 
-        params.push(format!("parcl_id={}", self.parcl_id));
-        params.push(format!("property_type={}", self.property_type.as_str()));
+        query.push("parcl_id", self.parcl_id);
+        query.push("property_type", self.property_type.as_str());
 
         if let Some(l) = self.limit {
-            params.push(format!("limit={}", l));
+            query.push("limit", l);
         }
         if let Some(o) = self.offset {
-            params.push(format!("offset={}", o));
+            query.push("offset", o);
         }
         if let Some(v) = self.square_footage_min {
-            params.push(format!("square_footage_min={}", v));
+            query.push("square_footage_min", v);
         }
         if let Some(v) = self.square_footage_max {
-            params.push(format!("square_footage_max={}", v));
+            query.push("square_footage_max", v);
         }
         if let Some(v) = self.bedrooms_min {
-            params.push(format!("bedrooms_min={}", v));
+            query.push("bedrooms_min", v);
         }
         if let Some(v) = self.bedrooms_max {
-            params.push(format!("bedrooms_max={}", v));
+            query.push("bedrooms_max", v);
         }
         if let Some(v) = self.bathrooms_min {
-            params.push(format!("bathrooms_min={}", v));
+            query.push("bathrooms_min", v);
         }
         if let Some(v) = self.bathrooms_max {
-            params.push(format!("bathrooms_max={}", v));
+            query.push("bathrooms_max", v);
         }
         if let Some(v) = self.year_built_min {
-            params.push(format!("year_built_min={}", v));
+            query.push("year_built_min", v);
         }
         if let Some(v) = self.year_built_max {
-            params.push(format!("year_built_max={}", v));
+            query.push("year_built_max", v);
         }
         if let Some(ref name) = self.current_entity_owner_name {
-            params.push(format!("current_entity_owner_name={}", name.as_str()));
+            query.push("current_entity_owner_name", name.as_str());
         }
         if let Some(v) = self.event_history_sale_flag {
-            params.push(format!("event_history_sale_flag={}", v as i32));
+            query.push("event_history_sale_flag", v as i32);
         }
         if let Some(v) = self.event_history_rental_flag {
-            params.push(format!("event_history_rental_flag={}", v as i32));
+            query.push("event_history_rental_flag", v as i32);
         }
         if let Some(v) = self.event_history_listing_flag {
-            params.push(format!("event_history_listing_flag={}", v as i32));
+            query.push("event_history_listing_flag", v as i32);
         }
         if let Some(v) = self.current_new_construction_flag {
-            params.push(format!("current_new_construction_flag={}", v as i32));
+            query.push("current_new_construction_flag", v as i32);
         }
         if let Some(v) = self.current_owner_occupied_flag {
-            params.push(format!("current_owner_occupied_flag={}", v as i32));
+            query.push("current_owner_occupied_flag", v as i32);
         }
         if let Some(v) = self.current_investor_owned_flag {
-            params.push(format!("current_investor_owned_flag={}", v as i32));
+            query.push("current_investor_owned_flag", v as i32);
         }
         if let Some(v) = self.current_on_market_flag {
-            params.push(format!("current_on_market_flag={}", v as i32));
+            query.push("current_on_market_flag", v as i32);
         }
         if let Some(v) = self.current_on_market_rental_flag {
-            params.push(format!("current_on_market_rental_flag={}", v as i32));
+            query.push("current_on_market_rental_flag", v as i32);
         }
         if let Some(ref d) = self.record_added_date_start {
-            params.push(format!("record_added_date_start={}", d));
+            query.push("record_added_date_start", d);
         }
         if let Some(ref d) = self.record_added_date_end {
-            params.push(format!("record_added_date_end={}", d));
+            query.push("record_added_date_end", d);
+        }
+        if let Some(sort_by) = self.sort_by {
+            query.push("sort_by", sort_by);
+        }
+        if let Some(order) = self.sort_order {
+            query.push("sort_order", order);
         }
 
-        format!("?{}", params.join("&"))
+        query.finish()
     }
 }
 
@@ -263,6 +322,8 @@ pub struct EventHistoryParams {
     pub entity_owner_name: Option<EntityOwnerName>,
     pub record_updated_date_start: Option<String>,
     pub record_updated_date_end: Option<String>,
+    /// Overrides the client's retry policy for this request.
+    pub retry: Option<RetryConfig>,
 }
 
 impl EventHistoryParams {
@@ -276,6 +337,7 @@ impl EventHistoryParams {
             entity_owner_name: None,
             record_updated_date_start: None,
             record_updated_date_end: None,
+            retry: None,
         }
     }
 
@@ -309,6 +371,65 @@ impl EventHistoryParams {
         self
     }
 
+    /// Overrides the client's retry policy for this request.
+    pub fn retry_config(mut self, config: RetryConfig) -> Self {
+        self.retry = Some(config);
+        self
+    }
+
+    /// Fails immediately on 429 instead of retrying, e.g. for interactive use.
+    pub fn no_retry(self) -> Self {
+        self.retry_config(RetryConfig::none())
+    }
+
+    /// Typed alternative to [`start_date`](Self::start_date).
+    pub fn since(self, date: NaiveDate) -> Self {
+        self.start_date(date.format(DATE_FORMAT).to_string())
+    }
+
+    /// Typed alternative to [`end_date`](Self::end_date).
+    pub fn until(self, date: NaiveDate) -> Self {
+        self.end_date(date.format(DATE_FORMAT).to_string())
+    }
+
+    /// Limits events to the last `years` years, ending today (UTC).
+    pub fn last_years(self, years: u32) -> Self {
+        self.last_months(years.saturating_mul(12))
+    }
+
+    /// Limits events to the last `months` months, ending today (UTC).
+    pub fn last_months(self, months: u32) -> Self {
+        self.months_ending(months, Utc::now().date_naive())
+    }
+
+    fn months_ending(self, months: u32, end: NaiveDate) -> Self {
+        let start = end
+            .checked_sub_months(Months::new(months))
+            .unwrap_or(NaiveDate::MIN);
+        self.since(start).until(end)
+    }
+
+    /// Checks the request against the API's limits before it is sent.
+    ///
+    /// Requires 1 to 1000 property IDs, `YYYY-MM-DD` dates, and ordered
+    /// event and record-updated windows. Called by
+    /// [`PropertyClient::event_history`].
+    pub fn validate(&self) -> Result<()> {
+        let ids = self.parcl_property_ids.len();
+        if ids == 0 || ids > MAX_EVENT_HISTORY_IDS {
+            return Err(ParclError::Validation(format!(
+                "event history takes 1 to {MAX_EVENT_HISTORY_IDS} property IDs, got {ids}"
+            )));
+        }
+        check_window("start_date", &self.start_date, "end_date", &self.end_date)?;
+        check_window(
+            "record_updated_date_start",
+            &self.record_updated_date_start,
+            "record_updated_date_end",
+            &self.record_updated_date_end,
+        )
+    }
+
     fn to_request_body(&self) -> serde_json::Value {
         let mut body = serde_json::json!({
             "parcl_property_id": self.parcl_property_ids,
@@ -338,8 +459,196 @@ impl EventHistoryParams {
     }
 }
 
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Maximum property IDs per event history request.
+const MAX_EVENT_HISTORY_IDS: usize = 1000;
+
+fn parse_date(field: &str, value: &Option<String>) -> Result<Option<NaiveDate>> {
+    value
+        .as_deref()
+        .map(|v| {
+            NaiveDate::parse_from_str(v, DATE_FORMAT).map_err(|_| {
+                ParclError::Validation(format!("{field} must be YYYY-MM-DD, got `{v}`"))
+            })
+        })
+        .transpose()
+}
+
+fn check_window(
+    start_field: &str,
+    start: &Option<String>,
+    end_field: &str,
+    end: &Option<String>,
+) -> Result<()> {
+    let start = parse_date(start_field, start)?;
+    let end = parse_date(end_field, end)?;
+    match (start, end) {
+        (Some(start), Some(end)) if start > end => Err(ParclError::Validation(format!(
+            "{start_field} {start} is after {end_field} {end}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Options for [`PropertyClient::search_by_address_csv`].
+#[cfg(feature = "csv")]
+#[derive(Debug, Clone)]
+pub struct AddressCsvOptions {
+    /// Maximum number of addresses sent per request.
+    pub chunk_size: usize,
+    /// Delay between consecutive chunk requests in milliseconds.
+    pub throttle_ms: u64,
+    /// Stops sending chunks once cancelled.
+    pub cancel: Option<CancellationToken>,
+    /// Stops sending chunks once this time has passed.
+    pub deadline: Option<Instant>,
+}
+
+#[cfg(feature = "csv")]
+impl Default for AddressCsvOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: 100,
+            throttle_ms: 250,
+            cancel: None,
+            deadline: None,
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl AddressCsvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of addresses sent per request (minimum 1).
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Delay between consecutive chunk requests in milliseconds.
+    pub fn throttle_ms(mut self, throttle_ms: u64) -> Self {
+        self.throttle_ms = throttle_ms;
+        self
+    }
+
+    /// Stops sending chunks once `token` is cancelled, returning the rows
+    /// matched so far. Compare the result's length with the input to tell a
+    /// cancelled run from a complete one.
+    pub fn cancel_token(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Stops sending chunks once `deadline` has passed, returning the rows
+    /// matched by then. Resume with the unsent rows, starting at the
+    /// result's length.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+}
+
+/// A CSV input row paired with the property it resolved to, if any.
+#[cfg(feature = "csv")]
+#[derive(Debug, Clone)]
+pub struct AddressMatch {
+    /// Zero-based index of the data row in the input CSV (header excluded).
+    pub row: usize,
+    /// The address parsed from the row.
+    pub request: AddressSearchRequest,
+    /// The matched property, or `None` if the API returned no match.
+    pub property: Option<Property>,
+}
+
+/// Parses address rows from CSV input with a header row.
+///
+/// Recognized columns (case-insensitive): `address`/`street`, `city`,
+/// `state_abbreviation`/`state`, and `zip_code`/`zip`/`zip5`.
+#[cfg(feature = "csv")]
+pub(crate) fn parse_address_csv<R: std::io::Read>(reader: R) -> Result<Vec<AddressSearchRequest>> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers: Vec<String> = rdr
+        .headers()?
+        .iter()
+        .map(|h| h.to_ascii_lowercase())
+        .collect();
+
+    let column = |names: &[&str]| -> Result<usize> {
+        headers
+            .iter()
+            .position(|h| names.contains(&h.as_str()))
+            .ok_or_else(|| {
+                ParclError::InvalidParameter(format!("CSV is missing a `{}` column", names[0]))
+            })
+    };
+    let address_col = column(&["address", "street"])?;
+    let city_col = column(&["city"])?;
+    let state_col = column(&["state_abbreviation", "state"])?;
+    let zip_col = column(&["zip_code", "zip", "zip5"])?;
+
+    let mut requests = Vec::new();
+    for record in rdr.records() {
+        let record = record?;
+        let field = |i: usize| record.get(i).unwrap_or_default().to_string();
+        requests.push(AddressSearchRequest {
+            address: field(address_col),
+            city: field(city_col),
+            state_abbreviation: field(state_col).to_uppercase(),
+            zip_code: field(zip_col),
+        });
+    }
+    Ok(requests)
+}
+
+/// Normalized join key for reconciling results with input rows.
+#[cfg(feature = "csv")]
+fn address_key(address: &str, zip_code: &str) -> String {
+    let address = address
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase();
+    let zip: String = zip_code.trim().chars().take(5).collect();
+    format!("{}|{}", address, zip)
+}
+
+/// Pairs each input row of a chunk with the returned property matching its address.
+#[cfg(feature = "csv")]
+fn join_address_matches(
+    first_row: usize,
+    requests: &[AddressSearchRequest],
+    properties: &[Property],
+) -> Vec<AddressMatch> {
+    let by_key: std::collections::HashMap<String, &Property> = properties
+        .iter()
+        .filter_map(|p| {
+            let address = p.address.as_deref()?;
+            let zip = p.zip_code.as_deref().unwrap_or_default();
+            Some((address_key(address, zip), p))
+        })
+        .collect();
+
+    requests
+        .iter()
+        .enumerate()
+        .map(|(i, req)| AddressMatch {
+            row: first_row + i,
+            request: req.clone(),
+            property: by_key
+                .get(&address_key(&req.address, &req.zip_code))
+                .map(|p| (*p).clone()),
+        })
+        .collect()
+}
+
 impl<'a> PropertyClient<'a> {
-    pub(crate) fn new(client: &'a ParclClient) -> Self {
+    pub(crate) fn new(client: Cow<'a, ParclClient>) -> Self {
         Self { client }
     }
 
@@ -349,11 +658,36 @@ impl<'a> PropertyClient<'a> {
     pub async fn search(&self, params: PropertySearchParams) -> Result<PropertySearchResponse> {
         let query = params.to_query_string();
         let url = format!("{}/v1/property/search{}", self.client.base_url, query);
-        let resp: PropertySearchResponse = self.fetch_get(&url).await?;
+        let (mut resp, meta): (PropertySearchResponse, _) = self
+            .fetch_get("/v1/property/search", &url, params.retry.as_ref())
+            .await?;
+        resp.meta = meta;
         self.client.update_credits(&resp.account);
         Ok(resp)
     }
 
+    /// Counts properties matching `params` without downloading them.
+    ///
+    /// Requests a single result and returns the total the API reports, or
+    /// `None` if the response carries no total. Any `limit`/`offset` in
+    /// `params` is ignored.
+    ///
+    /// `GET /v1/property/search`
+    pub async fn search_count(&self, params: PropertySearchParams) -> Result<Option<u64>> {
+        let mut params = params.limit(1);
+        params.offset = None;
+        let url = format!(
+            "{}/v1/property/search{}",
+            self.client.base_url,
+            params.to_query_string()
+        );
+        let (resp, _): (PropertyCountResponse, _) = self
+            .fetch_get("/v1/property/search", &url, params.retry.as_ref())
+            .await?;
+        self.client.update_credits(&resp.account);
+        Ok(resp.total)
+    }
+
     /// Look up property IDs by street address.
     ///
     /// `POST /v1/property/search_address`
@@ -362,11 +696,65 @@ impl<'a> PropertyClient<'a> {
         addresses: Vec<AddressSearchRequest>,
     ) -> Result<PropertySearchResponse> {
         let url = format!("{}/v1/property/search_address", self.client.base_url);
-        let resp: PropertySearchResponse = self.fetch_post(&url, &addresses).await?;
+        let (mut resp, meta): (PropertySearchResponse, _) = self
+            .fetch_post("/v1/property/search_address", &url, &addresses, None)
+            .await?;
+        resp.meta = meta;
         self.client.update_credits(&resp.account);
         Ok(resp)
     }
 
+    /// Look up property IDs for every address row in a CSV file.
+    ///
+    /// Rows are sent in chunks of `chunk_size` addresses, pausing `throttle_ms`
+    /// between requests. Results are returned in input order with the original
+    /// row index attached for reconciliation.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use parcllabs::ParclClient;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ParclClient::new()?;
+    /// let file = std::fs::File::open("addresses.csv")?;
+    /// let matches = client.property().search_by_address_csv(file, None).await?;
+    /// for m in matches.iter().filter(|m| m.property.is_none()) {
+    ///     println!("row {} unmatched: {}", m.row, m.request.address);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "csv")]
+    pub async fn search_by_address_csv<R: std::io::Read>(
+        &self,
+        reader: R,
+        options: Option<AddressCsvOptions>,
+    ) -> Result<Vec<AddressMatch>> {
+        let options = options.unwrap_or_default();
+        let requests = parse_address_csv(reader)?;
+        let mut matches = Vec::with_capacity(requests.len());
+
+        for (i, chunk) in requests.chunks(options.chunk_size.max(1)).enumerate() {
+            let cancelled = options.cancel.as_ref().is_some_and(|t| t.is_cancelled());
+            let expired = options
+                .deadline
+                .is_some_and(|d| self.client.timer.now() >= d);
+            if cancelled || expired {
+                break;
+            }
+            if i > 0 && options.throttle_ms > 0 {
+                self.client
+                    .timer
+                    .sleep(std::time::Duration::from_millis(options.throttle_ms))
+                    .await;
+            }
+            let resp = self.search_by_address(chunk.to_vec()).await?;
+            matches.extend(join_address_matches(matches.len(), chunk, &resp.items));
+        }
+
+        Ok(matches)
+    }
+
     /// Get event history for a list of property IDs.
     ///
     /// `POST /v1/property/event_history`
@@ -374,9 +762,19 @@ impl<'a> PropertyClient<'a> {
         &self,
         params: EventHistoryParams,
     ) -> Result<PropertyEventHistoryResponse> {
+        params.validate()?;
         let url = format!("{}/v1/property/event_history", self.client.base_url);
         let body = params.to_request_body();
-        self.fetch_post(&url, &body).await
+        let (mut resp, meta): (PropertyEventHistoryResponse, _) = self
+            .fetch_post(
+                "/v1/property/event_history",
+                &url,
+                &body,
+                params.retry.as_ref(),
+            )
+            .await?;
+        resp.meta = meta;
+        Ok(resp)
     }
 
     /// Advanced property search with nested filters (v2).
@@ -388,99 +786,227 @@ impl<'a> PropertyClient<'a> {
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> Result<PropertyV2SearchResponse> {
-        let mut query_parts = Vec::new();
+        let url = self.search_v2_url(limit, offset);
+        let (mut resp, meta): (PropertyV2SearchResponse, _) = self
+            .fetch_post("/v2/property_search", &url, &request, None)
+            .await?;
+        resp.meta = meta;
+        Ok(resp)
+    }
+
+    /// Like [`search_v2`](Self::search_v2), but passes each property to `f`
+    /// as soon as it is parsed instead of collecting the page.
+    ///
+    /// Large pages can be tens of megabytes; this decodes the body as it
+    /// arrives and keeps one `PropertyV2` alive at a time rather than the
+    /// whole page, so bulk exports can write rows out as they go. The first error returned by
+    /// `f` stops parsing and is returned. Returns the number of properties
+    /// passed to `f`, with retry telemetry.
+    ///
+    /// ```no_run
+    /// # use parcllabs::{ParclClient, PropertyV2SearchRequest};
+    /// # async fn example(client: ParclClient, request: PropertyV2SearchRequest) -> parcllabs::Result<()> {
+    /// let mut out = std::io::BufWriter::new(std::fs::File::create("properties.jsonl")?);
+    /// let (count, _meta) = client
+    ///     .property()
+    ///     .search_v2_each(request, Some(50_000), None, |property| {
+    ///         serde_json::to_writer(&mut out, &property)?;
+    ///         std::io::Write::write_all(&mut out, b"\n")?;
+    ///         Ok(())
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// `POST /v2/property_search`
+    pub async fn search_v2_each(
+        &self,
+        request: PropertyV2SearchRequest,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        f: impl FnMut(PropertyV2) -> Result<()>,
+    ) -> Result<(usize, ResponseMeta)> {
+        self.search_v2_each_as(request, limit, offset, f).await
+    }
+
+    /// Like [`search_v2_each`](Self::search_v2_each), decoding each property
+    /// into a caller-defined type.
+    ///
+    /// The API has no field selection, so this is how to slim down a
+    /// memory-sensitive pipeline: fields missing from `T` are skipped by the
+    /// parser without being allocated.
+    ///
+    /// ```no_run
+    /// # use parcllabs::{ParclClient, PropertyV2SearchRequest};
+    /// #[derive(serde::Deserialize)]
+    /// struct Id {
+    ///     parcl_property_id: i64,
+    /// }
+    ///
+    /// # async fn example(client: ParclClient, request: PropertyV2SearchRequest) -> parcllabs::Result<()> {
+    /// let mut ids = Vec::new();
+    /// client
+    ///     .property()
+    ///     .search_v2_each_as(request, Some(50_000), None, |p: Id| {
+    ///         ids.push(p.parcl_property_id);
+    ///         Ok(())
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// `POST /v2/property_search`
+    pub async fn search_v2_each_as<T: DeserializeOwned>(
+        &self,
+        request: PropertyV2SearchRequest,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        f: impl FnMut(T) -> Result<()>,
+    ) -> Result<(usize, ResponseMeta)> {
+        let items = ArrayItems::<PhantomData<T>>::new("properties");
+        self.search_v2_stream(request, limit, offset, items, f)
+            .await
+    }
+
+    /// Like [`search_v2_each`](Self::search_v2_each), passing each property
+    /// as a JSON object holding only the top-level `fields` listed, e.g.
+    /// `&["parcl_property_id", "property_metadata"]`.
+    ///
+    /// `POST /v2/property_search`
+    pub async fn search_v2_fields(
+        &self,
+        request: PropertyV2SearchRequest,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        fields: &[&str],
+        f: impl FnMut(serde_json::Map<String, serde_json::Value>) -> Result<()>,
+    ) -> Result<(usize, ResponseMeta)> {
+        let items = ArrayItems::projected("properties", fields);
+        self.search_v2_stream(request, limit, offset, items, f)
+            .await
+    }
+
+    /// Sends a v2 search and decodes the `properties` array with `items` as
+    /// the body arrives, passing each element to `f`.
+    ///
+    /// Falls back to the buffered body when the client records raw bytes
+    /// (audit log, `keep_raw`, content hashing), since those need all of it.
+    async fn search_v2_stream<S, F>(
+        &self,
+        request: PropertyV2SearchRequest,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        items: ArrayItems<S>,
+        f: F,
+    ) -> Result<(usize, ResponseMeta)>
+    where
+        S: for<'de> DeserializeSeed<'de> + Copy,
+        F: for<'de> FnMut(<S as DeserializeSeed<'de>>::Value) -> Result<()>,
+    {
+        let endpoint = "/v2/property_search";
+        let url = self.search_v2_url(limit, offset);
+        let mut body = serde_json::to_value(&request)?;
+        let policy = self.client.limit_policy;
+        crate::limits::apply_to_body(policy, endpoint, &mut body)?;
+        let url = crate::limits::apply_to_url(policy, endpoint, &url)?;
+        let (received, mut meta) =
+            super::common::send_streaming(&self.client, &url, Some(&body), None).await?;
+        let streaming = matches!(received, Received::Streaming(_));
+        let response = match received {
+            Received::Streaming(response) => response,
+            Received::Buffered(response) => {
+                self.client
+                    .response_limits
+                    .check(endpoint, meta.bytes_received, 0)?;
+                crate::protocol::check_status(
+                    &response,
+                    RequestContext::new(endpoint),
+                    meta.attempts_made,
+                )?;
+                response.map(|b| Box::new(Some(b)) as Box<dyn BodyStream>)
+            }
+        };
+        let mut streamed = 0;
+        let limits = &self.client.response_limits;
+        let count = super::stream::drain(
+            &mut *response.into_body(),
+            items,
+            |received| {
+                streamed = received;
+                limits.check(endpoint, received, 0)
+            },
+            f,
+        )
+        .await?;
+        if streaming {
+            meta.bytes_received = streamed;
+        }
+        Ok((count, meta))
+    }
+
+    fn search_v2_url(&self, limit: Option<u32>, offset: Option<u32>) -> String {
+        let mut query = Query::new();
         if let Some(l) = limit {
-            query_parts.push(format!("limit={}", l));
+            query.push("limit", l);
         }
         if let Some(o) = offset {
-            query_parts.push(format!("offset={}", o));
+            query.push("offset", o);
         }
-        let query = if query_parts.is_empty() {
-            String::new()
-        } else {
-            format!("?{}", query_parts.join("&"))
-        };
-        let url = format!("{}/v2/property_search{}", self.client.base_url, query);
-        self.fetch_post(&url, &request).await
-    }
-
-    async fn fetch_get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
-        for attempt in 0..=self.client.retry_config.max_retries {
-            let response = self
-                .client
-                .http
-                .get(url)
-                .header("Authorization", &self.client.api_key)
-                .send()
-                .await?;
-
-            let status = response.status();
-            if status.as_u16() == 429 && attempt < self.client.retry_config.max_retries {
-                let backoff = self.client.retry_config.initial_backoff_ms * 2u64.pow(attempt);
-                tokio::time::sleep(std::time::Duration::from_millis(backoff)).await;
-                continue;
-            }
-
-            if !status.is_success() {
-                let message = response.text().await.unwrap_or_default();
-                if status.as_u16() == 429 {
-                    return Err(ParclError::RateLimited {
-                        attempts: attempt + 1,
-                        message,
-                    });
-                }
-                return Err(ParclError::ApiError {
-                    status: status.as_u16(),
-                    message,
-                });
-            }
+        format!(
+            "{}/v2/property_search{}",
+            self.client.base_url,
+            query.finish()
+        )
+    }
 
-            let data: T = response.json().await?;
-            return Ok(data);
-        }
-        unreachable!()
+    async fn fetch_get<T: DeserializeOwned>(
+        &self,
+        endpoint: &'static str,
+        url: &str,
+        retry: Option<&RetryConfig>,
+    ) -> Result<(T, ResponseMeta)> {
+        let url = crate::limits::apply_to_url(self.client.limit_policy, endpoint, url)?;
+        let (response, meta) = super::common::send(&self.client, &url, None, retry).await?;
+        let data = super::common::decode(
+            &self.client,
+            &response,
+            &meta,
+            RequestContext::new(endpoint),
+        )?;
+        Ok((data, meta))
     }
 
     async fn fetch_post<B: Serialize, T: DeserializeOwned>(
         &self,
+        endpoint: &'static str,
         url: &str,
         body: &B,
-    ) -> Result<T> {
-        for attempt in 0..=self.client.retry_config.max_retries {
-            let response = self
-                .client
-                .http
-                .post(url)
-                .header("Authorization", &self.client.api_key)
-                .json(body)
-                .send()
-                .await?;
-
-            let status = response.status();
-            if status.as_u16() == 429 && attempt < self.client.retry_config.max_retries {
-                let backoff = self.client.retry_config.initial_backoff_ms * 2u64.pow(attempt);
-                tokio::time::sleep(std::time::Duration::from_millis(backoff)).await;
-                continue;
-            }
-
-            if !status.is_success() {
-                let message = response.text().await.unwrap_or_default();
-                if status.as_u16() == 429 {
-                    return Err(ParclError::RateLimited {
-                        attempts: attempt + 1,
-                        message,
-                    });
-                }
-                return Err(ParclError::ApiError {
-                    status: status.as_u16(),
-                    message,
-                });
-            }
+        retry: Option<&RetryConfig>,
+    ) -> Result<(T, ResponseMeta)> {
+        let (response, meta) = self.send_post(endpoint, url, body, retry).await?;
+        let data = super::common::decode(
+            &self.client,
+            &response,
+            &meta,
+            RequestContext::new(endpoint),
+        )?;
+        Ok((data, meta))
+    }
 
-            let data: T = response.json().await?;
-            return Ok(data);
-        }
-        unreachable!()
+    async fn send_post<B: Serialize>(
+        &self,
+        endpoint: &'static str,
+        url: &str,
+        body: &B,
+        retry: Option<&RetryConfig>,
+    ) -> Result<(HttpResponse, ResponseMeta)> {
+        let mut body = serde_json::to_value(body)?;
+        let policy = self.client.limit_policy;
+        crate::limits::apply_to_body(policy, endpoint, &mut body)?;
+        let url = crate::limits::apply_to_url(policy, endpoint, url)?;
+        super::common::send(&self.client, &url, Some(&body), retry).await
     }
 }
 
@@ -525,6 +1051,25 @@ mod tests {
         assert!(qs.contains("current_on_market_flag=0"));
     }
 
+    #[test]
+    fn property_search_params_sort() {
+        let qs = PropertySearchParams::new(1, PropertyType::SingleFamily)
+            .sort_by(PropertySortBy::SquareFootage)
+            .sort_order(SortOrder::Desc)
+            .to_query_string();
+        assert!(qs.ends_with("&sort_by=SQUARE_FOOTAGE&sort_order=DESC"));
+    }
+
+    #[test]
+    fn property_count_response_skips_items() {
+        let json = r#"{"items":[{"parcl_property_id":1},{"unexpected":true}],"total":4821,"account":null}"#;
+        let resp: PropertyCountResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.total, Some(4821));
+
+        let resp: PropertyCountResponse = serde_json::from_str(r#"{"items":[]}"#).unwrap();
+        assert_eq!(resp.total, None);
+    }
+
     #[test]
     fn property_search_params_bool_flags_as_int() {
         let params = PropertySearchParams::new(123, PropertyType::AllProperties)
@@ -571,6 +1116,101 @@ mod tests {
         assert_eq!(body["entity_owner_name"], "AMH");
     }
 
+    #[cfg(feature = "csv")]
+    #[test]
+    fn parse_address_csv_with_aliases() {
+        let input = "Street,City,State,Zip\n1225 W School St,Chicago,il,60657\n";
+        let rows = parse_address_csv(input.as_bytes()).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].address, "1225 W School St");
+        assert_eq!(rows[0].state_abbreviation, "IL");
+        assert_eq!(rows[0].zip_code, "60657");
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn parse_address_csv_missing_column() {
+        let input = "address,city,state\n1 Main St,Austin,TX\n";
+        let err = parse_address_csv(input.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("zip_code"));
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn join_address_matches_by_normalized_key() {
+        let requests = vec![
+            AddressSearchRequest {
+                address: "1225  w school st".into(),
+                city: "CHICAGO".into(),
+                state_abbreviation: "IL".into(),
+                zip_code: "60657".into(),
+            },
+            AddressSearchRequest {
+                address: "1 NOWHERE RD".into(),
+                city: "CHICAGO".into(),
+                state_abbreviation: "IL".into(),
+                zip_code: "60657".into(),
+            },
+        ];
+        let property: Property = serde_json::from_value(serde_json::json!({
+            "parcl_property_id": 63325076,
+            "address": "1225 W SCHOOL ST",
+            "zip_code": "60657-1234"
+        }))
+        .unwrap();
+
+        let matches = join_address_matches(10, &requests, &[property]);
+        assert_eq!(matches[0].row, 10);
+        assert_eq!(
+            matches[0].property.as_ref().unwrap().parcl_property_id,
+            63325076
+        );
+        assert_eq!(matches[1].row, 11);
+        assert!(matches[1].property.is_none());
+    }
+
+    #[test]
+    fn event_history_params_typed_dates() {
+        let end = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+        let params = EventHistoryParams::new(vec![1]).months_ending(60, end);
+        assert_eq!(params.start_date, Some("2019-03-31".into()));
+        assert_eq!(params.end_date, Some("2024-03-31".into()));
+
+        let params = EventHistoryParams::new(vec![1]).last_years(5);
+        assert!(params.start_date < params.end_date);
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn event_history_params_validation() {
+        let err = EventHistoryParams::new(vec![1])
+            .start_date("2024-06-01")
+            .end_date("2024-01-01")
+            .validate()
+            .unwrap_err();
+        assert!(matches!(err, ParclError::Validation(_)));
+        assert_eq!(
+            err.to_string(),
+            "Invalid request parameters: start_date 2024-06-01 is after end_date 2024-01-01"
+        );
+
+        let err = EventHistoryParams::new(vec![1])
+            .record_updated_date_end("01/02/2024")
+            .validate()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("record_updated_date_end must be YYYY-MM-DD"));
+
+        assert!(EventHistoryParams::new(vec![]).validate().is_err());
+        assert!(EventHistoryParams::new(vec![0; 1001]).validate().is_err());
+        assert!(EventHistoryParams::new(vec![1])
+            .start_date("2024-01-01")
+            .end_date("2024-01-01")
+            .validate()
+            .is_ok());
+    }
+
     #[test]
     fn event_history_params_record_updated_dates() {
         let params = EventHistoryParams::new(vec![1])
@@ -581,4 +1221,153 @@ mod tests {
         assert_eq!(body["record_updated_date_start"], "2024-06-01");
         assert_eq!(body["record_updated_date_end"], "2024-12-31");
     }
+
+    /// Serves a v2 search page of three properties, or a 404.
+    fn v2_page(status: u16) -> crate::test_util::CannedTransport {
+        crate::test_util::CannedTransport::new(move |request| {
+            assert!(request.uri().path().ends_with("/v2/property_search"));
+            let body = if status == 200 {
+                serde_json::json!({
+                    "properties": [
+                        {"parcl_property_id": 1, "events": []},
+                        {"parcl_property_id": 2},
+                        {"parcl_property_id": 3}
+                    ],
+                    "metadata": {}
+                })
+            } else {
+                serde_json::json!({"detail": "boom"})
+            };
+            crate::test_util::json_response(status, &body)
+        })
+    }
+
+    #[tokio::test]
+    async fn search_v2_each_streams_properties() {
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(v2_page(200));
+        let mut ids = Vec::new();
+        let (count, _) = client
+            .property()
+            .search_v2_each(PropertyV2SearchRequest::default(), Some(3), None, |p| {
+                ids.push(p.parcl_property_id);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!((count, ids), (3, vec![1, 2, 3]));
+
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(v2_page(404));
+        let err = client
+            .property()
+            .search_v2_each(PropertyV2SearchRequest::default(), None, None, |_| Ok(()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ParclError::ApiError { status: 404, .. }));
+    }
+
+    #[tokio::test]
+    async fn search_v2_fields_projects_each_property() {
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(v2_page(200));
+        let mut kept = Vec::new();
+        client
+            .property()
+            .search_v2_fields(
+                PropertyV2SearchRequest::default(),
+                None,
+                None,
+                &["parcl_property_id"],
+                |p| {
+                    kept.push(p);
+                    Ok(())
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(kept.len(), 3);
+        assert_eq!(kept[2]["parcl_property_id"], 3);
+        assert_eq!(kept[0].len(), 1);
+    }
+
+    /// Sends the v2 search body in fixed chunks, logging each one it hands out.
+    struct ChunkedTransport {
+        chunks: Vec<&'static str>,
+        log: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    struct LoggedChunks {
+        chunks: std::vec::IntoIter<&'static str>,
+        log: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl crate::protocol::BodyStream for LoggedChunks {
+        fn next_chunk(&mut self) -> crate::protocol::ChunkFuture<'_> {
+            let chunk = self.chunks.next().map(|c| {
+                self.log.lock().unwrap().push(format!("chunk {c}"));
+                c.as_bytes().to_vec()
+            });
+            Box::pin(async move { Ok(chunk) })
+        }
+    }
+
+    impl crate::protocol::Transport for ChunkedTransport {
+        fn execute(&self, _: crate::protocol::HttpRequest) -> crate::protocol::TransportFuture<'_> {
+            unreachable!("search_v2_each reads the body as a stream")
+        }
+
+        fn execute_streaming(
+            &self,
+            _: crate::protocol::HttpRequest,
+        ) -> crate::protocol::StreamingFuture<'_> {
+            let body = LoggedChunks {
+                chunks: self.chunks.clone().into_iter(),
+                log: self.log.clone(),
+            };
+            Box::pin(async move {
+                Ok(http::Response::new(
+                    Box::new(body) as Box<dyn crate::protocol::BodyStream>
+                ))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn search_v2_each_yields_properties_before_the_body_completes() {
+        let chunks = vec![
+            r#"{"properties": [{"parcl_property_id": 1},"#,
+            r#" {"parcl_property_id": 2}, {"parcl_"#,
+            r#"property_id": 3}], "metadata": {}}"#,
+        ];
+        let body_len = chunks.iter().map(|c| c.len() as u64).sum::<u64>();
+        let log = std::sync::Arc::default();
+        let transport = ChunkedTransport {
+            chunks,
+            log: std::sync::Arc::clone(&log),
+        };
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(transport);
+        let (count, meta) = client
+            .property()
+            .search_v2_each(PropertyV2SearchRequest::default(), None, None, |p| {
+                log.lock()
+                    .unwrap()
+                    .push(format!("property {}", p.parcl_property_id));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(meta.bytes_received, body_len);
+        let log = log.lock().unwrap();
+        let order: Vec<&str> = log
+            .iter()
+            .map(|entry| entry.split_once(' ').map_or("", |(kind, _)| kind))
+            .collect();
+        assert_eq!(
+            order,
+            ["chunk", "property", "chunk", "property", "chunk", "property"]
+        );
+    }
 }
@@ -0,0 +1,101 @@
+//! Query-string building shared by every params builder.
+
+use std::fmt::{Display, Write};
+
+/// Collects `key=value` pairs, percent-encoding each value.
+#[derive(Debug, Default)]
+pub(crate) struct Query(Vec<String>);
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, key: &str, value: impl Display) {
+        self.0.push(format!("{key}={}", encode(&value.to_string())));
+    }
+
+    /// `?` followed by the pairs joined with `&`, or an empty string.
+    pub fn finish(self) -> String {
+        if self.0.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", self.0.join("&"))
+        }
+    }
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters, so the
+/// result is safe as a query value: spaces become `%20`, and `&`, `=`, `+`,
+/// `#`, `/` and non-ASCII bytes are escaped.
+pub(crate) fn encode(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => {
+                let _ = write!(encoded, "%{byte:02X}");
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Reverses [`encode`], for checking round trips.
+    fn decode(input: &str) -> Vec<u8> {
+        let bytes = input.as_bytes();
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'%' {
+                out.push(u8::from_str_radix(&input[i + 1..i + 3], 16).unwrap());
+                i += 3;
+            } else {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn reserved_characters_are_escaped() {
+        assert_eq!(encode("hello world"), "hello%20world");
+        assert_eq!(encode("a+b&c=d#e/f?g"), "a%2Bb%26c%3Dd%23e%2Ff%3Fg");
+        assert_eq!(encode("Coeur d'Alene"), "Coeur%20d%27Alene");
+        assert_eq!(encode("San José"), "San%20Jos%C3%A9");
+        assert_eq!(encode("2024-01-01_a.b~c"), "2024-01-01_a.b~c");
+    }
+
+    #[test]
+    fn query_joins_pairs() {
+        assert_eq!(Query::new().finish(), "");
+        let mut query = Query::new();
+        query.push("query", "St. Louis, MO");
+        query.push("limit", 5);
+        assert_eq!(query.finish(), "?query=St.%20Louis%2C%20MO&limit=5");
+    }
+
+    proptest! {
+        #[test]
+        fn encoding_round_trips(input in any::<String>()) {
+            prop_assert_eq!(decode(&encode(&input)), input.as_bytes());
+        }
+
+        #[test]
+        fn encoded_values_only_use_unreserved_characters_and_escapes(input in any::<String>()) {
+            let encoded = encode(&input);
+            prop_assert!(encoded
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_.~%".contains(c)));
+            prop_assert!(!encoded.contains('&') && !encoded.contains('='));
+        }
+    }
+}
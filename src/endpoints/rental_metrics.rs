@@ -1,15 +1,19 @@
 //! Rental market metrics endpoints for tracking rental activity and yields.
 
+use super::batch::{BatchRequest, MetricFilters};
+use super::common::FetchOptions;
+use super::query::Query;
 use crate::error::Result;
 use crate::models::{
-    BatchMetricsResponse, GrossYield, MetricsResponse, PropertyType,
-    RentalNewListingsRollingCounts, RentalUnitsConcentration,
+    BatchMetricsResponse, GrossYield, HousingEventPrices, MetricsResponse, PropertyType,
+    RentalNewListingsRollingCounts, RentalPrice, RentalUnitsConcentration,
 };
 use crate::ParclClient;
+use std::borrow::Cow;
 
 /// Client for rental market metrics API endpoints.
 pub struct RentalMetricsClient<'a> {
-    client: &'a ParclClient,
+    client: Cow<'a, ParclClient>,
 }
 
 /// Query parameters for rental metrics requests.
@@ -21,6 +25,8 @@ pub struct RentalMetricsParams {
     pub end_date: Option<String>,
     pub property_type: Option<PropertyType>,
     pub auto_paginate: bool,
+    /// Retry policy, cancellation token and deadline for this request.
+    pub fetch: FetchOptions,
 }
 
 impl RentalMetricsParams {
@@ -64,217 +70,171 @@ impl RentalMetricsParams {
         self
     }
 
+    fetch_option_setters!();
+
     pub(crate) fn to_query_string(&self) -> String {
-        let mut params = Vec::new();
+        let mut query = Query::new();
 
         if let Some(l) = self.limit {
-            params.push(format!("limit={}", l));
+            query.push("limit", l);
         }
         if let Some(o) = self.offset {
-            params.push(format!("offset={}", o));
+            query.push("offset", o);
         }
         if let Some(ref s) = self.start_date {
-            params.push(format!("start_date={}", s));
+            query.push("start_date", s);
         }
         if let Some(ref e) = self.end_date {
-            params.push(format!("end_date={}", e));
+            query.push("end_date", e);
         }
         if let Some(pt) = self.property_type {
-            params.push(format!("property_type={}", pt.as_str()));
+            query.push("property_type", pt.as_str());
         }
 
-        if params.is_empty() {
-            String::new()
-        } else {
-            format!("?{}", params.join("&"))
-        }
+        query.finish()
     }
 
-    pub(crate) fn to_batch_body(&self, parcl_ids: &[i64]) -> serde_json::Value {
-        let mut body = serde_json::json!({ "parcl_id": parcl_ids });
-        let obj = body.as_object_mut().unwrap();
-        if let Some(l) = self.limit {
-            obj.insert("limit".into(), serde_json::json!(l));
-        }
-        if let Some(o) = self.offset {
-            obj.insert("offset".into(), serde_json::json!(o));
-        }
-        if let Some(ref s) = self.start_date {
-            obj.insert("start_date".into(), serde_json::json!(s));
-        }
-        if let Some(ref e) = self.end_date {
-            obj.insert("end_date".into(), serde_json::json!(e));
-        }
-        if let Some(pt) = self.property_type {
-            obj.insert("property_type".into(), serde_json::json!(pt.as_str()));
+    pub(crate) fn to_batch_body<'a>(
+        &'a self,
+        parcl_ids: &'a [i64],
+    ) -> BatchRequest<'a, MetricFilters<'a>> {
+        BatchRequest {
+            parcl_id: parcl_ids,
+            filters: MetricFilters {
+                limit: self.limit,
+                offset: self.offset,
+                start_date: self.start_date.as_deref(),
+                end_date: self.end_date.as_deref(),
+                property_type: self.property_type,
+            },
         }
-        body
     }
 }
 
 impl<'a> RentalMetricsClient<'a> {
-    pub(crate) fn new(client: &'a ParclClient) -> Self {
+    pub(crate) fn new(client: Cow<'a, ParclClient>) -> Self {
         Self { client }
     }
 
-    /// Retrieves gross rental yield metrics.
-    ///
-    /// Returns annual rental income divided by median sale price,
-    /// providing insight into rental investment returns.
-    pub async fn gross_yield(
-        &self,
-        parcl_id: i64,
-        params: Option<RentalMetricsParams>,
-    ) -> Result<MetricsResponse<GrossYield>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/rental_market_metrics/{}/gross_yield{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
+    define_endpoints! {
+        params = RentalMetricsParams;
 
-    /// Retrieves rental units concentration metrics.
-    ///
-    /// Returns the percentage of housing stock that are rental units.
-    pub async fn rental_units_concentration(
-        &self,
-        parcl_id: i64,
-        params: Option<RentalMetricsParams>,
-    ) -> Result<MetricsResponse<RentalUnitsConcentration>> {
-        let params = params.unwrap_or_default();
-        let url = format!(
-            "{}/v1/rental_market_metrics/{}/rental_units_concentration{}",
-            self.client.base_url,
-            parcl_id,
-            params.to_query_string()
-        );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
+        /// Retrieves gross rental yield metrics.
+        ///
+        /// Returns annual rental income divided by median sale price,
+        /// providing insight into rental investment returns.
+        get gross_yield("/v1/rental_market_metrics", "gross_yield") -> GrossYield;
+
+        /// Retrieves rental units concentration metrics.
+        ///
+        /// Returns the percentage of housing stock that are rental units.
+        get rental_units_concentration(
+            "/v1/rental_market_metrics",
+            "rental_units_concentration"
+        ) -> RentalUnitsConcentration;
+
+        /// Retrieves rolling counts of new rental listings.
+        ///
+        /// Returns rolling counts over 7, 30, 60, and 90 day periods
+        /// for newly listed rental properties in the market.
+        get new_listings_for_rent_rolling_counts(
+            "/v1/rental_market_metrics",
+            "new_listings_for_rent_rolling_counts"
+        ) -> RentalNewListingsRollingCounts;
+
+        // --- Latest-value methods ---
+
+        /// Most recent `gross_yield` item for a market.
+        latest gross_yield_latest(gross_yield) -> GrossYield;
+
+        /// Most recent `rental_units_concentration` item for a market.
+        latest rental_units_concentration_latest(rental_units_concentration) -> RentalUnitsConcentration;
+
+        /// Most recent `new_listings_for_rent_rolling_counts` item for a market.
+        latest new_listings_for_rent_rolling_counts_latest(new_listings_for_rent_rolling_counts) -> RentalNewListingsRollingCounts;
+
+        // --- By-name methods ---
+
+        /// `gross_yield` for a market given by name.
+        by_name gross_yield_by_name(gross_yield) -> GrossYield;
+
+        /// `rental_units_concentration` for a market given by name.
+        by_name rental_units_concentration_by_name(rental_units_concentration) -> RentalUnitsConcentration;
+
+        /// `new_listings_for_rent_rolling_counts` for a market given by name.
+        by_name new_listings_for_rent_rolling_counts_by_name(new_listings_for_rent_rolling_counts) -> RentalNewListingsRollingCounts;
+
+        // --- Batch POST methods ---
+
+        /// Batch retrieves gross yield for multiple markets.
+        batch batch_gross_yield("/v1/rental_market_metrics", "gross_yield") -> GrossYield;
+
+        /// Batch retrieves rental units concentration for multiple markets.
+        batch batch_rental_units_concentration(
+            "/v1/rental_market_metrics",
+            "rental_units_concentration"
+        ) -> RentalUnitsConcentration;
+
+        /// Batch retrieves new listings for rent rolling counts for multiple markets.
+        batch batch_new_listings_for_rent_rolling_counts(
+            "/v1/rental_market_metrics",
+            "new_listings_for_rent_rolling_counts"
+        ) -> RentalNewListingsRollingCounts;
     }
 
-    /// Retrieves rolling counts of new rental listings.
+    /// Retrieves asking rents for new rental listings.
     ///
-    /// Returns rolling counts over 7, 30, 60, and 90 day periods
-    /// for newly listed rental properties in the market.
-    pub async fn new_listings_for_rent_rolling_counts(
+    /// Rents are published inside `housing_event_prices` as the
+    /// `new_rental_listings` price; this fetches that endpoint and flattens
+    /// the rent statistics into a [`RentalPrice`] series.
+    pub async fn rental_prices(
         &self,
         parcl_id: i64,
         params: Option<RentalMetricsParams>,
-    ) -> Result<MetricsResponse<RentalNewListingsRollingCounts>> {
+    ) -> Result<MetricsResponse<RentalPrice>> {
         let params = params.unwrap_or_default();
         let url = format!(
-            "{}/v1/rental_market_metrics/{}/new_listings_for_rent_rolling_counts{}",
+            "{}/v1/market_metrics/{}/housing_event_prices{}",
             self.client.base_url,
             parcl_id,
             params.to_query_string()
         );
-        let resp = super::common::get_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
+        let resp: MetricsResponse<HousingEventPrices> = super::common::get_with_pagination(
+            &self.client,
+            "/v1/market_metrics/housing_event_prices",
             &url,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    // --- Batch POST methods ---
-
-    /// Batch retrieves gross yield for multiple markets.
-    pub async fn batch_gross_yield(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<RentalMetricsParams>,
-    ) -> Result<BatchMetricsResponse<GrossYield>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/rental_market_metrics/gross_yield",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
-        )
-        .await?;
-        self.client.update_credits(&resp.account);
-        Ok(resp)
-    }
-
-    /// Batch retrieves rental units concentration for multiple markets.
-    pub async fn batch_rental_units_concentration(
-        &self,
-        parcl_ids: Vec<i64>,
-        params: Option<RentalMetricsParams>,
-    ) -> Result<BatchMetricsResponse<RentalUnitsConcentration>> {
-        let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
-        let url = format!(
-            "{}/v1/rental_market_metrics/rental_units_concentration",
-            self.client.base_url
-        );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
-            &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
+            parcl_id,
+            params.fetch_options(),
         )
         .await?;
         self.client.update_credits(&resp.account);
-        Ok(resp)
+        Ok(resp.map_items(RentalPrice::from))
     }
 
-    /// Batch retrieves new listings for rent rolling counts for multiple markets.
-    pub async fn batch_new_listings_for_rent_rolling_counts(
+    /// Batch retrieves asking rents for multiple markets.
+    ///
+    /// See [`rental_prices`](Self::rental_prices).
+    pub async fn batch_rental_prices(
         &self,
         parcl_ids: Vec<i64>,
         params: Option<RentalMetricsParams>,
-    ) -> Result<BatchMetricsResponse<RentalNewListingsRollingCounts>> {
+    ) -> Result<BatchMetricsResponse<RentalPrice>> {
         let params = params.unwrap_or_default();
-        let body = params.to_batch_body(&parcl_ids);
         let url = format!(
-            "{}/v1/rental_market_metrics/new_listings_for_rent_rolling_counts",
+            "{}/v1/market_metrics/housing_event_prices",
             self.client.base_url
         );
-        let resp = super::common::post_with_pagination(
-            &self.client.http,
-            &self.client.api_key,
+        let resp: BatchMetricsResponse<HousingEventPrices> = super::common::post_batched(
+            &self.client,
+            "/v1/market_metrics/housing_event_prices",
             &url,
-            &body,
-            params.auto_paginate,
-            &self.client.retry_config,
+            &parcl_ids,
+            |ids| serde_json::to_value(params.to_batch_body(ids)),
+            params.fetch_options(),
         )
         .await?;
         self.client.update_credits(&resp.account);
-        Ok(resp)
+        Ok(resp.map_items(RentalPrice::from))
     }
 }
 
@@ -282,6 +242,33 @@ impl<'a> RentalMetricsClient<'a> {
 mod tests {
     use super::*;
 
+    /// Serves one gross yield item for market 1 and a 404 for any other.
+    fn one_yield() -> crate::test_util::CannedTransport {
+        crate::test_util::CannedTransport::new(|request| {
+            assert_eq!(request.uri().query(), Some("limit=1"));
+            let status = if request.uri().path().contains("/1/") {
+                200
+            } else {
+                404
+            };
+            let body = serde_json::json!({
+                "parcl_id": 1, "total": 60, "limit": 1, "offset": 0, "links": {},
+                "items": [{"date": "2024-06-01", "gross_yield": 6.5}]
+            });
+            crate::test_util::json_response(status, &body)
+        })
+    }
+
+    #[tokio::test]
+    async fn latest_returns_the_single_item() {
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(one_yield());
+        let rental = client.rental_metrics();
+        let latest = rental.gross_yield_latest(1).await.unwrap().unwrap();
+        assert_eq!(latest.date, "2024-06-01");
+        assert!(rental.gross_yield_latest(2).await.unwrap().is_none());
+    }
+
     #[test]
     fn rental_params_default() {
         let params = RentalMetricsParams::new();
@@ -338,7 +325,7 @@ mod tests {
     #[test]
     fn rental_params_batch_body_minimal() {
         let params = RentalMetricsParams::new();
-        let body = params.to_batch_body(&[100, 200]);
+        let body = serde_json::to_value(params.to_batch_body(&[100, 200])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100, 200]));
         assert!(!obj.contains_key("limit"));
@@ -352,7 +339,7 @@ mod tests {
             .start_date("2024-01-01")
             .end_date("2024-12-31")
             .property_type(PropertyType::Condo);
-        let body = params.to_batch_body(&[100]);
+        let body = serde_json::to_value(params.to_batch_body(&[100])).unwrap();
         let obj = body.as_object().unwrap();
         assert_eq!(obj["parcl_id"], serde_json::json!([100]));
         assert_eq!(obj["limit"], 10);
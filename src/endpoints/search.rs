@@ -1,12 +1,20 @@
 //! Market search endpoints for discovering Parcl market identifiers.
 
-use crate::error::{ParclError, Result};
-use crate::models::{LocationType, Market, PaginatedResponse, SortBy, SortOrder, USRegion};
-use crate::ParclClient;
+use super::common::FetchOptions;
+use super::query::Query;
+use super::sweep::Sweep;
+use crate::error::Result;
+use crate::models::{
+    LocationType, Market, PaginatedResponse, SortBy, SortOrder, USRegion, USState,
+};
+use crate::protocol::RequestContext;
+use crate::{ParclClient, RetryConfig};
+use std::borrow::Cow;
 
 /// Client for search API endpoints.
 pub struct SearchClient<'a> {
-    client: &'a ParclClient,
+    client: Cow<'a, ParclClient>,
+    cached: bool,
 }
 
 /// Query parameters for market search.
@@ -22,7 +30,18 @@ pub struct SearchParams {
     pub sort_by: Option<SortBy>,
     pub sort_order: Option<SortOrder>,
     pub limit: Option<u32>,
+    pub offset: Option<u32>,
     pub auto_paginate: bool,
+    /// Keep only markets with price feed data.
+    pub pricefeed_only: bool,
+    /// Keep only markets tradeable on the Parcl exchange.
+    pub exchange_only: bool,
+    /// Keep only members of the Case-Shiller 10-city composite.
+    pub case_shiller_10_only: bool,
+    /// Keep only members of the Case-Shiller 20-city composite.
+    pub case_shiller_20_only: bool,
+    /// Retry policy, cancellation token and deadline for this request.
+    pub fetch: FetchOptions,
 }
 
 impl SearchParams {
@@ -90,57 +109,184 @@ impl SearchParams {
         self
     }
 
+    /// Number of results to skip, for fetching a specific page.
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
     /// Automatically fetch all pages of results.
     pub fn auto_paginate(mut self, auto_paginate: bool) -> Self {
         self.auto_paginate = auto_paginate;
         self
     }
 
+    /// Returns only markets with price feed data.
+    ///
+    /// The API has no filter for this flag, so unless `sort_by` is set the
+    /// search is sorted by it server-side, flagged markets first, and
+    /// pagination stops at the first unflagged market. The rest are dropped
+    /// client-side; `total` still reports the unfiltered count.
+    pub fn pricefeed_only(mut self) -> Self {
+        self.pricefeed_only = true;
+        self
+    }
+
+    /// Returns only markets tradeable on the Parcl exchange.
+    ///
+    /// Works like [`pricefeed_only`](Self::pricefeed_only).
+    pub fn exchange_only(mut self) -> Self {
+        self.exchange_only = true;
+        self
+    }
+
+    /// Returns only members of the Case-Shiller 10-city composite.
+    ///
+    /// Works like [`pricefeed_only`](Self::pricefeed_only).
+    pub fn case_shiller_10_only(mut self) -> Self {
+        self.case_shiller_10_only = true;
+        self
+    }
+
+    /// Returns only members of the Case-Shiller 20-city composite.
+    ///
+    /// Works like [`pricefeed_only`](Self::pricefeed_only).
+    pub fn case_shiller_20_only(mut self) -> Self {
+        self.case_shiller_20_only = true;
+        self
+    }
+
+    fetch_option_setters!();
+
+    /// The flag the search is sorted by to serve `pricefeed_only` or
+    /// `exchange_only`, unless the caller chose a sort.
+    fn flag_sort(&self) -> Option<SortBy> {
+        if self.sort_by.is_some() {
+            None
+        } else if self.pricefeed_only {
+            Some(SortBy::PricefeedMarket)
+        } else if self.exchange_only {
+            Some(SortBy::ParclExchangeMarket)
+        } else if self.case_shiller_10_only {
+            Some(SortBy::CaseShiller10Market)
+        } else if self.case_shiller_20_only {
+            Some(SortBy::CaseShiller20Market)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if `market` passes the flag filters.
+    pub(crate) fn keeps(&self, market: &Market) -> bool {
+        (!self.pricefeed_only || market.has_price_feed())
+            && (!self.exchange_only || market.is_exchange_market())
+            && (!self.case_shiller_10_only || market.in_case_shiller_10())
+            && (!self.case_shiller_20_only || market.in_case_shiller_20())
+    }
+
+    /// Returns true if sorting by a flag means no later page can match.
+    fn flagged_exhausted(&self, page: &[Market]) -> bool {
+        self.flag_sort()
+            .is_some_and(|key| page.iter().any(|m| m.sort_value(key) < 1))
+    }
+
+    /// Key under which [`SearchCache`](crate::cache::SearchCache) stores
+    /// this search: the query string with the query trimmed, lowercased and
+    /// whitespace-collapsed, plus the flags that change the results.
+    pub(crate) fn cache_key(&self) -> String {
+        let normalized = SearchParams {
+            query: self.query.as_ref().map(|q| {
+                q.split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .to_lowercase()
+            }),
+            state_abbreviation: self
+                .state_abbreviation
+                .as_ref()
+                .map(|s| s.trim().to_uppercase()),
+            ..self.clone()
+        };
+        format!(
+            "{}|paginate={}|pricefeed={}|exchange={}|cs10={}|cs20={}",
+            normalized.to_query_string(),
+            self.auto_paginate,
+            self.pricefeed_only,
+            self.exchange_only,
+            self.case_shiller_10_only,
+            self.case_shiller_20_only
+        )
+    }
+
     pub(crate) fn to_query_string(&self) -> String {
-        let mut params = Vec::new();
+        let mut query = Query::new();
+        let flag_sort = self.flag_sort();
 
         if let Some(ref q) = self.query {
-            params.push(format!("query={}", urlencoding::encode(q)));
+            query.push("query", q);
         }
         if let Some(lt) = self.location_type {
-            params.push(format!("location_type={}", lt.as_str()));
+            query.push("location_type", lt.as_str());
         }
         if let Some(r) = self.region {
-            params.push(format!("region={}", r.as_str()));
+            query.push("region", r.as_str());
         }
         if let Some(ref s) = self.state_abbreviation {
-            params.push(format!("state_abbreviation={}", s));
+            query.push("state_abbreviation", s);
         }
         if let Some(ref s) = self.state_fips_code {
-            params.push(format!("state_fips_code={}", s));
+            query.push("state_fips_code", s);
         }
         if let Some(id) = self.parcl_id {
-            params.push(format!("parcl_id={}", id));
+            query.push("parcl_id", id);
         }
         if let Some(ref g) = self.geoid {
-            params.push(format!("geoid={}", g));
+            query.push("geoid", g);
         }
-        if let Some(sb) = self.sort_by {
-            params.push(format!("sort_by={}", sb.as_str()));
+        if let Some(sb) = self.sort_by.or(flag_sort) {
+            query.push("sort_by", sb.as_str());
         }
-        if let Some(so) = self.sort_order {
-            params.push(format!("sort_order={}", so.as_str()));
+        if flag_sort.is_some() {
+            query.push("sort_order", SortOrder::Desc.as_str());
+        } else if let Some(so) = self.sort_order {
+            query.push("sort_order", so.as_str());
         }
         if let Some(l) = self.limit {
-            params.push(format!("limit={}", l));
+            query.push("limit", l);
         }
-
-        if params.is_empty() {
-            String::new()
-        } else {
-            format!("?{}", params.join("&"))
+        if let Some(o) = self.offset {
+            query.push("offset", o);
         }
+
+        query.finish()
     }
 }
 
 impl<'a> SearchClient<'a> {
-    pub(crate) fn new(client: &'a ParclClient) -> Self {
-        Self { client }
+    pub(crate) fn new(client: Cow<'a, ParclClient>) -> Self {
+        Self {
+            client,
+            cached: false,
+        }
+    }
+
+    /// Serves [`markets`](Self::markets) from the client's
+    /// [`SearchCache`](crate::cache::SearchCache) when the same normalized
+    /// query and filters were searched within its TTL, and stores fresh
+    /// results there. Cache hits cost no credits.
+    ///
+    /// ```no_run
+    /// # use parcllabs::{ParclClient, SearchParams};
+    /// # async fn example(client: ParclClient) -> parcllabs::Result<()> {
+    /// let params = SearchParams::new().query("austin").state("TX");
+    /// let first = client.search().cached().markets(params.clone()).await?;
+    /// let again = client.search().cached().markets(params.query(" Austin ")).await?; // no request
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cached(mut self) -> Self {
+        self.cached = true;
+        self
     }
 
     /// Searches for markets using the provided parameters.
@@ -169,75 +315,123 @@ impl<'a> SearchClient<'a> {
     /// # }
     /// ```
     pub async fn markets(&self, params: SearchParams) -> Result<PaginatedResponse<Market>> {
+        if self.cached {
+            if let Some(response) = self.client.search_cache().get(&params) {
+                return Ok(response);
+            }
+        }
         let query = params.to_query_string();
         let url = format!("{}/v1/search/markets{}", self.client.base_url, query);
-
-        let mut response = self.fetch_page(&url).await?;
-
-        if params.auto_paginate {
-            while let Some(ref next_url) = response.links.next {
-                let next_page = self.fetch_page(next_url).await?;
+        let url =
+            crate::limits::apply_to_url(self.client.limit_policy, "/v1/search/markets", &url)?;
+
+        let options = params.fetch_options();
+        let mut response = self.fetch_page(&url, options.retry.as_ref()).await?;
+        let mut exhausted = params.flagged_exhausted(&response.items);
+        let check_total = |response: &PaginatedResponse<Market>| {
+            super::common::check_total(
+                &self.client,
+                "/v1/search/markets",
+                &response.meta,
+                response.items.len(),
+            )
+        };
+        check_total(&response)?;
+
+        if options.auto_paginate {
+            while let (Some(next_url), false) = (&response.links.next, exhausted) {
+                if options.should_stop(&self.client, &mut response.meta) {
+                    break;
+                }
+                let next_page = self.fetch_page(next_url, options.retry.as_ref()).await?;
                 self.client.update_credits(&next_page.account);
+                exhausted = params.flagged_exhausted(&next_page.items);
                 response.items.extend(next_page.items);
                 response.links = next_page.links;
+                response.meta.absorb(&next_page.meta);
+                check_total(&response)?;
             }
         }
 
         self.client.update_credits(&response.account);
+        self.client
+            .market_cache()
+            .extend(response.items.iter().cloned());
+        response.items.retain(|m| params.keeps(m));
+        if self.cached && !response.meta.is_partial() {
+            self.client.search_cache().insert(&params, response.clone());
+        }
         Ok(response)
     }
 
-    async fn fetch_page(&self, url: &str) -> Result<PaginatedResponse<Market>> {
-        for attempt in 0..=self.client.retry_config.max_retries {
-            let response = self
-                .client
-                .http
-                .get(url)
-                .header("Authorization", &self.client.api_key)
-                .send()
-                .await?;
-
-            let status = response.status();
-            if status.as_u16() == 429 && attempt < self.client.retry_config.max_retries {
-                let backoff = self.client.retry_config.initial_backoff_ms * 2u64.pow(attempt);
-                tokio::time::sleep(std::time::Duration::from_millis(backoff)).await;
-                continue;
-            }
+    /// Every member of the Case-Shiller 10-city composite, fetched across
+    /// as many pages as needed.
+    ///
+    /// ```no_run
+    /// # use parcllabs::ParclClient;
+    /// # async fn example(client: ParclClient) -> parcllabs::Result<()> {
+    /// let members = client.search().case_shiller_10_markets().await?;
+    /// let ids: Vec<i64> = members.iter().map(|m| m.parcl_id).collect();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn case_shiller_10_markets(&self) -> Result<Vec<Market>> {
+        let params = SearchParams::new()
+            .case_shiller_10_only()
+            .auto_paginate(true);
+        Ok(self.markets(params).await?.items)
+    }
 
-            if !status.is_success() {
-                let message = response.text().await.unwrap_or_default();
-                if status.as_u16() == 429 {
-                    return Err(ParclError::RateLimited {
-                        attempts: attempt + 1,
-                        message,
-                    });
-                }
-                return Err(ParclError::ApiError {
-                    status: status.as_u16(),
-                    message,
-                });
-            }
+    /// Every member of the Case-Shiller 20-city composite, fetched across
+    /// as many pages as needed.
+    pub async fn case_shiller_20_markets(&self) -> Result<Vec<Market>> {
+        let params = SearchParams::new()
+            .case_shiller_20_only()
+            .auto_paginate(true);
+        Ok(self.markets(params).await?.items)
+    }
 
-            let data: PaginatedResponse<Market> = response.json().await?;
-            return Ok(data);
-        }
-        unreachable!()
+    /// Walks every market of `location_type` in `states`, one page at a time.
+    ///
+    /// See [`Sweep`] for budgets and checkpoints.
+    ///
+    /// ```no_run
+    /// use parcllabs::{LocationType, ParclClient, USState};
+    ///
+    /// # async fn example() -> parcllabs::Result<()> {
+    /// let client = ParclClient::new()?;
+    /// let mut sweep = client
+    ///     .search()
+    ///     .sweep(&USState::ALL, LocationType::City)
+    ///     .budget(500);
+    /// while let Some(page) = sweep.next_page().await? {
+    ///     println!("{}: {} markets", page.state, page.markets.len());
+    /// }
+    /// if sweep.budget_exhausted() {
+    ///     let checkpoint = sweep.checkpoint(); // persist and resume later
+    /// #   let _ = checkpoint;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sweep(&self, states: &[USState], location_type: LocationType) -> Sweep<'a> {
+        Sweep::new(self.client.clone(), states, location_type)
     }
-}
 
-mod urlencoding {
-    pub fn encode(input: &str) -> String {
-        let mut encoded = String::new();
-        for byte in input.bytes() {
-            match byte {
-                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
-                    encoded.push(byte as char);
-                }
-                b' ' => encoded.push_str("%20"),
-                _ => encoded.push_str(&format!("%{:02X}", byte)),
-            }
-        }
-        encoded
+    async fn fetch_page(
+        &self,
+        url: &str,
+        retry: Option<&RetryConfig>,
+    ) -> Result<PaginatedResponse<Market>> {
+        let (response, meta) = super::common::send(&self.client, url, None, retry).await?;
+        let mut data: PaginatedResponse<Market> = super::common::decode(
+            &self.client,
+            &response,
+            &meta,
+            RequestContext::new("/v1/search/markets"),
+        )?;
+        data.meta = meta;
+        Ok(data)
     }
 }
 
@@ -245,6 +439,7 @@ mod urlencoding {
 mod tests {
     use super::*;
     use crate::models::{LocationType, SortBy, SortOrder, USRegion};
+    use crate::test_util::CannedTransport;
 
     #[test]
     fn search_params_default() {
@@ -270,6 +465,113 @@ mod tests {
         assert!(params.auto_paginate);
     }
 
+    #[test]
+    fn flag_filters_sort_by_flag_unless_sort_is_set() {
+        let qs = SearchParams::new().pricefeed_only().to_query_string();
+        assert_eq!(qs, "?sort_by=PRICEFEED_MARKET&sort_order=DESC");
+
+        let qs = SearchParams::new()
+            .exchange_only()
+            .sort_by(SortBy::TotalPopulation)
+            .sort_order(SortOrder::Asc)
+            .to_query_string();
+        assert_eq!(qs, "?sort_by=TOTAL_POPULATION&sort_order=ASC");
+    }
+
+    /// Serves a single page whose `next` link must not be followed.
+    fn one_page() -> CannedTransport {
+        CannedTransport::json(
+            200,
+            serde_json::json!({
+                "items": [
+                    {"parcl_id": 1, "name": "A", "location_type": "CITY", "pricefeed_market": 1},
+                    {"parcl_id": 2, "name": "B", "location_type": "CITY", "pricefeed_market": 0}
+                ],
+                "total": 900, "limit": 2, "offset": 0,
+                "links": {"next": "https://example.com/v1/search/markets?offset=2"}
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn pricefeed_only_stops_after_last_flagged_market() {
+        let transport = one_page();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(transport.clone());
+        let params = SearchParams::new().pricefeed_only().auto_paginate(true);
+        let resp = client.search().markets(params).await.unwrap();
+        let ids: Vec<_> = resp.items.iter().map(|m| m.parcl_id).collect();
+        assert_eq!(ids, [1]);
+        assert_eq!(transport.request_count(), 1);
+    }
+
+    /// Two pages sorted by the 20-city flag; the second must not be fetched.
+    fn case_shiller() -> CannedTransport {
+        CannedTransport::json(
+            200,
+            serde_json::json!({
+                "items": [
+                    {"parcl_id": 1, "name": "A", "location_type": "CBSA", "case_shiller_20_market": 1},
+                    {"parcl_id": 2, "name": "B", "location_type": "CBSA", "case_shiller_20_market": 1},
+                    {"parcl_id": 3, "name": "C", "location_type": "CBSA", "case_shiller_20_market": 0}
+                ],
+                "total": 900, "limit": 3, "offset": 0,
+                "links": {"next": "https://example.com/v1/search/markets?offset=3"}
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn case_shiller_markets_keep_members_only() {
+        let transport = case_shiller();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(transport.clone());
+        let members = client.search().case_shiller_20_markets().await.unwrap();
+        let ids: Vec<_> = members.iter().map(|m| m.parcl_id).collect();
+        assert_eq!(ids, [1, 2]);
+        assert_eq!(transport.request_count(), 1);
+        assert!(transport.uris()[0].contains("sort_by=CASE_SHILLER_20_MARKET&sort_order=DESC"));
+    }
+
+    #[tokio::test]
+    async fn owned_search_outlives_client_in_spawned_task() {
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(one_page());
+        let search = client.search_owned();
+        drop(client);
+        let resp = tokio::spawn(async move { search.markets(SearchParams::new()).await })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resp.items.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn cached_search_reuses_normalized_query() {
+        let transport = one_page();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(transport.clone());
+        let first = client
+            .search()
+            .cached()
+            .markets(SearchParams::new().query("San  Jose").limit(2))
+            .await
+            .unwrap();
+        let again = client
+            .search()
+            .cached()
+            .markets(SearchParams::new().query(" san jose ").limit(2))
+            .await
+            .unwrap();
+        assert_eq!(again.items.len(), first.items.len());
+        assert_eq!(transport.request_count(), 1);
+        assert_eq!(client.search_cache().len(), 1);
+        assert_ne!(
+            SearchParams::new().query("san jose").cache_key(),
+            SearchParams::new().query("san jose").limit(2).cache_key()
+        );
+    }
+
     #[test]
     fn search_params_state_uppercase() {
         let params = SearchParams::new().state("ca");
@@ -330,17 +632,20 @@ mod tests {
     }
 
     #[test]
-    fn urlencoding_basic() {
-        assert_eq!(urlencoding::encode("hello"), "hello");
-        assert_eq!(urlencoding::encode("hello world"), "hello%20world");
-        assert_eq!(urlencoding::encode("a+b"), "a%2Bb");
-        assert_eq!(urlencoding::encode("test@example"), "test%40example");
+    fn query_encoding_basic() {
+        assert_eq!(super::super::query::encode("hello"), "hello");
+        assert_eq!(super::super::query::encode("hello world"), "hello%20world");
+        assert_eq!(super::super::query::encode("a+b"), "a%2Bb");
+        assert_eq!(
+            super::super::query::encode("test@example"),
+            "test%40example"
+        );
     }
 
     #[test]
-    fn urlencoding_preserves_safe_chars() {
+    fn query_encoding_preserves_safe_chars() {
         assert_eq!(
-            urlencoding::encode("abc-123_456.789~xyz"),
+            super::super::query::encode("abc-123_456.789~xyz"),
             "abc-123_456.789~xyz"
         );
     }
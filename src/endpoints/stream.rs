@@ -0,0 +1,369 @@
+//! Item-by-item decoding of large JSON arrays inside a response body.
+//!
+//! Decoding a page into a `Vec` holds every typed item at once, often
+//! several times the size of the JSON itself. [`ArrayItems`] splits the
+//! elements of one top-level array out of the body as its bytes arrive and
+//! decodes each on its own, so only one item is alive at a time and the
+//! first is handed over before the rest of the body has been received.
+//! [`ArrayItems::projected`] goes further and skips unlisted fields of each
+//! item without allocating them.
+
+use crate::error::{ParclError, Result};
+use crate::protocol::BodyStream;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Incremental decoder for the array under one field of a JSON object.
+///
+/// Feed it the body with [`push`](Self::push) as chunks arrive; each
+/// element is decoded with the seed once its last byte is in, and bytes
+/// before the element being read are dropped. Other fields are skipped
+/// without being decoded, and `null` in place of the array means no items.
+pub(crate) struct ArrayItems<S> {
+    field: &'static str,
+    seed: S,
+    /// Unconsumed body bytes; `base` is the body offset of `buf[0]`.
+    buf: Vec<u8>,
+    base: usize,
+    /// Body offset of the next byte to scan.
+    pos: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    /// At the top level, the next string is a key.
+    expecting_key: bool,
+    /// Body offset where the key being read starts.
+    key_start: Option<usize>,
+    /// The top-level key whose value is being read.
+    key: Option<String>,
+    in_array: bool,
+    /// Body offset where the element being read starts.
+    item_start: Option<usize>,
+    done: bool,
+    count: usize,
+}
+
+impl<T: serde::de::DeserializeOwned> ArrayItems<PhantomData<T>> {
+    /// Decodes each element as `T`.
+    pub(crate) fn new(field: &'static str) -> Self {
+        Self::with_seed(field, PhantomData)
+    }
+}
+
+impl<'f> ArrayItems<Only<'f>> {
+    /// Decodes each element as a JSON object holding only the top-level
+    /// `fields` listed.
+    pub(crate) fn projected(field: &'static str, fields: &'f [&'f str]) -> Self {
+        Self::with_seed(field, Only(fields))
+    }
+}
+
+impl<S> ArrayItems<S>
+where
+    S: for<'de> DeserializeSeed<'de> + Copy,
+{
+    fn with_seed(field: &'static str, seed: S) -> Self {
+        Self {
+            field,
+            seed,
+            buf: Vec::new(),
+            base: 0,
+            pos: 0,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+            expecting_key: false,
+            key_start: None,
+            key: None,
+            in_array: false,
+            item_start: None,
+            done: false,
+            count: 0,
+        }
+    }
+
+    /// Scans `chunk`, calling `f` with every element it completes. The
+    /// first error from `f` is returned as is.
+    pub(crate) fn push<F>(&mut self, chunk: &[u8], f: &mut F) -> Result<()>
+    where
+        F: for<'de> FnMut(<S as DeserializeSeed<'de>>::Value) -> Result<()>,
+    {
+        self.buf.extend_from_slice(chunk);
+        while self.pos < self.base + self.buf.len() {
+            let at = self.pos;
+            let byte = self.buf[at - self.base];
+            self.pos += 1;
+            self.scan(at, byte, f)?;
+        }
+        // Keep only what the element or key being read still needs.
+        let keep = self.item_start.or(self.key_start).unwrap_or(self.pos);
+        self.buf.drain(..keep - self.base);
+        self.base = keep;
+        Ok(())
+    }
+
+    /// Checks the body ended as a complete object and returns how many
+    /// elements were passed to `f`.
+    pub(crate) fn finish(self) -> Result<usize> {
+        if !self.done {
+            return Err(malformed("the body ended before the object closed"));
+        }
+        Ok(self.count)
+    }
+
+    fn scan<F>(&mut self, at: usize, byte: u8, f: &mut F) -> Result<()>
+    where
+        F: for<'de> FnMut(<S as DeserializeSeed<'de>>::Value) -> Result<()>,
+    {
+        if self.in_string {
+            if self.escaped {
+                self.escaped = false;
+            } else if byte == b'\\' {
+                self.escaped = true;
+            } else if byte == b'"' {
+                self.in_string = false;
+                if let Some(start) = self.key_start.take() {
+                    let key = &self.buf[start - self.base..at - self.base];
+                    self.key = Some(String::from_utf8_lossy(key).into_owned());
+                }
+            }
+            return Ok(());
+        }
+        if byte.is_ascii_whitespace() {
+            return Ok(());
+        }
+        if self.done {
+            return Err(malformed("trailing characters after the object"));
+        }
+        if self.depth == 0 && byte != b'{' {
+            return Err(malformed("expected a JSON object"));
+        }
+        let element_level = self.in_array && self.depth == 2;
+        if element_level && self.item_start.is_none() && !matches!(byte, b',' | b']') {
+            self.item_start = Some(at);
+        }
+        match byte {
+            b'"' => {
+                self.in_string = true;
+                if self.depth == 1 && self.expecting_key {
+                    self.expecting_key = false;
+                    self.key_start = Some(at + 1);
+                }
+            }
+            b'{' | b'[' => {
+                if self.depth == 1 && byte == b'[' && self.key.as_deref() == Some(self.field) {
+                    self.in_array = true;
+                }
+                self.depth += 1;
+                self.expecting_key = self.depth == 1;
+            }
+            b'}' | b']' => {
+                if element_level && byte == b']' {
+                    self.emit(at, f)?;
+                    self.in_array = false;
+                }
+                self.depth = self
+                    .depth
+                    .checked_sub(1)
+                    .ok_or_else(|| malformed("unbalanced brackets"))?;
+                self.done = self.depth == 0;
+            }
+            b',' => {
+                if element_level {
+                    self.emit(at, f)?;
+                } else if self.depth == 1 {
+                    self.expecting_key = true;
+                    self.key = None;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Decodes the element ending before body offset `end`, if any.
+    fn emit<F>(&mut self, end: usize, f: &mut F) -> Result<()>
+    where
+        F: for<'de> FnMut(<S as DeserializeSeed<'de>>::Value) -> Result<()>,
+    {
+        let Some(start) = self.item_start.take() else {
+            return Ok(());
+        };
+        let bytes = &self.buf[start - self.base..end - self.base];
+        let mut de = serde_json::Deserializer::from_slice(bytes);
+        let item = self
+            .seed
+            .deserialize(&mut de)
+            .and_then(|item| de.end().map(|()| item))
+            .map_err(ParclError::ParseError)?;
+        self.count += 1;
+        f(item)
+    }
+}
+
+/// Reads `body` to the end through `items`, calling `f` with each element
+/// as soon as it is complete, and returns how many were passed.
+///
+/// `on_chunk` sees the running byte count after each chunk and can stop the
+/// read by failing, e.g. when a size limit is exceeded.
+pub(crate) async fn drain<S, F>(
+    body: &mut dyn BodyStream,
+    mut items: ArrayItems<S>,
+    mut on_chunk: impl FnMut(u64) -> Result<()>,
+    mut f: F,
+) -> Result<usize>
+where
+    S: for<'de> DeserializeSeed<'de> + Copy,
+    F: for<'de> FnMut(<S as DeserializeSeed<'de>>::Value) -> Result<()>,
+{
+    let mut received = 0;
+    while let Some(chunk) = body.next_chunk().await? {
+        received += chunk.len() as u64;
+        on_chunk(received)?;
+        items.push(&chunk, &mut f)?;
+    }
+    items.finish()
+}
+
+fn malformed(message: &str) -> ParclError {
+    ParclError::ParseError(de::Error::custom(message))
+}
+
+/// Decodes an object keeping only the listed keys.
+#[derive(Clone, Copy)]
+pub(crate) struct Only<'f>(&'f [&'f str]);
+
+impl<'de> DeserializeSeed<'de> for Only<'_> {
+    type Value = Map<String, Value>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        de: D,
+    ) -> std::result::Result<Self::Value, D::Error> {
+        de.deserialize_map(self)
+    }
+}
+
+impl<'de> de::Visitor<'de> for Only<'_> {
+    type Value = Map<String, Value>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> std::result::Result<Self::Value, A::Error> {
+        let mut kept = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if self.0.contains(&key.as_str()) {
+                kept.insert(key, map.next_value()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(kept)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds `body` to `items` in chunks of `size` bytes.
+    fn feed<S, F>(mut items: ArrayItems<S>, body: &[u8], size: usize, mut f: F) -> Result<usize>
+    where
+        S: for<'de> DeserializeSeed<'de> + Copy,
+        F: for<'de> FnMut(<S as DeserializeSeed<'de>>::Value) -> Result<()>,
+    {
+        for chunk in body.chunks(size) {
+            items.push(chunk, &mut f)?;
+        }
+        items.finish()
+    }
+
+    #[test]
+    fn items_are_passed_one_at_a_time() {
+        let body = br#"{"total": 3, "note": "a \"[,]\" b", "properties": [{"id": 1, "tags": ["x,]"]}, {"id": 2}, {"id": 3}], "meta": {"x": [1]}}"#;
+        for size in [1, 7, body.len()] {
+            let mut ids = Vec::new();
+            let items = ArrayItems::new("properties");
+            let count = feed(items, body, size, |item: Value| {
+                ids.push(item["id"].as_i64().unwrap());
+                Ok(())
+            })
+            .unwrap();
+            assert_eq!((count, ids), (3, vec![1, 2, 3]), "chunk size {size}");
+        }
+        let empty = ArrayItems::new("properties");
+        assert_eq!(
+            feed(empty, br#"{"properties": null}"#, 4, |_: i64| Ok(())).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn item_is_passed_once_its_last_byte_arrives() {
+        let mut items = ArrayItems::new("properties");
+        let mut seen = Vec::new();
+        let mut f = |item: i64| {
+            seen.push(item);
+            Ok(())
+        };
+        items.push(br#"{"properties": [1, 2"#, &mut f).unwrap();
+        items.push(b"3, 4", &mut f).unwrap();
+        items.push(b"]}", &mut f).unwrap();
+        assert_eq!(items.finish().unwrap(), 3);
+        assert_eq!(seen, [1, 23, 4]);
+    }
+
+    #[test]
+    fn callback_error_stops_parsing() {
+        let body = br#"{"properties": [1, 2, 3]}"#;
+        let mut seen = 0;
+        let err = feed(ArrayItems::new("properties"), body, 3, |item: i64| {
+            seen += 1;
+            if item == 2 {
+                return Err(ParclError::Io(std::io::Error::other("disk full")));
+            }
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(matches!(err, ParclError::Io(_)));
+        assert_eq!(seen, 2);
+        for bad in [
+            &b"[1]"[..],
+            br#"{"properties": [1, 2"#,
+            br#"{"properties": [1, x]}"#,
+        ] {
+            assert!(matches!(
+                feed(ArrayItems::new("properties"), bad, 2, |_: i64| Ok(())),
+                Err(ParclError::ParseError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn projection_keeps_only_listed_fields() {
+        let body =
+            br#"{"properties": [{"id": 1, "sq_ft": 900, "events": [{"price": 1}]}, {"id": 2}]}"#;
+        let mut kept = Vec::new();
+        let items = ArrayItems::projected("properties", &["id", "sq_ft"]);
+        let count = feed(items, body, 5, |item| {
+            kept.push(Value::Object(item));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            kept,
+            [
+                serde_json::json!({"id": 1, "sq_ft": 900}),
+                serde_json::json!({"id": 2})
+            ]
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! Multi-state market sweeps with credit budgets and checkpoints.
+//!
+//! A [`Sweep`] walks the market search state by state and page by page, so
+//! nationwide screeners can process markets as they arrive instead of
+//! holding every result in memory. It stops when an optional credit budget
+//! is spent, and its [`SweepCheckpoint`] lets a later run pick up where the
+//! last one stopped.
+
+use super::SearchClient;
+use crate::error::{ParclError, Result};
+use crate::models::{LocationType, Market, USState};
+use crate::{ParclClient, SearchParams};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Page size used unless [`Sweep::page_size`] is set (the search maximum).
+const DEFAULT_PAGE_SIZE: u32 = 1000;
+
+/// One page of markets from a single state.
+#[derive(Debug, Clone)]
+pub struct SweepPage {
+    pub state: USState,
+    pub markets: Vec<Market>,
+}
+
+/// Where a sweep stopped, for resuming it later.
+///
+/// Serializable so it can be written to disk between runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweepCheckpoint {
+    /// The next state to fetch, or `None` if the sweep finished.
+    pub state: Option<USState>,
+    /// Offset of the next page within `state`.
+    pub offset: u32,
+    /// Credits spent by the sweep so far, carried into the budget.
+    pub credits_used: i64,
+}
+
+/// Iterates markets of one location type across several states.
+///
+/// Created by [`SearchClient::sweep`]. Call [`next_page`](Self::next_page)
+/// until it returns `None`, then check
+/// [`budget_exhausted`](Self::budget_exhausted) to tell a finished sweep
+/// from one that ran out of credits.
+#[derive(Debug)]
+pub struct Sweep<'a> {
+    client: Cow<'a, ParclClient>,
+    states: Vec<USState>,
+    location_type: LocationType,
+    page_size: u32,
+    budget: Option<i64>,
+    state_index: usize,
+    offset: u32,
+    credits_used: i64,
+    budget_exhausted: bool,
+}
+
+impl<'a> Sweep<'a> {
+    pub(crate) fn new(
+        client: Cow<'a, ParclClient>,
+        states: &[USState],
+        location_type: LocationType,
+    ) -> Self {
+        Self {
+            client,
+            states: states.to_vec(),
+            location_type,
+            page_size: DEFAULT_PAGE_SIZE,
+            budget: None,
+            state_index: 0,
+            offset: 0,
+            credits_used: 0,
+            budget_exhausted: false,
+        }
+    }
+
+    /// Stops the sweep once it has spent `credits`.
+    ///
+    /// Checked before each page, so the last page may overshoot the budget
+    /// by up to one page's cost.
+    pub fn budget(mut self, credits: i64) -> Self {
+        self.budget = Some(credits);
+        self
+    }
+
+    /// Markets per request (default 1000, the search maximum).
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Continues from a checkpoint taken by an earlier sweep over the same
+    /// states.
+    ///
+    /// Fails with `ParclError::Validation` if the checkpoint's state is not
+    /// part of this sweep.
+    pub fn resume_from(mut self, checkpoint: &SweepCheckpoint) -> Result<Self> {
+        self.state_index = match checkpoint.state {
+            Some(state) => self
+                .states
+                .iter()
+                .position(|s| *s == state)
+                .ok_or_else(|| {
+                    ParclError::Validation(format!("checkpoint state {state} is not in this sweep"))
+                })?,
+            None => self.states.len(),
+        };
+        self.offset = checkpoint.offset;
+        self.credits_used = checkpoint.credits_used;
+        self.budget_exhausted = false;
+        Ok(self)
+    }
+
+    /// Fetches the next page, moving to the next state when one runs out.
+    ///
+    /// Returns `None` when every state is done or the budget is spent.
+    pub async fn next_page(&mut self) -> Result<Option<SweepPage>> {
+        while let Some(&state) = self.states.get(self.state_index) {
+            if self
+                .budget
+                .is_some_and(|budget| self.credits_used >= budget)
+            {
+                self.budget_exhausted = true;
+                return Ok(None);
+            }
+
+            let params = SearchParams::new()
+                .state(state.as_str())
+                .location_type(self.location_type)
+                .limit(self.page_size)
+                .offset(self.offset);
+            let page = SearchClient::new(Cow::Borrowed(&self.client))
+                .markets(params)
+                .await?;
+            if let Some(used) = page.account.as_ref().and_then(|a| a.est_credits_used) {
+                self.credits_used += used;
+            }
+
+            let fetched = u32::try_from(page.items.len()).unwrap_or(u32::MAX);
+            let next_offset = self.offset.saturating_add(fetched);
+            if fetched == 0 || u64::from(next_offset) >= page.total {
+                self.state_index += 1;
+                self.offset = 0;
+            } else {
+                self.offset = next_offset;
+            }
+            if fetched > 0 {
+                return Ok(Some(SweepPage {
+                    state,
+                    markets: page.items,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns where the sweep stands, for [`resume_from`](Self::resume_from).
+    pub fn checkpoint(&self) -> SweepCheckpoint {
+        SweepCheckpoint {
+            state: self.states.get(self.state_index).copied(),
+            offset: self.offset,
+            credits_used: self.credits_used,
+        }
+    }
+
+    /// Credits spent by this sweep, including any carried over by a checkpoint.
+    pub fn credits_used(&self) -> i64 {
+        self.credits_used
+    }
+
+    /// Returns true if the sweep stopped because its budget was spent.
+    pub fn budget_exhausted(&self) -> bool {
+        self.budget_exhausted
+    }
+
+    /// Returns true if every state has been fetched.
+    pub fn is_finished(&self) -> bool {
+        self.state_index >= self.states.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{json_response, CannedTransport};
+
+    /// Serves three CA markets and one TX market, in pages of `limit`,
+    /// charging one credit per page.
+    fn two_states() -> CannedTransport {
+        CannedTransport::new(|request| {
+            let url = request.uri().to_string();
+            let param = |name: &str| -> Option<String> {
+                url.split(['?', '&'])
+                    .find_map(|p| p.strip_prefix(name)?.strip_prefix('='))
+                    .map(String::from)
+            };
+            let ids: &[i64] = match param("state_abbreviation").as_deref() {
+                Some("CA") => &[1, 2, 3],
+                Some("TX") => &[4],
+                _ => &[],
+            };
+            let offset: usize = param("offset").unwrap().parse().unwrap();
+            let limit: usize = param("limit").unwrap().parse().unwrap();
+            let items: Vec<_> = ids
+                .iter()
+                .skip(offset)
+                .take(limit)
+                .map(|id| serde_json::json!({"parcl_id": id, "name": "M", "location_type": "CITY"}))
+                .collect();
+            json_response(
+                200,
+                &serde_json::json!({
+                    "items": items, "total": ids.len(), "limit": limit, "offset": offset,
+                    "links": {}, "account": {"est_credits_used": 1, "est_remaining_credits": 100}
+                }),
+            )
+        })
+    }
+
+    fn client(transport: CannedTransport) -> ParclClient {
+        ParclClient::with_config("key", "https://example.com").with_transport(transport)
+    }
+
+    async fn collect(sweep: &mut Sweep<'_>) -> Vec<(USState, Vec<i64>)> {
+        let mut pages = Vec::new();
+        while let Some(page) = sweep.next_page().await.unwrap() {
+            let ids = page.markets.iter().map(|m| m.parcl_id).collect();
+            pages.push((page.state, ids));
+        }
+        pages
+    }
+
+    #[tokio::test]
+    async fn sweeps_every_state_page_by_page() {
+        let transport = two_states();
+        let client = client(transport.clone());
+        let states = [USState::California, USState::Alaska, USState::Texas];
+        let mut sweep = client
+            .search()
+            .sweep(&states, LocationType::City)
+            .page_size(2);
+
+        let pages = collect(&mut sweep).await;
+        assert_eq!(
+            pages,
+            [
+                (USState::California, vec![1, 2]),
+                (USState::California, vec![3]),
+                (USState::Texas, vec![4]),
+            ]
+        );
+        assert!(sweep.is_finished());
+        assert!(!sweep.budget_exhausted());
+        assert_eq!(sweep.credits_used(), 4);
+        assert_eq!(sweep.checkpoint().state, None);
+        assert!(transport.uris()[0].contains("location_type=CITY"));
+    }
+
+    #[tokio::test]
+    async fn budget_stops_sweep_and_checkpoint_resumes_it() {
+        let client = client(two_states());
+        let states = [USState::California, USState::Texas];
+        let mut sweep = client
+            .search()
+            .sweep(&states, LocationType::City)
+            .page_size(2)
+            .budget(1);
+
+        assert_eq!(collect(&mut sweep).await.len(), 1);
+        assert!(sweep.budget_exhausted());
+        let checkpoint = sweep.checkpoint();
+        assert_eq!(
+            checkpoint,
+            SweepCheckpoint {
+                state: Some(USState::California),
+                offset: 2,
+                credits_used: 1,
+            }
+        );
+
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        assert!(json.contains("\"CA\""));
+        let checkpoint: SweepCheckpoint = serde_json::from_str(&json).unwrap();
+
+        let mut resumed = client
+            .search()
+            .sweep(&states, LocationType::City)
+            .page_size(2)
+            .budget(10)
+            .resume_from(&checkpoint)
+            .unwrap();
+        assert_eq!(
+            collect(&mut resumed).await,
+            [(USState::California, vec![3]), (USState::Texas, vec![4])]
+        );
+        assert_eq!(resumed.credits_used(), 3);
+
+        let err = client
+            .search()
+            .sweep(&[USState::Ohio], LocationType::City)
+            .resume_from(&checkpoint)
+            .unwrap_err();
+        assert!(matches!(err, ParclError::Validation(_)));
+    }
+}
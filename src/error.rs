@@ -1,29 +1,345 @@
 use thiserror::Error;
 
 /// Errors that can occur when interacting with the Parcl Labs API
+///
+/// Wrapped reqwest, serde and http errors are exposed through
+/// [`std::error::Error::source`], so reporters such as `anyhow` and `miette`
+/// (with the `miette` feature) print the full cause chain.
 #[derive(Error, Debug)]
+#[cfg_attr(feature = "miette", derive(miette::Diagnostic))]
 pub enum ParclError {
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(parcllabs::missing_api_key),
+            help("set PARCL_LABS_API_KEY or use ParclClient::with_api_key")
+        )
+    )]
     #[error("API key not provided. Set PARCL_LABS_API_KEY environment variable or pass it to the client")]
     MissingApiKey,
 
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::request_failed)))]
     #[error("HTTP request failed: {0}")]
     RequestFailed(#[from] reqwest::Error),
 
-    #[error("API error ({status}): {message}")]
-    ApiError { status: u16, message: String },
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(parcllabs::invalid_request),
+            help("check the base URL and AuthConfig header name")
+        )
+    )]
+    #[error("Failed to build request: {0}")]
+    InvalidRequest(#[from] http::Error),
 
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::api_error)))]
+    #[error("API error ({status}) from {endpoint}{}: {message}", parcl_id_suffix(.parcl_id))]
+    ApiError {
+        status: u16,
+        message: String,
+        /// API path that failed, e.g. `/v1/market_metrics/housing_stock`.
+        endpoint: &'static str,
+        /// The market requested, for single-market endpoints.
+        parcl_id: Option<i64>,
+    },
+
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::parse_error)))]
     #[error("Failed to parse response: {0}")]
     ParseError(#[from] serde_json::Error),
 
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::io)))]
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(parcllabs::decode),
+            help("the API response no longer matches the SDK models; please report the path and snippet")
+        )
+    )]
+    #[error("Failed to decode response at `{path}`: {source} (near `{snippet}`)")]
+    Decode {
+        /// JSON path of the field that failed, e.g. `items[3].sales`.
+        path: String,
+        /// Excerpt of the response body around the failure.
+        snippet: String,
+        source: serde_json::Error,
+    },
+
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::invalid_parameter)))]
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
 
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::validation)))]
+    #[error("Invalid request parameters: {0}")]
+    Validation(String),
+
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(parcllabs::rate_limited),
+            help("raise RetryConfig::max_retries or slow down requests")
+        )
+    )]
     #[error("Rate limited after {attempts} attempts: {message}")]
     RateLimited { attempts: u32, message: String },
+
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(parcllabs::response_too_large),
+            help("narrow the query (dates, markets, limit) or raise ResponseLimits")
+        )
+    )]
+    #[error("Response from {endpoint} exceeded {limit} ({received} received)")]
+    ResponseTooLarge {
+        endpoint: &'static str,
+        limit: crate::limits::SizeLimit,
+        received: u64,
+    },
+
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(parcllabs::not_in_plan),
+            help("check the key's Parcl Labs plan or the PlanTier given to the client")
+        )
+    )]
+    #[error("{endpoint} is not included in this API key's plan{}", message_suffix(.message))]
+    NotInPlan {
+        /// Endpoint path without the `parcl_id`, e.g.
+        /// `/v1/investor_metrics/housing_stock_ownership`.
+        endpoint: String,
+        /// The API's explanation, or `None` when refused by the declared
+        /// `PlanTier` without asking.
+        message: Option<String>,
+    },
+
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(parcllabs::no_price_feed),
+            help("check Market::has_price_feed before requesting a price feed")
+        )
+    )]
+    #[error("Market {parcl_id} has no price feed")]
+    NoPriceFeed { parcl_id: i64 },
+
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(parcllabs::market_not_found),
+            help(
+                "check the spelling, add a state (\"Denver, CO\"), or search and pass a parcl_id"
+            )
+        )
+    )]
+    #[error("No market named {name:?}")]
+    MarketNotFound { name: String },
+
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::step_failed)))]
+    #[error("{step} failed{}: {source}", parcl_id_suffix(.parcl_id))]
+    StepFailed {
+        step: &'static str,
+        parcl_id: Option<i64>,
+        source: Box<ParclError>,
+    },
+
+    #[cfg(feature = "csv")]
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::csv)))]
+    #[error("Failed to read CSV input: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[cfg(feature = "sqlite")]
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::sqlite)))]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[cfg(feature = "postgres")]
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::postgres)))]
+    #[error("PostgreSQL error: {0}")]
+    Postgres(#[from] sqlx::Error),
+
+    #[cfg(feature = "nats")]
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::nats)))]
+    #[error("NATS error: {0}")]
+    Nats(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[cfg(feature = "charts")]
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::chart)))]
+    #[error("Failed to draw chart: {0}")]
+    Chart(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[cfg(feature = "xlsx")]
+    #[cfg_attr(feature = "miette", diagnostic(code(parcllabs::xlsx)))]
+    #[error("Excel export error: {0}")]
+    Xlsx(#[from] rust_xlsxwriter::XlsxError),
+
+    #[cfg(feature = "vcr")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(parcllabs::cassette),
+            help("re-record the cassette with PARCL_LABS_VCR=record")
+        )
+    )]
+    #[error("Cassette error: {0}")]
+    Cassette(String),
 }
 
 pub type Result<T> = std::result::Result<T, ParclError>;
 
+impl ParclError {
+    /// Returns the innermost error in the `source()` chain.
+    pub fn root_cause(&self) -> &(dyn std::error::Error + 'static) {
+        let mut current: &(dyn std::error::Error + 'static) = self;
+        while let Some(next) = current.source() {
+            current = next;
+        }
+        current
+    }
+
+    /// Returns the HTTP status behind this error, if any.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ParclError::ApiError { status, .. } => Some(*status),
+            ParclError::RateLimited { .. } => Some(429),
+            ParclError::NotInPlan { .. } => Some(403),
+            ParclError::RequestFailed(e) => e.status().map(|s| s.as_u16()),
+            ParclError::StepFailed { source, .. } => source.status(),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the API rejected the request for exceeding its rate limit.
+    pub fn is_rate_limited(&self) -> bool {
+        self.status() == Some(429)
+    }
+
+    /// Returns true if the same request may succeed later: rate limits,
+    /// transient network failures (see [`NetworkErrorKind::is_transient`])
+    /// and 5xx server errors.
+    ///
+    /// Client errors (bad parameters, 4xx, unparseable responses) are terminal.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ParclError::RequestFailed(_)
+                if self.network_kind().is_some_and(|k| k.is_transient()) =>
+            {
+                true
+            }
+            ParclError::StepFailed { source, .. } => source.is_retryable(),
+            _ => matches!(self.status(), Some(408 | 429 | 500..=599)),
+        }
+    }
+
+    /// What went wrong below HTTP, for [`RequestFailed`](Self::RequestFailed)
+    /// errors that never produced a response.
+    pub fn network_kind(&self) -> Option<NetworkErrorKind> {
+        match self {
+            ParclError::RequestFailed(e) if e.status().is_none() => Some(NetworkErrorKind::of(e)),
+            ParclError::StepFailed { source, .. } => source.network_kind(),
+            _ => None,
+        }
+    }
+
+    /// Wraps this error with the name of the step that failed and its market.
+    pub(crate) fn in_step(self, step: &'static str, parcl_id: Option<i64>) -> Self {
+        ParclError::StepFailed {
+            step,
+            parcl_id,
+            source: Box::new(self),
+        }
+    }
+}
+
+/// The network layer a failed request broke in.
+///
+/// reqwest reports most of these as opaque connect errors; the kind is read
+/// from the error and its causes, so it is a best effort for unusual stacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NetworkErrorKind {
+    /// The host name could not be resolved.
+    Dns,
+    /// The connection was refused, reset or closed early.
+    Connect,
+    /// The TLS handshake failed, e.g. an interrupted proxy handshake.
+    Tls,
+    /// The server's certificate was rejected.
+    Certificate,
+    /// The request or connection timed out.
+    Timeout,
+    /// The response body was cut off while reading it.
+    Body,
+    /// Anything else, such as an invalid URL or redirect loop.
+    Other,
+}
+
+impl NetworkErrorKind {
+    /// Returns true for failures that flaky networks produce and a retry
+    /// can fix. Certificate rejections and `Other` are permanent.
+    pub fn is_transient(&self) -> bool {
+        !matches!(self, Self::Certificate | Self::Other)
+    }
+
+    fn of(error: &reqwest::Error) -> Self {
+        let mut causes = String::new();
+        let mut cause: Option<&(dyn std::error::Error + 'static)> = Some(error);
+        while let Some(e) = cause {
+            causes.push_str(&e.to_string().to_ascii_lowercase());
+            causes.push('\n');
+            cause = e.source();
+        }
+        Self::classify(
+            &causes,
+            error.is_timeout(),
+            error.is_connect(),
+            error.is_body() || error.is_decode(),
+        )
+    }
+
+    fn classify(causes: &str, timeout: bool, connect: bool, body: bool) -> Self {
+        let mentions = |needles: &[&str]| needles.iter().any(|n| causes.contains(n));
+        if timeout || mentions(&["timed out"]) {
+            Self::Timeout
+        } else if mentions(&["dns error", "failed to lookup address", "no such host"]) {
+            Self::Dns
+        } else if mentions(&["certificate", "unknownissuer"]) {
+            Self::Certificate
+        } else if mentions(&["tls", "handshake", "ssl"]) {
+            Self::Tls
+        } else if connect
+            || mentions(&[
+                "connection refused",
+                "connection reset",
+                "connection closed",
+                "broken pipe",
+            ])
+        {
+            Self::Connect
+        } else if body {
+            Self::Body
+        } else {
+            Self::Other
+        }
+    }
+}
+
+fn message_suffix(message: &Option<String>) -> String {
+    message
+        .as_ref()
+        .map(|m| format!(": {m}"))
+        .unwrap_or_default()
+}
+
+fn parcl_id_suffix(parcl_id: &Option<i64>) -> String {
+    parcl_id
+        .map(|id| format!(" for parcl_id {id}"))
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,8 +356,24 @@ mod tests {
         let err = ParclError::ApiError {
             status: 404,
             message: "Not found".into(),
+            endpoint: "/v1/market_metrics/housing_stock",
+            parcl_id: Some(2900187),
+        };
+        assert_eq!(
+            err.to_string(),
+            "API error (404) from /v1/market_metrics/housing_stock for parcl_id 2900187: Not found"
+        );
+
+        let batch = ParclError::ApiError {
+            status: 500,
+            message: "boom".into(),
+            endpoint: "/v1/market_metrics/housing_stock",
+            parcl_id: None,
         };
-        assert_eq!(err.to_string(), "API error (404): Not found");
+        assert_eq!(
+            batch.to_string(),
+            "API error (500) from /v1/market_metrics/housing_stock: boom"
+        );
     }
 
     #[test]
@@ -62,6 +394,100 @@ mod tests {
         );
     }
 
+    fn api_error(status: u16) -> ParclError {
+        ParclError::ApiError {
+            status,
+            message: String::new(),
+            endpoint: "/v1/search/markets",
+            parcl_id: None,
+        }
+    }
+
+    #[test]
+    fn status_and_rate_limit_accessors() {
+        assert_eq!(api_error(404).status(), Some(404));
+        assert!(!api_error(404).is_rate_limited());
+        let limited = ParclError::RateLimited {
+            attempts: 3,
+            message: String::new(),
+        };
+        assert_eq!(limited.status(), Some(429));
+        assert!(limited.is_rate_limited());
+        assert_eq!(ParclError::MissingApiKey.status(), None);
+    }
+
+    #[test]
+    fn retryable_classification() {
+        assert!(api_error(429).is_retryable());
+        assert!(api_error(503).is_retryable());
+        assert!(api_error(408).is_retryable());
+        assert!(!api_error(400).is_retryable());
+        assert!(!api_error(404).is_retryable());
+        assert!(!ParclError::MissingApiKey.is_retryable());
+        assert!(!ParclError::InvalidParameter("x".into()).is_retryable());
+        assert!(api_error(502).in_step("search", None).is_retryable());
+        assert_eq!(api_error(502).in_step("search", None).status(), Some(502));
+    }
+
+    #[test]
+    fn parcl_error_is_send_sync_static() {
+        fn assert_bounds<T: std::error::Error + Send + Sync + 'static>() {}
+        assert_bounds::<ParclError>();
+    }
+
+    #[test]
+    fn source_chain_preserves_inner_errors() {
+        use std::error::Error as _;
+
+        let json_err = serde_json::from_str::<i32>("x").unwrap_err();
+        let json_msg = json_err.to_string();
+        let err = ParclError::from(json_err).in_step("search", Some(1));
+
+        let step_source = err.source().unwrap();
+        assert!(step_source
+            .to_string()
+            .starts_with("Failed to parse response"));
+        assert!(step_source.source().unwrap().is::<serde_json::Error>());
+        assert_eq!(err.root_cause().to_string(), json_msg);
+    }
+
+    #[test]
+    fn converts_into_anyhow_with_chain() {
+        let json_err = serde_json::from_str::<i32>("x").unwrap_err();
+        let err: anyhow::Error = ParclError::from(json_err).into();
+        assert_eq!(err.chain().count(), 2);
+        assert!(err.downcast_ref::<ParclError>().is_some());
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn miette_diagnostic_codes() {
+        use miette::Diagnostic;
+        let err = ParclError::MissingApiKey;
+        assert_eq!(
+            err.code().unwrap().to_string(),
+            "parcllabs::missing_api_key"
+        );
+        assert!(err.help().is_some());
+    }
+
+    #[test]
+    fn decode_error_display_and_source() {
+        use std::error::Error as _;
+        let source = serde_json::from_str::<i32>("\"x\"").unwrap_err();
+        let err = ParclError::Decode {
+            path: "items[0].sales".into(),
+            snippet: r#""sales": "x""#.into(),
+            source,
+        };
+        assert!(err
+            .to_string()
+            .starts_with("Failed to decode response at `items[0].sales`: invalid type"));
+        assert!(err.to_string().ends_with(r#"(near `"sales": "x"`)"#));
+        assert!(err.source().unwrap().is::<serde_json::Error>());
+        assert!(!err.is_retryable());
+    }
+
     #[test]
     fn parse_error_from_serde() {
         let json_err = serde_json::from_str::<i32>("not a number").unwrap_err();
@@ -69,4 +495,52 @@ mod tests {
         assert!(matches!(err, ParclError::ParseError(_)));
         assert!(err.to_string().contains("Failed to parse response"));
     }
+
+    #[test]
+    fn network_errors_are_classified_from_their_causes() {
+        let classify = |causes: &str| NetworkErrorKind::classify(causes, false, true, false);
+        assert_eq!(
+            classify("error sending request\nclient error (connect)\ndns error: failed to lookup address information"),
+            NetworkErrorKind::Dns
+        );
+        assert_eq!(
+            classify("client error (connect)\ninvalid peer certificate: unknownissuer"),
+            NetworkErrorKind::Certificate
+        );
+        assert_eq!(
+            classify("client error (connect)\ntls handshake eof"),
+            NetworkErrorKind::Tls
+        );
+        assert_eq!(
+            classify("tcp connect error: connection refused (os error 111)"),
+            NetworkErrorKind::Connect
+        );
+        assert_eq!(
+            NetworkErrorKind::classify("error decoding response body", false, false, true),
+            NetworkErrorKind::Body
+        );
+        assert_eq!(
+            NetworkErrorKind::classify("builder error", false, false, false),
+            NetworkErrorKind::Other
+        );
+        assert!(NetworkErrorKind::Tls.is_transient());
+        assert!(!NetworkErrorKind::Certificate.is_transient());
+    }
+
+    #[tokio::test]
+    async fn refused_connections_are_retried_then_reported() {
+        let client = crate::ParclClient::with_config("key", "http://127.0.0.1:9")
+            .with_retry_config(crate::RetryConfig {
+                max_retries: 1,
+                initial_backoff_ms: 1,
+            });
+        let err = client
+            .market_metrics()
+            .housing_stock(1, None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.network_kind(), Some(NetworkErrorKind::Connect));
+        assert!(err.is_retryable());
+        assert_eq!(api_error(503).network_kind(), None);
+    }
 }
@@ -0,0 +1,206 @@
+//! Fuzzy market name matching over search results.
+//!
+//! Enabled with the `fuzzy` feature. The API's market search matches names
+//! fairly literally, so "Ft Lauderdale FL" finds nothing. [`find_market`]
+//! normalizes the query (common abbreviations, a trailing state code),
+//! searches with it, and scores the candidates with Jaro-Winkler similarity.
+//!
+//! [`find_market`]: crate::endpoints::SearchClient::find_market
+
+use crate::endpoints::SearchClient;
+use crate::error::Result;
+use crate::models::Market;
+use crate::SearchParams;
+
+/// Candidates fetched per search.
+const CANDIDATES: u32 = 50;
+
+/// Abbreviations expanded before matching.
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("ft", "fort"),
+    ("st", "saint"),
+    ("ste", "sainte"),
+    ("mt", "mount"),
+    ("pt", "point"),
+    ("n", "north"),
+    ("s", "south"),
+    ("e", "east"),
+    ("w", "west"),
+];
+
+const STATE_CODES: &[&str] = &[
+    "AK", "AL", "AR", "AZ", "CA", "CO", "CT", "DC", "DE", "FL", "GA", "HI", "IA", "ID", "IL", "IN",
+    "KS", "KY", "LA", "MA", "MD", "ME", "MI", "MN", "MO", "MS", "MT", "NC", "ND", "NE", "NH", "NJ",
+    "NM", "NV", "NY", "OH", "OK", "OR", "PA", "PR", "RI", "SC", "SD", "TN", "TX", "UT", "VA", "VT",
+    "WA", "WI", "WV", "WY",
+];
+
+/// A market matched to a free-form query.
+#[derive(Debug, Clone)]
+pub struct MarketMatch {
+    pub market: Market,
+    /// Name similarity from 0.0 to 1.0; 1.0 means the normalized names are equal.
+    pub confidence: f64,
+}
+
+/// A query split into a normalized name and an optional state code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedQuery {
+    pub name: String,
+    pub state: Option<String>,
+}
+
+/// Lowercases `query`, strips punctuation, expands abbreviations such as
+/// "Ft" and "St", and splits off a trailing two-letter state code.
+pub fn normalize_query(query: &str) -> NormalizedQuery {
+    let mut words = words(query);
+    let state = match words.last() {
+        Some(last) if words.len() > 1 && STATE_CODES.contains(&last.to_uppercase().as_str()) => {
+            words.pop().map(str::to_uppercase)
+        }
+        _ => None,
+    };
+    NormalizedQuery {
+        name: expand(words),
+        state,
+    }
+}
+
+fn words(s: &str) -> Vec<&str> {
+    s.split(|c: char| c.is_whitespace() || c == ',' || c == '.' || c == '-')
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Lowercases and joins `words`, expanding abbreviations.
+fn expand(words: Vec<&str>) -> String {
+    words
+        .into_iter()
+        .map(|w| {
+            let w = w.to_lowercase();
+            ABBREVIATIONS
+                .iter()
+                .find(|(short, _)| *short == w)
+                .map_or(w, |(_, long)| long.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Scores `markets` against `query`, best first.
+///
+/// Markets outside the query's state (if it names one) are dropped. Equal
+/// scores are ordered by population.
+pub fn rank_matches(query: &str, markets: &[Market]) -> Vec<MarketMatch> {
+    let query = normalize_query(query);
+    let mut matches: Vec<MarketMatch> = markets
+        .iter()
+        .filter(|m| match &query.state {
+            Some(state) => m
+                .state_abbreviation
+                .as_deref()
+                .is_some_and(|s| s.eq_ignore_ascii_case(state)),
+            None => true,
+        })
+        .map(|m| MarketMatch {
+            confidence: strsim::jaro_winkler(&query.name, &expand(words(&m.name))),
+            market: m.clone(),
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| b.market.total_population.cmp(&a.market.total_population))
+    });
+    matches
+}
+
+impl SearchClient<'_> {
+    /// Resolves a loosely written market name such as "Ft Lauderdale FL".
+    ///
+    /// Searches with the normalized name, falling back to its longest word
+    /// when that finds nothing, and returns the closest candidate with its
+    /// confidence. Returns `None` if the API has no candidates at all.
+    ///
+    /// ```no_run
+    /// # async fn example() -> parcllabs::Result<()> {
+    /// let client = parcllabs::ParclClient::new()?;
+    /// if let Some(found) = client.search().find_market("Ft Lauderdale FL").await? {
+    ///     println!("{} ({:.0}%)", found.market.name, found.confidence * 100.0);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn find_market(&self, query: &str) -> Result<Option<MarketMatch>> {
+        let normalized = normalize_query(query);
+        let search = |text: String| {
+            let mut params = SearchParams::new().query(text).limit(CANDIDATES);
+            if let Some(state) = &normalized.state {
+                params = params.state(state);
+            }
+            self.markets(params)
+        };
+
+        let mut candidates = search(normalized.name.clone()).await?.items;
+        if candidates.is_empty() {
+            let longest = normalized
+                .name
+                .split(' ')
+                .max_by_key(|w| w.len())
+                .unwrap_or_default();
+            if longest != normalized.name && !longest.is_empty() {
+                candidates = search(longest.to_string()).await?.items;
+            }
+        }
+        Ok(rank_matches(query, &candidates).into_iter().next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(parcl_id: i64, name: &str, state: &str, pop: i64) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "parcl_id": parcl_id,
+            "name": name,
+            "state_abbreviation": state,
+            "location_type": "CITY",
+            "total_population": pop
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn normalize_expands_abbreviations_and_state() {
+        assert_eq!(
+            normalize_query("Ft. Lauderdale, FL"),
+            NormalizedQuery {
+                name: "fort lauderdale".into(),
+                state: Some("FL".into()),
+            }
+        );
+        // A lone word is a name even if it looks like a state code.
+        assert_eq!(normalize_query("Mt").name, "mount");
+        assert_eq!(normalize_query("Mt").state, None);
+        // State codes are recognized before abbreviations are expanded.
+        assert_eq!(normalize_query("Helena MT").state.as_deref(), Some("MT"));
+    }
+
+    #[test]
+    fn rank_matches_scores_and_filters_by_state() {
+        let markets = [
+            market(1, "Lauderdale Lakes", "FL", 35_000),
+            market(2, "Fort Lauderdale", "FL", 180_000),
+            market(3, "Fort Lauderdale", "TN", 1_000),
+        ];
+        let ranked = rank_matches("Ft Lauderdale FL", &markets);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].market.parcl_id, 2);
+        assert!((ranked[0].confidence - 1.0).abs() < f64::EPSILON);
+        assert!(ranked[1].confidence < 1.0);
+
+        let ranked = rank_matches("st petersburg", &[market(4, "St. Petersburg", "FL", 1)]);
+        assert!((ranked[0].confidence - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,412 @@
+//! Response and request types generated from the Parcl Labs API schemas.
+//!
+//! @generated by `cargo xtask codegen`. Do not edit by hand; change the
+//! naming rules in `xtask/naming.json` and regenerate instead.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AccountInfo {
+    pub est_credits_used: Option<i64>,
+    pub est_remaining_credits: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AllCash {
+    pub count_sales: Option<i64>,
+    pub count_transfers: Option<i64>,
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub pct_sales: Option<f64>,
+    pub pct_transfers: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Envelope {
+    pub account: Option<AccountInfo>,
+    pub items: Vec<serde_json::Value>,
+    pub limit: Option<i64>,
+    pub links: Option<PaginationLinks>,
+    pub offset: Option<i64>,
+    pub parcl_id: Option<i64>,
+    pub total: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EventPrices {
+    pub new_listings_for_sale: Option<f64>,
+    pub new_rental_listings: Option<f64>,
+    pub sales: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ForSaleInventory {
+    pub date: String,
+    pub for_sale_inventory: Option<i64>,
+    pub parcl_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ForSaleInventoryPriceChanges {
+    pub count_price_change: Option<i64>,
+    pub count_price_drop: Option<i64>,
+    pub date: String,
+    pub median_days_bt_change: Option<f64>,
+    pub median_pct_price_change: Option<f64>,
+    pub median_price_change: Option<f64>,
+    pub parcl_id: Option<i64>,
+    pub pct_inventory_price_change: Option<f64>,
+    pub pct_inventory_price_drop: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GrossYield {
+    pub date: String,
+    pub gross_yield: Option<f64>,
+    pub parcl_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HousingEventCounts {
+    pub date: String,
+    pub new_listings_for_sale: Option<i64>,
+    pub new_rental_listings: Option<i64>,
+    pub parcl_id: Option<i64>,
+    pub sales: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HousingEventPrices {
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub price: Option<PriceStats>,
+    pub price_per_square_foot: Option<PriceStats>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HousingEventPropertyAttributes {
+    pub baths: Option<f64>,
+    pub beds: Option<i64>,
+    pub date: String,
+    pub lot_size: Option<i64>,
+    pub parcl_id: Option<i64>,
+    pub sqft: Option<i64>,
+    pub year_built: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HousingStock {
+    pub all_properties: Option<i64>,
+    pub condo: Option<i64>,
+    pub date: String,
+    pub other: Option<i64>,
+    pub parcl_id: Option<i64>,
+    pub single_family: Option<i64>,
+    pub townhouse: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InvestorHousingEventCounts {
+    pub acquisitions: Option<i64>,
+    pub date: String,
+    pub dispositions: Option<i64>,
+    pub new_listings_for_sale: Option<i64>,
+    pub new_rental_listings: Option<i64>,
+    pub parcl_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InvestorHousingStockOwnership {
+    pub count: Option<i64>,
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub pct_ownership: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InvestorNewListingsRollingCounts {
+    pub count: Option<RollingCounts>,
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub pct_for_sale_market: Option<RollingPercentages>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InvestorPurchaseToSaleRatio {
+    pub acquisitions: Option<i64>,
+    pub date: String,
+    pub dispositions: Option<i64>,
+    pub parcl_id: Option<i64>,
+    pub purchase_to_sale_ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Market {
+    pub case_shiller_10_market: Option<i64>,
+    pub case_shiller_20_market: Option<i64>,
+    pub country: Option<String>,
+    pub geoid: Option<String>,
+    pub location_type: String,
+    pub median_income: Option<i64>,
+    pub name: String,
+    pub parcl_exchange_market: Option<i64>,
+    pub parcl_id: i64,
+    pub pricefeed_market: Option<i64>,
+    pub region: Option<String>,
+    pub state_abbreviation: Option<String>,
+    pub state_fips_code: Option<String>,
+    pub total_population: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NewListingsRollingCounts {
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub rolling_30_day: Option<i64>,
+    pub rolling_60_day: Option<i64>,
+    pub rolling_7_day: Option<i64>,
+    pub rolling_90_day: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PaginationLinks {
+    pub first: Option<String>,
+    pub last: Option<String>,
+    pub next: Option<String>,
+    pub prev: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PortfolioHousingEventCounts {
+    pub acquisitions: Option<i64>,
+    pub date: String,
+    pub dispositions: Option<i64>,
+    pub new_listings_for_sale: Option<i64>,
+    pub new_rental_listings: Option<i64>,
+    pub parcl_id: Option<i64>,
+    pub transfers: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PortfolioNewListingsRollingCounts {
+    pub count: Option<RollingCounts>,
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub pct_sf_for_sale_market: Option<RollingPercentages>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PortfolioRentalListingsRollingCounts {
+    pub count: Option<RollingCounts>,
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub pct_sf_for_rent_market: Option<RollingPercentages>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PortfolioSizeBreakdown {
+    pub all_portfolios: Option<i64>,
+    pub portfolio_1000_plus: Option<i64>,
+    pub portfolio_100_to_999: Option<i64>,
+    pub portfolio_10_to_99: Option<i64>,
+    pub portfolio_2_to_9: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PortfolioSizePctBreakdown {
+    pub all_portfolios: Option<f64>,
+    pub portfolio_1000_plus: Option<f64>,
+    pub portfolio_100_to_999: Option<f64>,
+    pub portfolio_10_to_99: Option<f64>,
+    pub portfolio_2_to_9: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PortfolioStockOwnership {
+    pub count: Option<PortfolioSizeBreakdown>,
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub pct_sf_housing_stock: Option<PortfolioSizePctBreakdown>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PriceFeedEntry {
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub price: f64,
+    pub price_feed_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PriceStats {
+    pub median: Option<EventPrices>,
+    pub percentile_20th: Option<EventPrices>,
+    pub percentile_80th: Option<EventPrices>,
+    pub standard_deviation: Option<EventPrices>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Property {
+    pub address: Option<String>,
+    pub bathrooms: Option<f64>,
+    pub bedrooms: Option<i64>,
+    pub cbsa: Option<String>,
+    pub cbsa_parcl_id: Option<i64>,
+    pub city: Option<String>,
+    pub city_parcl_id: Option<i64>,
+    pub county: Option<String>,
+    pub county_parcl_id: Option<i64>,
+    pub current_entity_owner_name: Option<String>,
+    pub current_investor_owned_flag: Option<i64>,
+    pub current_new_construction_flag: Option<i64>,
+    pub current_on_market_flag: Option<i64>,
+    pub current_on_market_rental_flag: Option<i64>,
+    pub current_owner_occupied_flag: Option<i64>,
+    pub event_count: Option<i64>,
+    pub event_history_listing_flag: Option<i64>,
+    pub event_history_rental_flag: Option<i64>,
+    pub event_history_sale_flag: Option<i64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub parcl_property_id: i64,
+    pub property_type: Option<String>,
+    pub record_added_date: Option<String>,
+    pub square_footage: Option<i64>,
+    pub state_abbreviation: Option<String>,
+    pub unit: Option<String>,
+    pub year_built: Option<i64>,
+    pub zip_code: Option<String>,
+    pub zip_parcl_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PropertyEvent {
+    pub entity_owner_name: Option<String>,
+    pub event_date: Option<String>,
+    pub event_name: Option<String>,
+    pub event_type: Option<String>,
+    pub investor_flag: Option<i64>,
+    pub new_construction_flag: Option<i64>,
+    pub owner_occupied_flag: Option<i64>,
+    pub price: Option<i64>,
+    pub record_updated_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PropertyEventHistoryResponse {
+    pub properties: Vec<PropertyWithEvents>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PropertyMetadata {
+    pub address: Option<String>,
+    pub bathrooms: Option<f64>,
+    pub bedrooms: Option<i64>,
+    pub city: Option<String>,
+    pub property_type: Option<String>,
+    pub square_footage: Option<i64>,
+    pub state: Option<String>,
+    pub year_built: Option<i64>,
+    pub zip: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PropertySearchResponse {
+    pub account: Option<AccountInfo>,
+    pub items: Vec<Property>,
+    pub total: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PropertyV2 {
+    pub events: Option<Vec<PropertyV2Event>>,
+    pub parcl_property_id: i64,
+    pub property_metadata: Option<PropertyV2Metadata>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PropertyV2Event {
+    pub current_owner_flag: Option<i64>,
+    pub entity_owner_name: Option<String>,
+    pub event_date: Option<String>,
+    pub event_name: Option<String>,
+    pub event_type: Option<String>,
+    pub investor_flag: Option<i64>,
+    pub new_construction_flag: Option<i64>,
+    pub owner_occupied_flag: Option<i64>,
+    pub price: Option<i64>,
+    pub record_updated_date: Option<String>,
+    pub transfer_index: Option<i64>,
+    pub true_sale_index: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PropertyV2Metadata {
+    pub address1: Option<String>,
+    pub address2: Option<String>,
+    pub bathrooms: Option<f64>,
+    pub bedrooms: Option<i64>,
+    pub city: Option<String>,
+    pub city_name: Option<String>,
+    pub county_name: Option<String>,
+    pub current_entity_owner_name: Option<String>,
+    pub current_investor_owned_flag: Option<i64>,
+    pub current_new_construction_flag: Option<i64>,
+    pub current_on_market_flag: Option<i64>,
+    pub current_on_market_rental_flag: Option<i64>,
+    pub current_owner_occupied_flag: Option<i64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub metro_name: Option<String>,
+    pub property_type: Option<String>,
+    pub record_added_date: Option<String>,
+    pub sq_ft: Option<i64>,
+    pub state: Option<String>,
+    pub year_built: Option<i64>,
+    pub zip5: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PropertyV2SearchResponse {
+    pub properties: Vec<PropertyV2>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PropertyWithEvents {
+    pub events: Option<Vec<PropertyEvent>>,
+    pub parcl_property_id: i64,
+    pub property_metadata: Option<PropertyMetadata>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RentalNewListingsRollingCounts {
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub rolling_30_day: Option<i64>,
+    pub rolling_60_day: Option<i64>,
+    pub rolling_7_day: Option<i64>,
+    pub rolling_90_day: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RentalUnitsConcentration {
+    pub date: String,
+    pub parcl_id: Option<i64>,
+    pub rental_units_concentration: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RollingCounts {
+    pub rolling_30_day: Option<i64>,
+    pub rolling_60_day: Option<i64>,
+    pub rolling_7_day: Option<i64>,
+    pub rolling_90_day: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RollingPercentages {
+    pub rolling_30_day: Option<f64>,
+    pub rolling_60_day: Option<f64>,
+    pub rolling_7_day: Option<f64>,
+    pub rolling_90_day: Option<f64>,
+}
@@ -0,0 +1,227 @@
+//! Geographic hierarchy of markets: ZIP → city → county → CBSA.
+//!
+//! Market search does not link markets to each other, but every property
+//! carries the `parcl_id` of the ZIP, city, county and CBSA it sits in.
+//! [`MarketHierarchy`] resolves those ids to [`Market`]s, and
+//! [`ParclClient::child_markets`] collects them across a market's properties
+//! to drill down a level.
+
+use crate::error::Result;
+use crate::models::{LocationType, Market, Property, PropertyType};
+use crate::{ParclClient, PropertySearchParams};
+
+/// A level of the geographic hierarchy, smallest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GeoLevel {
+    Zip,
+    City,
+    County,
+    Cbsa,
+}
+
+impl GeoLevel {
+    /// Every level, smallest first.
+    pub const ALL: [GeoLevel; 4] = [Self::Zip, Self::City, Self::County, Self::Cbsa];
+
+    /// The market search location type for this level.
+    pub fn location_type(&self) -> LocationType {
+        match self {
+            Self::Zip => LocationType::Zip5,
+            Self::City => LocationType::City,
+            Self::County => LocationType::County,
+            Self::Cbsa => LocationType::Cbsa,
+        }
+    }
+
+    /// The level of a market's `location_type`, if it is one of the four.
+    pub fn of(market: &Market) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.location_type().as_str() == market.location_type)
+    }
+
+    /// The `parcl_id` of the market at this level containing `property`.
+    pub fn parcl_id_of(&self, property: &Property) -> Option<i64> {
+        match self {
+            Self::Zip => property.zip_parcl_id,
+            Self::City => property.city_parcl_id,
+            Self::County => property.county_parcl_id,
+            Self::Cbsa => property.cbsa_parcl_id,
+        }
+    }
+}
+
+/// The markets containing a property or market, one per level.
+///
+/// A level is `None` when the property has no market at that level or
+/// search did not return it.
+#[derive(Debug, Clone, Default)]
+pub struct MarketHierarchy {
+    pub zip: Option<Market>,
+    pub city: Option<Market>,
+    pub county: Option<Market>,
+    pub cbsa: Option<Market>,
+}
+
+impl MarketHierarchy {
+    pub fn get(&self, level: GeoLevel) -> Option<&Market> {
+        match level {
+            GeoLevel::Zip => self.zip.as_ref(),
+            GeoLevel::City => self.city.as_ref(),
+            GeoLevel::County => self.county.as_ref(),
+            GeoLevel::Cbsa => self.cbsa.as_ref(),
+        }
+    }
+
+    fn slot(&mut self, level: GeoLevel) -> &mut Option<Market> {
+        match level {
+            GeoLevel::Zip => &mut self.zip,
+            GeoLevel::City => &mut self.city,
+            GeoLevel::County => &mut self.county,
+            GeoLevel::Cbsa => &mut self.cbsa,
+        }
+    }
+
+    /// The resolved markets above `level`, nearest first.
+    pub fn parents(&self, level: GeoLevel) -> Vec<&Market> {
+        GeoLevel::ALL
+            .into_iter()
+            .filter(|l| *l > level)
+            .filter_map(|l| self.get(l))
+            .collect()
+    }
+}
+
+impl ParclClient {
+    /// Resolves the ZIP, city, county and CBSA markets a property sits in.
+    ///
+    /// Costs one search per market not already in the
+    /// [`MarketCache`](crate::MarketCache).
+    pub async fn property_hierarchy(&self, property: &Property) -> Result<MarketHierarchy> {
+        let mut hierarchy = MarketHierarchy::default();
+        for level in GeoLevel::ALL {
+            if let Some(parcl_id) = level.parcl_id_of(property) {
+                *hierarchy.slot(level) = self.resolve_market(parcl_id).await?;
+            }
+        }
+        Ok(hierarchy)
+    }
+
+    /// Resolves a market and the markets above it.
+    ///
+    /// The parents are read from one of the market's properties, so this
+    /// costs a `limit=1` property search on top of the market lookups. The
+    /// market itself fills its own level; levels below it are left empty.
+    pub async fn market_hierarchy(&self, parcl_id: i64) -> Result<MarketHierarchy> {
+        let market = self.resolve_market(parcl_id).await?;
+        let level = market.as_ref().and_then(GeoLevel::of);
+        let params = PropertySearchParams::new(parcl_id, PropertyType::AllProperties).limit(1);
+        let mut hierarchy = match self.property().search(params).await?.items.first() {
+            Some(property) => self.property_hierarchy(property).await?,
+            None => MarketHierarchy::default(),
+        };
+        if let Some(level) = level {
+            *hierarchy.slot(level) = market;
+            for below in GeoLevel::ALL.into_iter().filter(|l| *l < level) {
+                *hierarchy.slot(below) = None;
+            }
+        }
+        Ok(hierarchy)
+    }
+
+    /// Markets at `level` that contain at least one of the first `sample`
+    /// properties in `parcl_id`, in order of first appearance.
+    ///
+    /// Search has no parent filter, so children are discovered from property
+    /// records: a larger sample finds sparsely populated children at the cost
+    /// of a bigger property search.
+    pub async fn child_markets(
+        &self,
+        parcl_id: i64,
+        level: GeoLevel,
+        sample: u32,
+    ) -> Result<Vec<Market>> {
+        let params = PropertySearchParams::new(parcl_id, PropertyType::AllProperties).limit(sample);
+        let properties = self.property().search(params).await?.items;
+        let mut ids: Vec<i64> = Vec::new();
+        for id in properties.iter().filter_map(|p| level.parcl_id_of(p)) {
+            if id != parcl_id && !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        let mut resolved = self.market_cache().resolve_all(self, &ids).await?;
+        Ok(ids.iter().filter_map(|id| resolved.remove(id)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{json_response, CannedTransport};
+
+    fn market(parcl_id: i64, location_type: &str) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "parcl_id": parcl_id,
+            "name": format!("Market {parcl_id}"),
+            "location_type": location_type
+        }))
+        .unwrap()
+    }
+
+    /// Serves two properties in city 20: one in ZIP 10, one in ZIP 11.
+    fn properties() -> CannedTransport {
+        CannedTransport::new(|request| {
+            assert!(request.uri().path().ends_with("/v1/property/search"));
+            let property = |id: i64, zip: i64| {
+                serde_json::json!({
+                    "parcl_property_id": id,
+                    "zip_parcl_id": zip,
+                    "city_parcl_id": 20,
+                    "county_parcl_id": 30,
+                    "cbsa_parcl_id": 40
+                })
+            };
+            json_response(
+                200,
+                &serde_json::json!({
+                    "items": [property(1, 10), property(2, 11), property(3, 10)],
+                    "total": 3
+                }),
+            )
+        })
+    }
+
+    fn client() -> ParclClient {
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(properties());
+        client.market_cache().extend([
+            market(10, "ZIP5"),
+            market(11, "ZIP5"),
+            market(20, "CITY"),
+            market(30, "COUNTY"),
+            market(40, "CBSA"),
+        ]);
+        client
+    }
+
+    #[tokio::test]
+    async fn market_hierarchy_fills_own_level_and_parents() {
+        let hierarchy = client().market_hierarchy(20).await.unwrap();
+        assert!(hierarchy.zip.is_none());
+        assert_eq!(hierarchy.city.as_ref().unwrap().parcl_id, 20);
+        let parents: Vec<i64> = hierarchy
+            .parents(GeoLevel::City)
+            .iter()
+            .map(|m| m.parcl_id)
+            .collect();
+        assert_eq!(parents, [30, 40]);
+    }
+
+    #[tokio::test]
+    async fn child_markets_are_distinct_in_order() {
+        let zips = client().child_markets(20, GeoLevel::Zip, 3).await.unwrap();
+        let ids: Vec<i64> = zips.iter().map(|m| m.parcl_id).collect();
+        assert_eq!(ids, [10, 11]);
+        assert_eq!(GeoLevel::of(&zips[0]), Some(GeoLevel::Zip));
+    }
+}
@@ -0,0 +1,165 @@
+//! Tolerant deserializers for numeric fields, enabled with the `lenient`
+//! feature.
+//!
+//! The API occasionally sends a number as a string (`"123"` rather than
+//! `123`). With the feature on, every count, price, flag and coordinate
+//! field of the SDK models goes through [`int`], [`float`] or
+//! [`required_float`], which accept either form, so
+//! such a wobble decodes instead of failing the whole response. Custom
+//! models can opt in the same way:
+//!
+//! ```
+//! # #[cfg(feature = "lenient")] {
+//! #[derive(serde::Deserialize)]
+//! struct Row {
+//!     #[serde(default, deserialize_with = "parcllabs::lenient::int")]
+//!     sales: Option<i64>,
+//! }
+//!
+//! let row: Row = serde_json::from_str(r#"{"sales": "1,204"}"#).unwrap();
+//! assert_eq!(row.sales, Some(1204));
+//! # }
+//! ```
+
+use serde::de::{Deserializer, Error};
+use serde::Deserialize;
+
+/// A JSON value that should be a number.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Numeric {
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl Numeric {
+    /// The text with thousands separators removed, or `None` if blank or
+    /// `"null"`.
+    fn text(text: &str) -> Option<String> {
+        let text = text.trim().replace(',', "");
+        (!text.is_empty() && !text.eq_ignore_ascii_case("null")).then_some(text)
+    }
+}
+
+/// `f` as an integer if it is whole and within `i64`.
+fn whole(f: f64) -> Option<i64> {
+    (f.fract() == 0.0 && f.abs() < i64::MAX as f64).then_some(f as i64)
+}
+
+/// Reads an optional integer from a number or a numeric string.
+///
+/// Floats with no fractional part, such as `12.0`, are accepted; blank
+/// strings read as `None`. Values that don't fit the field's type, such as
+/// a flag of `"1e20"`, are errors rather than saturating.
+pub fn int<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<i64>,
+{
+    let n = match Option::<Numeric>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(Numeric::Int(n)) => n,
+        Some(Numeric::Float(f)) => {
+            whole(f).ok_or_else(|| D::Error::custom(format!("expected an integer, got {f}")))?
+        }
+        Some(Numeric::Text(text)) => match Numeric::text(&text) {
+            None => return Ok(None),
+            Some(digits) => digits
+                .parse::<i64>()
+                .ok()
+                .or_else(|| digits.parse::<f64>().ok().and_then(whole))
+                .ok_or_else(|| D::Error::custom(format!("expected an integer, got {text:?}")))?,
+        },
+    };
+    T::try_from(n)
+        .map(Some)
+        .map_err(|_| D::Error::custom(format!("integer {n} is out of range")))
+}
+
+/// Reads an optional float from a number or a numeric string; blank strings
+/// read as `None`.
+pub fn float<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    match Option::<Numeric>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Numeric::Int(n)) => Ok(Some(n as f64)),
+        Some(Numeric::Float(f)) => Ok(Some(f)),
+        Some(Numeric::Text(text)) => match Numeric::text(&text) {
+            None => Ok(None),
+            Some(digits) => digits
+                .parse::<f64>()
+                .map(Some)
+                .map_err(|_| D::Error::custom(format!("expected a number, got {text:?}"))),
+        },
+    }
+}
+
+/// Reads a required float from a number or a numeric string.
+pub fn required_float<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    float(deserializer)?.ok_or_else(|| D::Error::missing_field("number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{HousingEventCounts, HousingEventPrices, Market, PriceFeedEntry};
+
+    #[test]
+    fn numbers_as_strings_decode() {
+        let counts: HousingEventCounts = serde_json::from_value(serde_json::json!({
+            "date": "2024-01-01", "sales": "1,204", "new_listings_for_sale": 12.0,
+            "new_rental_listings": ""
+        }))
+        .unwrap();
+        assert_eq!(counts.sales, Some(1204));
+        assert_eq!(counts.new_listings_for_sale, Some(12));
+        assert_eq!(counts.new_rental_listings, None);
+        assert_eq!(counts.parcl_id, None);
+
+        let prices: HousingEventPrices = serde_json::from_value(serde_json::json!({
+            "date": "2024-01-01", "price": {"median": {"sales": "410000.5"}}
+        }))
+        .unwrap();
+        assert_eq!(prices.price.unwrap().median.unwrap().sales, Some(410000.5));
+
+        let err = serde_json::from_value::<HousingEventCounts>(serde_json::json!({
+            "date": "2024-01-01", "sales": "many"
+        }));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn prices_and_flags_decode_from_strings() {
+        let entry: PriceFeedEntry = serde_json::from_value(serde_json::json!({
+            "date": "2024-01-01", "price": "312,500.25"
+        }))
+        .unwrap();
+        assert_eq!(entry.price, 312500.25);
+
+        let market: Market = serde_json::from_value(serde_json::json!({
+            "parcl_id": 1, "name": "Austin", "location_type": "CITY",
+            "pricefeed_market": "1", "case_shiller_20_market": 0.0
+        }))
+        .unwrap();
+        assert!(market.has_price_feed());
+        assert_eq!(market.case_shiller_20_market, Some(0));
+    }
+
+    #[test]
+    fn out_of_range_integers_are_errors() {
+        for sales in [
+            serde_json::json!("1e30"),
+            serde_json::json!(1e30),
+            serde_json::json!("9223372036854775808"),
+        ] {
+            let decoded = serde_json::from_value::<HousingEventCounts>(serde_json::json!({
+                "date": "2024-01-01", "sales": sales
+            }));
+            assert!(decoded.is_err(), "{sales} decoded");
+        }
+        let flag = serde_json::from_value::<Market>(serde_json::json!({
+            "parcl_id": 1, "name": "Austin", "location_type": "CITY",
+            "pricefeed_market": "4294967296"
+        }));
+        assert!(flag.is_err());
+    }
+}
@@ -32,35 +32,150 @@
 //! }
 //! ```
 
+pub mod analysis;
+pub mod audit;
+pub mod basket;
+pub mod cache;
+pub mod capabilities;
+pub mod catalog;
+#[cfg(feature = "charts")]
+pub mod charts;
+pub mod debug;
+#[cfg(feature = "display")]
+pub mod display;
 pub mod endpoints;
 pub mod error;
+#[cfg(feature = "fuzzy")]
+pub mod fuzzy;
+pub mod generated;
+pub mod hierarchy;
+#[cfg(feature = "lenient")]
+pub mod lenient;
+pub mod limits;
+pub mod metric;
+#[cfg(feature = "tower")]
+pub mod middleware;
 pub mod models;
+pub mod pagination;
+pub mod pipeline;
+pub mod plan;
+pub mod prefetch;
+pub mod protocol;
+pub mod quota;
+pub mod reference;
+#[cfg(feature = "display")]
+pub mod report;
+pub mod runtime;
+#[cfg(feature = "schema-validation")]
+pub mod schema;
+pub mod sink;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+#[cfg(feature = "vcr")]
+pub mod vcr;
+pub mod watchdog;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
 
+pub use basket::{Basket, BasketMember};
+pub use cache::{ConditionalCache, ConditionalStats, Labeled, MarketCache, SearchCache};
+pub use capabilities::{MarketCapabilities, MetricFamily, Probe};
+pub use catalog::{FieldChange, MarketCatalog, MarketCatalogDiff, MarketChange};
+pub use endpoints::common::FetchOptions;
 pub use endpoints::for_sale_metrics::ForSaleMetricsParams;
 pub use endpoints::investor_metrics::InvestorMetricsParams;
 pub use endpoints::market_metrics::MetricsParams;
 pub use endpoints::new_construction_metrics::NewConstructionMetricsParams;
 pub use endpoints::portfolio_metrics::PortfolioMetricsParams;
+#[cfg(feature = "csv")]
+pub use endpoints::property::{AddressCsvOptions, AddressMatch};
 pub use endpoints::property::{EventHistoryParams, PropertySearchParams};
 pub use endpoints::rental_metrics::RentalMetricsParams;
 pub use endpoints::search::SearchParams;
-pub use error::{ParclError, Result};
+pub use endpoints::sweep::{Sweep, SweepCheckpoint, SweepPage};
+pub use error::{NetworkErrorKind, ParclError, Result};
+pub use hierarchy::{GeoLevel, MarketHierarchy};
+pub use limits::{LimitPolicy, ResponseLimits};
+pub use metric::{Fairness, Metric, MetricFetch, MetricSeries};
 pub use models::*;
-// RetryConfig is defined in this module (not models), so no re-export needed.
+pub use pagination::{Page, Paginator};
+pub use pipeline::{MarketSnapshot, MarketStep, Pipeline};
+pub use plan::PlanTier;
+pub use prefetch::PrefetchService;
+pub use protocol::{RateLimitStatus, ReqwestTransport, Transport};
+pub use quota::{QuotaExhausted, QuotaGuard, QuotaSignal};
+pub use runtime::Timer;
+pub use tokio_util::sync::CancellationToken;
+pub use watchdog::{SupplyWatchdog, SurgeAlert};
+// RetryConfig and AuthConfig are defined in this module (not models), so no re-export needed.
 
 use endpoints::{
     ForSaleMetricsClient, InvestorMetricsClient, MarketMetricsClient, NewConstructionMetricsClient,
     PortfolioMetricsClient, PriceFeedClient, PropertyClient, RentalMetricsClient, SearchClient,
 };
-use reqwest::Client;
+use protocol::RequestContext;
+use serde::de::DeserializeOwned;
+use std::borrow::Cow;
 use std::env;
 use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Cargo features this build of the crate was compiled with.
+///
+/// Useful for checking that a size-sensitive deployment really built
+/// without default features or optional integrations.
+pub const ENABLED_FEATURES: &[&str] = &[
+    #[cfg(feature = "reqwest-default")]
+    "reqwest-default",
+    #[cfg(feature = "tokio")]
+    "tokio",
+    #[cfg(feature = "csv")]
+    "csv",
+    #[cfg(feature = "miette")]
+    "miette",
+    #[cfg(feature = "schema-validation")]
+    "schema-validation",
+    #[cfg(feature = "display")]
+    "display",
+    #[cfg(feature = "locale")]
+    "locale",
+    #[cfg(feature = "vcr")]
+    "vcr",
+    #[cfg(feature = "async-std")]
+    "async-std",
+    #[cfg(feature = "fuzzy")]
+    "fuzzy",
+    #[cfg(feature = "tower")]
+    "tower",
+    #[cfg(feature = "sqlite")]
+    "sqlite",
+    #[cfg(feature = "postgres")]
+    "postgres",
+    #[cfg(feature = "nats")]
+    "nats",
+    #[cfg(feature = "charts")]
+    "charts",
+    #[cfg(feature = "xlsx")]
+    "xlsx",
+    #[cfg(feature = "test-util")]
+    "test-util",
+    #[cfg(feature = "lenient")]
+    "lenient",
+];
 
 const DEFAULT_BASE_URL: &str = "https://api.parcllabs.com";
 const ENV_API_KEY: &str = "PARCL_LABS_API_KEY";
+/// Search results checked by [`ParclClient::market_by_name`].
+const MARKET_NAME_CANDIDATES: u32 = 50;
 
-/// Configuration for automatic retry on rate-limited (429) responses.
-#[derive(Debug, Clone)]
+/// Configuration for automatic retry on rate-limited (429) responses and
+/// transient network failures such as DNS, connect and TLS handshake errors.
+///
+/// Set on the client with [`ParclClient::with_retry_config`], or per request
+/// with the `retry_config`/`no_retry` methods on params builders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RetryConfig {
     /// Maximum number of retry attempts before giving up.
     pub max_retries: u32,
@@ -77,22 +192,139 @@ impl Default for RetryConfig {
     }
 }
 
+impl RetryConfig {
+    /// Never retries: a 429 fails immediately with `ParclError::RateLimited`.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff_ms: 0,
+        }
+    }
+}
+
+/// How the API key is attached to each request.
+///
+/// Defaults to the bare key in the `Authorization` header, which is what the
+/// Parcl Labs API expects. Gateways and proxies may need a scheme prefix or a
+/// different header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthConfig {
+    /// Header name the key is sent in.
+    pub header: String,
+    /// Optional scheme prefix, e.g. `Bearer`, separated from the key by a space.
+    pub scheme: Option<String>,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            header: "Authorization".into(),
+            scheme: None,
+        }
+    }
+}
+
+impl AuthConfig {
+    /// Sends `Authorization: Bearer <key>`.
+    pub fn bearer() -> Self {
+        Self {
+            scheme: Some("Bearer".into()),
+            ..Self::default()
+        }
+    }
+
+    /// Sends the bare key in a custom header, e.g. `X-API-Key`.
+    pub fn header(name: impl Into<String>) -> Self {
+        Self {
+            header: name.into(),
+            scheme: None,
+        }
+    }
+
+    /// Sets the scheme prefix.
+    pub fn scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    /// Formats the header value for `api_key`.
+    pub(crate) fn header_value(&self, api_key: &str) -> String {
+        match &self.scheme {
+            Some(scheme) => format!("{scheme} {api_key}"),
+            None => api_key.to_string(),
+        }
+    }
+}
+
 /// Main client for interacting with the Parcl Labs API.
+///
+/// Cloning is cheap: clones share the HTTP connection pool, market and
+/// search caches, credit counters, rate-limit and quota state, so a client
+/// can be handed to spawned tasks or stored in web framework state without
+/// an extra `Arc`. Builder methods called on a clone only reconfigure that
+/// clone.
+#[derive(Clone)]
 pub struct ParclClient {
-    pub(crate) http: Client,
+    pub(crate) transport: Arc<dyn Transport>,
+    pub(crate) timer: Arc<dyn Timer>,
     pub(crate) base_url: String,
     pub(crate) api_key: String,
     pub(crate) retry_config: RetryConfig,
-    session_credits_used: AtomicI64,
-    remaining_credits: AtomicI64,
+    pub(crate) auth: AuthConfig,
+    pub(crate) limit_policy: LimitPolicy,
+    pub(crate) response_limits: ResponseLimits,
+    pub(crate) batch_concurrency: usize,
+    pub(crate) sdk_header: bool,
+    pub(crate) keep_raw: bool,
+    pub(crate) hash_content: bool,
+    pub(crate) price_feed_check: bool,
+    pub(crate) audit: Option<audit::AuditLog>,
+    #[cfg(feature = "schema-validation")]
+    pub(crate) schema_drift_handler: Option<schema::SchemaDriftHandler>,
+    pub(crate) quota: Option<Arc<quota::QuotaState>>,
+    pub(crate) plan: plan::PlanState,
+    rate_limit: Arc<Mutex<Option<RateLimitStatus>>>,
+    session_credits_used: Arc<AtomicI64>,
+    remaining_credits: Arc<AtomicI64>,
+    market_cache: Arc<MarketCache>,
+    search_cache: Arc<SearchCache>,
+    conditional_cache: Option<Arc<ConditionalCache>>,
 }
 
+// Checked at compile time: the client must stay shareable across tasks,
+// and owned sub-clients spawnable.
+const _: () = {
+    const fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+    const fn assert_spawnable<T: Send + Sync + 'static>() {}
+    assert_shareable::<ParclClient>();
+    assert_spawnable::<SearchClient<'static>>();
+    assert_spawnable::<MarketMetricsClient<'static>>();
+    assert_spawnable::<InvestorMetricsClient<'static>>();
+    assert_spawnable::<ForSaleMetricsClient<'static>>();
+    assert_spawnable::<RentalMetricsClient<'static>>();
+    assert_spawnable::<PriceFeedClient<'static>>();
+    assert_spawnable::<NewConstructionMetricsClient<'static>>();
+    assert_spawnable::<PortfolioMetricsClient<'static>>();
+    assert_spawnable::<PropertyClient<'static>>();
+};
+
 impl std::fmt::Debug for ParclClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ParclClient")
             .field("base_url", &self.base_url)
             .field("api_key", &"***")
             .field("retry_config", &self.retry_config)
+            .field("auth", &self.auth)
+            .field("limit_policy", &self.limit_policy)
+            .field("response_limits", &self.response_limits)
+            .field("batch_concurrency", &self.batch_concurrency)
+            .field("keep_raw", &self.keep_raw)
+            .field("hash_content", &self.hash_content)
+            .field("price_feed_check", &self.price_feed_check)
+            .field("audit", &self.audit.is_some())
+            .field("quota", &self.quota)
+            .field("plan", &self.plan.tier)
+            .field("rate_limit", &self.rate_limit_status())
             .field(
                 "session_credits_used",
                 &self.session_credits_used.load(Ordering::Relaxed),
@@ -101,6 +333,8 @@ impl std::fmt::Debug for ParclClient {
                 "remaining_credits",
                 &self.remaining_credits.load(Ordering::Relaxed),
             )
+            .field("cached_markets", &self.market_cache.len())
+            .field("cached_searches", &self.search_cache.len())
             .finish()
     }
 }
@@ -109,37 +343,52 @@ impl ParclClient {
     /// Creates a new client using the `PARCL_LABS_API_KEY` environment variable.
     pub fn new() -> Result<Self> {
         let api_key = env::var(ENV_API_KEY).map_err(|_| ParclError::MissingApiKey)?;
-        Ok(Self {
-            http: Client::new(),
-            base_url: DEFAULT_BASE_URL.to_string(),
-            api_key,
-            retry_config: RetryConfig::default(),
-            session_credits_used: AtomicI64::new(0),
-            remaining_credits: AtomicI64::new(0),
-        })
+        Ok(Self::with_api_key(api_key))
+    }
+
+    /// Returns a process-wide client configured from `PARCL_LABS_API_KEY`,
+    /// creating it on first use.
+    ///
+    /// Warm serverless invocations (e.g. AWS Lambda) reuse the same client,
+    /// and so its connection pool and market cache. If the key is missing
+    /// the call fails and a later call tries again.
+    pub fn global() -> Result<&'static ParclClient> {
+        static GLOBAL: OnceLock<ParclClient> = OnceLock::new();
+        init_once(&GLOBAL, Self::new)
     }
 
     /// Creates a new client with an explicit API key.
     pub fn with_api_key(api_key: impl Into<String>) -> Self {
-        Self {
-            http: Client::new(),
-            base_url: DEFAULT_BASE_URL.to_string(),
-            api_key: api_key.into(),
-            retry_config: RetryConfig::default(),
-            session_credits_used: AtomicI64::new(0),
-            remaining_credits: AtomicI64::new(0),
-        }
+        Self::with_config(api_key, DEFAULT_BASE_URL)
     }
 
     /// Creates a new client with custom configuration.
     pub fn with_config(api_key: impl Into<String>, base_url: impl Into<String>) -> Self {
         Self {
-            http: Client::new(),
+            transport: Arc::new(ReqwestTransport::default()),
+            timer: runtime::default_timer(),
             base_url: base_url.into(),
             api_key: api_key.into(),
             retry_config: RetryConfig::default(),
-            session_credits_used: AtomicI64::new(0),
-            remaining_credits: AtomicI64::new(0),
+            auth: AuthConfig::default(),
+            limit_policy: LimitPolicy::default(),
+            response_limits: ResponseLimits::default(),
+            batch_concurrency: 4,
+            sdk_header: false,
+            keep_raw: false,
+            hash_content: false,
+            price_feed_check: false,
+            audit: None,
+            #[cfg(feature = "schema-validation")]
+            schema_drift_handler: None,
+            quota: None,
+            plan: plan::PlanState::default(),
+            rate_limit: Arc::new(Mutex::new(None)),
+            session_credits_used: Arc::new(AtomicI64::new(0)),
+            remaining_credits: Arc::new(AtomicI64::new(0)),
+            market_cache: Arc::new(MarketCache::new()),
+            search_cache: Arc::new(SearchCache::default()),
+            conditional_cache: None,
         }
     }
 
@@ -149,6 +398,145 @@ impl ParclClient {
         self
     }
 
+    /// Replaces the HTTP executor, e.g. to run on a non-reqwest stack.
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Also sends an `X-Parcl-SDK` header naming this SDK and its version.
+    ///
+    /// Every request already carries a `parcllabs-rust/<version>` user agent;
+    /// the extra header helps when a proxy rewrites the user agent.
+    pub fn with_sdk_header(mut self, enabled: bool) -> Self {
+        self.sdk_header = enabled;
+        self
+    }
+
+    /// Keeps the exact body of every response in `meta.raw`, one entry per
+    /// page, alongside the typed items.
+    ///
+    /// For archiving payloads for audit or compliance. Off by default, since
+    /// it roughly doubles the memory a response holds.
+    pub fn with_raw(mut self, enabled: bool) -> Self {
+        self.keep_raw = enabled;
+        self
+    }
+
+    /// Records a [`ContentHash`] of every response in `meta.content_hash`,
+    /// so sync jobs can tell when nothing changed since their last pull.
+    ///
+    /// Off by default, since hashing re-parses each body.
+    pub fn with_content_hash(mut self, enabled: bool) -> Self {
+        self.hash_content = enabled;
+        self
+    }
+
+    /// Looks markets up before price feed requests, failing with
+    /// [`ParclError::NoPriceFeed`] instead of sending a request for a market
+    /// without a feed.
+    ///
+    /// Off by default, since a market missing from the cache costs a search.
+    /// Without it, the same error is reported once the API rejects the
+    /// request.
+    pub fn with_price_feed_check(mut self, enabled: bool) -> Self {
+        self.price_feed_check = enabled;
+        self
+    }
+
+    /// Revalidates repeat GET requests with `ETag` / `Last-Modified` instead
+    /// of downloading them again; see [`ConditionalCache`].
+    ///
+    /// Clones of the client share the cache.
+    pub fn with_conditional_cache(mut self, cache: ConditionalCache) -> Self {
+        self.conditional_cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Appends one JSON line per HTTP request to `log`; see the [`audit`]
+    /// module.
+    pub fn with_audit_log(mut self, log: audit::AuditLog) -> Self {
+        self.audit = Some(log);
+        self
+    }
+
+    /// Returns the version of this SDK, for bug reports and support requests.
+    pub fn sdk_version() -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    /// Sets how requested page sizes above an endpoint's maximum are handled.
+    ///
+    /// Defaults to [`LimitPolicy::Clamp`]; see the [`limits`] module.
+    pub fn with_limit_policy(mut self, policy: LimitPolicy) -> Self {
+        self.limit_policy = policy;
+        self
+    }
+
+    /// Caps how much one call may download before failing with
+    /// [`ParclError::ResponseTooLarge`].
+    ///
+    /// Unlimited by default. Useful in memory-constrained services where an
+    /// over-broad auto-paginated query could otherwise pull gigabytes.
+    pub fn with_response_limits(mut self, limits: ResponseLimits) -> Self {
+        self.response_limits = limits;
+        self
+    }
+
+    /// Sets how many requests a `batch_*` call runs at once when its ID list
+    /// is split into chunks of [`limits::MAX_BATCH_PARCL_IDS`]. Defaults to 4.
+    pub fn with_batch_concurrency(mut self, concurrency: usize) -> Self {
+        self.batch_concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Replaces the cache used by `search().cached()`, e.g. with one loaded
+    /// from disk or with a different TTL.
+    pub fn with_search_cache(mut self, cache: SearchCache) -> Self {
+        self.search_cache = Arc::new(cache);
+        self
+    }
+
+    /// Replaces the timer used for backoff, quota pauses and throttling.
+    ///
+    /// The default depends on the enabled runtime features; see the
+    /// [`runtime`] module.
+    pub fn with_timer(mut self, timer: impl Timer + 'static) -> Self {
+        self.timer = Arc::new(timer);
+        self
+    }
+
+    /// Routes schema discrepancies to `handler` instead of stderr.
+    #[cfg(feature = "schema-validation")]
+    pub fn with_schema_drift_handler(
+        mut self,
+        handler: impl Fn(&schema::SchemaDrift) + Send + Sync + 'static,
+    ) -> Self {
+        self.schema_drift_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Sets how the API key is attached to requests.
+    pub fn with_auth_config(mut self, auth: AuthConfig) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Fetches an API path (e.g. `/v1/...?limit=10`) or absolute URL and
+    /// decodes the JSON response, for endpoints the SDK does not wrap.
+    ///
+    /// Retries on 429 like the built-in endpoints. Pair with [`Paginator`]
+    /// for paginated endpoints.
+    pub async fn get_raw<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = if path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else {
+            format!("{}{}", self.base_url, path)
+        };
+        let (response, meta) = endpoints::common::send(self, &url, None, None).await?;
+        endpoints::common::decode(self, &response, &meta, RequestContext::new("get_raw"))
+    }
+
     /// Updates session credit tracking from an API response's account info.
     pub(crate) fn update_credits(&self, account: &Option<AccountInfo>) {
         if let Some(info) = account {
@@ -157,10 +545,30 @@ impl ParclClient {
             }
             if let Some(remaining) = info.est_remaining_credits {
                 self.remaining_credits.store(remaining, Ordering::Relaxed);
+                if remaining <= 0 {
+                    if let Some(quota) = &self.quota {
+                        quota.trip(quota::QuotaSignal::CreditsDepleted, self.timer.now());
+                    }
+                }
             }
         }
     }
 
+    /// Returns the rate-limit headers of the most recent response that had any.
+    ///
+    /// Lets schedulers adjust concurrency before hitting 429s. `None` until
+    /// the API sends `X-RateLimit-*` headers.
+    pub fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        *self.rate_limit.lock().unwrap()
+    }
+
+    pub(crate) fn record_rate_limit<B>(&self, response: &http::Response<B>) {
+        if let Some(mut status) = protocol::parse_rate_limit(response) {
+            status.observed_at = self.timer.now();
+            *self.rate_limit.lock().unwrap() = Some(status);
+        }
+    }
+
     /// Returns the accumulated session credit usage.
     pub fn account_info(&self) -> AccountUsage {
         AccountUsage {
@@ -179,56 +587,247 @@ impl ParclClient {
         self.remaining_credits.load(Ordering::Relaxed)
     }
 
+    /// Returns the client's market metadata cache.
+    ///
+    /// The cache is filled automatically from `search().markets()` results.
+    pub fn market_cache(&self) -> &MarketCache {
+        &self.market_cache
+    }
+
+    /// Returns the cache behind `search().cached()`.
+    pub fn search_cache(&self) -> &SearchCache {
+        &self.search_cache
+    }
+
+    /// Returns the cache installed with
+    /// [`with_conditional_cache`](Self::with_conditional_cache).
+    pub fn conditional_cache(&self) -> Option<&ConditionalCache> {
+        self.conditional_cache.as_deref()
+    }
+
+    /// Hits and misses of the conditional cache, or zeros without one.
+    pub fn conditional_stats(&self) -> ConditionalStats {
+        self.conditional_cache()
+            .map(ConditionalCache::stats)
+            .unwrap_or_default()
+    }
+
+    /// Resolves a `parcl_id` to its `Market`, searching only on a cache miss.
+    pub async fn resolve_market(&self, parcl_id: i64) -> Result<Option<Market>> {
+        self.market_cache.resolve(self, parcl_id).await
+    }
+
+    /// Resolves a market name such as `"Denver"` or `"Denver, CO"`.
+    ///
+    /// A trailing two-letter code after a comma filters by state. Names are
+    /// matched exactly, ignoring case; among several matches (a city and a
+    /// county of the same name) the most populous wins. Checks the
+    /// [`MarketCache`] first and otherwise runs a
+    /// [cached](crate::endpoints::SearchClient::cached) search, so repeat
+    /// lookups cost nothing. Fails with [`ParclError::MarketNotFound`] when
+    /// no market has that name.
+    pub async fn market_by_name(&self, name: &str) -> Result<Market> {
+        let (market_name, state) = match name.rsplit_once(',') {
+            Some((head, tail))
+                if tail.trim().len() == 2
+                    && tail.trim().chars().all(|c| c.is_ascii_alphabetic()) =>
+            {
+                (head.trim(), Some(tail.trim()))
+            }
+            _ => (name.trim(), None),
+        };
+        if let Some(market) = self.market_cache.find_by_name(market_name, state) {
+            return Ok(market);
+        }
+        let mut params = SearchParams::new()
+            .query(market_name)
+            .limit(MARKET_NAME_CANDIDATES);
+        if let Some(state) = state {
+            params = params.state(state);
+        }
+        let found = self.search().cached().markets(params).await?;
+        found
+            .items
+            .into_iter()
+            .filter(|m| m.name.eq_ignore_ascii_case(market_name))
+            .filter(|m| {
+                state.is_none_or(|s| {
+                    m.state_abbreviation
+                        .as_deref()
+                        .is_some_and(|ms| ms.eq_ignore_ascii_case(s))
+                })
+            })
+            .max_by_key(|m| m.total_population.unwrap_or(0))
+            .ok_or_else(|| ParclError::MarketNotFound {
+                name: name.to_string(),
+            })
+    }
+
+    /// Pairs a single-market response with its `Market` metadata.
+    pub async fn enrich_with_market<T>(
+        &self,
+        resp: MetricsResponse<T>,
+    ) -> Result<Labeled<MetricsResponse<T>>> {
+        let market = self.resolve_market(resp.parcl_id).await?;
+        Ok(Labeled { market, item: resp })
+    }
+
+    /// Labels each item of a batch response with its `Market` metadata.
+    ///
+    /// Each distinct `parcl_id` is resolved at most once.
+    pub async fn enrich_batch_with_market<T: HasParclId>(
+        &self,
+        resp: BatchMetricsResponse<T>,
+    ) -> Result<Vec<Labeled<T>>> {
+        let mut ids: Vec<i64> = resp.items.iter().filter_map(|i| i.parcl_id()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        self.market_cache.resolve_all(self, &ids).await?;
+        Ok(self.market_cache.label(resp.items))
+    }
+
     /// Returns a client for search endpoints.
     pub fn search(&self) -> SearchClient<'_> {
-        SearchClient::new(self)
+        SearchClient::new(Cow::Borrowed(self))
     }
 
     /// Returns a client for market metrics endpoints.
     pub fn market_metrics(&self) -> MarketMetricsClient<'_> {
-        MarketMetricsClient::new(self)
+        MarketMetricsClient::new(Cow::Borrowed(self))
     }
 
     /// Returns a client for investor metrics endpoints.
     pub fn investor_metrics(&self) -> InvestorMetricsClient<'_> {
-        InvestorMetricsClient::new(self)
+        InvestorMetricsClient::new(Cow::Borrowed(self))
     }
 
     /// Returns a client for for-sale market metrics endpoints.
     pub fn for_sale_metrics(&self) -> ForSaleMetricsClient<'_> {
-        ForSaleMetricsClient::new(self)
+        ForSaleMetricsClient::new(Cow::Borrowed(self))
     }
 
     /// Returns a client for rental market metrics endpoints.
     pub fn rental_metrics(&self) -> RentalMetricsClient<'_> {
-        RentalMetricsClient::new(self)
+        RentalMetricsClient::new(Cow::Borrowed(self))
     }
 
     /// Returns a client for price feed endpoints.
     pub fn price_feed(&self) -> PriceFeedClient<'_> {
-        PriceFeedClient::new(self)
+        PriceFeedClient::new(Cow::Borrowed(self))
     }
 
     /// Returns a client for new construction metrics endpoints.
     pub fn new_construction_metrics(&self) -> NewConstructionMetricsClient<'_> {
-        NewConstructionMetricsClient::new(self)
+        NewConstructionMetricsClient::new(Cow::Borrowed(self))
     }
 
     /// Returns a client for portfolio metrics endpoints.
     pub fn portfolio_metrics(&self) -> PortfolioMetricsClient<'_> {
-        PortfolioMetricsClient::new(self)
+        PortfolioMetricsClient::new(Cow::Borrowed(self))
     }
 
     /// Returns a client for property API endpoints.
     pub fn property(&self) -> PropertyClient<'_> {
-        PropertyClient::new(self)
+        PropertyClient::new(Cow::Borrowed(self))
+    }
+
+    /// Like [`search`](Self::search), but the sub-client owns a clone of
+    /// this client, so it is `'static` and can be stored in a struct or
+    /// moved into a spawned task. Clones share state, so this is cheap.
+    ///
+    /// ```no_run
+    /// # use parcllabs::{ParclClient, SearchParams};
+    /// # async fn example(client: ParclClient) {
+    /// let search = client.search_owned();
+    /// tokio::spawn(async move { search.markets(SearchParams::new().query("Austin")).await });
+    /// # }
+    /// ```
+    pub fn search_owned(&self) -> SearchClient<'static> {
+        SearchClient::new(Cow::Owned(self.clone()))
+    }
+
+    /// Owned, `'static` variant of [`market_metrics`](Self::market_metrics).
+    pub fn market_metrics_owned(&self) -> MarketMetricsClient<'static> {
+        MarketMetricsClient::new(Cow::Owned(self.clone()))
+    }
+
+    /// Owned, `'static` variant of [`investor_metrics`](Self::investor_metrics).
+    pub fn investor_metrics_owned(&self) -> InvestorMetricsClient<'static> {
+        InvestorMetricsClient::new(Cow::Owned(self.clone()))
+    }
+
+    /// Owned, `'static` variant of [`for_sale_metrics`](Self::for_sale_metrics).
+    pub fn for_sale_metrics_owned(&self) -> ForSaleMetricsClient<'static> {
+        ForSaleMetricsClient::new(Cow::Owned(self.clone()))
+    }
+
+    /// Owned, `'static` variant of [`rental_metrics`](Self::rental_metrics).
+    pub fn rental_metrics_owned(&self) -> RentalMetricsClient<'static> {
+        RentalMetricsClient::new(Cow::Owned(self.clone()))
+    }
+
+    /// Owned, `'static` variant of [`price_feed`](Self::price_feed).
+    pub fn price_feed_owned(&self) -> PriceFeedClient<'static> {
+        PriceFeedClient::new(Cow::Owned(self.clone()))
+    }
+
+    /// Owned, `'static` variant of [`new_construction_metrics`](Self::new_construction_metrics).
+    pub fn new_construction_metrics_owned(&self) -> NewConstructionMetricsClient<'static> {
+        NewConstructionMetricsClient::new(Cow::Owned(self.clone()))
+    }
+
+    /// Owned, `'static` variant of [`portfolio_metrics`](Self::portfolio_metrics).
+    pub fn portfolio_metrics_owned(&self) -> PortfolioMetricsClient<'static> {
+        PortfolioMetricsClient::new(Cow::Owned(self.clone()))
+    }
+
+    /// Owned, `'static` variant of [`property`](Self::property).
+    pub fn property_owned(&self) -> PropertyClient<'static> {
+        PropertyClient::new(Cow::Owned(self.clone()))
     }
 }
 
+/// Returns the value in `cell`, initializing it with `init` if empty.
+///
+/// Unlike `OnceLock::get_or_init`, a failed `init` leaves the cell empty.
+fn init_once<T>(cell: &OnceLock<T>, init: impl FnOnce() -> Result<T>) -> Result<&T> {
+    if let Some(value) = cell.get() {
+        return Ok(value);
+    }
+    let value = init()?;
+    Ok(cell.get_or_init(|| value))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn enabled_features_match_cfg() {
+        let has = |name| ENABLED_FEATURES.contains(&name);
+        assert_eq!(has("reqwest-default"), cfg!(feature = "reqwest-default"));
+        assert_eq!(has("tokio"), cfg!(feature = "tokio"));
+        assert_eq!(has("csv"), cfg!(feature = "csv"));
+        assert_eq!(has("miette"), cfg!(feature = "miette"));
+        assert_eq!(
+            has("schema-validation"),
+            cfg!(feature = "schema-validation")
+        );
+        assert_eq!(has("display"), cfg!(feature = "display"));
+        assert_eq!(has("locale"), cfg!(feature = "locale"));
+        assert_eq!(has("vcr"), cfg!(feature = "vcr"));
+        assert_eq!(has("async-std"), cfg!(feature = "async-std"));
+        assert_eq!(has("fuzzy"), cfg!(feature = "fuzzy"));
+        assert_eq!(has("tower"), cfg!(feature = "tower"));
+        assert_eq!(has("sqlite"), cfg!(feature = "sqlite"));
+        assert_eq!(has("postgres"), cfg!(feature = "postgres"));
+        assert_eq!(has("nats"), cfg!(feature = "nats"));
+        assert_eq!(has("charts"), cfg!(feature = "charts"));
+        assert_eq!(has("xlsx"), cfg!(feature = "xlsx"));
+        assert_eq!(has("test-util"), cfg!(feature = "test-util"));
+        assert_eq!(has("lenient"), cfg!(feature = "lenient"));
+    }
+
     #[test]
     fn client_with_api_key() {
         let client = ParclClient::with_api_key("test-key");
@@ -236,6 +835,27 @@ mod tests {
         assert_eq!(client.base_url, DEFAULT_BASE_URL);
     }
 
+    #[tokio::test]
+    async fn clones_share_state_across_tasks() {
+        let client = ParclClient::with_api_key("test-key");
+        let tasks: Vec<_> = (0..4)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move {
+                    client.update_credits(&Some(AccountInfo {
+                        est_credits_used: Some(5),
+                        est_remaining_credits: Some(100),
+                    }));
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(client.session_credits_used(), 20);
+        assert_eq!(client.remaining_credits(), 100);
+    }
+
     #[test]
     fn client_with_config() {
         let client = ParclClient::with_config("my-key", "https://custom.api.com");
@@ -277,6 +897,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn init_once_reuses_value_and_retries_failures() {
+        static CELL: OnceLock<ParclClient> = OnceLock::new();
+        assert!(init_once(&CELL, || Err(ParclError::MissingApiKey)).is_err());
+        let first = init_once(&CELL, || Ok(ParclClient::with_api_key("first"))).unwrap();
+        let second = init_once(&CELL, || Ok(ParclClient::with_api_key("second"))).unwrap();
+        assert!(std::ptr::eq(first, second));
+        assert_eq!(second.api_key, "first");
+    }
+
     #[test]
     fn client_with_retry_config() {
         let config = RetryConfig {
@@ -295,6 +925,24 @@ mod tests {
         assert_eq!(config.initial_backoff_ms, 1000);
     }
 
+    #[test]
+    fn auth_config_default_sends_bare_key() {
+        let auth = AuthConfig::default();
+        assert_eq!(auth.header, "Authorization");
+        assert_eq!(auth.header_value("key"), "key");
+    }
+
+    #[test]
+    fn auth_config_bearer_and_custom_header() {
+        assert_eq!(AuthConfig::bearer().header_value("key"), "Bearer key");
+        let custom = AuthConfig::header("X-API-Key").scheme("Token");
+        assert_eq!(custom.header, "X-API-Key");
+        assert_eq!(custom.header_value("key"), "Token key");
+
+        let client = ParclClient::with_api_key("test").with_auth_config(AuthConfig::bearer());
+        assert_eq!(client.auth, AuthConfig::bearer());
+    }
+
     #[test]
     fn update_credits_accumulates() {
         let client = ParclClient::with_api_key("test");
@@ -344,6 +992,12 @@ mod tests {
         assert!(debug.contains("***"));
     }
 
+    #[test]
+    fn client_market_cache_starts_empty() {
+        let client = ParclClient::with_api_key("test");
+        assert!(client.market_cache().is_empty());
+    }
+
     #[test]
     fn client_returns_search_client() {
         let client = ParclClient::with_api_key("test");
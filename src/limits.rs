@@ -0,0 +1,311 @@
+//! Maximum page sizes per endpoint.
+//!
+//! Each endpoint caps the `limit` it accepts and answers larger values with
+//! a 422. The client checks requested limits against [`max_limit`] before
+//! sending and, depending on its [`LimitPolicy`], lowers them to the maximum
+//! or fails with [`ParclError::Validation`]. Limits in pagination links
+//! returned by the API are left alone.
+//!
+//! Batch endpoints also cap the markets per request at
+//! [`MAX_BATCH_PARCL_IDS`]; `batch_*` methods split longer ID lists into
+//! several requests and merge the results.
+//!
+//! Separately, [`ResponseLimits`] caps how much a single call may download,
+//! so an over-broad query with auto-pagination fails with
+//! [`ParclError::ResponseTooLarge`] instead of exhausting memory.
+
+use crate::error::{ParclError, Result};
+use std::borrow::Cow;
+
+/// What the client does when a requested `limit` exceeds the endpoint maximum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LimitPolicy {
+    /// Lower the limit to the maximum and send the request.
+    #[default]
+    Clamp,
+    /// Fail with `ParclError::Validation` without sending the request.
+    Reject,
+}
+
+/// Caps on the data one client call may download, across all its pages.
+///
+/// Both are unset by default. Set them with
+/// `ParclClient::with_response_limits`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResponseLimits {
+    /// Response body bytes, summed over pages and retries.
+    pub max_response_bytes: Option<u64>,
+    /// Items accumulated by auto-pagination.
+    pub max_total_items: Option<u64>,
+}
+
+impl ResponseLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_response_bytes(mut self, bytes: u64) -> Self {
+        self.max_response_bytes = Some(bytes);
+        self
+    }
+
+    pub fn max_total_items(mut self, items: u64) -> Self {
+        self.max_total_items = Some(items);
+        self
+    }
+
+    /// Fails if `bytes` or `items` received so far exceed a limit.
+    pub(crate) fn check(&self, endpoint: &'static str, bytes: u64, items: usize) -> Result<()> {
+        let items = items as u64;
+        let exceeded = match (self.max_response_bytes, self.max_total_items) {
+            (Some(max), _) if bytes > max => Some((SizeLimit::Bytes(max), bytes)),
+            (_, Some(max)) if items > max => Some((SizeLimit::Items(max), items)),
+            _ => None,
+        };
+        match exceeded {
+            Some((limit, received)) => Err(ParclError::ResponseTooLarge {
+                endpoint,
+                limit,
+                received,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The [`ResponseLimits`] cap that a response exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeLimit {
+    Bytes(u64),
+    Items(u64),
+}
+
+impl std::fmt::Display for SizeLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bytes(max) => write!(f, "{max} bytes"),
+            Self::Items(max) => write!(f, "{max} items"),
+        }
+    }
+}
+
+/// Most `parcl_id`s a batch POST accepts in one request.
+pub const MAX_BATCH_PARCL_IDS: usize = 1000;
+
+/// Endpoint path (or family prefix ending in `/`) and its maximum `limit`.
+const ROUTE_LIMITS: &[(&str, u32)] = &[
+    ("/v1/search/markets", 1000),
+    ("/v1/market_metrics/", 1000),
+    ("/v1/for_sale_market_metrics/", 1000),
+    ("/v1/investor_metrics/", 1000),
+    ("/v1/new_construction_metrics/", 1000),
+    ("/v1/portfolio_metrics/", 1000),
+    ("/v1/rental_market_metrics/", 1000),
+    ("/v1/price_feed/", 1000),
+    ("/v1/property/search", 50_000),
+    ("/v2/property_search", 50_000),
+];
+
+/// Returns the largest `limit` accepted by `endpoint`, if it is paginated.
+pub fn max_limit(endpoint: &str) -> Option<u32> {
+    ROUTE_LIMITS
+        .iter()
+        .find(|(route, _)| {
+            if route.ends_with('/') {
+                endpoint.starts_with(route)
+            } else {
+                endpoint == *route
+            }
+        })
+        .map(|&(_, max)| max)
+}
+
+/// Checks `limit` against `endpoint`'s maximum, returning the limit to send.
+pub fn check_limit(policy: LimitPolicy, endpoint: &str, limit: u32) -> Result<u32> {
+    match max_limit(endpoint) {
+        Some(max) if limit > max => match policy {
+            LimitPolicy::Clamp => Ok(max),
+            LimitPolicy::Reject => Err(ParclError::Validation(format!(
+                "limit {limit} exceeds the maximum of {max} for {endpoint}"
+            ))),
+        },
+        _ => Ok(limit),
+    }
+}
+
+/// Applies [`check_limit`] to a `limit=` query parameter in `url`.
+pub(crate) fn apply_to_url<'a>(
+    policy: LimitPolicy,
+    endpoint: &str,
+    url: &'a str,
+) -> Result<Cow<'a, str>> {
+    let Some((base, query)) = url.split_once('?') else {
+        return Ok(Cow::Borrowed(url));
+    };
+    let mut changed = false;
+    let mut params = Vec::new();
+    for pair in query.split('&') {
+        match pair.strip_prefix("limit=").map(str::parse::<u32>) {
+            Some(Ok(limit)) => {
+                let checked = check_limit(policy, endpoint, limit)?;
+                changed |= checked != limit;
+                params.push(format!("limit={checked}"));
+            }
+            _ => params.push(pair.to_string()),
+        }
+    }
+    Ok(if changed {
+        Cow::Owned(format!("{base}?{}", params.join("&")))
+    } else {
+        Cow::Borrowed(url)
+    })
+}
+
+/// Applies [`check_limit`] to a top-level `limit` field in a JSON body.
+pub(crate) fn apply_to_body(
+    policy: LimitPolicy,
+    endpoint: &str,
+    body: &mut serde_json::Value,
+) -> Result<()> {
+    let Some(field) = body.get_mut("limit") else {
+        return Ok(());
+    };
+    if let Some(limit) = field.as_u64().and_then(|l| u32::try_from(l).ok()) {
+        *field = check_limit(policy, endpoint, limit)?.into();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CannedTransport;
+
+    #[test]
+    fn max_limit_matches_exact_routes_and_families() {
+        assert_eq!(max_limit("/v1/market_metrics/housing_stock"), Some(1000));
+        assert_eq!(max_limit("/v1/search/markets"), Some(1000));
+        assert_eq!(max_limit("/v2/property_search"), Some(50_000));
+        assert_eq!(max_limit("/v1/property/search_address"), None);
+        assert_eq!(max_limit("/v1/property/event_history"), None);
+    }
+
+    #[test]
+    fn url_limit_is_clamped_or_rejected() {
+        let url = "https://api/v1/search/markets?query=austin&limit=5000&offset=10";
+        let clamped = apply_to_url(LimitPolicy::Clamp, "/v1/search/markets", url).unwrap();
+        assert_eq!(
+            clamped,
+            "https://api/v1/search/markets?query=austin&limit=1000&offset=10"
+        );
+
+        let err = apply_to_url(LimitPolicy::Reject, "/v1/search/markets", url).unwrap_err();
+        assert!(matches!(err, ParclError::Validation(msg) if msg.contains("maximum of 1000")));
+
+        let ok = "https://api/v1/search/markets?limit=10";
+        let unchanged = apply_to_url(LimitPolicy::Reject, "/v1/search/markets", ok).unwrap();
+        assert!(matches!(unchanged, Cow::Borrowed(_)));
+    }
+
+    #[tokio::test]
+    async fn reject_policy_fails_before_sending() {
+        let client = crate::ParclClient::with_config("key", "https://example.com")
+            .with_transport(CannedTransport::new(|_| {
+                panic!("request should not be sent")
+            }))
+            .with_limit_policy(LimitPolicy::Reject);
+        let params = crate::MetricsParams::new().limit(5000);
+        let err = client
+            .market_metrics()
+            .housing_stock(7, Some(params))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ParclError::Validation(_)));
+    }
+
+    #[test]
+    fn body_limit_is_clamped() {
+        let mut body = serde_json::json!({"parcl_id": [1, 2], "limit": 2000});
+        apply_to_body(
+            LimitPolicy::Clamp,
+            "/v1/market_metrics/housing_stock",
+            &mut body,
+        )
+        .unwrap();
+        assert_eq!(body["limit"], 1000);
+    }
+
+    #[test]
+    fn response_limits_report_the_exceeded_cap() {
+        let limits = ResponseLimits::new()
+            .max_response_bytes(1000)
+            .max_total_items(10);
+        assert!(limits.check("/v1/x", 1000, 10).is_ok());
+        let err = limits.check("/v1/x", 1001, 3).unwrap_err();
+        assert!(matches!(
+            err,
+            ParclError::ResponseTooLarge {
+                limit: SizeLimit::Bytes(1000),
+                received: 1001,
+                ..
+            }
+        ));
+        assert_eq!(
+            limits.check("/v1/x", 10, 11).unwrap_err().to_string(),
+            "Response from /v1/x exceeded 10 items (11 received)"
+        );
+        assert!(ResponseLimits::default()
+            .check("/v1/x", u64::MAX, 1)
+            .is_ok());
+    }
+
+    /// Serves two-item pages that always link to another page.
+    fn endless() -> CannedTransport {
+        CannedTransport::json(
+            200,
+            serde_json::json!({
+                "parcl_id": 7,
+                "items": [{"date": "2024-02-01"}, {"date": "2024-01-01"}],
+                "total": 1_000_000, "limit": 2, "offset": 0,
+                "links": {"next": "https://example.com/v1/market_metrics/7/housing_stock?offset=2"}
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn auto_pagination_stops_at_response_limits() {
+        let client = crate::ParclClient::with_config("key", "https://example.com")
+            .with_transport(endless())
+            .with_response_limits(ResponseLimits::new().max_total_items(5));
+        let params = crate::MetricsParams::new().auto_paginate(true);
+        let err = client
+            .market_metrics()
+            .housing_stock(7, Some(params))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ParclError::ResponseTooLarge {
+                endpoint: "/v1/market_metrics/housing_stock",
+                limit: SizeLimit::Items(5),
+                received: 6,
+            }
+        ));
+
+        let client = crate::ParclClient::with_config("key", "https://example.com")
+            .with_transport(endless())
+            .with_response_limits(ResponseLimits::new().max_response_bytes(10));
+        let err = client
+            .market_metrics()
+            .housing_stock(7, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ParclError::ResponseTooLarge {
+                limit: SizeLimit::Bytes(10),
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,512 @@
+//! Runtime-selected metric fetching for config-driven pipelines.
+//!
+//! [`Metric`] names every single-market metric endpoint, and
+//! [`ParclClient::fetch_metric`] fetches one into a [`MetricSeries`], so a
+//! job can read "these metrics for these markets" from configuration without
+//! a match arm per item type.
+//!
+//! ```no_run
+//! use parcllabs::{Metric, MetricsParams, ParclClient};
+//!
+//! # async fn example() -> parcllabs::Result<()> {
+//! let client = ParclClient::new()?;
+//! let metrics: Vec<Metric> = ["housing_stock", "gross_yield", "price_feed"]
+//!     .iter()
+//!     .map(|name| name.parse())
+//!     .collect::<parcllabs::Result<_>>()?;
+//!
+//! for parcl_id in [2900187, 2900078] {
+//!     for &metric in &metrics {
+//!         let series = client
+//!             .fetch_metric(metric, parcl_id, Some(MetricsParams::new().limit(12)))
+//!             .await?;
+//!         println!("{parcl_id} {metric}: {} rows", series.len());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::capabilities::MetricFamily;
+use crate::endpoints::common;
+use crate::error::{ParclError, Result};
+use crate::models::*;
+use crate::runtime;
+use crate::{MetricsParams, ParclClient};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+
+macro_rules! metrics {
+    ($(
+        $(#[$meta:meta])*
+        $variant:ident($name:literal, $family:ident, $service:literal, $metric:literal) -> $item:ty;
+    )*) => {
+        /// A single-market metric endpoint.
+        ///
+        /// Parses from and serializes as its snake_case name, e.g.
+        /// `"housing_stock"` or `"investor_housing_event_counts"`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        pub enum Metric {
+            $(
+                $(#[$meta])*
+                #[serde(rename = $name)]
+                $variant,
+            )*
+        }
+
+        impl Metric {
+            /// Every metric, grouped by family.
+            pub const ALL: &'static [Metric] = &[$(Self::$variant),*];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)*
+                }
+            }
+
+            /// The endpoint family the metric belongs to.
+            pub fn family(&self) -> MetricFamily {
+                match self {
+                    $(Self::$variant => MetricFamily::$family,)*
+                }
+            }
+
+            /// The endpoint path without the `parcl_id`, e.g.
+            /// `"/v1/market_metrics/housing_stock"`.
+            pub fn endpoint(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => concat!($service, "/", $metric),)*
+                }
+            }
+        }
+
+        /// A metric response tagged with the metric it answers.
+        ///
+        /// Serializes as `{"metric": "housing_stock", "response": {...}}`.
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[serde(tag = "metric", content = "response")]
+        pub enum MetricSeries {
+            $(
+                #[serde(rename = $name)]
+                $variant(MetricsResponse<$item>),
+            )*
+        }
+
+        impl MetricSeries {
+            pub fn metric(&self) -> Metric {
+                match self {
+                    $(Self::$variant(_) => Metric::$variant,)*
+                }
+            }
+
+            pub fn parcl_id(&self) -> i64 {
+                match self {
+                    $(Self::$variant(r) => r.parcl_id,)*
+                }
+            }
+
+            /// Number of items fetched.
+            pub fn len(&self) -> usize {
+                match self {
+                    $(Self::$variant(r) => r.items.len(),)*
+                }
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            /// Each item's date and [`Headline`] value, in API order.
+            pub fn headline_values(&self) -> Vec<(&str, Option<f64>)> {
+                match self {
+                    $(Self::$variant(r) => r
+                        .items
+                        .iter()
+                        .map(|i| (i.date.as_str(), i.headline()))
+                        .collect(),)*
+                }
+            }
+
+            /// The items as JSON objects, for writing rows generically.
+            pub fn items_json(&self) -> Result<Vec<serde_json::Value>> {
+                let items = match self {
+                    $(Self::$variant(r) => serde_json::to_value(&r.items)?,)*
+                };
+                Ok(serde_json::from_value(items)?)
+            }
+        }
+
+        impl ParclClient {
+            /// Fetches `metric` for one market.
+            ///
+            /// Every family accepts the common [`MetricsParams`] (paging,
+            /// dates, property type, retry); family-specific filters such as
+            /// portfolio size need the typed endpoint methods.
+            pub async fn fetch_metric(
+                &self,
+                metric: Metric,
+                parcl_id: i64,
+                params: Option<MetricsParams>,
+            ) -> Result<MetricSeries> {
+                let params = params.unwrap_or_default();
+                Ok(match metric {
+                    $(Metric::$variant => MetricSeries::$variant(
+                        self.fetch_endpoint(metric.endpoint(), parcl_id, &params).await?,
+                    ),)*
+                })
+            }
+        }
+    };
+}
+
+metrics! {
+    HousingEventCounts("housing_event_counts", MarketMetrics,
+        "/v1/market_metrics", "housing_event_counts") -> HousingEventCounts;
+    HousingStock("housing_stock", MarketMetrics,
+        "/v1/market_metrics", "housing_stock") -> HousingStock;
+    HousingEventPrices("housing_event_prices", MarketMetrics,
+        "/v1/market_metrics", "housing_event_prices") -> HousingEventPrices;
+    AllCash("all_cash", MarketMetrics,
+        "/v1/market_metrics", "all_cash") -> AllCash;
+    HousingEventPropertyAttributes("housing_event_property_attributes", MarketMetrics,
+        "/v1/market_metrics", "housing_event_property_attributes") -> HousingEventPropertyAttributes;
+
+    InvestorHousingStockOwnership("investor_housing_stock_ownership", InvestorMetrics,
+        "/v1/investor_metrics", "housing_stock_ownership") -> InvestorHousingStockOwnership;
+    InvestorPurchaseToSaleRatio("investor_purchase_to_sale_ratio", InvestorMetrics,
+        "/v1/investor_metrics", "purchase_to_sale_ratio") -> InvestorPurchaseToSaleRatio;
+    InvestorHousingEventCounts("investor_housing_event_counts", InvestorMetrics,
+        "/v1/investor_metrics", "housing_event_counts") -> InvestorHousingEventCounts;
+    InvestorHousingEventPrices("investor_housing_event_prices", InvestorMetrics,
+        "/v1/investor_metrics", "housing_event_prices") -> HousingEventPrices;
+    InvestorNewListingsForSaleRollingCounts("investor_new_listings_for_sale_rolling_counts", InvestorMetrics,
+        "/v1/investor_metrics", "new_listings_for_sale_rolling_counts") -> InvestorNewListingsRollingCounts;
+
+    ForSaleInventory("for_sale_inventory", ForSaleMetrics,
+        "/v1/for_sale_market_metrics", "for_sale_inventory") -> ForSaleInventory;
+    ForSaleInventoryPriceChanges("for_sale_inventory_price_changes", ForSaleMetrics,
+        "/v1/for_sale_market_metrics", "for_sale_inventory_price_changes") -> ForSaleInventoryPriceChanges;
+    NewListingsRollingCounts("new_listings_rolling_counts", ForSaleMetrics,
+        "/v1/for_sale_market_metrics", "new_listings_rolling_counts") -> NewListingsRollingCounts;
+
+    GrossYield("gross_yield", RentalMetrics,
+        "/v1/rental_market_metrics", "gross_yield") -> GrossYield;
+    RentalUnitsConcentration("rental_units_concentration", RentalMetrics,
+        "/v1/rental_market_metrics", "rental_units_concentration") -> RentalUnitsConcentration;
+    NewListingsForRentRollingCounts("new_listings_for_rent_rolling_counts", RentalMetrics,
+        "/v1/rental_market_metrics", "new_listings_for_rent_rolling_counts") -> RentalNewListingsRollingCounts;
+
+    NewConstructionHousingEventCounts("new_construction_housing_event_counts", NewConstructionMetrics,
+        "/v1/new_construction_metrics", "housing_event_counts") -> HousingEventCounts;
+    NewConstructionHousingEventPrices("new_construction_housing_event_prices", NewConstructionMetrics,
+        "/v1/new_construction_metrics", "housing_event_prices") -> HousingEventPrices;
+
+    PortfolioSfHousingStockOwnership("portfolio_sf_housing_stock_ownership", PortfolioMetrics,
+        "/v1/portfolio_metrics", "sf_housing_stock_ownership") -> PortfolioStockOwnership;
+    PortfolioSfHousingEventCounts("portfolio_sf_housing_event_counts", PortfolioMetrics,
+        "/v1/portfolio_metrics", "sf_housing_event_counts") -> PortfolioHousingEventCounts;
+    PortfolioSfNewListingsForSaleRollingCounts("portfolio_sf_new_listings_for_sale_rolling_counts", PortfolioMetrics,
+        "/v1/portfolio_metrics", "sf_new_listings_for_sale_rolling_counts") -> PortfolioNewListingsRollingCounts;
+    PortfolioSfNewListingsForRentRollingCounts("portfolio_sf_new_listings_for_rent_rolling_counts", PortfolioMetrics,
+        "/v1/portfolio_metrics", "sf_new_listings_for_rent_rolling_counts") -> PortfolioRentalListingsRollingCounts;
+
+    PriceFeed("price_feed", PriceFeed,
+        "/v1/price_feed", "history") -> PriceFeedEntry;
+    RentalPriceFeed("rental_price_feed", PriceFeed,
+        "/v1/price_feed", "rental_price_feed") -> PriceFeedEntry;
+}
+
+impl ParclClient {
+    pub(crate) async fn fetch_endpoint<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &'static str,
+        parcl_id: i64,
+        params: &MetricsParams,
+    ) -> Result<MetricsResponse<T>> {
+        let (service, metric) = endpoint
+            .rsplit_once('/')
+            .expect("metric endpoints have a service path");
+        let url = format!(
+            "{}{service}/{parcl_id}/{metric}{}",
+            self.base_url,
+            params.to_query_string()
+        );
+        let resp =
+            common::get_with_pagination(self, endpoint, &url, parcl_id, params.fetch_options())
+                .await?;
+        self.update_credits(&resp.account);
+        Ok(resp)
+    }
+
+    /// Fetches every `(parcl_id, metric)` pair with bounded concurrency.
+    ///
+    /// Requests are dispatched round-robin across markets by default, so
+    /// results for every market arrive at a similar pace instead of one
+    /// market's metrics occupying every slot.
+    ///
+    /// ```no_run
+    /// use parcllabs::{Metric, ParclClient};
+    ///
+    /// # async fn example() -> parcllabs::Result<()> {
+    /// let client = ParclClient::new()?;
+    /// let jobs = [2900187, 2900078]
+    ///     .into_iter()
+    ///     .flat_map(|id| [Metric::HousingStock, Metric::GrossYield].map(|m| (id, m)));
+    ///
+    /// client
+    ///     .fetch_metrics(jobs)
+    ///     .concurrency(4)
+    ///     .for_each(|parcl_id, metric, result| match result {
+    ///         Ok(series) => println!("{parcl_id} {metric}: {} rows", series.len()),
+    ///         Err(e) => eprintln!("{parcl_id} {metric}: {e}"),
+    ///     })
+    ///     .await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fetch_metrics(&self, jobs: impl IntoIterator<Item = (i64, Metric)>) -> MetricFetch<'_> {
+        MetricFetch {
+            client: self,
+            jobs: jobs.into_iter().collect(),
+            params: MetricsParams::default(),
+            concurrency: 4,
+            fairness: Fairness::default(),
+        }
+    }
+}
+
+/// The order in which [`MetricFetch`] dispatches requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Fairness {
+    /// Alternate between markets: the first metric of every market, then
+    /// the second of every market, and so on.
+    #[default]
+    RoundRobin,
+    /// Dispatch jobs in the order given.
+    InOrder,
+}
+
+type FetchResult = (i64, Metric, Result<MetricSeries>);
+
+/// Concurrent fetch of many metrics across many markets.
+///
+/// Created by [`ParclClient::fetch_metrics`].
+#[derive(Debug)]
+pub struct MetricFetch<'a> {
+    client: &'a ParclClient,
+    jobs: Vec<(i64, Metric)>,
+    params: MetricsParams,
+    concurrency: usize,
+    fairness: Fairness,
+}
+
+impl<'a> MetricFetch<'a> {
+    /// Maximum requests in flight (default 4, minimum 1).
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn fairness(mut self, fairness: Fairness) -> Self {
+        self.fairness = fairness;
+        self
+    }
+
+    /// Query parameters for every request.
+    pub fn params(mut self, params: MetricsParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// The jobs in dispatch order.
+    pub fn order(&self) -> Vec<(i64, Metric)> {
+        match self.fairness {
+            Fairness::InOrder => self.jobs.clone(),
+            Fairness::RoundRobin => round_robin(&self.jobs),
+        }
+    }
+
+    /// Runs every job, calling `on_result` as each one completes.
+    pub async fn for_each(self, mut on_result: impl FnMut(i64, Metric, Result<MetricSeries>)) {
+        let client = self.client;
+        let _ = runtime::for_each_concurrent(
+            self.order(),
+            self.concurrency,
+            |(parcl_id, metric)| {
+                let params = self.params.clone();
+                async move {
+                    let result = client.fetch_metric(metric, parcl_id, Some(params)).await;
+                    (parcl_id, metric, result)
+                }
+            },
+            |(parcl_id, metric, result)| {
+                on_result(parcl_id, metric, result);
+                Ok::<_, Infallible>(())
+            },
+        )
+        .await;
+    }
+
+    /// Runs every job and returns the results in completion order.
+    pub async fn collect(self) -> Vec<FetchResult> {
+        let mut results = Vec::with_capacity(self.jobs.len());
+        self.for_each(|parcl_id, metric, result| results.push((parcl_id, metric, result)))
+            .await;
+        results
+    }
+}
+
+/// Interleaves jobs by market, keeping each market's own order and the
+/// markets in order of first appearance.
+fn round_robin(jobs: &[(i64, Metric)]) -> Vec<(i64, Metric)> {
+    let mut slots = HashMap::new();
+    let mut markets: Vec<(i64, VecDeque<Metric>)> = Vec::new();
+    for &(parcl_id, metric) in jobs {
+        let slot = *slots.entry(parcl_id).or_insert_with(|| {
+            markets.push((parcl_id, VecDeque::new()));
+            markets.len() - 1
+        });
+        markets[slot].1.push_back(metric);
+    }
+    // Each pass takes one job per market and drops the exhausted ones.
+    let mut order = Vec::with_capacity(jobs.len());
+    while !markets.is_empty() {
+        markets.retain_mut(|(parcl_id, metrics)| {
+            if let Some(metric) = metrics.pop_front() {
+                order.push((*parcl_id, metric));
+            }
+            !metrics.is_empty()
+        });
+    }
+    order
+}
+
+impl std::fmt::Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Metric {
+    type Err = ParclError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|m| m.as_str().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParclError::Validation(format!("unknown metric: {s}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CannedTransport;
+
+    /// Answers with one gross yield item.
+    fn recorder() -> CannedTransport {
+        CannedTransport::json(
+            200,
+            serde_json::json!({
+                "parcl_id": 7,
+                "items": [{"date": "2024-01-01", "gross_yield": 6.5}],
+                "total": 1, "limit": 1, "offset": 0, "links": {}
+            }),
+        )
+    }
+
+    #[test]
+    fn names_are_unique_and_round_trip() {
+        let mut names: Vec<&str> = Metric::ALL.iter().map(Metric::as_str).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), Metric::ALL.len());
+
+        for metric in Metric::ALL {
+            assert_eq!(metric.as_str().parse::<Metric>().unwrap(), *metric);
+            let json = serde_json::to_string(metric).unwrap();
+            assert_eq!(json, format!("\"{metric}\""));
+        }
+        assert!(matches!(
+            "housing".parse::<Metric>(),
+            Err(ParclError::Validation(_))
+        ));
+        assert_eq!(
+            Metric::InvestorHousingEventCounts.endpoint(),
+            "/v1/investor_metrics/housing_event_counts"
+        );
+        assert_eq!(Metric::GrossYield.family(), MetricFamily::RentalMetrics);
+    }
+
+    #[tokio::test]
+    async fn fetch_metric_returns_tagged_series() {
+        let recorder = recorder();
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(recorder.clone());
+
+        let series = client
+            .fetch_metric(Metric::GrossYield, 7, Some(MetricsParams::new().limit(1)))
+            .await
+            .unwrap();
+        assert_eq!(
+            recorder.uris()[0],
+            "https://example.com/v1/rental_market_metrics/7/gross_yield?limit=1"
+        );
+        assert_eq!(series.metric(), Metric::GrossYield);
+        assert_eq!(series.parcl_id(), 7);
+        assert_eq!(series.len(), 1);
+        assert_eq!(series.items_json().unwrap()[0]["gross_yield"], 6.5);
+        let MetricSeries::GrossYield(response) = &series else {
+            panic!("expected gross yield, got {}", series.metric());
+        };
+        assert_eq!(response.items[0].gross_yield, Some(6.5));
+
+        let json = serde_json::to_value(&series).unwrap();
+        assert_eq!(json["metric"], "gross_yield");
+        let restored: MetricSeries = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.metric(), Metric::GrossYield);
+    }
+
+    #[test]
+    fn round_robin_interleaves_markets() {
+        use Metric::{GrossYield as Y, HousingStock as S, PriceFeed as P};
+        let jobs = [(1, S), (1, Y), (1, P), (2, S), (3, S), (2, Y)];
+        assert_eq!(
+            round_robin(&jobs),
+            [(1, S), (2, S), (3, S), (1, Y), (2, Y), (1, P)]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_metrics_dispatches_fairly() {
+        let recorder = recorder();
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(recorder.clone());
+        let jobs = [
+            (1, Metric::HousingStock),
+            (1, Metric::GrossYield),
+            (2, Metric::HousingStock),
+        ];
+
+        let results = client.fetch_metrics(jobs).concurrency(1).collect().await;
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|(_, _, r)| r.is_ok()));
+        assert_eq!(
+            recorder.uris(),
+            [
+                "https://example.com/v1/market_metrics/1/housing_stock",
+                "https://example.com/v1/market_metrics/2/housing_stock",
+                "https://example.com/v1/rental_market_metrics/1/gross_yield",
+            ]
+        );
+
+        let order = client
+            .fetch_metrics(jobs)
+            .fairness(Fairness::InOrder)
+            .order();
+        assert_eq!(order, jobs);
+    }
+}
@@ -0,0 +1,475 @@
+//! tower middleware for services that expose Parcl Labs data.
+//!
+//! Enabled with the `tower` feature. [`ParclLayer`] puts a shared
+//! `Arc<ParclClient>` into every request's extensions, so handlers reach it
+//! with Axum's `Extension` extractor instead of threading it through state.
+//! [`ResponseCacheLayer`] caches successful GET responses for a fixed time,
+//! so repeated requests for the same market don't spend credits again.
+//! Callers with different credentials never share an entry.
+//!
+//! ```no_run
+//! use axum::{routing::get, Extension, Router};
+//! use parcllabs::middleware::{ParclLayer, ResponseCacheLayer};
+//! use parcllabs::ParclClient;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! async fn stock(Extension(client): Extension<Arc<ParclClient>>) -> String {
+//!     let resp = client.market_metrics().housing_stock(2900187, None).await;
+//!     format!("{:?}", resp.map(|r| r.items.len()))
+//! }
+//!
+//! # fn app() -> parcllabs::Result<Router> {
+//! let app = Router::new()
+//!     .route("/stock", get(stock))
+//!     .layer(ResponseCacheLayer::new(Duration::from_secs(300)))
+//!     .layer(ParclLayer::new(Arc::new(ParclClient::new()?)));
+//! # Ok(app)
+//! # }
+//! ```
+
+use crate::ParclClient;
+use bytes::Bytes;
+use http::{HeaderMap, Method, Request, Response, StatusCode};
+use http_body_util::BodyExt;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Entries kept by [`ResponseCacheLayer`] unless
+/// [`max_entries`](ResponseCacheLayer::max_entries) is set.
+const DEFAULT_MAX_ENTRIES: usize = 1024;
+
+/// Request headers that are part of the cache key, so callers with
+/// different credentials or content negotiation never share an entry.
+const KEY_HEADERS: [&str; 5] = [
+    "authorization",
+    "x-api-key",
+    "cookie",
+    "accept",
+    "accept-encoding",
+];
+
+/// Layer that adds a shared `Arc<ParclClient>` to request extensions.
+#[derive(Debug, Clone)]
+pub struct ParclLayer {
+    client: Arc<ParclClient>,
+}
+
+impl ParclLayer {
+    pub fn new(client: Arc<ParclClient>) -> Self {
+        Self { client }
+    }
+
+    /// The client handed to every request.
+    pub fn client(&self) -> &Arc<ParclClient> {
+        &self.client
+    }
+}
+
+impl<S> Layer<S> for ParclLayer {
+    type Service = ParclService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ParclService {
+            inner,
+            client: Arc::clone(&self.client),
+        }
+    }
+}
+
+/// Service produced by [`ParclLayer`].
+#[derive(Debug, Clone)]
+pub struct ParclService<S> {
+    inner: S,
+    client: Arc<ParclClient>,
+}
+
+impl<S, B> Service<Request<B>> for ParclService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        request.extensions_mut().insert(Arc::clone(&self.client));
+        self.inner.call(request)
+    }
+}
+
+/// Layer that caches successful GET responses for a fixed time, keyed by
+/// URI and the request's credential and content-negotiation headers.
+///
+/// Only `200 OK` responses are stored, with their headers and buffered
+/// body. Responses marked `Cache-Control: private` or `no-store`, that set
+/// a cookie, or that vary on headers outside the key are passed through
+/// uncached. Clones of the layer, and every service it produces, share one
+/// cache. When the cache is full, expired entries are dropped first and
+/// new responses are not cached until space frees up.
+#[derive(Debug, Clone)]
+pub struct ResponseCacheLayer {
+    cache: Arc<ResponseCache>,
+}
+
+impl ResponseCacheLayer {
+    /// Caches responses for `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            cache: Arc::new(ResponseCache::new(ttl, DEFAULT_MAX_ENTRIES)),
+        }
+    }
+
+    /// Caps the number of cached responses (default 1024).
+    ///
+    /// Starts a new, empty cache; call it before cloning the layer.
+    pub fn max_entries(self, max_entries: usize) -> Self {
+        Self {
+            cache: Arc::new(ResponseCache::new(self.cache.ttl, max_entries)),
+        }
+    }
+
+    /// Number of cached responses, including expired ones not yet dropped.
+    pub fn len(&self) -> usize {
+        self.cache.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every cached response.
+    pub fn clear(&self) {
+        self.cache.lock().clear();
+    }
+}
+
+impl<S> Layer<S> for ResponseCacheLayer {
+    type Service = ResponseCacheService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ResponseCacheService {
+            inner,
+            cache: Arc::clone(&self.cache),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ResponseCache {
+    ttl: Duration,
+    max_entries: usize,
+    /// Hashes the key headers, so credentials are not kept in the key.
+    hasher: RandomState,
+    entries: Mutex<HashMap<String, CachedResponse>>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    headers: HeaderMap,
+    body: Bytes,
+    expires_at: Instant,
+}
+
+impl ResponseCache {
+    fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            hasher: RandomState::new(),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The URI plus a hash of the request's key headers.
+    fn key(&self, uri: &http::Uri, headers: &HeaderMap) -> String {
+        let values: Vec<(&str, &[u8])> = KEY_HEADERS
+            .iter()
+            .flat_map(|name| {
+                headers
+                    .get_all(*name)
+                    .iter()
+                    .map(move |value| (*name, value.as_bytes()))
+            })
+            .collect();
+        format!("{uri} {:016x}", self.hasher.hash_one(values))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedResponse>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut entries = self.lock();
+        match entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: String, headers: HeaderMap, body: Bytes) {
+        let now = Instant::now();
+        let mut entries = self.lock();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.expires_at > now);
+            if entries.len() >= self.max_entries {
+                return;
+            }
+        }
+        let expires_at = now + self.ttl;
+        entries.insert(
+            key,
+            CachedResponse {
+                headers,
+                body,
+                expires_at,
+            },
+        );
+    }
+}
+
+/// Service produced by [`ResponseCacheLayer`].
+#[derive(Debug, Clone)]
+pub struct ResponseCacheService<S> {
+    inner: S,
+    cache: Arc<ResponseCache>,
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for ResponseCacheService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    ReqBody: Send + 'static,
+    ResBody: http_body::Body + From<Bytes> + Send + 'static,
+    ResBody::Data: Send,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        // The clone may not be ready; call the instance that was polled.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        if request.method() != Method::GET {
+            let future = inner.call(request);
+            return Box::pin(future);
+        }
+        let key = self.cache.key(request.uri(), request.headers());
+        if let Some(hit) = self.cache.get(&key) {
+            let mut response = Response::new(ResBody::from(hit.body));
+            *response.headers_mut() = hit.headers;
+            return Box::pin(async move { Ok(response) });
+        }
+
+        let cache = Arc::clone(&self.cache);
+        Box::pin(async move {
+            let response = inner.call(request).await?;
+            if response.status() != StatusCode::OK {
+                return Ok(response);
+            }
+            let (parts, body) = response.into_parts();
+            let Ok(collected) = body.collect().await else {
+                // The inner service failed mid-body; there is nothing to cache
+                // or forward.
+                let mut response = Response::new(ResBody::from(Bytes::new()));
+                *response.status_mut() = StatusCode::BAD_GATEWAY;
+                return Ok(response);
+            };
+            let bytes = collected.to_bytes();
+            if is_shareable(&parts.headers) {
+                cache.insert(key, parts.headers.clone(), bytes.clone());
+            }
+            Ok(Response::from_parts(parts, ResBody::from(bytes)))
+        })
+    }
+}
+
+/// Whether a response may be served to later requests with the same key:
+/// not private, storable, setting no cookie, and varying only on key
+/// headers.
+fn is_shareable(headers: &HeaderMap) -> bool {
+    let directives = |name| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|directive| directive.trim().to_ascii_lowercase())
+            .collect::<Vec<_>>()
+    };
+    let cache_control = directives(http::header::CACHE_CONTROL);
+    let private = cache_control
+        .iter()
+        .any(|d| d == "no-store" || d == "private" || d.starts_with("private="));
+    let vary_outside_key = directives(http::header::VARY)
+        .iter()
+        .any(|name| !KEY_HEADERS.contains(&name.as_str()));
+    !private && !vary_outside_key && !headers.contains_key(http::header::SET_COOKIE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::Full;
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::{service_fn, ServiceExt};
+
+    #[tokio::test]
+    async fn parcl_layer_injects_client() {
+        let client = Arc::new(ParclClient::with_config("key", "https://example.com"));
+        let service = ParclLayer::new(Arc::clone(&client)).layer(service_fn(
+            |request: Request<()>| async move {
+                let injected = request.extensions().get::<Arc<ParclClient>>().cloned();
+                Ok::<_, Infallible>(injected)
+            },
+        ));
+        let injected = service.oneshot(Request::new(())).await.unwrap().unwrap();
+        assert!(Arc::ptr_eq(&injected, &client));
+    }
+
+    /// Counts calls and answers `/missing` with a 404.
+    fn counting_service(
+        calls: Arc<AtomicUsize>,
+    ) -> impl Service<
+        Request<()>,
+        Response = Response<Full<Bytes>>,
+        Error = Infallible,
+        Future = impl Send,
+    > + Clone {
+        service_fn(move |request: Request<()>| {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let mut response = Response::new(Full::from(format!("call {n}")));
+                if request.uri().path() == "/missing" {
+                    *response.status_mut() = StatusCode::NOT_FOUND;
+                }
+                response
+                    .headers_mut()
+                    .insert("content-type", "text/plain".parse().unwrap());
+                Ok(response)
+            }
+        })
+    }
+
+    async fn body(response: Response<Full<Bytes>>) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    fn get(uri: &str) -> Request<()> {
+        Request::get(uri).body(()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn caches_successful_get_responses() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = ResponseCacheLayer::new(Duration::from_secs(60));
+        let service = layer.layer(counting_service(Arc::clone(&calls)));
+
+        let first = service.clone().oneshot(get("/stock?id=1")).await.unwrap();
+        assert_eq!(body(first).await, "call 0");
+        let second = service.clone().oneshot(get("/stock?id=1")).await.unwrap();
+        assert_eq!(second.headers()["content-type"], "text/plain");
+        assert_eq!(body(second).await, "call 0");
+
+        let other = service.clone().oneshot(get("/stock?id=2")).await.unwrap();
+        assert_eq!(body(other).await, "call 1");
+        let post = Request::post("/stock?id=1").body(()).unwrap();
+        assert_eq!(
+            body(service.clone().oneshot(post).await.unwrap()).await,
+            "call 2"
+        );
+        service.clone().oneshot(get("/missing")).await.unwrap();
+        service.clone().oneshot(get("/missing")).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+        assert_eq!(layer.len(), 2);
+        layer.clear();
+        assert!(layer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn expired_and_overflowing_entries_are_not_served() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = ResponseCacheLayer::new(Duration::ZERO);
+        let service = layer.layer(counting_service(Arc::clone(&calls)));
+        service.clone().oneshot(get("/a")).await.unwrap();
+        service.clone().oneshot(get("/a")).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let layer = ResponseCacheLayer::new(Duration::from_secs(60)).max_entries(1);
+        let service = layer.layer(counting_service(Arc::clone(&calls)));
+        service.clone().oneshot(get("/a")).await.unwrap();
+        service.clone().oneshot(get("/b")).await.unwrap();
+        assert_eq!(layer.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn callers_with_different_keys_do_not_share_entries() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = ResponseCacheLayer::new(Duration::from_secs(60));
+        let service = layer.layer(counting_service(Arc::clone(&calls)));
+        let as_key = |key: &str| {
+            Request::get("/stock?id=1")
+                .header("authorization", key)
+                .body(())
+                .unwrap()
+        };
+
+        let alice = service.clone().oneshot(as_key("alice")).await.unwrap();
+        assert_eq!(body(alice).await, "call 0");
+        let bob = service.clone().oneshot(as_key("bob")).await.unwrap();
+        assert_eq!(body(bob).await, "call 1");
+        let alice = service.clone().oneshot(as_key("alice")).await.unwrap();
+        assert_eq!(body(alice).await, "call 0");
+        let anonymous = service.clone().oneshot(get("/stock?id=1")).await.unwrap();
+        assert_eq!(body(anonymous).await, "call 2");
+        assert_eq!(layer.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn private_responses_are_not_stored() {
+        let layer = ResponseCacheLayer::new(Duration::from_secs(60));
+        let service = layer.layer(service_fn(|request: Request<()>| async move {
+            let (name, value) = match request.uri().path() {
+                "/private" => ("cache-control", "max-age=60, private"),
+                "/no-store" => ("cache-control", "no-store"),
+                "/cookie" => ("set-cookie", "session=1"),
+                "/vary" => ("vary", "X-Tenant"),
+                _ => ("vary", "Accept-Encoding"),
+            };
+            let mut response = Response::new(Full::<Bytes>::from("ok"));
+            response.headers_mut().insert(name, value.parse().unwrap());
+            Ok::<_, Infallible>(response)
+        }));
+        for path in ["/private", "/no-store", "/cookie", "/vary", "/public"] {
+            service.clone().oneshot(get(path)).await.unwrap();
+        }
+        assert_eq!(layer.len(), 1);
+    }
+}
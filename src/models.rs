@@ -1,6 +1,18 @@
 //! Response types for the Parcl Labs API.
-
+//!
+//! Types here track the API as the SDK currently models it. Pipelines that
+//! need to pin a shape across SDK upgrades can import from [`v1`] instead.
+//!
+//! Three [`ForSaleInventoryPriceChanges`] fields will take the API's names
+//! in 0.2. Until then the current names are deprecated in favour of
+//! accessors that already use the new ones.
+
+pub mod v1;
+
+#[cfg(feature = "lenient")]
+use crate::lenient;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Paginated API response wrapper (for search endpoints).
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -12,9 +24,16 @@ pub struct PaginatedResponse<T> {
     pub links: PaginationLinks,
     #[serde(default)]
     pub account: Option<AccountInfo>,
+    /// Retry telemetry recorded by the client (not part of the API payload).
+    #[serde(skip)]
+    pub meta: ResponseMeta,
 }
 
 /// Paginated response for market metrics (includes parcl_id at top level).
+///
+/// Items keep the API's order, usually newest first; auto-pagination appends
+/// later pages in that same order. Use [`sort_ascending`](Self::sort_ascending)
+/// for chronological order rather than relying on it.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct MetricsResponse<T> {
     pub parcl_id: i64,
@@ -25,9 +44,15 @@ pub struct MetricsResponse<T> {
     pub links: PaginationLinks,
     #[serde(default)]
     pub account: Option<AccountInfo>,
+    /// Retry telemetry recorded by the client (not part of the API payload).
+    #[serde(skip)]
+    pub meta: ResponseMeta,
 }
 
 /// Paginated response for batch POST requests (no top-level parcl_id).
+///
+/// Items keep the API's order; see [`sort_ascending`](Self::sort_ascending)
+/// to group them by market in chronological order.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BatchMetricsResponse<T> {
     pub items: Vec<T>,
@@ -37,6 +62,300 @@ pub struct BatchMetricsResponse<T> {
     pub links: PaginationLinks,
     #[serde(default)]
     pub account: Option<AccountInfo>,
+    /// Retry telemetry recorded by the client (not part of the API payload).
+    #[serde(skip)]
+    pub meta: ResponseMeta,
+}
+
+/// Retry and transfer telemetry for a request, summed across pages when
+/// auto-paginating.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResponseMeta {
+    /// HTTP requests made, including retries.
+    pub attempts_made: u32,
+    /// Requests that were retried after a 429 response or a transient
+    /// network failure.
+    pub retries: u32,
+    /// Total time spent waiting in retry backoff.
+    pub total_backoff: Duration,
+    /// Response body bytes received, including retried responses.
+    pub bytes_received: u64,
+    /// Auto-pagination was cancelled before the last page; `items` holds
+    /// the pages fetched so far and `links.next` the first page skipped.
+    pub cancelled: bool,
+    /// Auto-pagination reached its deadline before the last page; as with
+    /// `cancelled`, `items` holds the pages fetched by then.
+    pub deadline_exceeded: bool,
+    /// Exact response bodies, one per page, when the client was built
+    /// with `with_raw(true)`.
+    pub raw: Vec<RawPayload>,
+    /// Hash of the response content, combined across pages, when the
+    /// client was built with `with_content_hash(true)`.
+    pub content_hash: Option<ContentHash>,
+}
+
+impl ResponseMeta {
+    /// Returns true if any request had to be retried.
+    pub fn was_retried(&self) -> bool {
+        self.retries > 0
+    }
+
+    /// Returns true if auto-pagination stopped before the last page.
+    pub fn is_partial(&self) -> bool {
+        self.cancelled || self.deadline_exceeded
+    }
+
+    /// Adds another page's telemetry into this one.
+    pub(crate) fn absorb(&mut self, other: &ResponseMeta) {
+        self.attempts_made += other.attempts_made;
+        self.retries += other.retries;
+        self.total_backoff += other.total_backoff;
+        self.bytes_received += other.bytes_received;
+        self.cancelled |= other.cancelled;
+        self.deadline_exceeded |= other.deadline_exceeded;
+        self.raw.extend(other.raw.iter().cloned());
+        self.content_hash = match (self.content_hash, other.content_hash) {
+            (Some(a), Some(b)) => Some(a.then(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// Parses each kept page body as JSON.
+    pub fn raw_json(&self) -> serde_json::Result<Vec<serde_json::Value>> {
+        self.raw.iter().map(RawPayload::to_json).collect()
+    }
+}
+
+/// A response body exactly as received, kept by `ParclClient::with_raw`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RawPayload(Vec<u8>);
+
+impl RawPayload {
+    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<serde_json::Value> {
+        serde_json::from_slice(&self.0)
+    }
+}
+
+impl std::fmt::Debug for RawPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RawPayload({} bytes)", self.0.len())
+    }
+}
+
+/// A stable fingerprint of a response body, kept by
+/// `ParclClient::with_content_hash`.
+///
+/// Equal hashes mean the API returned the same content, so a sync job can
+/// store the hash from its last pull and skip processing when it matches.
+/// JSON bodies are hashed without their top-level `account` object, whose
+/// credit counters change on every request, and with keys in sorted order.
+/// The hash is 64-bit FNV-1a: stable across runs and SDK versions, but not
+/// cryptographic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContentHash(u64);
+
+impl ContentHash {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    pub(crate) fn of(body: &[u8]) -> Self {
+        let canonical = match serde_json::from_slice::<serde_json::Value>(body) {
+            Ok(serde_json::Value::Object(mut object)) => {
+                object.remove("account");
+                serde_json::to_vec(&object).ok()
+            }
+            Ok(other) => serde_json::to_vec(&other).ok(),
+            Err(_) => None,
+        };
+        Self(Self::OFFSET).feed(canonical.as_deref().unwrap_or(body))
+    }
+
+    /// The hash of this page followed by `next`.
+    pub(crate) fn then(self, next: ContentHash) -> Self {
+        self.feed(&next.0.to_le_bytes())
+    }
+
+    fn feed(self, bytes: &[u8]) -> Self {
+        Self(
+            bytes
+                .iter()
+                .fold(self.0, |h, b| (h ^ u64::from(*b)).wrapping_mul(Self::PRIME)),
+        )
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Formats as 16 lowercase hex digits, the form [`FromStr`](std::str::FromStr) reads back.
+impl std::fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl std::str::FromStr for ContentHash {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        u64::from_str_radix(s, 16).map(Self)
+    }
+}
+
+/// Whether a metrics response carries any data points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Availability {
+    /// At least one item was returned.
+    Available,
+    /// The metric has no data for the requested market(s) and window.
+    NoData,
+}
+
+impl<T> MetricsResponse<T> {
+    /// Creates an empty, well-formed response for a market without data.
+    pub fn empty(parcl_id: i64) -> Self {
+        Self {
+            parcl_id,
+            items: Vec::new(),
+            total: 0,
+            limit: 0,
+            offset: 0,
+            links: PaginationLinks::default(),
+            account: None,
+            meta: ResponseMeta::default(),
+        }
+    }
+
+    /// Returns true if the response contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Converts each item, keeping pagination, account and retry metadata.
+    pub fn map_items<U>(self, f: impl FnMut(T) -> U) -> MetricsResponse<U> {
+        MetricsResponse {
+            parcl_id: self.parcl_id,
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            limit: self.limit,
+            offset: self.offset,
+            links: self.links,
+            account: self.account,
+            meta: self.meta,
+        }
+    }
+
+    /// Reports whether this metric has data for the market.
+    ///
+    /// Endpoints that answer 404 for unsupported markets (e.g. small ZIPs)
+    /// are normalized into empty responses reporting [`Availability::NoData`].
+    pub fn availability(&self) -> Availability {
+        if self.items.is_empty() {
+            Availability::NoData
+        } else {
+            Availability::Available
+        }
+    }
+}
+
+impl<T: Dated> MetricsResponse<T> {
+    /// Sorts items by date in `order`. The sort is stable, so items sharing
+    /// a date keep their relative order.
+    pub fn sort_by_date(&mut self, order: SortOrder) {
+        match order {
+            SortOrder::Asc => self.items.sort_by(|a, b| a.date().cmp(b.date())),
+            SortOrder::Desc => self.items.sort_by(|a, b| b.date().cmp(a.date())),
+        }
+    }
+
+    /// Sorts items oldest first.
+    pub fn sort_ascending(&mut self) {
+        self.sort_by_date(SortOrder::Asc);
+    }
+
+    /// Sorts items newest first.
+    pub fn sort_descending(&mut self) {
+        self.sort_by_date(SortOrder::Desc);
+    }
+}
+
+impl<T> BatchMetricsResponse<T> {
+    /// Creates an empty, well-formed response for markets without data.
+    pub fn empty() -> Self {
+        Self {
+            items: Vec::new(),
+            total: 0,
+            limit: 0,
+            offset: 0,
+            links: PaginationLinks::default(),
+            account: None,
+            meta: ResponseMeta::default(),
+        }
+    }
+
+    /// Returns true if the response contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Converts each item, keeping pagination, account and retry metadata.
+    pub fn map_items<U>(self, f: impl FnMut(T) -> U) -> BatchMetricsResponse<U> {
+        BatchMetricsResponse {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            limit: self.limit,
+            offset: self.offset,
+            links: self.links,
+            account: self.account,
+            meta: self.meta,
+        }
+    }
+
+    /// Reports whether this metric has data for any requested market.
+    pub fn availability(&self) -> Availability {
+        if self.items.is_empty() {
+            Availability::NoData
+        } else {
+            Availability::Available
+        }
+    }
+}
+
+impl<T: Dated + HasParclId> BatchMetricsResponse<T> {
+    /// Groups items by ascending `parcl_id`, then sorts each market's items
+    /// by date in `order`.
+    pub fn sort_by_date(&mut self, order: SortOrder) {
+        self.items.sort_by(|a, b| {
+            let by_date = match order {
+                SortOrder::Asc => a.date().cmp(b.date()),
+                SortOrder::Desc => b.date().cmp(a.date()),
+            };
+            a.parcl_id().cmp(&b.parcl_id()).then(by_date)
+        });
+    }
+
+    /// Groups items by market, oldest first within each.
+    pub fn sort_ascending(&mut self) {
+        self.sort_by_date(SortOrder::Asc);
+    }
+
+    /// Groups items by market, newest first within each.
+    pub fn sort_descending(&mut self) {
+        self.sort_by_date(SortOrder::Desc);
+    }
 }
 
 /// Navigation links for paginated responses.
@@ -48,6 +367,224 @@ pub struct PaginationLinks {
     pub last: Option<String>,
 }
 
+/// Item types that carry the `parcl_id` of the market they describe.
+pub trait HasParclId {
+    fn parcl_id(&self) -> Option<i64>;
+}
+
+macro_rules! impl_has_parcl_id {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl HasParclId for $t {
+                fn parcl_id(&self) -> Option<i64> {
+                    self.parcl_id
+                }
+            }
+        )*
+    };
+}
+
+impl_has_parcl_id!(
+    HousingEventCounts,
+    HousingStock,
+    HousingEventPrices,
+    AllCash,
+    HousingEventPropertyAttributes,
+    PriceFeedEntry,
+    InvestorHousingStockOwnership,
+    InvestorPurchaseToSaleRatio,
+    InvestorHousingEventCounts,
+    InvestorNewListingsRollingCounts,
+    ForSaleInventory,
+    ForSaleInventoryPriceChanges,
+    NewListingsRollingCounts,
+    GrossYield,
+    RentalUnitsConcentration,
+    RentalNewListingsRollingCounts,
+    RentalPrice,
+    PortfolioStockOwnership,
+    PortfolioHousingEventCounts,
+    PortfolioNewListingsRollingCounts,
+    PortfolioRentalListingsRollingCounts,
+);
+
+/// Item types observed on a single date (`YYYY-MM-DD`).
+///
+/// Implemented by every per-date metric model, so generic code such as
+/// [`analysis::series`](crate::analysis::series) works on any metric.
+pub trait Dated {
+    fn date(&self) -> &str;
+
+    /// The date parsed, or `None` if the API sent something other than
+    /// `YYYY-MM-DD`.
+    fn naive_date(&self) -> Option<chrono::NaiveDate> {
+        chrono::NaiveDate::parse_from_str(self.date(), "%Y-%m-%d").ok()
+    }
+}
+
+macro_rules! impl_dated {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl Dated for $t {
+                fn date(&self) -> &str {
+                    &self.date
+                }
+            }
+        )*
+    };
+}
+
+impl_dated!(
+    HousingEventCounts,
+    HousingStock,
+    HousingEventPrices,
+    AllCash,
+    HousingEventPropertyAttributes,
+    PriceFeedEntry,
+    InvestorHousingStockOwnership,
+    InvestorPurchaseToSaleRatio,
+    InvestorHousingEventCounts,
+    InvestorNewListingsRollingCounts,
+    ForSaleInventory,
+    ForSaleInventoryPriceChanges,
+    NewListingsRollingCounts,
+    GrossYield,
+    RentalUnitsConcentration,
+    RentalNewListingsRollingCounts,
+    RentalPrice,
+    PortfolioStockOwnership,
+    PortfolioHousingEventCounts,
+    PortfolioNewListingsRollingCounts,
+    PortfolioRentalListingsRollingCounts,
+);
+
+/// Item types reducible to one representative number per date, e.g. sales
+/// for event counts or the median sale price for event prices.
+///
+/// Used where a metric has to be treated as a single series, such as
+/// [`analysis::correlations`](crate::analysis::correlations).
+pub trait Headline {
+    fn headline(&self) -> Option<f64>;
+}
+
+macro_rules! impl_headline {
+    ($($t:ty => |$item:ident| $value:expr;)*) => {
+        $(
+            impl Headline for $t {
+                fn headline(&self) -> Option<f64> {
+                    let $item = self;
+                    $value
+                }
+            }
+        )*
+    };
+}
+
+impl_headline! {
+    HousingEventCounts => |i| i.sales.map(|v| v as f64);
+    HousingStock => |i| i.all_properties.map(|v| v as f64);
+    HousingEventPrices => |i| i.price.as_ref()?.median.as_ref()?.sales;
+    AllCash => |i| i.pct_sales;
+    HousingEventPropertyAttributes => |i| i.sqft.map(|v| v as f64);
+    PriceFeedEntry => |i| Some(i.price);
+    InvestorHousingStockOwnership => |i| i.investor_owned_pct;
+    InvestorPurchaseToSaleRatio => |i| i.purchase_to_sale_ratio;
+    InvestorHousingEventCounts => |i| i.acquisitions.map(|v| v as f64);
+    InvestorNewListingsRollingCounts => |i| i.count.as_ref()?.rolling_30_day.map(|v| v as f64);
+    ForSaleInventory => |i| i.for_sale_inventory.map(|v| v as f64);
+    ForSaleInventoryPriceChanges => |i| i.pct_inventory_price_drop();
+    NewListingsRollingCounts => |i| i.rolling_30_day_count.map(|v| v as f64);
+    GrossYield => |i| i.gross_yield;
+    RentalUnitsConcentration => |i| i.rental_units_concentration;
+    RentalNewListingsRollingCounts => |i| i.rolling_30_day_count.map(|v| v as f64);
+    PortfolioStockOwnership => |i| i.pct_sf_housing_stock.as_ref()?.all_portfolios;
+    PortfolioHousingEventCounts => |i| i.acquisitions.map(|v| v as f64);
+    PortfolioNewListingsRollingCounts => |i| i.count.as_ref()?.rolling_30_day.map(|v| v as f64);
+    PortfolioRentalListingsRollingCounts => |i| i.count.as_ref()?.rolling_30_day.map(|v| v as f64);
+}
+
+/// A metric value that keeps "no data" distinct from zero.
+///
+/// Model fields are `Option`s, and `unwrap_or(0)` silently turns a missing
+/// observation into a real zero. The `*_or_missing` helpers on count models
+/// return this instead; [`analysis::MissingPolicy`](crate::analysis::MissingPolicy)
+/// decides how aggregates treat `Missing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataPoint<T> {
+    Value(T),
+    /// The API reported no value for this field.
+    Missing,
+}
+
+impl<T> DataPoint<T> {
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Self::Missing)
+    }
+
+    pub fn value(self) -> Option<T> {
+        match self {
+            Self::Value(value) => Some(value),
+            Self::Missing => None,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> DataPoint<U> {
+        match self {
+            Self::Value(value) => DataPoint::Value(f(value)),
+            Self::Missing => DataPoint::Missing,
+        }
+    }
+}
+
+impl<T> From<Option<T>> for DataPoint<T> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Missing, Self::Value)
+    }
+}
+
+macro_rules! impl_data_points {
+    ($($t:ty { $($method:ident => $field:ident),* $(,)? })*) => {
+        $(
+            impl $t {
+                $(
+                    #[doc = concat!("`", stringify!($field), "`, with no data as [`DataPoint::Missing`].")]
+                    pub fn $method(&self) -> DataPoint<i64> {
+                        self.$field.into()
+                    }
+                )*
+            }
+        )*
+    };
+}
+
+impl_data_points! {
+    HousingEventCounts {
+        sales_or_missing => sales,
+        new_listings_for_sale_or_missing => new_listings_for_sale,
+        new_rental_listings_or_missing => new_rental_listings,
+    }
+    HousingStock {
+        single_family_or_missing => single_family,
+        condo_or_missing => condo,
+        townhouse_or_missing => townhouse,
+        other_or_missing => other,
+        all_properties_or_missing => all_properties,
+    }
+    InvestorHousingEventCounts {
+        acquisitions_or_missing => acquisitions,
+        dispositions_or_missing => dispositions,
+    }
+    ForSaleInventory {
+        for_sale_inventory_or_missing => for_sale_inventory,
+    }
+}
+
+impl HasParclId for Market {
+    fn parcl_id(&self) -> Option<i64> {
+        Some(self.parcl_id)
+    }
+}
+
 // ============================================================================
 // Search
 // ============================================================================
@@ -61,11 +598,15 @@ pub struct Market {
     pub state_abbreviation: Option<String>,
     pub state_fips_code: Option<String>,
     pub location_type: String,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub total_population: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub median_income: Option<i64>,
     /// Whether this market is tradeable on the Parcl exchange (0 or 1).
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_exchange_market: Option<i32>,
     /// Whether this market has price feed data (0 or 1).
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub pricefeed_market: Option<i32>,
     /// Country code (e.g. "US").
     pub country: Option<String>,
@@ -74,8 +615,10 @@ pub struct Market {
     /// US Census region.
     pub region: Option<String>,
     /// Whether this market is in the Case-Shiller 10-city index (0 or 1).
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub case_shiller_10_market: Option<i32>,
     /// Whether this market is in the Case-Shiller 20-city index (0 or 1).
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub case_shiller_20_market: Option<i32>,
 }
 
@@ -89,6 +632,107 @@ impl Market {
     pub fn has_price_feed(&self) -> bool {
         self.pricefeed_market == Some(1)
     }
+
+    /// Returns true if this market is in the Case-Shiller 10-city composite.
+    pub fn in_case_shiller_10(&self) -> bool {
+        self.case_shiller_10_market == Some(1)
+    }
+
+    /// Returns true if this market is in the Case-Shiller 20-city composite.
+    pub fn in_case_shiller_20(&self) -> bool {
+        self.case_shiller_20_market == Some(1)
+    }
+
+    /// The value this market is ranked on for `key`; missing values rank last.
+    pub fn sort_value(&self, key: SortBy) -> i64 {
+        let value = match key {
+            SortBy::TotalPopulation => self.total_population,
+            SortBy::MedianIncome => self.median_income,
+            SortBy::CaseShiller20Market => self.case_shiller_20_market.map(i64::from),
+            SortBy::CaseShiller10Market => self.case_shiller_10_market.map(i64::from),
+            SortBy::PricefeedMarket => self.pricefeed_market.map(i64::from),
+            SortBy::ParclExchangeMarket => self.parcl_exchange_market.map(i64::from),
+        };
+        value.unwrap_or(i64::MIN)
+    }
+}
+
+impl PaginatedResponse<Market> {
+    /// Sorts markets by `key`, highest first. Ties keep the API's order.
+    pub fn rank_by(mut self, key: SortBy) -> Self {
+        self.items
+            .sort_by_key(|m| std::cmp::Reverse(m.sort_value(key)));
+        self
+    }
+
+    /// Drops markets whose `geoid` already appeared earlier in the list.
+    ///
+    /// Markets without a `geoid` are kept. Rank first to control which
+    /// duplicate survives.
+    pub fn dedupe_by_geoid(mut self) -> Self {
+        let mut seen = std::collections::HashSet::new();
+        self.items.retain(|m| match &m.geoid {
+            Some(geoid) => seen.insert(geoid.clone()),
+            None => true,
+        });
+        self
+    }
+
+    /// Picks the market `query` most likely refers to.
+    ///
+    /// An exact name match beats a prefix match, which beats a substring
+    /// match. A trailing state (`"Springfield, IL"` or `"Springfield IL"`)
+    /// must agree when given. Remaining ties go to the most populous market.
+    /// Returns `None` when no name contains the query.
+    pub fn best_match(&self, query: &str) -> Option<&Market> {
+        let (name, state) = split_state_hint(query, &self.items);
+        let name = name.to_lowercase();
+        self.items
+            .iter()
+            .filter(|m| {
+                state.is_none_or(|s| {
+                    m.state_abbreviation
+                        .as_deref()
+                        .is_some_and(|ms| ms.eq_ignore_ascii_case(s))
+                })
+            })
+            .filter_map(|m| {
+                let market_name = m.name.to_lowercase();
+                let name_score = if market_name == name {
+                    3
+                } else if market_name.starts_with(&name) {
+                    2
+                } else if market_name.contains(&name) {
+                    1
+                } else {
+                    return None;
+                };
+                Some((m, (name_score, m.total_population.unwrap_or(0))))
+            })
+            .max_by_key(|(_, score)| *score)
+            .map(|(m, _)| m)
+    }
+}
+
+/// Splits a trailing state abbreviation off `query` if one of `markets` is
+/// in that state.
+pub(crate) fn split_state_hint<'q>(
+    query: &'q str,
+    markets: &[Market],
+) -> (&'q str, Option<&'q str>) {
+    let query = query.trim();
+    if let Some((name, state)) = query.rsplit_once([',', ' ']) {
+        let state = state.trim();
+        let known = markets.iter().any(|m| {
+            m.state_abbreviation
+                .as_deref()
+                .is_some_and(|ms| ms.eq_ignore_ascii_case(state))
+        });
+        if state.len() == 2 && known {
+            return (name.trim().trim_end_matches(',').trim(), Some(state));
+        }
+    }
+    (query, None)
 }
 
 /// Location type filter for market search.
@@ -163,6 +807,291 @@ impl std::fmt::Display for USRegion {
     }
 }
 
+/// A US state (or the District of Columbia), by postal abbreviation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum USState {
+    #[serde(rename = "AL")]
+    Alabama,
+    #[serde(rename = "AK")]
+    Alaska,
+    #[serde(rename = "AZ")]
+    Arizona,
+    #[serde(rename = "AR")]
+    Arkansas,
+    #[serde(rename = "CA")]
+    California,
+    #[serde(rename = "CO")]
+    Colorado,
+    #[serde(rename = "CT")]
+    Connecticut,
+    #[serde(rename = "DE")]
+    Delaware,
+    #[serde(rename = "DC")]
+    DistrictOfColumbia,
+    #[serde(rename = "FL")]
+    Florida,
+    #[serde(rename = "GA")]
+    Georgia,
+    #[serde(rename = "HI")]
+    Hawaii,
+    #[serde(rename = "ID")]
+    Idaho,
+    #[serde(rename = "IL")]
+    Illinois,
+    #[serde(rename = "IN")]
+    Indiana,
+    #[serde(rename = "IA")]
+    Iowa,
+    #[serde(rename = "KS")]
+    Kansas,
+    #[serde(rename = "KY")]
+    Kentucky,
+    #[serde(rename = "LA")]
+    Louisiana,
+    #[serde(rename = "ME")]
+    Maine,
+    #[serde(rename = "MD")]
+    Maryland,
+    #[serde(rename = "MA")]
+    Massachusetts,
+    #[serde(rename = "MI")]
+    Michigan,
+    #[serde(rename = "MN")]
+    Minnesota,
+    #[serde(rename = "MS")]
+    Mississippi,
+    #[serde(rename = "MO")]
+    Missouri,
+    #[serde(rename = "MT")]
+    Montana,
+    #[serde(rename = "NE")]
+    Nebraska,
+    #[serde(rename = "NV")]
+    Nevada,
+    #[serde(rename = "NH")]
+    NewHampshire,
+    #[serde(rename = "NJ")]
+    NewJersey,
+    #[serde(rename = "NM")]
+    NewMexico,
+    #[serde(rename = "NY")]
+    NewYork,
+    #[serde(rename = "NC")]
+    NorthCarolina,
+    #[serde(rename = "ND")]
+    NorthDakota,
+    #[serde(rename = "OH")]
+    Ohio,
+    #[serde(rename = "OK")]
+    Oklahoma,
+    #[serde(rename = "OR")]
+    Oregon,
+    #[serde(rename = "PA")]
+    Pennsylvania,
+    #[serde(rename = "RI")]
+    RhodeIsland,
+    #[serde(rename = "SC")]
+    SouthCarolina,
+    #[serde(rename = "SD")]
+    SouthDakota,
+    #[serde(rename = "TN")]
+    Tennessee,
+    #[serde(rename = "TX")]
+    Texas,
+    #[serde(rename = "UT")]
+    Utah,
+    #[serde(rename = "VT")]
+    Vermont,
+    #[serde(rename = "VA")]
+    Virginia,
+    #[serde(rename = "WA")]
+    Washington,
+    #[serde(rename = "WV")]
+    WestVirginia,
+    #[serde(rename = "WI")]
+    Wisconsin,
+    #[serde(rename = "WY")]
+    Wyoming,
+}
+
+impl USState {
+    /// All 50 states and DC, in alphabetical order of their names.
+    pub const ALL: [USState; 51] = [
+        Self::Alabama,
+        Self::Alaska,
+        Self::Arizona,
+        Self::Arkansas,
+        Self::California,
+        Self::Colorado,
+        Self::Connecticut,
+        Self::Delaware,
+        Self::DistrictOfColumbia,
+        Self::Florida,
+        Self::Georgia,
+        Self::Hawaii,
+        Self::Idaho,
+        Self::Illinois,
+        Self::Indiana,
+        Self::Iowa,
+        Self::Kansas,
+        Self::Kentucky,
+        Self::Louisiana,
+        Self::Maine,
+        Self::Maryland,
+        Self::Massachusetts,
+        Self::Michigan,
+        Self::Minnesota,
+        Self::Mississippi,
+        Self::Missouri,
+        Self::Montana,
+        Self::Nebraska,
+        Self::Nevada,
+        Self::NewHampshire,
+        Self::NewJersey,
+        Self::NewMexico,
+        Self::NewYork,
+        Self::NorthCarolina,
+        Self::NorthDakota,
+        Self::Ohio,
+        Self::Oklahoma,
+        Self::Oregon,
+        Self::Pennsylvania,
+        Self::RhodeIsland,
+        Self::SouthCarolina,
+        Self::SouthDakota,
+        Self::Tennessee,
+        Self::Texas,
+        Self::Utah,
+        Self::Vermont,
+        Self::Virginia,
+        Self::Washington,
+        Self::WestVirginia,
+        Self::Wisconsin,
+        Self::Wyoming,
+    ];
+
+    /// The two-letter postal abbreviation, as used by `state_abbreviation`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Alabama => "AL",
+            Self::Alaska => "AK",
+            Self::Arizona => "AZ",
+            Self::Arkansas => "AR",
+            Self::California => "CA",
+            Self::Colorado => "CO",
+            Self::Connecticut => "CT",
+            Self::Delaware => "DE",
+            Self::DistrictOfColumbia => "DC",
+            Self::Florida => "FL",
+            Self::Georgia => "GA",
+            Self::Hawaii => "HI",
+            Self::Idaho => "ID",
+            Self::Illinois => "IL",
+            Self::Indiana => "IN",
+            Self::Iowa => "IA",
+            Self::Kansas => "KS",
+            Self::Kentucky => "KY",
+            Self::Louisiana => "LA",
+            Self::Maine => "ME",
+            Self::Maryland => "MD",
+            Self::Massachusetts => "MA",
+            Self::Michigan => "MI",
+            Self::Minnesota => "MN",
+            Self::Mississippi => "MS",
+            Self::Missouri => "MO",
+            Self::Montana => "MT",
+            Self::Nebraska => "NE",
+            Self::Nevada => "NV",
+            Self::NewHampshire => "NH",
+            Self::NewJersey => "NJ",
+            Self::NewMexico => "NM",
+            Self::NewYork => "NY",
+            Self::NorthCarolina => "NC",
+            Self::NorthDakota => "ND",
+            Self::Ohio => "OH",
+            Self::Oklahoma => "OK",
+            Self::Oregon => "OR",
+            Self::Pennsylvania => "PA",
+            Self::RhodeIsland => "RI",
+            Self::SouthCarolina => "SC",
+            Self::SouthDakota => "SD",
+            Self::Tennessee => "TN",
+            Self::Texas => "TX",
+            Self::Utah => "UT",
+            Self::Vermont => "VT",
+            Self::Virginia => "VA",
+            Self::Washington => "WA",
+            Self::WestVirginia => "WV",
+            Self::Wisconsin => "WI",
+            Self::Wyoming => "WY",
+        }
+    }
+
+    /// Parses a postal abbreviation, ignoring case.
+    pub fn from_abbreviation(code: &str) -> Option<Self> {
+        Some(match code.trim().to_ascii_uppercase().as_str() {
+            "AL" => Self::Alabama,
+            "AK" => Self::Alaska,
+            "AZ" => Self::Arizona,
+            "AR" => Self::Arkansas,
+            "CA" => Self::California,
+            "CO" => Self::Colorado,
+            "CT" => Self::Connecticut,
+            "DE" => Self::Delaware,
+            "DC" => Self::DistrictOfColumbia,
+            "FL" => Self::Florida,
+            "GA" => Self::Georgia,
+            "HI" => Self::Hawaii,
+            "ID" => Self::Idaho,
+            "IL" => Self::Illinois,
+            "IN" => Self::Indiana,
+            "IA" => Self::Iowa,
+            "KS" => Self::Kansas,
+            "KY" => Self::Kentucky,
+            "LA" => Self::Louisiana,
+            "ME" => Self::Maine,
+            "MD" => Self::Maryland,
+            "MA" => Self::Massachusetts,
+            "MI" => Self::Michigan,
+            "MN" => Self::Minnesota,
+            "MS" => Self::Mississippi,
+            "MO" => Self::Missouri,
+            "MT" => Self::Montana,
+            "NE" => Self::Nebraska,
+            "NV" => Self::Nevada,
+            "NH" => Self::NewHampshire,
+            "NJ" => Self::NewJersey,
+            "NM" => Self::NewMexico,
+            "NY" => Self::NewYork,
+            "NC" => Self::NorthCarolina,
+            "ND" => Self::NorthDakota,
+            "OH" => Self::Ohio,
+            "OK" => Self::Oklahoma,
+            "OR" => Self::Oregon,
+            "PA" => Self::Pennsylvania,
+            "RI" => Self::RhodeIsland,
+            "SC" => Self::SouthCarolina,
+            "SD" => Self::SouthDakota,
+            "TN" => Self::Tennessee,
+            "TX" => Self::Texas,
+            "UT" => Self::Utah,
+            "VT" => Self::Vermont,
+            "VA" => Self::Virginia,
+            "WA" => Self::Washington,
+            "WV" => Self::WestVirginia,
+            "WI" => Self::Wisconsin,
+            "WY" => Self::Wyoming,
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for USState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Sort field for market search.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SortBy {
@@ -193,8 +1122,9 @@ impl std::fmt::Display for SortBy {
     }
 }
 
-/// Sort order for market search.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Sort order for market and property search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum SortOrder {
     Asc,
     Desc,
@@ -215,6 +1145,38 @@ impl std::fmt::Display for SortOrder {
     }
 }
 
+/// Sort field for property search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PropertySortBy {
+    RecordAddedDate,
+    SquareFootage,
+    YearBuilt,
+    Bedrooms,
+    Bathrooms,
+    /// Price of the most recent sale.
+    LastSalePrice,
+}
+
+impl PropertySortBy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RecordAddedDate => "RECORD_ADDED_DATE",
+            Self::SquareFootage => "SQUARE_FOOTAGE",
+            Self::YearBuilt => "YEAR_BUILT",
+            Self::Bedrooms => "BEDROOMS",
+            Self::Bathrooms => "BATHROOMS",
+            Self::LastSalePrice => "LAST_SALE_PRICE",
+        }
+    }
+}
+
+impl std::fmt::Display for PropertySortBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Property type filter for market metrics.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum PropertyType {
@@ -354,28 +1316,39 @@ impl std::fmt::Display for EntityOwnerName {
 /// Housing transaction and listing counts.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct HousingEventCounts {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub sales: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub new_listings_for_sale: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub new_rental_listings: Option<i64>,
 }
 
 /// Housing unit counts by property type.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct HousingStock {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub single_family: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub condo: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub townhouse: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub other: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub all_properties: Option<i64>,
 }
 
 /// Housing event prices with statistical breakdowns.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct HousingEventPrices {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     pub price: Option<PriceStats>,
@@ -394,40 +1367,238 @@ pub struct PriceStats {
 /// Price values for each event type.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct EventPrices {
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub sales: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub new_listings_for_sale: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub new_rental_listings: Option<f64>,
 }
 
+/// A statistic in [`PriceStats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PriceStat {
+    Median,
+    StandardDeviation,
+    Percentile20th,
+    Percentile80th,
+}
+
+impl PriceStat {
+    /// Every statistic, in the API's field order.
+    pub const ALL: [Self; 4] = [
+        Self::Median,
+        Self::StandardDeviation,
+        Self::Percentile20th,
+        Self::Percentile80th,
+    ];
+
+    /// The API field name, e.g. `percentile_20th`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Median => "median",
+            Self::StandardDeviation => "standard_deviation",
+            Self::Percentile20th => "percentile_20th",
+            Self::Percentile80th => "percentile_80th",
+        }
+    }
+}
+
+impl std::fmt::Display for PriceStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An event type priced in [`EventPrices`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PriceEvent {
+    Sales,
+    NewListingsForSale,
+    NewRentalListings,
+}
+
+impl PriceEvent {
+    /// Every event type, in the API's field order.
+    pub const ALL: [Self; 3] = [
+        Self::Sales,
+        Self::NewListingsForSale,
+        Self::NewRentalListings,
+    ];
+
+    /// The API field name, e.g. `new_listings_for_sale`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sales => "sales",
+            Self::NewListingsForSale => "new_listings_for_sale",
+            Self::NewRentalListings => "new_rental_listings",
+        }
+    }
+}
+
+impl std::fmt::Display for PriceEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PriceStats {
+    /// The prices for one statistic.
+    pub fn stat(&self, stat: PriceStat) -> Option<&EventPrices> {
+        match stat {
+            PriceStat::Median => self.median.as_ref(),
+            PriceStat::StandardDeviation => self.standard_deviation.as_ref(),
+            PriceStat::Percentile20th => self.percentile_20th.as_ref(),
+            PriceStat::Percentile80th => self.percentile_80th.as_ref(),
+        }
+    }
+
+    /// One cell of the statistic × event matrix, e.g. the median sale price.
+    pub fn get(&self, stat: PriceStat, event: PriceEvent) -> Option<f64> {
+        self.stat(stat)?.get(event)
+    }
+
+    /// Every reported `(stat, event, value)`, statistic by statistic.
+    /// Cells the API left out are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = (PriceStat, PriceEvent, f64)> + '_ {
+        PriceStat::ALL.into_iter().flat_map(move |stat| {
+            self.stat(stat)
+                .into_iter()
+                .flat_map(move |prices| prices.iter().map(move |(event, v)| (stat, event, v)))
+        })
+    }
+}
+
+impl EventPrices {
+    pub fn get(&self, event: PriceEvent) -> Option<f64> {
+        match event {
+            PriceEvent::Sales => self.sales,
+            PriceEvent::NewListingsForSale => self.new_listings_for_sale,
+            PriceEvent::NewRentalListings => self.new_rental_listings,
+        }
+    }
+
+    /// Every reported `(event, value)`, skipping missing ones.
+    pub fn iter(&self) -> impl Iterator<Item = (PriceEvent, f64)> + '_ {
+        PriceEvent::ALL
+            .into_iter()
+            .filter_map(move |event| Some((event, self.get(event)?)))
+    }
+}
+
+/// Asking rents for new rental listings, flattened from [`HousingEventPrices`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RentalPrice {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
+    pub parcl_id: Option<i64>,
+    pub date: String,
+    /// Median asking rent.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
+    pub median: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
+    pub standard_deviation: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
+    pub percentile_20th: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
+    pub percentile_80th: Option<f64>,
+    /// Median asking rent per square foot.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
+    pub median_per_square_foot: Option<f64>,
+}
+
+impl From<HousingEventPrices> for RentalPrice {
+    fn from(prices: HousingEventPrices) -> Self {
+        let rent = |stats: &Option<PriceStats>, pick: fn(&PriceStats) -> &Option<EventPrices>| {
+            stats
+                .as_ref()
+                .and_then(|s| pick(s).as_ref())
+                .and_then(|p| p.new_rental_listings)
+        };
+        Self {
+            median: rent(&prices.price, |s| &s.median),
+            standard_deviation: rent(&prices.price, |s| &s.standard_deviation),
+            percentile_20th: rent(&prices.price, |s| &s.percentile_20th),
+            percentile_80th: rent(&prices.price, |s| &s.percentile_80th),
+            median_per_square_foot: rent(&prices.price_per_square_foot, |s| &s.median),
+            parcl_id: prices.parcl_id,
+            date: prices.date,
+        }
+    }
+}
+
 /// All-cash transaction metrics.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AllCash {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Count of all-cash arms-length sales.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub count_sales: Option<i64>,
     /// Percentage of arms-length sales completed as all-cash.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub pct_sales: Option<f64>,
     /// Count of all-cash transfers across all sale types.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub count_transfers: Option<i64>,
     /// Percentage of transfers completed as all-cash.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub pct_transfers: Option<f64>,
 }
 
 /// Physical attributes of properties involved in housing events.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct HousingEventPropertyAttributes {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Median bedroom count.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub beds: Option<i64>,
     /// Median bathroom count.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub baths: Option<f64>,
     /// Median square footage.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub sqft: Option<i64>,
     /// Median lot size in square feet.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub lot_size: Option<i64>,
     /// Median year built.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub year_built: Option<i64>,
 }
 
@@ -438,10 +1609,61 @@ pub struct HousingEventPropertyAttributes {
 /// Price feed data point for trading.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PriceFeedEntry {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(deserialize_with = "lenient::required_float")
+    )]
     pub price: f64,
-    pub price_feed_type: Option<String>,
+    pub price_feed_type: Option<PriceFeedType>,
+}
+
+/// Which price feed series an entry belongs to.
+///
+/// Unrecognized values are kept in [`PriceFeedType::Other`] so new feed types
+/// do not break deserialization.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum PriceFeedType {
+    /// The raw daily feed.
+    Daily,
+    /// A smoothed feed derived from the daily series.
+    Smoothed,
+    Other(String),
+}
+
+impl PriceFeedType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Daily => "daily",
+            Self::Smoothed => "smoothed",
+            Self::Other(s) => s,
+        }
+    }
+}
+
+impl From<String> for PriceFeedType {
+    fn from(s: String) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "daily" => Self::Daily,
+            "smoothed" => Self::Smoothed,
+            _ => Self::Other(s),
+        }
+    }
+}
+
+impl From<PriceFeedType> for String {
+    fn from(t: PriceFeedType) -> Self {
+        t.as_str().to_string()
+    }
+}
+
+impl std::fmt::Display for PriceFeedType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 // ============================================================================
@@ -451,41 +1673,59 @@ pub struct PriceFeedEntry {
 /// Investor housing stock ownership data.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct InvestorHousingStockOwnership {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Count of properties owned by investors.
     #[serde(rename = "count")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub investor_owned_count: Option<i64>,
     /// Percentage of housing stock owned by investors.
     #[serde(rename = "pct_ownership")]
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub investor_owned_pct: Option<f64>,
 }
 
 /// Investor purchase-to-sale ratio data.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct InvestorPurchaseToSaleRatio {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Number of acquisitions by investors.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub acquisitions: Option<i64>,
     /// Number of dispositions by investors.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub dispositions: Option<i64>,
     /// Ratio of purchases to sales (>1 = net buyer, <1 = net seller).
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub purchase_to_sale_ratio: Option<f64>,
 }
 
 /// Investor housing event counts.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct InvestorHousingEventCounts {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Count of investor property acquisitions.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub acquisitions: Option<i64>,
     /// Count of investor property dispositions.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub dispositions: Option<i64>,
     /// Properties newly listed for sale by investors.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub new_listings_for_sale: Option<i64>,
     /// Properties newly listed for rent by investors.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub new_rental_listings: Option<i64>,
 }
 
@@ -493,12 +1733,16 @@ pub struct InvestorHousingEventCounts {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RollingCounts {
     /// 7-day rolling count.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_7_day: Option<i64>,
     /// 30-day rolling count.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_30_day: Option<i64>,
     /// 60-day rolling count.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_60_day: Option<i64>,
     /// 90-day rolling count.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_90_day: Option<i64>,
 }
 
@@ -506,18 +1750,35 @@ pub struct RollingCounts {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RollingPercentages {
     /// 7-day rolling percentage.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub rolling_7_day: Option<f64>,
     /// 30-day rolling percentage.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub rolling_30_day: Option<f64>,
     /// 60-day rolling percentage.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub rolling_60_day: Option<f64>,
     /// 90-day rolling percentage.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub rolling_90_day: Option<f64>,
 }
 
 /// Rolling counts for investor new listings.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct InvestorNewListingsRollingCounts {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Rolling counts of new listings.
@@ -533,52 +1794,103 @@ pub struct InvestorNewListingsRollingCounts {
 /// For-sale inventory metrics.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ForSaleInventory {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Total count of properties listed for sale.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub for_sale_inventory: Option<i64>,
 }
 
 /// For-sale inventory price change metrics.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ForSaleInventoryPriceChanges {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Count of listings with any price change.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub count_price_change: Option<i64>,
     /// Count of listings with price drops.
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub count_price_drop: Option<i64>,
     /// Median days between price changes.
+    #[deprecated(note = "use `median_days_bt_change()`; the field takes that name in 0.2")]
     #[serde(rename = "median_days_bt_change")]
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub median_days_bt_price_change: Option<f64>,
     /// Median price change amount.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub median_price_change: Option<f64>,
     /// Median percentage price change.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub median_pct_price_change: Option<f64>,
     /// Percentage of inventory with price changes.
+    #[deprecated(note = "use `pct_inventory_price_change()`; the field takes that name in 0.2")]
     #[serde(rename = "pct_inventory_price_change")]
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub pct_price_change: Option<f64>,
     /// Percentage of inventory with price drops.
+    #[deprecated(note = "use `pct_inventory_price_drop()`; the field takes that name in 0.2")]
     #[serde(rename = "pct_inventory_price_drop")]
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub pct_price_drop: Option<f64>,
 }
 
+#[allow(deprecated)]
+impl ForSaleInventoryPriceChanges {
+    /// Median days between price changes.
+    pub fn median_days_bt_change(&self) -> Option<f64> {
+        self.median_days_bt_price_change
+    }
+
+    /// Percentage of inventory with price changes.
+    pub fn pct_inventory_price_change(&self) -> Option<f64> {
+        self.pct_price_change
+    }
+
+    /// Percentage of inventory with price drops.
+    pub fn pct_inventory_price_drop(&self) -> Option<f64> {
+        self.pct_price_drop
+    }
+}
+
 /// Rolling counts for new for-sale listings.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct NewListingsRollingCounts {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// 7-day rolling count.
     #[serde(rename = "rolling_7_day")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_7_day_count: Option<i64>,
     /// 30-day rolling count.
     #[serde(rename = "rolling_30_day")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_30_day_count: Option<i64>,
     /// 60-day rolling count.
     #[serde(rename = "rolling_60_day")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_60_day_count: Option<i64>,
     /// 90-day rolling count.
     #[serde(rename = "rolling_90_day")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_90_day_count: Option<i64>,
 }
 
@@ -589,37 +1901,52 @@ pub struct NewListingsRollingCounts {
 /// Gross rental yield metrics.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GrossYield {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Annual rental income divided by median sale price.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub gross_yield: Option<f64>,
 }
 
 /// Rental units concentration metrics.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RentalUnitsConcentration {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Percentage of housing stock that are rental units.
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub rental_units_concentration: Option<f64>,
 }
 
 /// Rolling counts for new rental listings.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RentalNewListingsRollingCounts {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// 7-day rolling count.
     #[serde(rename = "rolling_7_day")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_7_day_count: Option<i64>,
     /// 30-day rolling count.
     #[serde(rename = "rolling_30_day")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_30_day_count: Option<i64>,
     /// 60-day rolling count.
     #[serde(rename = "rolling_60_day")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_60_day_count: Option<i64>,
     /// 90-day rolling count.
     #[serde(rename = "rolling_90_day")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub rolling_90_day_count: Option<i64>,
 }
 
@@ -630,26 +1957,52 @@ pub struct RentalNewListingsRollingCounts {
 /// Count breakdown by portfolio size for housing stock ownership.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PortfolioSizeBreakdown {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub portfolio_2_to_9: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub portfolio_10_to_99: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub portfolio_100_to_999: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub portfolio_1000_plus: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub all_portfolios: Option<i64>,
 }
 
 /// Percentage breakdown by portfolio size for housing stock ownership.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PortfolioSizePctBreakdown {
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub portfolio_2_to_9: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub portfolio_10_to_99: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub portfolio_100_to_999: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub portfolio_1000_plus: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub all_portfolios: Option<f64>,
 }
 
 /// SF housing stock ownership broken down by portfolio size.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PortfolioStockOwnership {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Owned property counts by portfolio size.
@@ -661,18 +2014,25 @@ pub struct PortfolioStockOwnership {
 /// Portfolio holder housing event counts.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PortfolioHousingEventCounts {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub acquisitions: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub dispositions: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub new_listings_for_sale: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub new_rental_listings: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub transfers: Option<i64>,
 }
 
 /// Rolling counts for portfolio new for-sale listings.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PortfolioNewListingsRollingCounts {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Rolling counts of new for-sale listings.
@@ -684,6 +2044,7 @@ pub struct PortfolioNewListingsRollingCounts {
 /// Rolling counts for portfolio new rental listings.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PortfolioRentalListingsRollingCounts {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub parcl_id: Option<i64>,
     pub date: String,
     /// Rolling counts of new rental listings.
@@ -699,7 +2060,9 @@ pub struct PortfolioRentalListingsRollingCounts {
 /// API account/credit usage info returned in API responses.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AccountInfo {
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub est_credits_used: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub est_remaining_credits: Option<i64>,
 }
 
@@ -714,7 +2077,14 @@ pub struct AccountUsage {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PropertySearchResponse {
     pub items: Vec<Property>,
+    /// Total matching properties, when the API reports it.
+    #[serde(default)]
+    #[cfg_attr(feature = "lenient", serde(deserialize_with = "lenient::int"))]
+    pub total: Option<u64>,
     pub account: Option<AccountInfo>,
+    /// Retry telemetry recorded by the client (not part of the API payload).
+    #[serde(skip)]
+    pub meta: ResponseMeta,
 }
 
 /// A property returned from the v1 property search endpoint.
@@ -728,26 +2098,54 @@ pub struct Property {
     pub state_abbreviation: Option<String>,
     pub county: Option<String>,
     pub cbsa: Option<String>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub latitude: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub longitude: Option<f64>,
     pub property_type: Option<String>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub bedrooms: Option<i32>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub bathrooms: Option<f64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub square_footage: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub year_built: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub cbsa_parcl_id: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub county_parcl_id: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub city_parcl_id: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub zip_parcl_id: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub event_count: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub event_history_sale_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub event_history_rental_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub event_history_listing_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_new_construction_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_owner_occupied_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_investor_owned_flag: Option<i32>,
     pub current_entity_owner_name: Option<String>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_on_market_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_on_market_rental_flag: Option<i32>,
     pub record_added_date: Option<String>,
 }
@@ -756,6 +2154,9 @@ pub struct Property {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PropertyEventHistoryResponse {
     pub properties: Vec<PropertyWithEvents>,
+    /// Retry telemetry recorded by the client (not part of the API payload).
+    #[serde(skip)]
+    pub meta: ResponseMeta,
 }
 
 /// A property with its event history.
@@ -773,9 +2174,16 @@ pub struct PropertyMetadata {
     pub city: Option<String>,
     pub state: Option<String>,
     pub zip: Option<String>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub bedrooms: Option<i32>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub bathrooms: Option<f64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub square_footage: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub year_built: Option<i32>,
     pub property_type: Option<String>,
 }
@@ -786,10 +2194,14 @@ pub struct PropertyEvent {
     pub event_type: Option<String>,
     pub event_name: Option<String>,
     pub event_date: Option<String>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub price: Option<i64>,
     pub entity_owner_name: Option<String>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub investor_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub owner_occupied_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub new_construction_flag: Option<i32>,
     pub record_updated_date: Option<String>,
 }
@@ -798,6 +2210,9 @@ pub struct PropertyEvent {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PropertyV2SearchResponse {
     pub properties: Vec<PropertyV2>,
+    /// Retry telemetry recorded by the client (not part of the API payload).
+    #[serde(skip)]
+    pub meta: ResponseMeta,
 }
 
 /// A property returned from the v2 search endpoint.
@@ -811,9 +2226,16 @@ pub struct PropertyV2 {
 /// Detailed property metadata from v2 search.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PropertyV2Metadata {
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub bathrooms: Option<f64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub bedrooms: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub sq_ft: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub year_built: Option<i32>,
     pub property_type: Option<String>,
     pub address1: Option<String>,
@@ -821,16 +2243,29 @@ pub struct PropertyV2Metadata {
     pub city: Option<String>,
     pub state: Option<String>,
     pub zip5: Option<String>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub latitude: Option<f64>,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub longitude: Option<f64>,
     pub city_name: Option<String>,
     pub county_name: Option<String>,
     pub metro_name: Option<String>,
     pub record_added_date: Option<String>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_on_market_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_on_market_rental_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_new_construction_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_owner_occupied_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_investor_owned_flag: Option<i32>,
     pub current_entity_owner_name: Option<String>,
 }
@@ -842,12 +2277,19 @@ pub struct PropertyV2Event {
     pub event_name: Option<String>,
     pub event_date: Option<String>,
     pub entity_owner_name: Option<String>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub true_sale_index: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub price: Option<i64>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub transfer_index: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub investor_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub owner_occupied_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub new_construction_flag: Option<i32>,
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub current_owner_flag: Option<i32>,
     pub record_updated_date: Option<String>,
 }
@@ -880,13 +2322,31 @@ pub struct PropertyV2SearchRequest {
     pub event_filters: Option<V2EventFilters>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub owner_filters: Option<OwnerFilters>,
+    /// Server-side ordering, so a `limit` returns the top of the ranking
+    /// instead of an arbitrary page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<PropertySortBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<SortOrder>,
 }
 
 /// Geographic search coordinates for v2 property search.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeoCoordinates {
+    #[cfg_attr(
+        feature = "lenient",
+        serde(deserialize_with = "lenient::required_float")
+    )]
     pub latitude: f64,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(deserialize_with = "lenient::required_float")
+    )]
     pub longitude: f64,
+    #[cfg_attr(
+        feature = "lenient",
+        serde(deserialize_with = "lenient::required_float")
+    )]
     pub radius_miles: f64,
 }
 
@@ -898,20 +2358,34 @@ pub struct PropertyFilters {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub property_types: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub min_beds: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub max_beds: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub min_baths: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        feature = "lenient",
+        serde(default, deserialize_with = "lenient::float")
+    )]
     pub max_baths: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub min_sqft: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub max_sqft: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub min_year_built: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub max_year_built: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub current_entity_owner_name: Option<String>,
@@ -941,8 +2415,10 @@ pub struct V2EventFilters {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_event_date: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub min_event_price: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "lenient", serde(default, deserialize_with = "lenient::int"))]
     pub max_event_price: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub include_events: Option<bool>,
@@ -994,6 +2470,87 @@ mod tests {
         }
     }
 
+    #[test]
+    fn generated_types_round_trip_model_json() {
+        fn round_trip<M, W>(model: &M)
+        where
+            M: Serialize + serde::de::DeserializeOwned,
+            W: Serialize + serde::de::DeserializeOwned,
+        {
+            let json = serde_json::to_value(model).unwrap();
+            let wire: W = serde_json::from_value(json.clone()).unwrap();
+            let back: M = serde_json::from_value(serde_json::to_value(wire).unwrap()).unwrap();
+            assert_eq!(serde_json::to_value(back).unwrap(), json);
+        }
+
+        round_trip::<_, crate::generated::Market>(&sample_market(Some(1), None));
+        round_trip::<_, crate::generated::HousingEventCounts>(&HousingEventCounts {
+            parcl_id: Some(7),
+            date: "2024-01-01".into(),
+            sales: Some(120),
+            new_listings_for_sale: None,
+            new_rental_listings: Some(40),
+        });
+    }
+
+    fn search_results(markets: &[(&str, &str, &str, i64)]) -> PaginatedResponse<Market> {
+        let items = markets
+            .iter()
+            .enumerate()
+            .map(|(i, &(name, state, geoid, pop))| Market {
+                parcl_id: i as i64,
+                name: name.into(),
+                state_abbreviation: Some(state.into()),
+                geoid: Some(geoid.into()),
+                total_population: Some(pop),
+                ..sample_market(None, None)
+            })
+            .collect();
+        PaginatedResponse {
+            items,
+            total: markets.len() as u64,
+            limit: 10,
+            offset: 0,
+            links: PaginationLinks::default(),
+            account: None,
+            meta: ResponseMeta::default(),
+        }
+    }
+
+    #[test]
+    fn rank_and_dedupe_search_results() {
+        let resp = search_results(&[
+            ("Springfield", "MO", "1", 170_000),
+            ("Springfield", "IL", "2", 114_000),
+            ("Springfield", "MA", "3", 155_000),
+            ("Springfield", "MO", "1", 170_000),
+        ])
+        .rank_by(SortBy::TotalPopulation)
+        .dedupe_by_geoid();
+        let states: Vec<_> = resp
+            .items
+            .iter()
+            .map(|m| m.state_abbreviation.as_deref().unwrap())
+            .collect();
+        assert_eq!(states, ["MO", "MA", "IL"]);
+    }
+
+    #[test]
+    fn best_match_prefers_exact_name_then_population() {
+        let resp = search_results(&[
+            ("Springfield Gardens", "NY", "1", 900_000),
+            ("Springfield", "IL", "2", 114_000),
+            ("Springfield", "MO", "3", 170_000),
+            ("West Springfield", "MA", "4", 28_000),
+        ]);
+        assert_eq!(resp.best_match("springfield").unwrap().parcl_id, 2);
+        assert_eq!(resp.best_match("Springfield, IL").unwrap().parcl_id, 1);
+        assert_eq!(resp.best_match("Springfield MA").unwrap().parcl_id, 3);
+        assert!(resp.best_match("Shelbyville").is_none());
+        assert!(resp.best_match("Shelbyville, IL").is_none());
+        assert!(search_results(&[]).best_match("x").is_none());
+    }
+
     #[test]
     fn market_is_exchange_market() {
         assert!(sample_market(Some(1), None).is_exchange_market());
@@ -1031,6 +2588,16 @@ mod tests {
         assert_eq!(USRegion::EastNorthCentral.as_str(), "EAST_NORTH_CENTRAL");
     }
 
+    #[test]
+    fn us_state_codes_round_trip() {
+        assert_eq!(USState::DistrictOfColumbia.as_str(), "DC");
+        assert_eq!(USState::from_abbreviation(" ny"), Some(USState::NewYork));
+        assert_eq!(USState::from_abbreviation("PR"), None);
+        for state in USState::ALL {
+            assert_eq!(USState::from_abbreviation(state.as_str()), Some(state));
+        }
+    }
+
     #[test]
     fn sort_by_as_str() {
         assert_eq!(SortBy::TotalPopulation.as_str(), "TOTAL_POPULATION");
@@ -1065,6 +2632,135 @@ mod tests {
         assert!(links.last.is_none());
     }
 
+    #[test]
+    fn metrics_response_empty_reports_no_data() {
+        let resp: MetricsResponse<HousingEventCounts> = MetricsResponse::empty(42);
+        assert_eq!(resp.parcl_id, 42);
+        assert!(resp.is_empty());
+        assert_eq!(resp.total, 0);
+        assert!(resp.links.next.is_none());
+        assert_eq!(resp.availability(), Availability::NoData);
+    }
+
+    #[test]
+    fn metrics_response_availability_with_items() {
+        let json = r#"{
+            "parcl_id": 42,
+            "items": [{"date": "2024-01-01", "sales": 3}],
+            "total": 1,
+            "limit": 1,
+            "offset": 0,
+            "links": {}
+        }"#;
+        let resp: MetricsResponse<HousingEventCounts> = serde_json::from_str(json).unwrap();
+        assert!(!resp.is_empty());
+        assert_eq!(resp.availability(), Availability::Available);
+    }
+
+    #[test]
+    fn sorts_items_by_date() {
+        let mut resp: MetricsResponse<HousingEventCounts> =
+            serde_json::from_value(serde_json::json!({
+                "parcl_id": 1, "total": 3, "limit": 3, "offset": 0, "links": {},
+                "items": [{"date": "2024-02-01"}, {"date": "2024-03-01"}, {"date": "2024-01-01"}]
+            }))
+            .unwrap();
+        resp.sort_ascending();
+        let dates: Vec<&str> = resp.items.iter().map(Dated::date).collect();
+        assert_eq!(dates, ["2024-01-01", "2024-02-01", "2024-03-01"]);
+        resp.sort_descending();
+        assert_eq!(resp.items[0].date(), "2024-03-01");
+
+        let mut batch: BatchMetricsResponse<HousingEventCounts> =
+            serde_json::from_value(serde_json::json!({
+                "total": 3, "limit": 3, "offset": 0, "links": {},
+                "items": [
+                    {"parcl_id": 2, "date": "2024-01-01"},
+                    {"parcl_id": 1, "date": "2024-02-01"},
+                    {"parcl_id": 1, "date": "2024-01-01"}
+                ]
+            }))
+            .unwrap();
+        batch.sort_ascending();
+        let keys: Vec<(Option<i64>, &str)> = batch
+            .items
+            .iter()
+            .map(|i| (i.parcl_id, i.date.as_str()))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                (Some(1), "2024-01-01"),
+                (Some(1), "2024-02-01"),
+                (Some(2), "2024-01-01")
+            ]
+        );
+    }
+
+    #[test]
+    fn response_meta_absorb_sums_pages() {
+        let mut meta = ResponseMeta {
+            attempts_made: 1,
+            retries: 0,
+            total_backoff: Duration::ZERO,
+            bytes_received: 100,
+            cancelled: false,
+            deadline_exceeded: false,
+            raw: Vec::new(),
+            content_hash: None,
+        };
+        assert!(!meta.was_retried());
+        meta.absorb(&ResponseMeta {
+            attempts_made: 3,
+            retries: 2,
+            total_backoff: Duration::from_millis(3000),
+            bytes_received: 50,
+            cancelled: false,
+            deadline_exceeded: false,
+            raw: Vec::new(),
+            content_hash: None,
+        });
+        assert_eq!(meta.attempts_made, 4);
+        assert_eq!(meta.retries, 2);
+        assert_eq!(meta.total_backoff, Duration::from_millis(3000));
+        assert_eq!(meta.bytes_received, 150);
+        assert!(meta.was_retried());
+    }
+
+    #[test]
+    fn content_hash_ignores_account_and_key_order() {
+        let a = ContentHash::of(br#"{"items":[1],"total":1,"account":{"remaining_credits":90}}"#);
+        let b = ContentHash::of(br#"{"total":1,"account":{"remaining_credits":80},"items":[1]}"#);
+        assert_eq!(a, b);
+        assert_ne!(a, ContentHash::of(br#"{"items":[2],"total":1}"#));
+        assert_eq!(a.to_string().parse::<ContentHash>().unwrap(), a);
+
+        let mut meta = ResponseMeta {
+            content_hash: Some(a),
+            ..Default::default()
+        };
+        meta.absorb(&ResponseMeta {
+            content_hash: Some(b),
+            ..Default::default()
+        });
+        assert_eq!(meta.content_hash, Some(a.then(b)));
+        assert_ne!(a.then(b), a);
+    }
+
+    #[test]
+    fn response_meta_not_serialized() {
+        let resp: MetricsResponse<HousingEventCounts> = MetricsResponse::empty(1);
+        let json = serde_json::to_value(&resp).unwrap();
+        assert!(json.get("meta").is_none());
+    }
+
+    #[test]
+    fn batch_metrics_response_empty_reports_no_data() {
+        let resp: BatchMetricsResponse<AllCash> = BatchMetricsResponse::empty();
+        assert!(resp.is_empty());
+        assert_eq!(resp.availability(), Availability::NoData);
+    }
+
     #[test]
     fn market_deserialize() {
         let json = r#"{
@@ -1101,6 +2797,43 @@ mod tests {
         assert_eq!(counts.new_listings_for_sale, Some(2000));
     }
 
+    #[test]
+    fn price_stats_matrix_access() {
+        let prices: HousingEventPrices = serde_json::from_value(serde_json::json!({
+            "date": "2024-01-01",
+            "price": {
+                "median": {"sales": 410000.0, "new_listings_for_sale": 450000.0},
+                "percentile_80th": {"new_rental_listings": 2900.0}
+            }
+        }))
+        .unwrap();
+        let price = prices.price.unwrap();
+        assert_eq!(
+            price.get(PriceStat::Median, PriceEvent::Sales),
+            Some(410000.0)
+        );
+        assert_eq!(
+            price.get(PriceStat::Median, PriceEvent::NewRentalListings),
+            None
+        );
+        assert_eq!(
+            price.get(PriceStat::StandardDeviation, PriceEvent::Sales),
+            None
+        );
+        let cells: Vec<String> = price
+            .iter()
+            .map(|(stat, event, value)| format!("{stat}.{event}={value}"))
+            .collect();
+        assert_eq!(
+            cells,
+            [
+                "median.sales=410000",
+                "median.new_listings_for_sale=450000",
+                "percentile_80th.new_rental_listings=2900"
+            ]
+        );
+    }
+
     #[test]
     fn housing_stock_deserialize() {
         let json = r#"{
@@ -1128,7 +2861,59 @@ mod tests {
         let entry: PriceFeedEntry = serde_json::from_str(json).unwrap();
         assert_eq!(entry.date, "2024-01-01");
         assert!((entry.price - 750000.50).abs() < f64::EPSILON);
-        assert_eq!(entry.price_feed_type, Some("daily".into()));
+        assert_eq!(entry.price_feed_type, Some(PriceFeedType::Daily));
+    }
+
+    #[test]
+    fn price_feed_type_round_trips_unknown_values() {
+        let parse =
+            |s: &str| serde_json::from_value::<PriceFeedType>(serde_json::json!(s)).unwrap();
+        assert_eq!(parse("SMOOTHED"), PriceFeedType::Smoothed);
+        let other = parse("weekly");
+        assert_eq!(other, PriceFeedType::Other("weekly".into()));
+        assert_eq!(serde_json::to_value(&other).unwrap(), "weekly");
+        assert_eq!(PriceFeedType::Daily.to_string(), "daily");
+    }
+
+    #[test]
+    fn rental_price_from_event_prices() {
+        let prices: HousingEventPrices = serde_json::from_value(serde_json::json!({
+            "parcl_id": 7,
+            "date": "2024-01-01",
+            "price": {
+                "median": {"sales": 400000.0, "new_listings_for_sale": 420000.0, "new_rental_listings": 2100.0},
+                "standard_deviation": null,
+                "percentile_20th": {"sales": 300000.0, "new_listings_for_sale": null, "new_rental_listings": 1600.0},
+                "percentile_80th": {"sales": 500000.0, "new_listings_for_sale": null, "new_rental_listings": null}
+            },
+            "price_per_square_foot": {
+                "median": {"sales": 250.0, "new_listings_for_sale": 260.0, "new_rental_listings": 1.4},
+                "standard_deviation": null,
+                "percentile_20th": null,
+                "percentile_80th": null
+            }
+        }))
+        .unwrap();
+        let rent = RentalPrice::from(prices);
+        assert_eq!(rent.parcl_id, Some(7));
+        assert_eq!(rent.median, Some(2100.0));
+        assert_eq!(rent.percentile_20th, Some(1600.0));
+        assert_eq!(rent.percentile_80th, None);
+        assert_eq!(rent.standard_deviation, None);
+        assert_eq!(rent.median_per_square_foot, Some(1.4));
+    }
+
+    #[test]
+    fn metrics_response_map_items_keeps_metadata() {
+        let mut resp = MetricsResponse::<i64>::empty(3);
+        resp.items = vec![1, 2];
+        resp.total = 2;
+        resp.meta.retries = 1;
+        let mapped = resp.map_items(|n| n.to_string());
+        assert_eq!(mapped.items, vec!["1", "2"]);
+        assert_eq!(mapped.parcl_id, 3);
+        assert_eq!(mapped.total, 2);
+        assert_eq!(mapped.meta.retries, 1);
     }
 
     #[test]
@@ -1344,11 +3129,14 @@ mod tests {
         assert_eq!(changes.date, "2024-01-01");
         assert_eq!(changes.count_price_change, Some(150));
         assert_eq!(changes.count_price_drop, Some(120));
-        assert!((changes.median_days_bt_price_change.unwrap() - 21.5).abs() < f64::EPSILON);
+        assert!((changes.median_days_bt_change().unwrap() - 21.5).abs() < f64::EPSILON);
         assert!((changes.median_price_change.unwrap() - (-25000.0)).abs() < f64::EPSILON);
         assert!((changes.median_pct_price_change.unwrap() - (-2.5)).abs() < f64::EPSILON);
-        assert!((changes.pct_price_change.unwrap() - 12.5).abs() < f64::EPSILON);
-        assert!((changes.pct_price_drop.unwrap() - 10.2).abs() < f64::EPSILON);
+        assert!((changes.pct_inventory_price_change().unwrap() - 12.5).abs() < f64::EPSILON);
+        assert!((changes.pct_inventory_price_drop().unwrap() - 10.2).abs() < f64::EPSILON);
+        #[allow(deprecated)]
+        let old_names = (changes.median_days_bt_price_change, changes.pct_price_drop);
+        assert_eq!(old_names, (Some(21.5), Some(10.2)));
     }
 
     #[test]
@@ -1367,8 +3155,8 @@ mod tests {
         assert_eq!(changes.date, "2024-01-01");
         assert_eq!(changes.count_price_change, Some(100));
         assert!(changes.count_price_drop.is_none());
-        assert!(changes.median_days_bt_price_change.is_none());
-        assert!((changes.pct_price_change.unwrap() - 8.0).abs() < f64::EPSILON);
+        assert!(changes.median_days_bt_change().is_none());
+        assert!((changes.pct_inventory_price_change().unwrap() - 8.0).abs() < f64::EPSILON);
     }
 
     #[test]
@@ -1880,5 +3668,19 @@ mod tests {
         assert_eq!(json["property_filters"]["include_property_details"], true);
         assert_eq!(json["event_filters"]["include_events"], true);
         assert!(json.get("owner_filters").is_none());
+        assert!(json.get("sort_by").is_none());
+    }
+
+    #[test]
+    fn property_v2_search_request_serializes_sort() {
+        let req = PropertyV2SearchRequest {
+            sort_by: Some(PropertySortBy::LastSalePrice),
+            sort_order: Some(SortOrder::Asc),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["sort_by"], "LAST_SALE_PRICE");
+        assert_eq!(json["sort_order"], "ASC");
+        assert_eq!(PropertySortBy::YearBuilt.to_string(), "YEAR_BUILT");
     }
 }
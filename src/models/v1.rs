@@ -0,0 +1,31 @@
+//! The v1 model namespace: every response and request type as of the first
+//! API version the SDK targets.
+//!
+//! The list below is frozen: types added later are not re-exported here.
+//! When a top-level model changes incompatibly at a major release (a field
+//! renamed to match the API, a type split in two), `v1` keeps the old shape
+//! as its own `#[deprecated]` definition with `From` conversions both ways,
+//! so code importing from here keeps compiling, with warnings naming what to
+//! migrate, until the following major release.
+//!
+//! ```
+//! use parcllabs::models::v1::{ForSaleInventoryPriceChanges, HousingEventCounts};
+//! # let _: Option<(ForSaleInventoryPriceChanges, HousingEventCounts)> = None;
+//! ```
+
+pub use super::{
+    AccountInfo, AccountUsage, AddressSearchRequest, AllCash, BatchMetricsResponse,
+    EntityOwnerName, EventPrices, EventType, ForSaleInventory, ForSaleInventoryPriceChanges,
+    GeoCoordinates, GrossYield, HousingEventCounts, HousingEventPrices,
+    HousingEventPropertyAttributes, HousingStock, InvestorHousingEventCounts,
+    InvestorHousingStockOwnership, InvestorNewListingsRollingCounts, InvestorPurchaseToSaleRatio,
+    LocationType, Market, MetricsResponse, NewListingsRollingCounts, OwnerFilters,
+    PaginatedResponse, PaginationLinks, PortfolioHousingEventCounts,
+    PortfolioNewListingsRollingCounts, PortfolioRentalListingsRollingCounts, PortfolioSize,
+    PortfolioSizeBreakdown, PortfolioSizePctBreakdown, PortfolioStockOwnership, PriceFeedEntry,
+    PriceStats, Property, PropertyEvent, PropertyEventHistoryResponse, PropertyFilters,
+    PropertyMetadata, PropertySearchResponse, PropertyType, PropertyV2, PropertyV2Event,
+    PropertyV2Metadata, PropertyV2SearchRequest, PropertyV2SearchResponse, PropertyWithEvents,
+    RentalNewListingsRollingCounts, RentalUnitsConcentration, RollingCounts, RollingPercentages,
+    SortBy, SortOrder, USRegion, V2EventFilters,
+};
@@ -0,0 +1,230 @@
+//! Offset/link pagination for custom endpoints fetched with
+//! [`ParclClient::get_raw`](crate::ParclClient::get_raw).
+
+use crate::models::{BatchMetricsResponse, MetricsResponse, PaginatedResponse, PaginationLinks};
+
+/// A page of results that a [`Paginator`] can advance past.
+pub trait Page {
+    /// Number of items on this page.
+    fn item_count(&self) -> usize;
+    /// Total items available across all pages, if reported.
+    fn total(&self) -> Option<u64>;
+    fn links(&self) -> &PaginationLinks;
+}
+
+macro_rules! impl_page {
+    ($($ty:ident),*) => {
+        $(
+            impl<T> Page for $ty<T> {
+                fn item_count(&self) -> usize {
+                    self.items.len()
+                }
+                fn total(&self) -> Option<u64> {
+                    Some(self.total)
+                }
+                fn links(&self) -> &PaginationLinks {
+                    &self.links
+                }
+            }
+
+            impl<T> $ty<T> {
+                /// Offset of the page `links.next` points at.
+                ///
+                /// After a cancelled or deadline-bounded fetch, pass this to
+                /// the params' `offset` to pick up where it stopped.
+                pub fn next_offset(&self) -> Option<u32> {
+                    next_offset(&self.links)
+                }
+            }
+        )*
+    };
+}
+
+impl_page!(PaginatedResponse, MetricsResponse, BatchMetricsResponse);
+
+fn next_offset(links: &PaginationLinks) -> Option<u32> {
+    let (_, query) = links.next.as_deref()?.split_once('?')?;
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("offset="))?
+        .parse()
+        .ok()
+}
+
+/// Tracks page size and offset progression across a paginated endpoint.
+///
+/// Follows the API's `links.next` when present and falls back to
+/// `limit`/`offset` arithmetic otherwise.
+///
+/// # Example
+///
+/// ```no_run
+/// use parcllabs::{HousingEventCounts, MetricsResponse, Paginator, ParclClient};
+///
+/// # async fn example() -> parcllabs::Result<()> {
+/// let client = ParclClient::new()?;
+/// let mut paginator = Paginator::new(100);
+/// let mut items = Vec::new();
+/// while let Some(url) = paginator.next_request("/v1/market_metrics/2900187/housing_event_counts") {
+///     let page: MetricsResponse<HousingEventCounts> = client.get_raw(&url).await?;
+///     paginator.advance(&page);
+///     items.extend(page.items);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paginator {
+    page_size: u32,
+    offset: u64,
+    next_link: Option<String>,
+    max_items: Option<u64>,
+    started: bool,
+    done: bool,
+}
+
+impl Paginator {
+    pub fn new(page_size: u32) -> Self {
+        Self {
+            page_size: page_size.max(1),
+            offset: 0,
+            next_link: None,
+            max_items: None,
+            started: false,
+            done: false,
+        }
+    }
+
+    /// Starts from `offset` instead of the first item.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Stops after `max_items` items have been requested.
+    pub fn max_items(mut self, max_items: u64) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    pub fn page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    /// Offset of the next page to request.
+    pub fn current_offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Returns the `limit`/`offset` query parameters for the next page.
+    pub fn query(&self) -> String {
+        let limit = match self.max_items {
+            Some(max) => (max.saturating_sub(self.offset)).min(self.page_size as u64),
+            None => self.page_size as u64,
+        };
+        format!("limit={}&offset={}", limit, self.offset)
+    }
+
+    /// Returns the path or URL of the next page, or `None` once exhausted.
+    ///
+    /// The first request is `path` with this paginator's query appended;
+    /// later requests use the API's `next` link when it provided one.
+    pub fn next_request(&self, path: &str) -> Option<String> {
+        if self.done {
+            return None;
+        }
+        if self.started {
+            if let Some(link) = &self.next_link {
+                return Some(link.clone());
+            }
+        }
+        let separator = if path.contains('?') { '&' } else { '?' };
+        Some(format!("{path}{separator}{}", self.query()))
+    }
+
+    /// Records a fetched page and decides whether another one follows.
+    pub fn advance(&mut self, page: &impl Page) {
+        self.started = true;
+        let count = page.item_count() as u64;
+        self.offset += count;
+        self.next_link = page.links().next.clone();
+
+        let reached_total = page.total().is_some_and(|total| self.offset >= total);
+        let reached_max = self.max_items.is_some_and(|max| self.offset >= max);
+        let short_page = count < self.page_size as u64 && self.next_link.is_none();
+        self.done = count == 0 || reached_total || reached_max || short_page;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HousingEventCounts;
+    use crate::test_util::fixtures::event_counts;
+
+    fn page(
+        count: usize,
+        total: u64,
+        next: Option<&str>,
+    ) -> BatchMetricsResponse<HousingEventCounts> {
+        let mut resp = BatchMetricsResponse::empty();
+        resp.items = (0..count)
+            .map(|_| event_counts(1, "2024-01-01", None))
+            .collect();
+        resp.total = total;
+        resp.links.next = next.map(String::from);
+        resp
+    }
+
+    #[test]
+    fn paginator_first_request_appends_query() {
+        let paginator = Paginator::new(50);
+        assert_eq!(
+            paginator.next_request("/v1/x").unwrap(),
+            "/v1/x?limit=50&offset=0"
+        );
+        assert_eq!(
+            paginator.next_request("/v1/x?property_type=CONDO").unwrap(),
+            "/v1/x?property_type=CONDO&limit=50&offset=0"
+        );
+    }
+
+    #[test]
+    fn paginator_follows_next_links_until_total() {
+        let mut paginator = Paginator::new(2);
+        paginator.advance(&page(2, 5, Some("https://api/x?offset=2")));
+        assert_eq!(
+            paginator.next_request("/v1/x").unwrap(),
+            "https://api/x?offset=2"
+        );
+        paginator.advance(&page(2, 5, None));
+        assert!(!paginator.is_done());
+        assert_eq!(
+            paginator.next_request("/v1/x").unwrap(),
+            "/v1/x?limit=2&offset=4"
+        );
+        paginator.advance(&page(1, 5, None));
+        assert!(paginator.is_done());
+        assert!(paginator.next_request("/v1/x").is_none());
+    }
+
+    #[test]
+    fn paginator_respects_max_items() {
+        let mut paginator = Paginator::new(10).max_items(15);
+        paginator.advance(&page(10, 100, None));
+        assert_eq!(paginator.query(), "limit=5&offset=10");
+        paginator.advance(&page(5, 100, None));
+        assert!(paginator.is_done());
+    }
+
+    #[test]
+    fn paginator_stops_on_empty_page() {
+        let mut paginator = Paginator::new(10).offset(30);
+        paginator.advance(&page(0, 0, Some("ignored")));
+        assert!(paginator.is_done());
+    }
+}
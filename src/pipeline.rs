@@ -0,0 +1,220 @@
+//! Helpers for the common "find a market, then fetch its metrics" workflow.
+//!
+//! Market lookups go through the client's [`MarketCache`](crate::MarketCache),
+//! independent fetches run concurrently, and any failure is reported as
+//! [`ParclError::StepFailed`] naming the step and market.
+
+use crate::error::{ParclError, Result};
+use crate::models::{
+    HousingEventCounts, HousingEventPrices, HousingStock, Market, MetricsResponse, SortBy,
+    SortOrder,
+};
+use crate::runtime::try_join;
+use crate::{MetricsParams, ParclClient, SearchParams};
+use std::future::Future;
+
+/// Entry point for dependent queries, created by [`ParclClient::pipeline`].
+#[derive(Debug, Clone, Copy)]
+pub struct Pipeline<'a> {
+    client: &'a ParclClient,
+}
+
+impl<'a> Pipeline<'a> {
+    pub(crate) fn new(client: &'a ParclClient) -> Self {
+        Self { client }
+    }
+
+    /// Finds a market by name, optionally within a state.
+    ///
+    /// Checks the market cache first and only searches on a miss. Among search
+    /// results, an exact name match wins over the most populous partial match.
+    pub async fn search(&self, query: &str, state: Option<&str>) -> Result<MarketStep<'a>> {
+        if let Some(market) = self.client.market_cache().find_by_name(query, state) {
+            return Ok(self.step_for(market));
+        }
+
+        let mut params = SearchParams::new()
+            .query(query)
+            .sort_by(SortBy::TotalPopulation)
+            .sort_order(SortOrder::Desc)
+            .limit(10);
+        if let Some(state) = state {
+            params = params.state(state);
+        }
+        let resp = self
+            .client
+            .search()
+            .markets(params)
+            .await
+            .map_err(|e| e.in_step("search", None))?;
+
+        let market = resp
+            .items
+            .iter()
+            .find(|m| m.name.eq_ignore_ascii_case(query.trim()))
+            .or_else(|| resp.items.first())
+            .cloned()
+            .ok_or_else(|| {
+                ParclError::InvalidParameter(format!("no market matches `{query}`"))
+                    .in_step("search", None)
+            })?;
+        Ok(self.step_for(market))
+    }
+
+    /// Starts from a known `parcl_id`, resolving its metadata through the cache.
+    pub async fn market(&self, parcl_id: i64) -> Result<MarketStep<'a>> {
+        let market = self
+            .client
+            .resolve_market(parcl_id)
+            .await
+            .map_err(|e| e.in_step("resolve", Some(parcl_id)))?
+            .ok_or_else(|| {
+                ParclError::InvalidParameter(format!("unknown parcl_id {parcl_id}"))
+                    .in_step("resolve", Some(parcl_id))
+            })?;
+        Ok(self.step_for(market))
+    }
+
+    fn step_for(&self, market: Market) -> MarketStep<'a> {
+        MarketStep {
+            client: self.client,
+            market,
+        }
+    }
+}
+
+/// A resolved market, ready for follow-up fetches.
+#[derive(Debug, Clone)]
+pub struct MarketStep<'a> {
+    client: &'a ParclClient,
+    market: Market,
+}
+
+impl<'a> MarketStep<'a> {
+    pub fn market(&self) -> &Market {
+        &self.market
+    }
+
+    pub fn parcl_id(&self) -> i64 {
+        self.market.parcl_id
+    }
+
+    pub fn client(&self) -> &'a ParclClient {
+        self.client
+    }
+
+    /// Runs `fut`, attributing any error to step `name` for this market.
+    ///
+    /// Combine several with `tokio::try_join!` to fetch concurrently.
+    pub async fn step<T>(
+        &self,
+        name: &'static str,
+        fut: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        fut.await
+            .map_err(|e| e.in_step(name, Some(self.parcl_id())))
+    }
+
+    /// Fetches event counts, event prices and housing stock concurrently.
+    pub async fn snapshot(&self, params: Option<MetricsParams>) -> Result<MarketSnapshot> {
+        let id = self.parcl_id();
+        let metrics = self.client.market_metrics();
+        let (event_counts, (event_prices, housing_stock)) = try_join(
+            self.step(
+                "housing_event_counts",
+                metrics.housing_event_counts(id, params.clone()),
+            ),
+            try_join(
+                self.step(
+                    "housing_event_prices",
+                    metrics.housing_event_prices(id, params.clone()),
+                ),
+                self.step("housing_stock", metrics.housing_stock(id, params)),
+            ),
+        )
+        .await?;
+        Ok(MarketSnapshot {
+            market: self.market.clone(),
+            event_counts,
+            event_prices,
+            housing_stock,
+        })
+    }
+}
+
+/// Core market metrics for one market, fetched together.
+#[derive(Debug, Clone)]
+pub struct MarketSnapshot {
+    pub market: Market,
+    pub event_counts: MetricsResponse<HousingEventCounts>,
+    pub event_prices: MetricsResponse<HousingEventPrices>,
+    pub housing_stock: MetricsResponse<HousingStock>,
+}
+
+impl ParclClient {
+    /// Starts a dependent query, e.g. search a market then fetch its metrics.
+    pub fn pipeline(&self) -> Pipeline<'_> {
+        Pipeline::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(parcl_id: i64, name: &str, state: &str, pop: i64) -> Market {
+        serde_json::from_value(serde_json::json!({
+            "parcl_id": parcl_id,
+            "name": name,
+            "state_abbreviation": state,
+            "location_type": "CITY",
+            "total_population": pop
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pipeline_search_uses_cache() {
+        // Unroutable base URL: only cache hits can succeed.
+        let client = ParclClient::with_config("test", "http://127.0.0.1:9");
+        client.market_cache().extend(vec![
+            market(1, "Portland", "ME", 68_000),
+            market(2, "Portland", "OR", 650_000),
+        ]);
+        let step = client.pipeline().search("portland", None).await.unwrap();
+        assert_eq!(step.parcl_id(), 2);
+        let step = client
+            .pipeline()
+            .search("Portland", Some("ME"))
+            .await
+            .unwrap();
+        assert_eq!(step.parcl_id(), 1);
+    }
+
+    #[tokio::test]
+    async fn pipeline_step_errors_name_step_and_market() {
+        let client = ParclClient::with_config("test", "http://127.0.0.1:9");
+        client
+            .market_cache()
+            .insert(market(5, "Austin", "TX", 960_000));
+        let step = client.pipeline().market(5).await.unwrap();
+        let err = step
+            .step("custom", async {
+                Err::<(), _>(ParclError::InvalidParameter("bad".into()))
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ParclError::StepFailed {
+                step: "custom",
+                parcl_id: Some(5),
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "custom failed for parcl_id 5: Invalid parameter: bad"
+        );
+    }
+}
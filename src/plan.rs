@@ -0,0 +1,364 @@
+//! Plan awareness, so endpoints outside the key's plan fail fast.
+//!
+//! Parcl Labs gates endpoints by subscription plan and answers requests
+//! outside the plan with a 403, which otherwise reads like an SDK bug. A 403
+//! whose body says the plan does not cover the request becomes
+//! [`ParclError::NotInPlan`], carrying the server's message, and the client
+//! refuses the same request path locally until the denial expires (see
+//! [`ParclClient::with_plan_denial_ttl`]). Other 403s are reported as
+//! ordinary API errors and not remembered.
+//!
+//! Which endpoints each plan includes is not published, so the SDK assumes
+//! nothing. Callers who know their plan can declare it with a [`PlanTier`]
+//! listing the endpoints it includes; everything else then fails without
+//! sending a request.
+//!
+//! Endpoints are named by their path without the `parcl_id`, as in
+//! [`Metric::endpoint`], e.g. `/v1/investor_metrics/housing_stock_ownership`.
+
+use crate::error::{ParclError, Result};
+use crate::metric::Metric;
+use crate::protocol::HttpResponse;
+use crate::ParclClient;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Endpoints with no `Metric` variant.
+const OTHER_ENDPOINTS: [&str; 5] = [
+    "/v1/search/markets",
+    "/v1/property/search",
+    "/v1/property/search_address",
+    "/v1/property/event_history",
+    "/v2/property_search",
+];
+
+/// How long a plan denial is remembered by default.
+const DEFAULT_DENIAL_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The endpoints an API key's plan includes, as declared by the caller.
+///
+/// ```
+/// use parcllabs::PlanTier;
+///
+/// let plan = PlanTier::new()
+///     .include("/v1/search")
+///     .include("/v1/market_metrics");
+/// assert!(plan.includes("/v1/market_metrics/housing_stock"));
+/// assert!(!plan.includes("/v1/investor_metrics/housing_stock_ownership"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanTier {
+    prefixes: BTreeSet<String>,
+}
+
+impl PlanTier {
+    /// A plan that includes nothing until endpoints are added.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Includes `prefix` and every endpoint below it: a family such as
+    /// `/v1/market_metrics` or a single endpoint path.
+    pub fn include(mut self, prefix: impl Into<String>) -> Self {
+        let prefix: String = prefix.into();
+        self.prefixes
+            .insert(prefix.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Whether this plan includes `endpoint`.
+    pub fn includes(&self, endpoint: &str) -> bool {
+        self.prefixes.iter().any(|prefix| {
+            endpoint
+                .strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+/// A request path the API refused as outside the plan.
+#[derive(Debug, Clone)]
+struct Denial {
+    at: Instant,
+    message: Option<String>,
+}
+
+/// Declared plan plus recently denied request paths, shared by clones.
+#[derive(Debug, Clone)]
+pub(crate) struct PlanState {
+    pub tier: Option<PlanTier>,
+    pub denial_ttl: Duration,
+    denied: Arc<Mutex<HashMap<String, Denial>>>,
+}
+
+impl Default for PlanState {
+    fn default() -> Self {
+        Self {
+            tier: None,
+            denial_ttl: DEFAULT_DENIAL_TTL,
+            denied: Arc::default(),
+        }
+    }
+}
+
+impl PlanState {
+    /// Fails with `NotInPlan` if `path` is outside the declared plan or was
+    /// denied less than the TTL before `now`.
+    pub fn check(&self, path: &str, now: Instant) -> Result<()> {
+        let endpoint = endpoint_of(path);
+        if self
+            .tier
+            .as_ref()
+            .is_some_and(|tier| !tier.includes(&endpoint))
+        {
+            return Err(ParclError::NotInPlan {
+                endpoint,
+                message: None,
+            });
+        }
+        let mut denied = self.lock();
+        match denied.get(path) {
+            Some(denial) if now.saturating_duration_since(denial.at) < self.denial_ttl => {
+                Err(ParclError::NotInPlan {
+                    endpoint,
+                    message: denial.message.clone(),
+                })
+            }
+            Some(_) => {
+                denied.remove(path);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Remembers that the API refused `path`, returning the error to report.
+    pub fn deny(&self, path: &str, message: Option<String>, now: Instant) -> ParclError {
+        let denial = Denial {
+            at: now,
+            message: message.clone(),
+        };
+        self.lock().insert(path.to_string(), denial);
+        ParclError::NotInPlan {
+            endpoint: endpoint_of(path),
+            message,
+        }
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Denial>> {
+        self.denied.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The server's explanation if `response` is a 403 for a request outside
+/// the plan: its body mentions the plan, subscription or an upgrade.
+///
+/// Returns the body's `detail` or `message` field when it has one, else
+/// the body text.
+pub(crate) fn plan_denial(response: &HttpResponse) -> Option<String> {
+    if response.status() != http::StatusCode::FORBIDDEN {
+        return None;
+    }
+    let body = String::from_utf8_lossy(response.body());
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| {
+            ["detail", "message"]
+                .iter()
+                .find_map(|key| json.get(key)?.as_str().map(str::to_string))
+        })
+        .unwrap_or_else(|| body.trim().to_string());
+    let lower = message.to_ascii_lowercase();
+    ["plan", "subscription", "upgrade"]
+        .iter()
+        .any(|word| lower.contains(word))
+        .then_some(message)
+}
+
+/// The path of a request URL, query dropped.
+pub(crate) fn path_of(url: &str) -> String {
+    url.parse::<http::Uri>()
+        .map_or_else(|_| url.to_string(), |uri| uri.path().to_string())
+}
+
+/// The endpoint of a request path: numeric segments such as the
+/// `parcl_id` removed.
+pub(crate) fn endpoint_of(path: &str) -> String {
+    path.split('/')
+        .filter(|segment| segment.parse::<i64>().is_err())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl ParclClient {
+    /// Declares the endpoints the key's plan includes, so others fail with
+    /// [`ParclError::NotInPlan`] before any request is sent.
+    pub fn with_plan_tier(mut self, tier: PlanTier) -> Self {
+        self.plan.tier = Some(tier);
+        self
+    }
+
+    /// The plan set with [`with_plan_tier`](Self::with_plan_tier), if any.
+    pub fn plan_tier(&self) -> Option<&PlanTier> {
+        self.plan.tier.as_ref()
+    }
+
+    /// How long a request path the API refused as outside the plan is
+    /// refused locally. Defaults to one hour; `Duration::ZERO` always asks
+    /// the API.
+    pub fn with_plan_denial_ttl(mut self, ttl: Duration) -> Self {
+        self.plan.denial_ttl = ttl;
+        self
+    }
+
+    /// Forgets every plan denial, e.g. after upgrading the plan.
+    pub fn clear_plan_denials(&self) {
+        self.plan.clear();
+    }
+
+    /// Endpoints this key can call, as far as the client knows: those in
+    /// the declared plan (all, without one), minus any the API refused as
+    /// outside the plan within the denial TTL.
+    ///
+    /// Denials are matched by request path, as the client applies them: a
+    /// denial for one market's path leaves the endpoint listed, since other
+    /// markets are still asked.
+    pub fn available_endpoints(&self) -> Vec<&'static str> {
+        let now = self.timer.now();
+        let mut endpoints: Vec<&'static str> = Metric::ALL
+            .iter()
+            .map(Metric::endpoint)
+            .chain(OTHER_ENDPOINTS)
+            .filter(|endpoint| self.plan.check(endpoint, now).is_ok())
+            .collect();
+        endpoints.sort_unstable();
+        endpoints.dedup();
+        endpoints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::MockClock;
+    use crate::test_util::CannedTransport;
+
+    /// Refuses every request with a 403 and `detail`.
+    fn forbidden(detail: &str) -> CannedTransport {
+        CannedTransport::json(403, serde_json::json!({ "detail": detail }))
+    }
+
+    const PLAN_DETAIL: &str = "Not available on your plan";
+
+    #[test]
+    fn declared_plans_match_whole_segments() {
+        let plan = PlanTier::new()
+            .include("/v1/market_metrics/")
+            .include("/v1/search");
+        assert!(plan.includes("/v1/market_metrics/housing_stock"));
+        assert!(plan.includes("/v1/search/markets"));
+        assert!(!plan.includes("/v1/searchable"));
+        assert!(!plan.includes("/v1/rental_market_metrics/gross_yield"));
+        assert_eq!(
+            endpoint_of(&path_of(
+                "https://api.parcllabs.com/v1/market_metrics/2900187/housing_stock?limit=1"
+            )),
+            "/v1/market_metrics/housing_stock"
+        );
+    }
+
+    #[tokio::test]
+    async fn declared_plan_fails_fast() {
+        let sent = forbidden(PLAN_DETAIL);
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(sent.clone())
+            .with_plan_tier(PlanTier::new().include("/v1/market_metrics"));
+        let err = client
+            .investor_metrics()
+            .housing_stock_ownership(7, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ParclError::NotInPlan { ref endpoint, message: None }
+                if endpoint == "/v1/investor_metrics/housing_stock_ownership"
+        ));
+        assert_eq!(sent.request_count(), 0);
+
+        let available = client.available_endpoints();
+        assert!(available.contains(&"/v1/market_metrics/housing_stock"));
+        assert!(!available.iter().any(|e| e.starts_with("/v1/search")));
+    }
+
+    #[tokio::test]
+    async fn plan_denials_are_scoped_to_the_path_and_expire() {
+        let sent = forbidden(PLAN_DETAIL);
+        let clock = MockClock::new();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(sent.clone())
+            .with_timer(clock.clone())
+            .with_plan_denial_ttl(Duration::from_secs(60));
+        for _ in 0..2 {
+            let err = client
+                .rental_metrics()
+                .gross_yield(7, None)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                ParclError::NotInPlan { message: Some(ref m), .. } if m == "Not available on your plan"
+            ));
+        }
+        assert_eq!(sent.request_count(), 1);
+        assert!(client
+            .available_endpoints()
+            .contains(&"/v1/rental_market_metrics/gross_yield"));
+
+        // Another market is still asked.
+        let _ = client.rental_metrics().gross_yield(8, None).await;
+        assert_eq!(sent.request_count(), 2);
+
+        clock.advance(Duration::from_secs(61));
+        let _ = client.rental_metrics().gross_yield(7, None).await;
+        assert_eq!(sent.request_count(), 3);
+
+        client.clear_plan_denials();
+        let _ = client.rental_metrics().gross_yield(7, None).await;
+        assert_eq!(sent.request_count(), 4);
+    }
+
+    #[test]
+    fn denied_paths_hide_only_themselves() {
+        let client = ParclClient::with_config("key", "https://example.com");
+        let now = client.timer.now();
+        client.plan.deny("/v1/property/search", None, now);
+        let available = client.available_endpoints();
+        assert!(!available.contains(&"/v1/property/search"));
+        assert!(available.contains(&"/v1/property/search_address"));
+        assert!(available.contains(&"/v1/property/event_history"));
+    }
+
+    #[tokio::test]
+    async fn other_403s_are_api_errors() {
+        let sent = forbidden("Invalid API key");
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(sent.clone());
+        for _ in 0..2 {
+            let err = client
+                .rental_metrics()
+                .gross_yield(7, None)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ParclError::ApiError { status: 403, .. }));
+        }
+        assert_eq!(sent.request_count(), 2);
+        assert!(client
+            .available_endpoints()
+            .contains(&"/v1/rental_market_metrics/gross_yield"));
+    }
+}
@@ -0,0 +1,273 @@
+//! Background refresh of hot market metrics.
+//!
+//! A [`PrefetchService`] keeps the latest response for a fixed set of
+//! markets and metric endpoints in memory and refreshes them on a schedule,
+//! so request handlers read from memory instead of waiting on the API.
+//!
+//! ```no_run
+//! use parcllabs::{HousingStock, MetricsResponse, ParclClient, PrefetchService};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> parcllabs::Result<()> {
+//! let client = Arc::new(ParclClient::new()?);
+//! let prefetch = Arc::new(
+//!     PrefetchService::new(client, Duration::from_secs(15 * 60))
+//!         .watch([2900187, 2900078], ["/v1/market_metrics/housing_stock"]),
+//! );
+//! tokio::spawn({
+//!     let prefetch = Arc::clone(&prefetch);
+//!     async move { prefetch.run().await }
+//! });
+//!
+//! // In a handler:
+//! let stock: MetricsResponse<HousingStock> = prefetch
+//!     .get_cached_or_fetch(2900187, "/v1/market_metrics/housing_stock")
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::endpoints::common;
+use crate::error::{ParclError, Result};
+use crate::models::MetricsResponse;
+use crate::{MetricsParams, ParclClient};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+type Key = (i64, &'static str);
+
+#[derive(Debug, Clone)]
+struct Entry {
+    response: MetricsResponse<serde_json::Value>,
+    fetched_at: Instant,
+}
+
+/// Keeps metric responses for watched markets fresh in memory.
+///
+/// Metrics are named by endpoint path, e.g.
+/// `"/v1/market_metrics/housing_stock"`, and fetched with
+/// `GET {service}/{parcl_id}/{metric}`. Responses are stored untyped and
+/// decoded into the caller's item type on read.
+#[derive(Debug)]
+pub struct PrefetchService {
+    client: Arc<ParclClient>,
+    interval: Duration,
+    params: MetricsParams,
+    watched: Vec<Key>,
+    entries: RwLock<HashMap<Key, Entry>>,
+}
+
+impl PrefetchService {
+    /// Creates a service that refreshes every `interval` once [`run`](Self::run)
+    /// is started.
+    pub fn new(client: Arc<ParclClient>, interval: Duration) -> Self {
+        Self {
+            client,
+            interval,
+            params: MetricsParams::default(),
+            watched: Vec::new(),
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Adds every combination of `parcl_ids` and metric `endpoints` to the
+    /// refresh set.
+    pub fn watch(
+        mut self,
+        parcl_ids: impl IntoIterator<Item = i64>,
+        endpoints: impl IntoIterator<Item = &'static str> + Clone,
+    ) -> Self {
+        for parcl_id in parcl_ids {
+            for endpoint in endpoints.clone() {
+                if !self.watched.contains(&(parcl_id, endpoint)) {
+                    self.watched.push((parcl_id, endpoint));
+                }
+            }
+        }
+        self
+    }
+
+    /// Query parameters for every fetch, e.g. a `limit` or `start_date`.
+    pub fn params(mut self, params: MetricsParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Refreshes the watched metrics every interval, forever.
+    ///
+    /// Spawn it on the runtime; it sleeps through the client's
+    /// [`Timer`](crate::Timer). Failed refreshes keep the previous response.
+    pub async fn run(&self) {
+        loop {
+            self.refresh_all().await;
+            self.client.timer.sleep(self.interval).await;
+        }
+    }
+
+    /// Fetches every watched metric once, returning how many succeeded.
+    pub async fn refresh_all(&self) -> usize {
+        let mut refreshed = 0;
+        for &(parcl_id, endpoint) in &self.watched {
+            if self.refresh(parcl_id, endpoint).await.is_ok() {
+                refreshed += 1;
+            }
+        }
+        refreshed
+    }
+
+    /// Fetches one metric and stores the response.
+    pub async fn refresh(&self, parcl_id: i64, endpoint: &'static str) -> Result<()> {
+        let (service, metric) = endpoint
+            .rsplit_once('/')
+            .filter(|(service, _)| !service.is_empty())
+            .ok_or_else(|| {
+                ParclError::Validation(format!(
+                    "expected a metric endpoint like /v1/market_metrics/housing_stock, got {endpoint}"
+                ))
+            })?;
+        let url = format!(
+            "{}{service}/{parcl_id}/{metric}{}",
+            self.client.base_url,
+            self.params.to_query_string()
+        );
+        let response = common::get_with_pagination(
+            &self.client,
+            endpoint,
+            &url,
+            parcl_id,
+            self.params.fetch_options(),
+        )
+        .await?;
+        self.client.update_credits(&response.account);
+        self.write().insert(
+            (parcl_id, endpoint),
+            Entry {
+                response,
+                fetched_at: self.client.timer.now(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns the stored response without touching the network.
+    pub fn get_cached<T: DeserializeOwned>(
+        &self,
+        parcl_id: i64,
+        endpoint: &'static str,
+    ) -> Option<Result<MetricsResponse<T>>> {
+        let entry = self.read().get(&(parcl_id, endpoint)).cloned()?;
+        Some(decode(entry.response))
+    }
+
+    /// Returns the stored response, fetching and storing it on a miss.
+    ///
+    /// Misses on unwatched metrics are stored too but not refreshed.
+    pub async fn get_cached_or_fetch<T: DeserializeOwned>(
+        &self,
+        parcl_id: i64,
+        endpoint: &'static str,
+    ) -> Result<MetricsResponse<T>> {
+        if let Some(cached) = self.get_cached(parcl_id, endpoint) {
+            return cached;
+        }
+        self.refresh(parcl_id, endpoint).await?;
+        self.get_cached(parcl_id, endpoint)
+            .expect("refresh stores the response")
+    }
+
+    /// How long ago the stored response was fetched, if there is one.
+    pub fn age(&self, parcl_id: i64, endpoint: &'static str) -> Option<Duration> {
+        self.read().get(&(parcl_id, endpoint)).map(|entry| {
+            self.client
+                .timer
+                .now()
+                .saturating_duration_since(entry.fetched_at)
+        })
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<Key, Entry>> {
+        self.entries.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<Key, Entry>> {
+        self.entries.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Re-types a stored response, keeping the client-side metadata.
+fn decode<T: DeserializeOwned>(
+    response: MetricsResponse<serde_json::Value>,
+) -> Result<MetricsResponse<T>> {
+    let meta = response.meta.clone();
+    let mut typed: MetricsResponse<T> = serde_json::from_value(serde_json::to_value(response)?)?;
+    typed.meta = meta;
+    Ok(typed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HousingStock;
+    use crate::test_util::{json_response, CannedTransport};
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    /// Answers every request with one housing stock item whose
+    /// `single_family` counts the requests served.
+    fn counter() -> CannedTransport {
+        let served = AtomicI64::new(0);
+        CannedTransport::new(move |request| {
+            assert!(request
+                .uri()
+                .path()
+                .ends_with("/v1/market_metrics/7/housing_stock"));
+            let n = served.fetch_add(1, Ordering::SeqCst) + 1;
+            json_response(
+                200,
+                &serde_json::json!({
+                    "parcl_id": 7,
+                    "items": [{"date": "2024-01-01", "single_family": n}],
+                    "total": 1, "limit": 1, "offset": 0, "links": {}
+                }),
+            )
+        })
+    }
+
+    const STOCK: &str = "/v1/market_metrics/housing_stock";
+
+    fn units(resp: &MetricsResponse<HousingStock>) -> Option<i64> {
+        resp.items[0].single_family
+    }
+
+    #[tokio::test]
+    async fn serves_cached_until_refreshed() {
+        let counter = counter();
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(counter.clone());
+        let prefetch =
+            PrefetchService::new(Arc::new(client), Duration::from_secs(60)).watch([7], [STOCK]);
+
+        assert!(prefetch.get_cached::<HousingStock>(7, STOCK).is_none());
+        let first = prefetch.get_cached_or_fetch(7, STOCK).await.unwrap();
+        assert_eq!(units(&first), Some(1));
+        let again = prefetch.get_cached_or_fetch(7, STOCK).await.unwrap();
+        assert_eq!(units(&again), Some(1));
+        assert_eq!(counter.request_count(), 1);
+
+        assert_eq!(prefetch.refresh_all().await, 1);
+        let refreshed = prefetch.get_cached(7, STOCK).unwrap().unwrap();
+        assert_eq!(units(&refreshed), Some(2));
+        assert!(prefetch.age(7, STOCK).unwrap() < Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_endpoint() {
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(counter());
+        let prefetch = PrefetchService::new(Arc::new(client), Duration::from_secs(60));
+        let err = prefetch.refresh(7, "housing_stock").await.unwrap_err();
+        assert!(matches!(err, ParclError::Validation(_)));
+    }
+}
@@ -0,0 +1,584 @@
+//! Sans-IO request construction and response parsing.
+//!
+//! Everything here is pure: requests are built as [`http::Request`] values and
+//! responses are parsed from [`http::Response`] values, so the models and
+//! error mapping can be reused with any HTTP stack (hyper, isahc, or a custom
+//! one). `ParclClient` drives these functions through a [`Transport`], with
+//! [`ReqwestTransport`] as the default executor.
+
+use crate::error::{ParclError, Result};
+use crate::{AuthConfig, RetryConfig};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// An API request with a buffered body.
+pub type HttpRequest = http::Request<Vec<u8>>;
+
+/// An API response with a buffered body.
+pub type HttpResponse = http::Response<Vec<u8>>;
+
+/// `User-Agent` sent with every request, e.g. `parcllabs-rust/0.1.3`.
+pub const USER_AGENT: &str = concat!("parcllabs-rust/", env!("CARGO_PKG_VERSION"));
+
+/// Header identifying the SDK version to Parcl Labs support, sent when
+/// enabled with `ParclClient::with_sdk_header`.
+pub const SDK_HEADER: &str = "X-Parcl-SDK";
+
+/// Builds a GET request, or a JSON POST request when `body` is set.
+///
+/// Every request carries [`USER_AGENT`].
+pub fn build_request(
+    auth: &AuthConfig,
+    api_key: &str,
+    url: &str,
+    body: Option<&serde_json::Value>,
+) -> Result<HttpRequest> {
+    let builder = http::Request::builder()
+        .uri(url)
+        .header(http::header::USER_AGENT, USER_AGENT)
+        .header(auth.header.as_str(), auth.header_value(api_key));
+    let request = match body {
+        Some(body) => builder
+            .method(http::Method::POST)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(body)?)?,
+        None => builder.method(http::Method::GET).body(Vec::new())?,
+    };
+    Ok(request)
+}
+
+/// Returns how long to wait before retrying, or `None` if `response` should
+/// be returned as-is.
+///
+/// Only 429 responses are retried, with exponential backoff from
+/// `initial_backoff_ms`. `attempt` is zero-based.
+pub fn retry_delay(
+    config: &RetryConfig,
+    response: &HttpResponse,
+    attempt: u32,
+) -> Option<Duration> {
+    if response.status() == http::StatusCode::TOO_MANY_REQUESTS && attempt < config.max_retries {
+        Some(backoff(config, attempt))
+    } else {
+        None
+    }
+}
+
+/// Delay before retry number `attempt + 1`, doubling from the initial backoff.
+pub(crate) fn backoff(config: &RetryConfig, attempt: u32) -> Duration {
+    Duration::from_millis(config.initial_backoff_ms * 2u64.pow(attempt))
+}
+
+/// Returns true for a 404, which the metrics endpoints use to report that
+/// no data exists for the request.
+pub fn is_no_data(response: &HttpResponse) -> bool {
+    response.status() == http::StatusCode::NOT_FOUND
+}
+
+/// Returns true if the API refused the request because the account's credit
+/// quota is used up: a 402, or a 429 whose body mentions the quota or credits.
+pub fn is_quota_exhausted(response: &HttpResponse) -> bool {
+    match response.status() {
+        http::StatusCode::PAYMENT_REQUIRED => true,
+        http::StatusCode::TOO_MANY_REQUESTS => {
+            let body = String::from_utf8_lossy(response.body()).to_ascii_lowercase();
+            body.contains("quota") || body.contains("credit")
+        }
+        _ => false,
+    }
+}
+
+/// Rate-limit state reported in response headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Requests allowed in the current window.
+    pub limit: Option<u64>,
+    /// Requests left in the current window.
+    pub remaining: Option<u64>,
+    /// Time until the window resets, as of `observed_at`.
+    pub reset_after: Option<Duration>,
+    /// When the response carrying these headers was received.
+    pub observed_at: Instant,
+}
+
+/// Reads `X-RateLimit-Limit`/`-Remaining`/`-Reset` (or the unprefixed
+/// `RateLimit-*` forms) from `response`.
+///
+/// Returns `None` if none are present. A reset value larger than a year is
+/// treated as a Unix timestamp rather than a number of seconds.
+pub fn parse_rate_limit<B>(response: &http::Response<B>) -> Option<RateLimitStatus> {
+    let header = |name: &str| {
+        [format!("x-ratelimit-{name}"), format!("ratelimit-{name}")]
+            .iter()
+            .find_map(|key| response.headers().get(key.as_str()))
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+    let limit = header("limit");
+    let remaining = header("remaining");
+    let reset_after = header("reset").map(|reset| {
+        const YEAR_SECS: u64 = 365 * 24 * 60 * 60;
+        if reset > YEAR_SECS {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            Duration::from_secs(reset.saturating_sub(now))
+        } else {
+            Duration::from_secs(reset)
+        }
+    });
+    if limit.is_none() && remaining.is_none() && reset_after.is_none() {
+        return None;
+    }
+    Some(RateLimitStatus {
+        limit,
+        remaining,
+        reset_after,
+        observed_at: Instant::now(),
+    })
+}
+
+/// Identifies the request a response belongs to, for error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestContext {
+    /// API path without the base URL or `parcl_id`, e.g. `/v1/market_metrics/housing_stock`.
+    pub endpoint: &'static str,
+    /// The market requested, for single-market endpoints.
+    pub parcl_id: Option<i64>,
+}
+
+impl RequestContext {
+    pub fn new(endpoint: &'static str) -> Self {
+        Self {
+            endpoint,
+            parcl_id: None,
+        }
+    }
+
+    pub fn parcl_id(mut self, parcl_id: i64) -> Self {
+        self.parcl_id = Some(parcl_id);
+        self
+    }
+}
+
+/// Parses a JSON response body, mapping error statuses to `ParclError`.
+///
+/// `attempts` is the number of requests made, reported on rate-limit errors.
+pub fn parse_response<T: DeserializeOwned>(
+    response: &HttpResponse,
+    context: RequestContext,
+    attempts: u32,
+) -> Result<T> {
+    check_status(response, context, attempts)?;
+    decode_body(response.body())
+}
+
+/// Maps a non-2xx response to `RateLimited` or `ApiError`.
+pub(crate) fn check_status(
+    response: &HttpResponse,
+    context: RequestContext,
+    attempts: u32,
+) -> Result<()> {
+    let status = response.status();
+    if !status.is_success() {
+        let message = String::from_utf8_lossy(response.body()).into_owned();
+        if status == http::StatusCode::TOO_MANY_REQUESTS {
+            return Err(ParclError::RateLimited { attempts, message });
+        }
+        return Err(ParclError::ApiError {
+            status: status.as_u16(),
+            message,
+            endpoint: context.endpoint,
+            parcl_id: context.parcl_id,
+        });
+    }
+    Ok(())
+}
+
+/// Bytes of context kept on each side of a decode failure.
+const SNIPPET_RADIUS: usize = 60;
+
+/// Deserializes `body`, reporting the failing field path and nearby payload.
+fn decode_body<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    let de = &mut serde_json::Deserializer::from_slice(body);
+    serde_path_to_error::deserialize(de).map_err(|e| {
+        let path = e.path().to_string();
+        let source = e.into_inner();
+        ParclError::Decode {
+            snippet: snippet_at(body, source.line(), source.column()),
+            path,
+            source,
+        }
+    })
+}
+
+/// Extracts the text around a 1-based `line`/`column` position in `body`.
+fn snippet_at(body: &[u8], line: usize, column: usize) -> String {
+    let line_start: usize = body
+        .split(|&b| b == b'\n')
+        .take(line.saturating_sub(1))
+        .map(|l| l.len() + 1)
+        .sum();
+    let offset = (line_start + column.saturating_sub(1)).min(body.len());
+    let start = offset.saturating_sub(SNIPPET_RADIUS);
+    let end = (offset + SNIPPET_RADIUS).min(body.len());
+    String::from_utf8_lossy(&body[start..end])
+        .trim()
+        .to_string()
+}
+
+/// Boxed future returned by [`Transport::execute`].
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<HttpResponse>> + Send + 'a>>;
+
+/// Boxed future returned by [`BodyStream::next_chunk`].
+pub type ChunkFuture<'a> = Pin<Box<dyn Future<Output = Result<Option<Vec<u8>>>> + Send + 'a>>;
+
+/// A response body read as it arrives.
+pub trait BodyStream: Send {
+    /// The next chunk of the body, or `None` once it is complete.
+    fn next_chunk(&mut self) -> ChunkFuture<'_>;
+}
+
+/// A body that has already arrived, delivered as a single chunk.
+impl BodyStream for Option<Vec<u8>> {
+    fn next_chunk(&mut self) -> ChunkFuture<'_> {
+        let chunk = self.take();
+        Box::pin(async move { Ok(chunk) })
+    }
+}
+
+/// An API response whose body is read as it arrives.
+pub type StreamingResponse = http::Response<Box<dyn BodyStream>>;
+
+/// Boxed future returned by [`Transport::execute_streaming`].
+pub type StreamingFuture<'a> = Pin<Box<dyn Future<Output = Result<StreamingResponse>> + Send + 'a>>;
+
+/// Reads the rest of a streaming response into a buffered one.
+pub async fn buffer(response: StreamingResponse) -> Result<HttpResponse> {
+    let (parts, mut stream) = response.into_parts();
+    let mut body = Vec::new();
+    while let Some(chunk) = stream.next_chunk().await? {
+        body.extend_from_slice(&chunk);
+    }
+    Ok(http::Response::from_parts(parts, body))
+}
+
+/// Executes built requests over the network.
+///
+/// Implement this to run the SDK on an HTTP stack other than reqwest, then
+/// install it with `ParclClient::with_transport`.
+pub trait Transport: Send + Sync {
+    fn execute(&self, request: HttpRequest) -> TransportFuture<'_>;
+
+    /// Like [`execute`](Self::execute), but resolves once the headers arrive
+    /// and hands over the body as it is received.
+    ///
+    /// Used where the SDK can process a large body incrementally, such as
+    /// `PropertyClient::search_v2_each`. The default waits for
+    /// [`execute`](Self::execute) and delivers the body as one chunk.
+    fn execute_streaming(&self, request: HttpRequest) -> StreamingFuture<'_> {
+        Box::pin(async move {
+            let response = self.execute(request).await?;
+            Ok(response.map(|body| Box::new(Some(body)) as Box<dyn BodyStream>))
+        })
+    }
+}
+
+/// The default [`Transport`], backed by a `reqwest::Client`.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn execute(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let request = reqwest::Request::try_from(request)?;
+            let response = self.client.execute(request).await?;
+
+            let mut builder = http::Response::builder().status(response.status());
+            if let Some(headers) = builder.headers_mut() {
+                *headers = response.headers().clone();
+            }
+            let body = response.bytes().await?.to_vec();
+            Ok(builder.body(body)?)
+        })
+    }
+
+    fn execute_streaming(&self, request: HttpRequest) -> StreamingFuture<'_> {
+        Box::pin(async move {
+            let request = reqwest::Request::try_from(request)?;
+            let response = self.client.execute(request).await?;
+
+            let mut builder = http::Response::builder().status(response.status());
+            if let Some(headers) = builder.headers_mut() {
+                *headers = response.headers().clone();
+            }
+            Ok(builder.body(Box::new(ReqwestBody(response)) as Box<dyn BodyStream>)?)
+        })
+    }
+}
+
+/// A reqwest response body, read chunk by chunk.
+struct ReqwestBody(reqwest::Response);
+
+impl BodyStream for ReqwestBody {
+    fn next_chunk(&mut self) -> ChunkFuture<'_> {
+        Box::pin(async move { Ok(self.0.chunk().await?.map(|chunk| chunk.to_vec())) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CannedTransport;
+
+    fn response(status: u16, body: &str) -> HttpResponse {
+        http::Response::builder()
+            .status(status)
+            .body(body.as_bytes().to_vec())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn client_runs_on_custom_transport() {
+        let transport = CannedTransport::sequence(vec![
+            response(429, ""),
+            response(
+                200,
+                r#"{"parcl_id":7,"items":[{"date":"2024-01-01","sales":3}],"total":1,"limit":1,"offset":0,"links":{}}"#,
+            ),
+        ]);
+        let client = crate::ParclClient::with_config("key", "https://example.com")
+            .with_retry_config(RetryConfig {
+                max_retries: 1,
+                initial_backoff_ms: 0,
+            })
+            .with_transport(transport.clone())
+            .with_sdk_header(true);
+
+        let resp = client
+            .market_metrics()
+            .housing_event_counts(7, None)
+            .await
+            .unwrap();
+        assert_eq!(resp.items[0].sales, Some(3));
+        assert_eq!(resp.meta.attempts_made, 2);
+        assert_eq!(resp.meta.retries, 1);
+
+        let seen = transport.requests();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[1].headers()[SDK_HEADER], USER_AGENT);
+        assert!(seen[0]
+            .uri()
+            .to_string()
+            .starts_with("https://example.com/v1/market_metrics/7/housing_event_counts"));
+    }
+
+    #[tokio::test]
+    async fn params_retry_override_beats_client_config() {
+        let transport = CannedTransport::sequence(vec![response(429, "slow down")]);
+        let client = crate::ParclClient::with_config("key", "https://example.com")
+            .with_retry_config(RetryConfig {
+                max_retries: 5,
+                initial_backoff_ms: 0,
+            })
+            .with_transport(transport.clone());
+
+        let err = client
+            .market_metrics()
+            .housing_event_counts(7, Some(crate::MetricsParams::new().no_retry()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ParclError::RateLimited { attempts: 1, .. }));
+        assert_eq!(transport.request_count(), 1);
+    }
+
+    #[test]
+    fn build_request_get_and_post() {
+        let auth = AuthConfig::default();
+        let get = build_request(&auth, "key", "https://example.com/v1/x?limit=1", None).unwrap();
+        assert_eq!(get.method(), http::Method::GET);
+        assert_eq!(get.headers()["Authorization"], "key");
+        assert_eq!(
+            get.headers()["user-agent"],
+            format!("parcllabs-rust/{}", crate::ParclClient::sdk_version())
+        );
+        assert!(get.body().is_empty());
+
+        let body = serde_json::json!({"parcl_id": [1, 2]});
+        let post = build_request(
+            &AuthConfig::bearer(),
+            "key",
+            "https://example.com",
+            Some(&body),
+        )
+        .unwrap();
+        assert_eq!(post.method(), http::Method::POST);
+        assert_eq!(post.headers()["Authorization"], "Bearer key");
+        assert_eq!(post.headers()["content-type"], "application/json");
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(post.body()).unwrap(),
+            body
+        );
+    }
+
+    #[test]
+    fn build_request_rejects_bad_header_name() {
+        let auth = AuthConfig::header("bad header");
+        assert!(build_request(&auth, "key", "https://example.com", None).is_err());
+    }
+
+    #[test]
+    fn retry_delay_backs_off_on_429_only() {
+        let config = RetryConfig {
+            max_retries: 2,
+            initial_backoff_ms: 100,
+        };
+        let limited = response(429, "");
+        assert_eq!(
+            retry_delay(&config, &limited, 0),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            retry_delay(&config, &limited, 1),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(retry_delay(&config, &limited, 2), None);
+        assert_eq!(retry_delay(&config, &response(500, ""), 0), None);
+    }
+
+    #[test]
+    fn parse_response_reports_decode_path_and_snippet() {
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Item {
+            date: String,
+            sales: Option<i64>,
+        }
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Page {
+            items: Vec<Item>,
+        }
+
+        let body =
+            r#"{"items":[{"date":"2024-01-01","sales":1},{"date":"2024-02-01","sales":"many"}]}"#;
+        let ctx = RequestContext::new("/v1/test");
+        let err = parse_response::<Page>(&response(200, body), ctx, 1).unwrap_err();
+        match err {
+            ParclError::Decode { path, snippet, .. } => {
+                assert_eq!(path, "items[1].sales");
+                assert!(snippet.contains(r#""sales":"many""#));
+            }
+            other => panic!("expected Decode, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn snippet_at_handles_multiline_and_bounds() {
+        let body = b"line one\nline two is here";
+        assert_eq!(snippet_at(body, 2, 6), "line one\nline two is here");
+        assert_eq!(snippet_at(b"", 1, 1), "");
+        assert_eq!(snippet_at(b"abc", 5, 99), "abc");
+    }
+
+    #[test]
+    fn parse_response_maps_statuses() {
+        let ctx = RequestContext::new("/v1/market_metrics/housing_stock").parcl_id(5);
+        let ok: serde_json::Value = parse_response(&response(200, r#"{"a":1}"#), ctx, 1).unwrap();
+        assert_eq!(ok["a"], 1);
+
+        let err =
+            parse_response::<serde_json::Value>(&response(429, "slow down"), ctx, 4).unwrap_err();
+        assert!(matches!(err, ParclError::RateLimited { attempts: 4, .. }));
+
+        let err = parse_response::<serde_json::Value>(&response(500, "boom"), ctx, 1).unwrap_err();
+        assert!(matches!(
+            err,
+            ParclError::ApiError {
+                status: 500,
+                endpoint: "/v1/market_metrics/housing_stock",
+                parcl_id: Some(5),
+                ..
+            }
+        ));
+        assert!(is_no_data(&response(404, "")));
+    }
+
+    #[test]
+    fn parse_rate_limit_headers() {
+        assert!(parse_rate_limit(&response(200, "")).is_none());
+
+        let resp = http::Response::builder()
+            .header("X-RateLimit-Limit", "100")
+            .header("X-RateLimit-Remaining", "7")
+            .header("X-RateLimit-Reset", "30")
+            .body(Vec::<u8>::new())
+            .unwrap();
+        let status = parse_rate_limit(&resp).unwrap();
+        assert_eq!(status.limit, Some(100));
+        assert_eq!(status.remaining, Some(7));
+        assert_eq!(status.reset_after, Some(Duration::from_secs(30)));
+
+        let epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 60;
+        let resp = http::Response::builder()
+            .header("RateLimit-Remaining", "0")
+            .header("RateLimit-Reset", epoch.to_string())
+            .body(Vec::<u8>::new())
+            .unwrap();
+        let status = parse_rate_limit(&resp).unwrap();
+        assert_eq!(status.limit, None);
+        assert_eq!(status.remaining, Some(0));
+        assert!(status.reset_after.unwrap() <= Duration::from_secs(60));
+        assert!(status.reset_after.unwrap() >= Duration::from_secs(58));
+    }
+
+    #[tokio::test]
+    async fn client_tracks_latest_rate_limit_status() {
+        let body = r#"{"parcl_id":7,"items":[],"total":0,"limit":1,"offset":0,"links":{}}"#;
+        let limited = http::Response::builder()
+            .header("X-RateLimit-Limit", "60")
+            .header("X-RateLimit-Remaining", "59")
+            .body(body.as_bytes().to_vec())
+            .unwrap();
+        let transport = CannedTransport::sequence(vec![limited, response(200, body)]);
+        let client =
+            crate::ParclClient::with_config("key", "https://example.com").with_transport(transport);
+        assert!(client.rate_limit_status().is_none());
+
+        for _ in 0..2 {
+            client
+                .market_metrics()
+                .housing_event_counts(7, None)
+                .await
+                .unwrap();
+            let status = client.rate_limit_status().unwrap();
+            assert_eq!(status.remaining, Some(59));
+        }
+    }
+
+    #[test]
+    fn quota_exhaustion_is_recognized() {
+        assert!(is_quota_exhausted(&response(402, "")));
+        assert!(is_quota_exhausted(&response(
+            429,
+            "Monthly Credit limit reached"
+        )));
+        assert!(!is_quota_exhausted(&response(429, "slow down")));
+        assert!(!is_quota_exhausted(&response(500, "quota service down")));
+    }
+}
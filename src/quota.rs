@@ -0,0 +1,201 @@
+//! Pausing outgoing requests once the account's credit quota is exhausted.
+//!
+//! Without a guard, every request made after credits run out fails on its
+//! own. With [`ParclClient::with_quota_guard`], the first sign of exhaustion
+//! (a response reporting `est_remaining_credits == 0`, a 402, or a 429 whose
+//! body mentions the quota) opens the breaker: a [`QuotaExhausted`] event is
+//! emitted and later requests wait until the configured reset time instead of
+//! being sent. The request that detected exhaustion returns its own result.
+
+use crate::runtime::Timer;
+use crate::ParclClient;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Receives an event each time the quota breaker opens.
+pub type QuotaHandler = Arc<dyn Fn(&QuotaExhausted) + Send + Sync>;
+
+/// What revealed that the quota is exhausted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaSignal {
+    /// A response reported zero remaining credits.
+    CreditsDepleted,
+    /// The API rejected a request with this status (402, or 429 citing the quota).
+    Rejected { status: u16 },
+}
+
+/// Emitted when the quota breaker opens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaExhausted {
+    pub signal: QuotaSignal,
+    /// When queued requests will be sent again.
+    pub resume_at: Instant,
+}
+
+/// Configures how long requests pause once the quota is exhausted.
+#[derive(Clone)]
+pub struct QuotaGuard {
+    reset_after: Duration,
+    handler: Option<QuotaHandler>,
+}
+
+impl QuotaGuard {
+    /// Pauses requests for `reset_after` each time the quota runs out.
+    pub fn new(reset_after: Duration) -> Self {
+        Self {
+            reset_after,
+            handler: None,
+        }
+    }
+
+    /// Calls `handler` whenever the breaker opens.
+    pub fn on_exhausted(
+        mut self,
+        handler: impl Fn(&QuotaExhausted) + Send + Sync + 'static,
+    ) -> Self {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+
+    pub fn reset_after(&self) -> Duration {
+        self.reset_after
+    }
+}
+
+impl std::fmt::Debug for QuotaGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuotaGuard")
+            .field("reset_after", &self.reset_after)
+            .field("handler", &self.handler.is_some())
+            .finish()
+    }
+}
+
+/// A [`QuotaGuard`] plus the breaker state it drives.
+#[derive(Debug)]
+pub(crate) struct QuotaState {
+    guard: QuotaGuard,
+    paused_until: Mutex<Option<Instant>>,
+}
+
+impl QuotaState {
+    pub(crate) fn new(guard: QuotaGuard) -> Self {
+        Self {
+            guard,
+            paused_until: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn paused_until(&self, now: Instant) -> Option<Instant> {
+        let mut paused = self.paused_until.lock().unwrap();
+        if paused.is_some_and(|until| until <= now) {
+            *paused = None;
+        }
+        *paused
+    }
+
+    /// Waits until the breaker closes, if it is open.
+    pub(crate) async fn wait(&self, timer: &dyn Timer) {
+        let now = timer.now();
+        if let Some(until) = self.paused_until(now) {
+            timer.sleep(until.saturating_duration_since(now)).await;
+        }
+    }
+
+    /// Opens the breaker, emitting an event unless it is already open.
+    pub(crate) fn trip(&self, signal: QuotaSignal, now: Instant) {
+        let event = {
+            let mut paused = self.paused_until.lock().unwrap();
+            if paused.is_some_and(|until| until > now) {
+                return;
+            }
+            let resume_at = now + self.guard.reset_after;
+            *paused = Some(resume_at);
+            QuotaExhausted { signal, resume_at }
+        };
+        if let Some(handler) = &self.guard.handler {
+            handler(&event);
+        }
+    }
+}
+
+impl ParclClient {
+    /// Pauses outgoing requests once the credit quota is exhausted instead of
+    /// letting each one fail. See the [`quota`](crate::quota) module.
+    pub fn with_quota_guard(mut self, guard: QuotaGuard) -> Self {
+        self.quota = Some(Arc::new(QuotaState::new(guard)));
+        self
+    }
+
+    /// Returns when paused requests will resume, if the quota breaker is open.
+    pub fn quota_paused_until(&self) -> Option<Instant> {
+        let now = self.timer.now();
+        self.quota
+            .as_ref()
+            .and_then(|quota| quota.paused_until(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AccountInfo;
+
+    #[test]
+    fn trip_emits_once_while_open() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let state = QuotaState::new(
+            QuotaGuard::new(Duration::from_secs(60))
+                .on_exhausted(move |e| sink.lock().unwrap().push(e.signal)),
+        );
+        assert!(state.paused_until(Instant::now()).is_none());
+        state.trip(QuotaSignal::Rejected { status: 402 }, Instant::now());
+        state.trip(QuotaSignal::CreditsDepleted, Instant::now());
+        assert!(state.paused_until(Instant::now()).is_some());
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![QuotaSignal::Rejected { status: 402 }]
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_holds_requests_until_reset() {
+        let state = QuotaState::new(QuotaGuard::new(Duration::from_millis(40)));
+        let start = Instant::now();
+        state.wait(&crate::runtime::ThreadTimer).await;
+        assert!(start.elapsed() < Duration::from_millis(40));
+
+        state.trip(QuotaSignal::CreditsDepleted, Instant::now());
+        state.wait(&crate::runtime::ThreadTimer).await;
+        assert!(start.elapsed() >= Duration::from_millis(40));
+        assert!(state.paused_until(Instant::now()).is_none());
+    }
+
+    #[tokio::test]
+    async fn pauses_run_on_virtual_time() {
+        let clock = crate::runtime::MockClock::new();
+        let state = QuotaState::new(QuotaGuard::new(Duration::from_secs(3600)));
+        state.trip(QuotaSignal::CreditsDepleted, clock.now());
+        clock.advance(Duration::from_secs(600));
+        state.wait(&clock).await;
+        assert_eq!(clock.sleeps(), [Duration::from_secs(3000)]);
+        assert!(state.paused_until(clock.now()).is_none());
+    }
+
+    #[test]
+    fn zero_remaining_credits_opens_breaker() {
+        let client = ParclClient::with_api_key("test")
+            .with_quota_guard(QuotaGuard::new(Duration::from_secs(60)));
+        client.update_credits(&Some(AccountInfo {
+            est_credits_used: Some(5),
+            est_remaining_credits: Some(10),
+        }));
+        assert!(client.quota_paused_until().is_none());
+        client.update_credits(&Some(AccountInfo {
+            est_credits_used: Some(10),
+            est_remaining_credits: Some(0),
+        }));
+        assert!(client.quota_paused_until().is_some());
+    }
+}
@@ -0,0 +1,226 @@
+//! Offline `parcl_id` reference index for resolving common markets without
+//! spending search credits.
+//!
+//! Build an index from the live API with [`ReferenceIndex::refresh`],
+//! persist it with [`ReferenceIndex::to_json`] and load it back with
+//! [`ReferenceIndex::from_json`]. The `refresh_reference_data` example does
+//! the first two.
+
+use crate::cache::{most_populous_named, NamedMarket};
+use crate::error::Result;
+use crate::models::{LocationType, Market, SortBy, SortOrder};
+use crate::{ParclClient, SearchParams};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Compact market record stored in a reference index.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ReferenceMarket {
+    pub parcl_id: i64,
+    pub name: String,
+    pub state_abbreviation: Option<String>,
+    pub location_type: String,
+    pub total_population: Option<i64>,
+}
+
+impl From<&Market> for ReferenceMarket {
+    fn from(m: &Market) -> Self {
+        Self {
+            parcl_id: m.parcl_id,
+            name: m.name.clone(),
+            state_abbreviation: m.state_abbreviation.clone(),
+            location_type: m.location_type.clone(),
+            total_population: m.total_population,
+        }
+    }
+}
+
+impl NamedMarket for ReferenceMarket {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn state_abbreviation(&self) -> Option<&str> {
+        self.state_abbreviation.as_deref()
+    }
+
+    fn total_population(&self) -> Option<i64> {
+        self.total_population
+    }
+}
+
+/// Lookup table from market names to `parcl_id`s, usable without network access.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceIndex {
+    markets: Vec<ReferenceMarket>,
+    by_id: HashMap<i64, usize>,
+}
+
+impl ReferenceIndex {
+    /// Builds an index from market records. Later duplicates of a `parcl_id` win.
+    pub fn new(markets: Vec<ReferenceMarket>) -> Self {
+        let mut index = Self::default();
+        for market in markets {
+            match index.by_id.get(&market.parcl_id) {
+                Some(&i) => index.markets[i] = market,
+                None => {
+                    index.by_id.insert(market.parcl_id, index.markets.len());
+                    index.markets.push(market);
+                }
+            }
+        }
+        index
+    }
+
+    /// Parses an index from a JSON array of [`ReferenceMarket`] records.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    /// Serializes the index as a pretty-printed JSON array.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.markets)?)
+    }
+
+    /// Returns the record for `parcl_id`, if indexed.
+    pub fn get(&self, parcl_id: i64) -> Option<&ReferenceMarket> {
+        self.by_id.get(&parcl_id).map(|&i| &self.markets[i])
+    }
+
+    /// Finds a market by exact (case-insensitive) name, optionally within a state.
+    ///
+    /// When several markets share the name, the most populous one is returned.
+    pub fn find(&self, name: &str, state: Option<&str>) -> Option<&ReferenceMarket> {
+        most_populous_named(&self.markets, name, state)
+    }
+
+    /// Returns markets whose name contains `query` (case-insensitive).
+    pub fn search(&self, query: &str) -> Vec<&ReferenceMarket> {
+        let query = query.trim().to_lowercase();
+        self.markets
+            .iter()
+            .filter(|m| m.name.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    /// Returns all indexed markets in insertion order.
+    pub fn markets(&self) -> &[ReferenceMarket] {
+        &self.markets
+    }
+
+    pub fn len(&self) -> usize {
+        self.markets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.markets.is_empty()
+    }
+
+    /// Rebuilds an index from the live API.
+    ///
+    /// For each location type, fetches the `per_type_limit` most populous
+    /// markets. Costs one search request per location type.
+    pub async fn refresh(
+        client: &ParclClient,
+        location_types: &[LocationType],
+        per_type_limit: u32,
+    ) -> Result<Self> {
+        let mut markets = Vec::new();
+        for &location_type in location_types {
+            let params = SearchParams::new()
+                .location_type(location_type)
+                .sort_by(SortBy::TotalPopulation)
+                .sort_order(SortOrder::Desc)
+                .limit(per_type_limit);
+            let resp = client.search().markets(params).await?;
+            markets.extend(resp.items.iter().map(ReferenceMarket::from));
+        }
+        Ok(Self::new(markets))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{json_response, CannedTransport};
+
+    fn entry(parcl_id: i64, name: &str, state: &str, pop: i64) -> ReferenceMarket {
+        ReferenceMarket {
+            parcl_id,
+            name: name.into(),
+            state_abbreviation: Some(state.into()),
+            location_type: "CITY".into(),
+            total_population: Some(pop),
+        }
+    }
+
+    #[test]
+    fn reference_index_get_and_find() {
+        let index = ReferenceIndex::new(vec![
+            entry(1, "Springfield", "IL", 114_000),
+            entry(2, "Springfield", "MO", 169_000),
+            entry(3, "Denver", "CO", 715_000),
+        ]);
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.get(3).unwrap().name, "Denver");
+        assert_eq!(index.find("springfield", None).unwrap().parcl_id, 2);
+        assert_eq!(index.find("Springfield", Some("il")).unwrap().parcl_id, 1);
+        assert!(index.find("Springfield", Some("CA")).is_none());
+        assert_eq!(index.search("spring").len(), 2);
+    }
+
+    #[test]
+    fn reference_index_dedupes_by_parcl_id() {
+        let index = ReferenceIndex::new(vec![
+            entry(1, "Old Name", "CO", 1),
+            entry(1, "New Name", "CO", 2),
+        ]);
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.get(1).unwrap().name, "New Name");
+    }
+
+    #[test]
+    fn reference_index_json_round_trip() {
+        let index = ReferenceIndex::new(vec![entry(3, "Denver", "CO", 715_000)]);
+        let json = index.to_json().unwrap();
+        let parsed = ReferenceIndex::from_json(&json).unwrap();
+        assert_eq!(parsed.markets(), index.markets());
+    }
+
+    #[tokio::test]
+    async fn refreshed_index_resolves_markets() {
+        let transport = CannedTransport::new(|request| {
+            let page = if request.uri().query().unwrap_or("").contains("CBSA") {
+                serde_json::json!([{
+                    "parcl_id": 2900078, "name": "Los Angeles-Long Beach-Anaheim",
+                    "state_abbreviation": "CA", "location_type": "CBSA",
+                    "total_population": 13_111_917
+                }])
+            } else {
+                serde_json::json!([
+                    {"parcl_id": 5373892, "name": "Springfield", "state_abbreviation": "MO",
+                     "location_type": "CITY", "total_population": 169_176},
+                    {"parcl_id": 5387853, "name": "Springfield", "state_abbreviation": "IL",
+                     "location_type": "CITY", "total_population": 114_394}
+                ])
+            };
+            json_response(
+                200,
+                &serde_json::json!({ "items": page, "total": 2, "limit": 2, "offset": 0, "links": {} }),
+            )
+        });
+        let client =
+            ParclClient::with_config("key", "https://example.com").with_transport(transport);
+
+        let index = ReferenceIndex::refresh(&client, &[LocationType::Cbsa, LocationType::City], 2)
+            .await
+            .unwrap();
+        let index = ReferenceIndex::from_json(&index.to_json().unwrap()).unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(
+            index.find("springfield", Some("IL")).unwrap().parcl_id,
+            5387853
+        );
+        assert_eq!(index.get(2900078).unwrap().location_type, "CBSA");
+    }
+}
@@ -0,0 +1,328 @@
+//! Markdown summaries of market snapshots, for Slack, Notion or READMEs.
+//!
+//! ```no_run
+//! use parcllabs::report::{markdown, MarketReport};
+//! use parcllabs::{MetricsParams, ParclClient};
+//!
+//! # async fn example() -> parcllabs::Result<()> {
+//! let client = ParclClient::new()?;
+//! let params = MetricsParams::new().limit(12);
+//! let austin = client.pipeline().market(2900078).await?.snapshot(Some(params.clone())).await?;
+//! let denver = client.pipeline().market(2900187).await?.snapshot(Some(params)).await?;
+//!
+//! let report = MarketReport::new("Monthly housing update")
+//!     .market(austin)
+//!     .market(denver);
+//! println!("{}", markdown(&report));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::display::{format_currency, format_number, sparkline, Trend};
+use crate::pipeline::MarketSnapshot;
+
+/// Moves smaller than this share of the previous value count as flat.
+const FLAT_TOLERANCE: f64 = 0.005;
+
+/// Markets to summarize under one title.
+#[derive(Debug, Clone)]
+pub struct MarketReport {
+    pub title: String,
+    pub markets: Vec<MarketSnapshot>,
+}
+
+impl MarketReport {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            markets: Vec::new(),
+        }
+    }
+
+    /// Adds a market section.
+    pub fn market(mut self, snapshot: MarketSnapshot) -> Self {
+        self.markets.push(snapshot);
+        self
+    }
+}
+
+impl From<MarketSnapshot> for MarketReport {
+    /// A single-market report titled with the market name.
+    fn from(snapshot: MarketSnapshot) -> Self {
+        Self::new(snapshot.market.name.clone()).market(snapshot)
+    }
+}
+
+/// Renders `report` as GitHub-flavored markdown.
+///
+/// With more than one market, a comparison table of the latest values comes
+/// first. Each market then gets a table with the latest value of every key
+/// metric, the change from the previous period, a trend arrow and a
+/// sparkline of the fetched history.
+pub fn markdown(report: &MarketReport) -> String {
+    let mut out = format!("# {}\n", escape(&report.title));
+
+    if report.markets.len() > 1 {
+        out.push_str("\n## Comparison\n\n");
+        let labels: Vec<&str> = rows(&report.markets[0])
+            .iter()
+            .map(|row| row.label)
+            .collect();
+        out.push_str(&format!("| Market | {} |\n", labels.join(" | ")));
+        out.push_str(&format!("|---|{}\n", "---:|".repeat(labels.len())));
+        for snapshot in &report.markets {
+            let cells: Vec<String> = rows(snapshot)
+                .iter()
+                .map(|row| row.latest().map_or_else(na, |v| row.unit.format(v)))
+                .collect();
+            out.push_str(&format!(
+                "| {} | {} |\n",
+                escape(&snapshot.market.name),
+                cells.join(" | ")
+            ));
+        }
+    }
+
+    for snapshot in &report.markets {
+        out.push_str(&format!(
+            "\n## {}\n\n",
+            escape(&snapshot.market.to_string())
+        ));
+        let rows = rows(snapshot);
+        let Some(date) = rows.iter().find_map(|row| row.points.first().map(|p| p.0)) else {
+            out.push_str("_No data._\n");
+            continue;
+        };
+        out.push_str(&format!(
+            "| Metric | {date} | Previous | Change | Trend | History |\n\
+             |---|---:|---:|---:|:-:|---|\n"
+        ));
+        for row in &rows {
+            let latest = row.latest();
+            let previous = row.previous();
+            let (change, trend) = match (latest, previous) {
+                (Some(latest), Some(previous)) => (
+                    percent_change(previous, latest),
+                    Trend::between(previous, latest, previous.abs() * FLAT_TOLERANCE).to_string(),
+                ),
+                _ => (na(), String::new()),
+            };
+            out.push_str(&format!(
+                "| {} | {} | {} | {change} | {trend} | {} |\n",
+                row.label,
+                latest.map_or_else(na, |v| row.unit.format(v)),
+                previous.map_or_else(na, |v| row.unit.format(v)),
+                row.history(),
+            ));
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Unit {
+    Count,
+    Currency,
+}
+
+impl Unit {
+    fn format(self, value: f64) -> String {
+        match self {
+            Self::Count => format_number(value.round() as i64),
+            Self::Currency => format_currency(value),
+        }
+    }
+}
+
+/// One metric's values, newest first as the API returns them.
+struct Row<'a> {
+    label: &'static str,
+    unit: Unit,
+    points: Vec<(&'a str, Option<f64>)>,
+}
+
+impl Row<'_> {
+    fn latest(&self) -> Option<f64> {
+        self.points.first().and_then(|p| p.1)
+    }
+
+    fn previous(&self) -> Option<f64> {
+        self.points.get(1).and_then(|p| p.1)
+    }
+
+    /// Sparkline from oldest to newest, empty with fewer than two values.
+    fn history(&self) -> String {
+        if self.points.iter().filter(|p| p.1.is_some()).count() < 2 {
+            return String::new();
+        }
+        let values: Vec<f64> = self
+            .points
+            .iter()
+            .rev()
+            .map(|p| p.1.unwrap_or(f64::NAN))
+            .collect();
+        sparkline(&values)
+    }
+}
+
+fn rows(s: &MarketSnapshot) -> Vec<Row<'_>> {
+    let counts = &s.event_counts.items;
+    let prices = &s.event_prices.items;
+    let median = |select: fn(&crate::models::EventPrices) -> Option<f64>| {
+        prices
+            .iter()
+            .map(|p| {
+                let value = p
+                    .price
+                    .as_ref()
+                    .and_then(|s| s.median.as_ref())
+                    .and_then(select);
+                (p.date.as_str(), value)
+            })
+            .collect()
+    };
+    vec![
+        Row {
+            label: "Sales",
+            unit: Unit::Count,
+            points: counts
+                .iter()
+                .map(|c| (c.date.as_str(), c.sales.map(|v| v as f64)))
+                .collect(),
+        },
+        Row {
+            label: "New listings for sale",
+            unit: Unit::Count,
+            points: counts
+                .iter()
+                .map(|c| (c.date.as_str(), c.new_listings_for_sale.map(|v| v as f64)))
+                .collect(),
+        },
+        Row {
+            label: "New rental listings",
+            unit: Unit::Count,
+            points: counts
+                .iter()
+                .map(|c| (c.date.as_str(), c.new_rental_listings.map(|v| v as f64)))
+                .collect(),
+        },
+        Row {
+            label: "Median sale price",
+            unit: Unit::Currency,
+            points: median(|m| m.sales),
+        },
+        Row {
+            label: "Median list price",
+            unit: Unit::Currency,
+            points: median(|m| m.new_listings_for_sale),
+        },
+        Row {
+            label: "Median rent",
+            unit: Unit::Currency,
+            points: median(|m| m.new_rental_listings),
+        },
+        Row {
+            label: "Housing stock",
+            unit: Unit::Count,
+            points: s
+                .housing_stock
+                .items
+                .iter()
+                .map(|h| (h.date.as_str(), h.all_properties.map(|v| v as f64)))
+                .collect(),
+        },
+    ]
+}
+
+fn percent_change(previous: f64, latest: f64) -> String {
+    if previous == 0.0 {
+        return na();
+    }
+    format!("{:+.1}%", (latest - previous) / previous.abs() * 100.0)
+}
+
+fn na() -> String {
+    "n/a".to_string()
+}
+
+/// Escapes characters that would break a table cell or heading.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Market, MetricsResponse};
+
+    fn snapshot(parcl_id: i64, name: &str, sales: [i64; 2]) -> MarketSnapshot {
+        let market: Market = serde_json::from_value(serde_json::json!({
+            "parcl_id": parcl_id,
+            "name": name,
+            "state_abbreviation": "TX",
+            "location_type": "CITY"
+        }))
+        .unwrap();
+        let mut event_counts = MetricsResponse::empty(parcl_id);
+        event_counts.items = serde_json::from_value(serde_json::json!([
+            {"date": "2024-02-01", "sales": sales[0], "new_listings_for_sale": 800,
+             "new_rental_listings": null},
+            {"date": "2024-01-01", "sales": sales[1], "new_listings_for_sale": 800,
+             "new_rental_listings": 900},
+        ]))
+        .unwrap();
+        let mut event_prices = MetricsResponse::empty(parcl_id);
+        event_prices.items = serde_json::from_value(serde_json::json!([
+            {"date": "2024-02-01", "price": {"median": {"sales": 400000.0}}},
+            {"date": "2024-01-01", "price": {"median": {"sales": 425000.0}}},
+        ]))
+        .unwrap();
+        MarketSnapshot {
+            market,
+            event_counts,
+            event_prices,
+            housing_stock: MetricsResponse::empty(parcl_id),
+        }
+    }
+
+    #[test]
+    fn single_market_table_shows_deltas_and_trends() {
+        let text = markdown(&snapshot(7, "Austin", [1500, 1200]).into());
+        assert_eq!(
+            text,
+            "# Austin\n\
+             \n\
+             ## Austin, TX (CITY, parcl_id 7)\n\
+             \n\
+             | Metric | 2024-02-01 | Previous | Change | Trend | History |\n\
+             |---|---:|---:|---:|:-:|---|\n\
+             | Sales | 1.5K | 1.2K | +25.0% | ↑ | ▁█ |\n\
+             | New listings for sale | 800 | 800 | +0.0% | → | ▄▄ |\n\
+             | New rental listings | n/a | 900 | n/a |  |  |\n\
+             | Median sale price | $400K | $425K | -5.9% | ↓ | █▁ |\n\
+             | Median list price | n/a | n/a | n/a |  |  |\n\
+             | Median rent | n/a | n/a | n/a |  |  |\n\
+             | Housing stock | n/a | n/a | n/a |  |  |\n"
+        );
+    }
+
+    #[test]
+    fn several_markets_get_a_comparison_table() {
+        let report = MarketReport::new("Texas | monthly")
+            .market(snapshot(7, "Austin", [1500, 1200]))
+            .market(snapshot(8, "Dallas", [4000, 4100]));
+        let text = markdown(&report);
+        assert!(text.starts_with("# Texas \\| monthly\n\n## Comparison\n"));
+        assert!(text.contains("| Market | Sales | New listings for sale |"));
+        assert!(text.contains("\n| Dallas | 4.0K | 800 | n/a | $400K |"));
+        assert_eq!(text.matches("| Metric |").count(), 2);
+
+        let empty = MarketReport::new("Empty").market(MarketSnapshot {
+            event_counts: MetricsResponse::empty(9),
+            event_prices: MetricsResponse::empty(9),
+            housing_stock: MetricsResponse::empty(9),
+            ..snapshot(9, "Waco", [0, 0])
+        });
+        assert!(markdown(&empty).ends_with("_No data._\n"));
+    }
+}
@@ -0,0 +1,387 @@
+//! Timers used for retry backoff, quota pauses and request throttling.
+//!
+//! The client never sleeps or reads the time directly; it asks its
+//! [`Timer`], which doubles as its clock. The default is `TokioTimer` with
+//! the `tokio` feature (on by default), else `AsyncStdTimer` with the
+//! `async-std` feature, else [`ThreadTimer`], which needs no runtime. Other
+//! runtimes implement [`Timer`] and install it with
+//! `ParclClient::with_timer`, typically alongside a matching
+//! [`Transport`](crate::Transport).
+//!
+//! In tests, [`MockClock`] replaces real waiting with virtual time, so retry
+//! backoff and quota pauses finish instantly and deterministically:
+//!
+//! ```
+//! use parcllabs::runtime::MockClock;
+//! use parcllabs::ParclClient;
+//! use std::time::Duration;
+//!
+//! let clock = MockClock::new();
+//! let client = ParclClient::with_api_key("test").with_timer(clock.clone());
+//! // ... exercise code that retries, then:
+//! assert!(clock.sleeps().iter().all(|d| *d <= Duration::from_secs(60)));
+//! ```
+
+use std::future::{poll_fn, Future};
+use std::pin::{pin, Pin};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Boxed future returned by [`Timer::sleep`].
+pub type SleepFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Waits for a duration on some async runtime, and tells the time.
+pub trait Timer: Send + Sync {
+    fn sleep(&self, duration: Duration) -> SleepFuture;
+
+    /// The current time, used for quota pauses, rate-limit observations and
+    /// cache ages. Defaults to `Instant::now()`.
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    /// The wall-clock time, used for date windows such as a watchdog's
+    /// lookback. Defaults to `SystemTime::now()`.
+    fn system_now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// The default [`Timer`], backed by `tokio::time::sleep`.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioTimer;
+
+#[cfg(feature = "tokio")]
+impl Timer for TokioTimer {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// A [`Timer`] backed by `async_std::task::sleep`.
+#[cfg(feature = "async-std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncStdTimer;
+
+#[cfg(feature = "async-std")]
+impl Timer for AsyncStdTimer {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
+
+/// A [`Timer`] that needs no async runtime: each sleep waits on a helper
+/// thread, then wakes the task.
+///
+/// The default when neither the `tokio` nor the `async-std` feature is
+/// enabled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadTimer;
+
+impl Timer for ThreadTimer {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        if duration.is_zero() {
+            return Box::pin(async {});
+        }
+        let state: Arc<Mutex<(bool, Option<Waker>)>> = Arc::default();
+        let shared = Arc::clone(&state);
+        std::thread::spawn(move || {
+            std::thread::sleep(duration);
+            let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+            state.0 = true;
+            if let Some(waker) = state.1.take() {
+                waker.wake();
+            }
+        });
+        Box::pin(poll_fn(move |cx| {
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            if state.0 {
+                Poll::Ready(())
+            } else {
+                state.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }))
+    }
+}
+
+/// The timer a new client starts with; see the module docs.
+pub(crate) fn default_timer() -> Arc<dyn Timer> {
+    #[cfg(feature = "tokio")]
+    return Arc::new(TokioTimer);
+    #[cfg(all(not(feature = "tokio"), feature = "async-std"))]
+    return Arc::new(AsyncStdTimer);
+    #[cfg(not(any(feature = "tokio", feature = "async-std")))]
+    return Arc::new(ThreadTimer);
+}
+
+/// Runs two fallible futures concurrently on any executor, failing as soon
+/// as either does.
+pub(crate) async fn try_join<A, B, E>(
+    a: impl Future<Output = Result<A, E>>,
+    b: impl Future<Output = Result<B, E>>,
+) -> Result<(A, B), E> {
+    let (mut a, mut b) = (pin!(a), pin!(b));
+    let (mut a_out, mut b_out) = (None, None);
+    poll_fn(|cx| {
+        if a_out.is_none() {
+            if let Poll::Ready(out) = a.as_mut().poll(cx) {
+                a_out = Some(out?);
+            }
+        }
+        if b_out.is_none() {
+            if let Poll::Ready(out) = b.as_mut().poll(cx) {
+                b_out = Some(out?);
+            }
+        }
+        match (a_out.take(), b_out.take()) {
+            (Some(a), Some(b)) => Poll::Ready(Ok((a, b))),
+            (a, b) => {
+                (a_out, b_out) = (a, b);
+                Poll::Pending
+            }
+        }
+    })
+    .await
+}
+
+/// Runs `task` on every item, at most `limit` at a time, on any executor,
+/// handing each output to `on_done` as it completes. Returns the first error
+/// from `on_done`, dropping the tasks still in flight.
+pub(crate) async fn for_each_concurrent<T, F: Future, E>(
+    items: impl IntoIterator<Item = T>,
+    limit: usize,
+    mut task: impl FnMut(T) -> F,
+    mut on_done: impl FnMut(F::Output) -> Result<(), E>,
+) -> Result<(), E> {
+    let limit = limit.max(1);
+    let mut queue = items.into_iter();
+    let mut in_flight = Vec::with_capacity(limit);
+    loop {
+        while in_flight.len() < limit {
+            let Some(item) = queue.next() else {
+                break;
+            };
+            in_flight.push(Box::pin(task(item)));
+        }
+        if in_flight.is_empty() {
+            return Ok(());
+        }
+        let done = poll_fn(|cx| {
+            for i in 0..in_flight.len() {
+                if let Poll::Ready(done) = in_flight[i].as_mut().poll(cx) {
+                    drop(in_flight.swap_remove(i));
+                    return Poll::Ready(done);
+                }
+            }
+            Poll::Pending
+        })
+        .await;
+        on_done(done)?;
+    }
+}
+
+/// A [`Timer`] running on virtual time, for tests.
+///
+/// `sleep` returns immediately and moves the clock forward by the requested
+/// duration; [`advance`](Self::advance) moves it without sleeping. Clones
+/// share the same time, so keep one to inspect after handing another to the
+/// client.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    start: Instant,
+    start_system: SystemTime,
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    elapsed: Duration,
+    sleeps: Vec<Duration>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            start_system: SystemTime::now(),
+            state: Arc::default(),
+        }
+    }
+
+    /// Starts the wall clock at `time` instead of the real time, so date
+    /// windows are deterministic. Set it before cloning the clock.
+    pub fn starting_at(mut self, time: SystemTime) -> Self {
+        self.start_system = time;
+        self
+    }
+
+    /// Moves the clock forward without recording a sleep.
+    pub fn advance(&self, duration: Duration) {
+        self.lock().elapsed += duration;
+    }
+
+    /// Virtual time passed since the clock was created.
+    pub fn elapsed(&self) -> Duration {
+        self.lock().elapsed
+    }
+
+    /// Every sleep requested so far, in order.
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.lock().sleeps.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Timer for MockClock {
+    fn sleep(&self, duration: Duration) -> SleepFuture {
+        let mut state = self.lock();
+        state.elapsed += duration;
+        state.sleeps.push(duration);
+        Box::pin(async {})
+    }
+
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn system_now(&self) -> SystemTime {
+        self.start_system + self.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CannedTransport;
+    use crate::RetryConfig;
+    use std::sync::{Arc, Mutex};
+
+    /// Records requested sleeps without waiting.
+    #[derive(Clone, Default)]
+    struct RecordingTimer {
+        sleeps: Arc<Mutex<Vec<Duration>>>,
+    }
+
+    impl Timer for RecordingTimer {
+        fn sleep(&self, duration: Duration) -> SleepFuture {
+            self.sleeps.lock().unwrap().push(duration);
+            Box::pin(async {})
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_backoff_goes_through_timer() {
+        let timer = RecordingTimer::default();
+        let client = crate::ParclClient::with_config("key", "https://example.com")
+            .with_retry_config(RetryConfig {
+                max_retries: 2,
+                initial_backoff_ms: 60_000,
+            })
+            .with_transport(CannedTransport::json(429, serde_json::Value::Null))
+            .with_timer(timer.clone());
+
+        let err = client
+            .market_metrics()
+            .housing_event_counts(7, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::ParclError::RateLimited { .. }));
+        assert_eq!(
+            *timer.sleeps.lock().unwrap(),
+            vec![Duration::from_secs(60), Duration::from_secs(120)]
+        );
+    }
+
+    #[tokio::test]
+    async fn mock_clock_moves_on_sleep_and_advance() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        clock.sleep(Duration::from_secs(30)).await;
+        clock.clone().advance(Duration::from_secs(5));
+        assert_eq!(clock.now() - start, Duration::from_secs(35));
+        assert_eq!(clock.elapsed(), Duration::from_secs(35));
+        assert_eq!(clock.sleeps(), [Duration::from_secs(30)]);
+    }
+
+    #[tokio::test]
+    async fn thread_timer_sleeps_without_a_runtime_timer() {
+        let start = Instant::now();
+        ThreadTimer.sleep(Duration::from_millis(10)).await;
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+
+    #[tokio::test]
+    async fn try_join_fails_without_waiting_for_the_other() {
+        let slow = async {
+            ThreadTimer.sleep(Duration::from_secs(60)).await;
+            Ok(1)
+        };
+        let failed = async { Err::<i32, _>("boom") };
+        let start = Instant::now();
+        assert_eq!(try_join(slow, failed).await, Err("boom"));
+        assert!(start.elapsed() < Duration::from_secs(60));
+
+        let both = try_join(async { Ok::<_, ()>(1) }, async { Ok(2) }).await;
+        assert_eq!(both, Ok((1, 2)));
+    }
+
+    #[tokio::test]
+    async fn for_each_concurrent_bounds_in_flight_and_stops_on_error() {
+        let running = Arc::new(Mutex::new((0, 0)));
+        let task = |n: u64| {
+            let running = Arc::clone(&running);
+            async move {
+                {
+                    let mut running = running.lock().unwrap();
+                    running.0 += 1;
+                    running.1 = running.1.max(running.0);
+                }
+                ThreadTimer.sleep(Duration::from_millis(10 * (5 - n))).await;
+                running.lock().unwrap().0 -= 1;
+                n
+            }
+        };
+
+        let mut done = Vec::new();
+        let result = for_each_concurrent(0..5, 2, task, |n| {
+            done.push(n);
+            Ok::<_, ()>(())
+        })
+        .await;
+        assert_eq!(result, Ok(()));
+        assert_eq!(done.len(), 5);
+        assert_eq!(running.lock().unwrap().1, 2);
+
+        let mut seen = 0;
+        let result = for_each_concurrent(0..5, 1, task, |n| {
+            seen += 1;
+            if n == 1 {
+                Err(n)
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+        assert_eq!((result, seen), (Err(1), 2));
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn async_std_timer_sleeps_without_tokio() {
+        let start = std::time::Instant::now();
+        async_std::task::block_on(AsyncStdTimer.sleep(Duration::from_millis(10)));
+        assert!(start.elapsed() >= Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,297 @@
+//! Response schema drift detection.
+//!
+//! With the `schema-validation` feature, every successful response is checked
+//! against the JSON Schemas bundled in `schemas/responses.json`. Discrepancies
+//! (fields the SDK does not know about, missing required fields, changed
+//! types) are reported to a handler instead of failing the request, so API
+//! drift shows up in production logs before it breaks deserialization.
+//!
+//! The validator understands the subset of JSON Schema used by the bundled
+//! file: `type`, `properties`, `required`, `additionalProperties`, `items`,
+//! `anyOf` and local `$ref`s.
+
+use serde_json::Value;
+use std::sync::{Arc, OnceLock};
+
+/// Receives each schema discrepancy found in a response.
+pub type SchemaDriftHandler = Arc<dyn Fn(&SchemaDrift) + Send + Sync>;
+
+/// What kind of discrepancy was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DriftKind {
+    /// The response has a field the SDK models do not declare.
+    UnknownField,
+    /// A field the SDK models require is absent.
+    MissingField,
+    /// A field has a different JSON type than the SDK models expect.
+    TypeMismatch {
+        expected: String,
+        found: &'static str,
+    },
+}
+
+/// A single discrepancy between a response and its bundled schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaDrift {
+    pub endpoint: &'static str,
+    /// JSON path of the field, e.g. `items[0].sales`.
+    pub path: String,
+    pub kind: DriftKind,
+}
+
+impl std::fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            DriftKind::UnknownField => {
+                write!(f, "{}: unknown field `{}`", self.endpoint, self.path)
+            }
+            DriftKind::MissingField => {
+                write!(f, "{}: missing field `{}`", self.endpoint, self.path)
+            }
+            DriftKind::TypeMismatch { expected, found } => write!(
+                f,
+                "{}: `{}` is {found}, expected {expected}",
+                self.endpoint, self.path
+            ),
+        }
+    }
+}
+
+/// The default handler: writes each discrepancy to stderr.
+pub(crate) fn log_drift(drift: &SchemaDrift) {
+    eprintln!("parcllabs: schema drift: {drift}");
+}
+
+fn bundled() -> &'static Value {
+    static SCHEMAS: OnceLock<Value> = OnceLock::new();
+    SCHEMAS.get_or_init(|| {
+        serde_json::from_str(include_str!("../schemas/responses.json"))
+            .expect("bundled schemas are valid JSON")
+    })
+}
+
+/// Returns true if a bundled schema exists for `endpoint`.
+pub fn has_schema(endpoint: &str) -> bool {
+    bundled()["endpoints"].get(endpoint).is_some()
+}
+
+/// Checks a response body against the bundled schema for `endpoint`.
+///
+/// Returns no discrepancies for endpoints without a bundled schema.
+pub fn check(endpoint: &'static str, body: &Value) -> Vec<SchemaDrift> {
+    let schemas = bundled();
+    let mut validator = Validator {
+        endpoint,
+        defs: &schemas["$defs"],
+        drift: Vec::new(),
+    };
+    let target = &schemas["endpoints"][endpoint];
+
+    if let Some(root) = target["root"].as_str() {
+        validator.validate(&def_ref(root), body, "");
+    } else if let Some(item) = target["items"].as_str() {
+        validator.validate(&def_ref("Envelope"), body, "");
+        let item_schema = def_ref(item);
+        if let Some(items) = body["items"].as_array() {
+            for (i, value) in items.iter().enumerate() {
+                validator.validate(&item_schema, value, &format!("items[{i}]"));
+            }
+        }
+    }
+    validator.drift
+}
+
+fn def_ref(name: &str) -> Value {
+    serde_json::json!({ "$ref": format!("#/$defs/{name}") })
+}
+
+struct Validator<'a> {
+    endpoint: &'static str,
+    defs: &'a Value,
+    drift: Vec<SchemaDrift>,
+}
+
+impl Validator<'_> {
+    fn report(&mut self, path: &str, kind: DriftKind) {
+        self.drift.push(SchemaDrift {
+            endpoint: self.endpoint,
+            path: path.to_string(),
+            kind,
+        });
+    }
+
+    fn validate(&mut self, schema: &Value, value: &Value, path: &str) {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let defs = self.defs;
+            let name = reference.trim_start_matches("#/$defs/");
+            return self.validate(&defs[name], value, path);
+        }
+
+        if let Some(branches) = schema["anyOf"].as_array() {
+            // Report against the branch that fits best.
+            let best = branches
+                .iter()
+                .map(|branch| {
+                    let mut inner = Validator {
+                        endpoint: self.endpoint,
+                        defs: self.defs,
+                        drift: Vec::new(),
+                    };
+                    inner.validate(branch, value, path);
+                    inner.drift
+                })
+                .min_by_key(Vec::len)
+                .unwrap_or_default();
+            self.drift.extend(best);
+            return;
+        }
+
+        if let Some(expected) = type_names(&schema["type"]) {
+            if !expected.iter().any(|t| matches_type(t, value)) {
+                self.report(
+                    path,
+                    DriftKind::TypeMismatch {
+                        expected: expected.join(" or "),
+                        found: json_type(value),
+                    },
+                );
+                return;
+            }
+        }
+
+        match value {
+            Value::Object(map) => {
+                let properties = schema["properties"].as_object();
+                if let Some(required) = schema["required"].as_array() {
+                    for key in required.iter().filter_map(Value::as_str) {
+                        if !map.contains_key(key) {
+                            self.report(&join(path, key), DriftKind::MissingField);
+                        }
+                    }
+                }
+                for (key, field) in map {
+                    match properties.and_then(|p| p.get(key)) {
+                        Some(field_schema) => self.validate(field_schema, field, &join(path, key)),
+                        None if schema["additionalProperties"] == Value::Bool(false) => {
+                            self.report(&join(path, key), DriftKind::UnknownField)
+                        }
+                        None => {}
+                    }
+                }
+            }
+            Value::Array(items) if schema.get("items").is_some() => {
+                for (i, item) in items.iter().enumerate() {
+                    self.validate(&schema["items"], item, &format!("{path}[{i}]"));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn type_names(ty: &Value) -> Option<Vec<&str>> {
+    match ty {
+        Value::String(s) => Some(vec![s.as_str()]),
+        Value::Array(list) => Some(list.iter().filter_map(Value::as_str).collect()),
+        _ => None,
+    }
+}
+
+fn matches_type(ty: &str, value: &Value) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn bundled_schemas_cover_endpoints() {
+        assert!(has_schema("/v1/market_metrics/housing_event_counts"));
+        assert!(has_schema("/v1/search/markets"));
+        assert!(has_schema("/v2/property_search"));
+        assert!(!has_schema("/v1/unknown"));
+    }
+
+    #[test]
+    fn matching_response_has_no_drift() {
+        let body = json!({
+            "parcl_id": 1,
+            "items": [{"parcl_id": 1, "date": "2024-01-01", "sales": 10,
+                       "new_listings_for_sale": null, "new_rental_listings": 3}],
+            "total": 1, "limit": 1, "offset": 0,
+            "links": {"first": "x", "next": null, "prev": null, "last": "x"},
+            "account": {"est_credits_used": 1, "est_remaining_credits": 99}
+        });
+        assert!(check("/v1/market_metrics/housing_event_counts", &body).is_empty());
+    }
+
+    #[test]
+    fn reports_unknown_missing_and_type_changes() {
+        let body = json!({
+            "parcl_id": 1,
+            "items": [{"sales": "ten", "new_metric": 4}],
+            "total": 1, "limit": 1, "offset": 0,
+            "links": {},
+            "request_id": "abc"
+        });
+        let drift = check("/v1/market_metrics/housing_event_counts", &body);
+        let describe: Vec<String> = drift.iter().map(ToString::to_string).collect();
+        assert!(drift.contains(&SchemaDrift {
+            endpoint: "/v1/market_metrics/housing_event_counts",
+            path: "request_id".into(),
+            kind: DriftKind::UnknownField,
+        }));
+        assert!(drift
+            .iter()
+            .any(|d| d.path == "items[0].date" && d.kind == DriftKind::MissingField));
+        assert!(drift
+            .iter()
+            .any(|d| d.path == "items[0].new_metric" && d.kind == DriftKind::UnknownField));
+        assert!(describe.contains(
+            &"/v1/market_metrics/housing_event_counts: `items[0].sales` is string, expected integer or null"
+                .to_string()
+        ));
+    }
+
+    #[test]
+    fn nested_optional_objects_are_checked() {
+        let body = json!({
+            "items": [{"parcl_property_id": 1, "city": "Austin", "bedrooms": 3.5}],
+            "account": null
+        });
+        let drift = check("/v1/property/search", &body);
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].path, "items[0].bedrooms");
+    }
+}
@@ -0,0 +1,73 @@
+//! Destinations for rows produced by batch jobs.
+//!
+//! A [`Sink`] accepts batches of serializable rows, typically metric items
+//! or properties, and lands them somewhere durable. With the `postgres`
+//! feature, `PostgresSink` upserts them into a PostgreSQL table; with the
+//! `nats` feature, `NatsSink` publishes them as messages so downstream
+//! consumers see new data points as they are fetched.
+
+#[cfg(feature = "nats")]
+mod nats;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(any(feature = "sqlite", feature = "postgres", feature = "xlsx"))]
+pub(crate) mod rows;
+
+#[cfg(feature = "nats")]
+pub use nats::NatsSink;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresSink;
+
+use crate::error::Result;
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Boxed future returned by [`Sink::write_batch`], resolving to the number of
+/// rows written.
+pub type SinkFuture<'a> = Pin<Box<dyn Future<Output = Result<u64>> + Send + 'a>>;
+
+/// Writes batches of rows to a destination.
+///
+/// Implementations should make a batch land atomically where the
+/// destination allows it, so a failed job can rerun the same batch.
+pub trait Sink<R: Serialize + Sync>: Send + Sync {
+    fn write_batch<'a>(&'a self, rows: &'a [R]) -> SinkFuture<'a>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Collects rows in memory.
+    #[derive(Default)]
+    struct MemorySink(Mutex<Vec<serde_json::Value>>);
+
+    impl<R: Serialize + Sync> Sink<R> for MemorySink {
+        fn write_batch<'a>(&'a self, rows: &'a [R]) -> SinkFuture<'a> {
+            Box::pin(async move {
+                let values = rows
+                    .iter()
+                    .map(serde_json::to_value)
+                    .collect::<serde_json::Result<Vec<_>>>()?;
+                let written = values.len() as u64;
+                self.0.lock().unwrap().extend(values);
+                Ok(written)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn sinks_are_object_safe() {
+        let memory = MemorySink::default();
+        let sink: &dyn Sink<crate::models::HousingStock> = &memory;
+        let rows: Vec<crate::models::HousingStock> = serde_json::from_value(serde_json::json!([
+            {"date": "2024-01-01", "single_family": 1},
+            {"date": "2024-02-01", "single_family": 2}
+        ]))
+        .unwrap();
+        assert_eq!(sink.write_batch(&rows).await.unwrap(), 2);
+        assert_eq!(memory.0.lock().unwrap()[1]["single_family"], 2);
+    }
+}
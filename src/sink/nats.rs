@@ -0,0 +1,134 @@
+//! NATS sink backed by async-nats.
+
+use super::{Sink, SinkFuture};
+use crate::error::{ParclError, Result};
+use serde::Serialize;
+
+/// Publishes each row as a JSON message to a NATS subject.
+///
+/// With [`subject_by`](Self::subject_by), the value of a row field is
+/// appended to the subject, e.g. `parcl.housing_stock.2900187`, so consumers
+/// can subscribe to single markets. The batch is flushed before
+/// `write_batch` returns.
+///
+/// ```no_run
+/// use parcllabs::sink::{NatsSink, Sink};
+/// use parcllabs::ParclClient;
+///
+/// # async fn example() -> parcllabs::Result<()> {
+/// let client = ParclClient::new()?;
+/// let sink = NatsSink::connect("nats://localhost:4222", "parcl.housing_stock")
+///     .await?
+///     .subject_by("parcl_id");
+///
+/// let stock = client.market_metrics().batch_housing_stock(vec![2900187, 2900078], None).await?;
+/// sink.write_batch(&stock.items).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct NatsSink {
+    client: async_nats::Client,
+    subject: String,
+    subject_field: Option<String>,
+}
+
+impl NatsSink {
+    /// Publishes to `subject` through an existing connection.
+    pub fn new(client: async_nats::Client, subject: impl Into<String>) -> Self {
+        Self {
+            client,
+            subject: subject.into(),
+            subject_field: None,
+        }
+    }
+
+    /// Connects to the server at `url` and publishes to `subject`.
+    pub async fn connect(url: &str, subject: impl Into<String>) -> Result<Self> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| ParclError::Nats(e.into()))?;
+        Ok(Self::new(client, subject))
+    }
+
+    /// Appends the value of `field` in each row as a final subject token.
+    ///
+    /// Rows where the field is missing or null go to the base subject.
+    pub fn subject_by(mut self, field: impl Into<String>) -> Self {
+        self.subject_field = Some(field.into());
+        self
+    }
+
+    fn subject_for(&self, row: &serde_json::Value) -> String {
+        let token = self
+            .subject_field
+            .as_deref()
+            .and_then(|field| match row.get(field)? {
+                serde_json::Value::String(s) => Some(subject_token(s)),
+                serde_json::Value::Null => None,
+                other => Some(subject_token(&other.to_string())),
+            });
+        match token {
+            Some(token) if !token.is_empty() => format!("{}.{token}", self.subject),
+            _ => self.subject.clone(),
+        }
+    }
+}
+
+/// Replaces characters NATS reserves in subjects.
+fn subject_token(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '.' | '*' | '>' => '_',
+            c if c.is_whitespace() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+impl<R: Serialize + Sync> Sink<R> for NatsSink {
+    fn write_batch<'a>(&'a self, rows: &'a [R]) -> SinkFuture<'a> {
+        Box::pin(async move {
+            for row in rows {
+                let value = serde_json::to_value(row)?;
+                let payload = serde_json::to_vec(&value)?;
+                self.client
+                    .publish(self.subject_for(&value), payload.into())
+                    .await
+                    .map_err(|e| ParclError::Nats(e.into()))?;
+            }
+            self.client
+                .flush()
+                .await
+                .map_err(|e| ParclError::Nats(e.into()))?;
+            Ok(rows.len() as u64)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn subject_includes_sanitized_field_value() {
+        // Connecting lazily never touches the network until first use.
+        let client = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect("nats://127.0.0.1:1")
+            .await
+            .unwrap();
+        let sink = NatsSink::new(client, "parcl.stock").subject_by("name");
+
+        let row = serde_json::json!({"parcl_id": 7, "name": "St. Louis"});
+        assert_eq!(sink.subject_for(&row), "parcl.stock.St__Louis");
+        let row = serde_json::json!({"name": null});
+        assert_eq!(sink.subject_for(&row), "parcl.stock");
+        let sink = sink.subject_by("parcl_id");
+        assert_eq!(
+            sink.subject_for(&serde_json::json!({"parcl_id": 7})),
+            "parcl.stock.7"
+        );
+    }
+}
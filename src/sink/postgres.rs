@@ -0,0 +1,230 @@
+//! PostgreSQL sink backed by sqlx.
+
+use super::rows::{column_names, flatten, quote, sample, METRIC_KEY};
+use super::{Sink, SinkFuture};
+use crate::error::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::postgres::PgPool;
+use sqlx::types::Json;
+
+/// Upserts rows into a PostgreSQL table.
+///
+/// Rows are flattened like the SQLite exporter (`price_median_sales`). The
+/// table is created on the first batch, gains columns as new fields appear,
+/// and is keyed by whichever of the key columns the rows have (by default
+/// `parcl_id`, `date` and `property_type`), so rerunning a job updates rows
+/// instead of duplicating them. Each batch is one transaction.
+///
+/// The `postgres` feature enables sqlx without TLS; add sqlx's `tls-*`
+/// features in your own manifest to reach servers that require it.
+///
+/// ```no_run
+/// use parcllabs::sink::{PostgresSink, Sink};
+/// use parcllabs::ParclClient;
+///
+/// # async fn example() -> parcllabs::Result<()> {
+/// let client = ParclClient::new()?;
+/// let sink = PostgresSink::connect("postgres://localhost/housing", "housing_stock").await?;
+///
+/// let stock = client.market_metrics().batch_housing_stock(vec![2900187, 2900078], None).await?;
+/// sink.write_batch(&stock.items).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PostgresSink {
+    pool: PgPool,
+    table: String,
+    key: Vec<String>,
+}
+
+impl PostgresSink {
+    /// Writes to `table` through an existing pool.
+    pub fn new(pool: PgPool, table: impl Into<String>) -> Self {
+        Self {
+            pool,
+            table: table.into(),
+            key: METRIC_KEY.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    /// Connects a new pool to `url` and writes to `table`.
+    pub async fn connect(url: &str, table: impl Into<String>) -> Result<Self> {
+        Ok(Self::new(PgPool::connect(url).await?, table))
+    }
+
+    /// Columns that identify a row, e.g. `["parcl_property_id"]` for
+    /// properties.
+    pub fn key(mut self, columns: &[&str]) -> Self {
+        self.key = columns.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    async fn write_rows(&self, rows: Vec<Map<String, Value>>) -> Result<u64> {
+        let columns = column_names(&rows);
+        if columns.is_empty() {
+            return Ok(0);
+        }
+        let key: Vec<&str> = self
+            .key
+            .iter()
+            .map(String::as_str)
+            .filter(|k| columns.contains(k))
+            .collect();
+
+        let mut tx = self.pool.begin().await?;
+        let existing: Vec<String> = sqlx::query_scalar(
+            "SELECT column_name::text FROM information_schema.columns \
+             WHERE table_schema = current_schema() AND table_name = $1",
+        )
+        .bind(&self.table)
+        .fetch_all(&mut *tx)
+        .await?;
+        for statement in schema_sql(&self.table, &rows, &key, &existing) {
+            sqlx::query(&statement).execute(&mut *tx).await?;
+        }
+        let written = sqlx::query(&upsert_sql(&self.table, &columns, &key))
+            .bind(Json(&rows))
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        tx.commit().await?;
+        Ok(written)
+    }
+}
+
+impl<R: Serialize + Sync> Sink<R> for PostgresSink {
+    fn write_batch<'a>(&'a self, rows: &'a [R]) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let rows = rows.iter().map(flatten).collect::<Result<Vec<_>>>()?;
+            self.write_rows(rows).await
+        })
+    }
+}
+
+fn pg_type(value: &Value) -> &'static str {
+    match value {
+        Value::Bool(_) => "BOOLEAN",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "BIGINT",
+        Value::Number(_) => "DOUBLE PRECISION",
+        Value::Array(_) | Value::Object(_) => "JSONB",
+        Value::String(_) | Value::Null => "TEXT",
+    }
+}
+
+/// `CREATE TABLE` for a new table, or `ALTER TABLE` for columns missing
+/// from `existing`.
+fn schema_sql(
+    table: &str,
+    rows: &[Map<String, Value>],
+    key: &[&str],
+    existing: &[String],
+) -> Vec<String> {
+    let columns = column_names(rows);
+    let definition = |c: &str| format!("{} {}", quote(c), pg_type(sample(rows, c)));
+    if existing.is_empty() {
+        let mut defs: Vec<String> = columns.iter().map(|c| definition(c)).collect();
+        if !key.is_empty() {
+            let key: Vec<String> = key.iter().map(|k| quote(k)).collect();
+            defs.push(format!("PRIMARY KEY ({})", key.join(", ")));
+        }
+        return vec![format!(
+            "CREATE TABLE IF NOT EXISTS {} ({})",
+            quote(table),
+            defs.join(", ")
+        )];
+    }
+    columns
+        .iter()
+        .filter(|c| !existing.iter().any(|e| e == *c))
+        .map(|c| {
+            format!(
+                "ALTER TABLE {} ADD COLUMN IF NOT EXISTS {}",
+                quote(table),
+                definition(c)
+            )
+        })
+        .collect()
+}
+
+/// Inserts the JSON array bound as `$1`, letting PostgreSQL convert each
+/// field to its column type.
+fn upsert_sql(table: &str, columns: &[&str], key: &[&str]) -> String {
+    let table = quote(table);
+    let list = columns
+        .iter()
+        .map(|c| quote(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sql = format!(
+        "INSERT INTO {table} ({list}) SELECT {list} FROM jsonb_populate_recordset(NULL::{table}, $1)"
+    );
+    if !key.is_empty() {
+        let target = key.iter().map(|k| quote(k)).collect::<Vec<_>>().join(", ");
+        let updates: Vec<String> = columns
+            .iter()
+            .filter(|c| !key.contains(c))
+            .map(|c| format!("{0} = EXCLUDED.{0}", quote(c)))
+            .collect();
+        if updates.is_empty() {
+            sql.push_str(&format!(" ON CONFLICT ({target}) DO NOTHING"));
+        } else {
+            sql.push_str(&format!(
+                " ON CONFLICT ({target}) DO UPDATE SET {}",
+                updates.join(", ")
+            ));
+        }
+    }
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<Map<String, Value>> {
+        vec![
+            flatten(&serde_json::json!({"parcl_id": 7, "date": "2024-01-01", "price": null})).unwrap(),
+            flatten(&serde_json::json!({"parcl_id": 7, "date": "2024-02-01", "price": 1.5, "tags": [1]}))
+                .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn creates_table_with_key_then_adds_columns() {
+        let rows = rows();
+        let create = schema_sql("stock", &rows, &["parcl_id", "date"], &[]);
+        assert_eq!(
+            create,
+            [
+                r#"CREATE TABLE IF NOT EXISTS "stock" ("date" TEXT, "parcl_id" BIGINT, "price" DOUBLE PRECISION, "tags" JSONB, PRIMARY KEY ("parcl_id", "date"))"#
+            ]
+        );
+
+        let existing = ["parcl_id", "date", "price"].map(String::from);
+        let alter = schema_sql("stock", &rows, &["parcl_id", "date"], &existing);
+        assert_eq!(
+            alter,
+            [r#"ALTER TABLE "stock" ADD COLUMN IF NOT EXISTS "tags" JSONB"#]
+        );
+    }
+
+    #[test]
+    fn upserts_on_key_columns() {
+        let sql = upsert_sql(
+            "stock",
+            &["parcl_id", "date", "price"],
+            &["parcl_id", "date"],
+        );
+        assert_eq!(
+            sql,
+            r#"INSERT INTO "stock" ("parcl_id", "date", "price") SELECT "parcl_id", "date", "price" FROM jsonb_populate_recordset(NULL::"stock", $1) ON CONFLICT ("parcl_id", "date") DO UPDATE SET "price" = EXCLUDED."price""#
+        );
+        assert!(!upsert_sql("events", &["a"], &[]).contains("ON CONFLICT"));
+    }
+}
@@ -0,0 +1,84 @@
+//! Flattening rows into columns, shared by the SQLite exporter, the
+//! PostgreSQL sink and the Excel exporter.
+
+use crate::error::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Columns that identify a metric row, when present.
+pub(crate) const METRIC_KEY: &[&str] = &["parcl_id", "date", "property_type"];
+
+/// Serializes `value` into a flat column map, joining nested keys with `_`,
+/// so `price.median.sales` becomes `price_median_sales`.
+pub(crate) fn flatten<T: Serialize>(value: &T) -> Result<Map<String, Value>> {
+    fn walk(prefix: &str, value: Value, out: &mut Map<String, Value>) {
+        match value {
+            Value::Object(fields) => {
+                for (key, value) in fields {
+                    let name = if prefix.is_empty() {
+                        key
+                    } else {
+                        format!("{prefix}_{key}")
+                    };
+                    walk(&name, value, out);
+                }
+            }
+            other => {
+                out.insert(prefix.to_string(), other);
+            }
+        }
+    }
+    let mut out = Map::new();
+    match serde_json::to_value(value)? {
+        Value::Object(fields) => walk("", Value::Object(fields), &mut out),
+        other => walk("value", other, &mut out),
+    }
+    Ok(out)
+}
+
+/// Column names across `rows`, in first-seen order (keys within a row are
+/// sorted).
+pub(crate) fn column_names(rows: &[Map<String, Value>]) -> Vec<&str> {
+    let mut names: Vec<&str> = Vec::new();
+    for row in rows {
+        for column in row.keys() {
+            if !names.contains(&column.as_str()) {
+                names.push(column);
+            }
+        }
+    }
+    names
+}
+
+/// The first non-null value of `column` across `rows`, for its SQL type.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn sample<'a>(rows: &'a [Map<String, Value>], column: &str) -> &'a Value {
+    rows.iter()
+        .filter_map(|row| row.get(column))
+        .find(|v| !v.is_null())
+        .unwrap_or(&Value::Null)
+}
+
+/// Quotes a SQL identifier.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_joins_nested_keys() {
+        let row = flatten(&serde_json::json!({
+            "date": "2024-01-01",
+            "price": {"median": {"sales": 1.5}},
+            "tags": [1, 2]
+        }))
+        .unwrap();
+        assert_eq!(row["price_median_sales"], 1.5);
+        assert_eq!(row["tags"], serde_json::json!([1, 2]));
+        assert_eq!(flatten(&3).unwrap()["value"], 3);
+    }
+}
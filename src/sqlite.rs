@@ -0,0 +1,401 @@
+//! Snapshots of API responses in a SQLite database.
+//!
+//! Enabled with the `sqlite` feature. [`SqliteExporter`] writes metric
+//! series into one table per metric and property searches into
+//! `properties` and `property_events`, so results can be queried and joined
+//! with plain SQL. Tables are created from the first rows written and gain
+//! columns as new fields appear. Nested objects such as `PriceStats` are
+//! flattened into `price_median_sales`-style columns.
+//!
+//! ```no_run
+//! use parcllabs::sqlite::SqliteExporter;
+//! use parcllabs::ParclClient;
+//!
+//! # async fn example() -> parcllabs::Result<()> {
+//! let client = ParclClient::new()?;
+//! let mut db = SqliteExporter::open("parcl.db")?;
+//!
+//! let prices = client.market_metrics().housing_event_prices(2900187, None).await?;
+//! db.write_metrics("housing_event_prices", &prices)?;
+//! // SELECT date, price_median_sales FROM housing_event_prices WHERE parcl_id = 2900187
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::models::{
+    BatchMetricsResponse, MetricsResponse, Property, PropertyV2, PropertyWithEvents,
+};
+use crate::sink::rows::{column_names, flatten, quote, sample, METRIC_KEY};
+use rusqlite::{types::Value as SqlValue, Connection, Transaction};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+const PROPERTIES: &str = "properties";
+const PROPERTY_EVENTS: &str = "property_events";
+
+/// Writes API responses into SQLite tables.
+#[derive(Debug)]
+pub struct SqliteExporter {
+    conn: Connection,
+}
+
+impl SqliteExporter {
+    /// Opens or creates the database file at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::from_connection(Connection::open(path)?))
+    }
+
+    /// Opens a database that lives only as long as the exporter.
+    pub fn in_memory() -> Result<Self> {
+        Ok(Self::from_connection(Connection::open_in_memory()?))
+    }
+
+    pub fn from_connection(conn: Connection) -> Self {
+        Self { conn }
+    }
+
+    /// The underlying connection, for queries.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    pub fn into_connection(self) -> Connection {
+        self.conn
+    }
+
+    /// Writes a single market's series into `table`, one row per item.
+    ///
+    /// Rows are keyed by `parcl_id`, `date` and, if the metric has one,
+    /// `property_type`; writing the same dates again replaces them.
+    /// Returns the number of rows written.
+    pub fn write_metrics<T: Serialize>(
+        &mut self,
+        table: &str,
+        response: &MetricsResponse<T>,
+    ) -> Result<usize> {
+        let rows = response
+            .items
+            .iter()
+            .map(|item| {
+                let mut row = flatten(item)?;
+                if row.get("parcl_id").is_none_or(Value::is_null) {
+                    row.insert("parcl_id".into(), response.parcl_id.into());
+                }
+                Ok(row)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.upsert(table, METRIC_KEY, rows)
+    }
+
+    /// Writes a batch response into `table`, like [`write_metrics`](Self::write_metrics).
+    pub fn write_batch_metrics<T: Serialize>(
+        &mut self,
+        table: &str,
+        response: &BatchMetricsResponse<T>,
+    ) -> Result<usize> {
+        let rows = response.items.iter().map(flatten).collect::<Result<_>>()?;
+        self.upsert(table, METRIC_KEY, rows)
+    }
+
+    /// Writes v1 property search results into `properties`, keyed by
+    /// `parcl_property_id`.
+    pub fn write_properties(&mut self, properties: &[Property]) -> Result<usize> {
+        let rows = properties.iter().map(flatten).collect::<Result<_>>()?;
+        self.upsert(PROPERTIES, &["parcl_property_id"], rows)
+    }
+
+    /// Writes v1 event history: metadata into `properties` and events into
+    /// `property_events`.
+    ///
+    /// A property's stored events are replaced by the ones given here.
+    /// Returns the number of events written.
+    pub fn write_event_history(&mut self, properties: &[PropertyWithEvents]) -> Result<usize> {
+        let entries = properties
+            .iter()
+            .map(|p| (p.parcl_property_id, &p.property_metadata, &p.events));
+        self.write_with_events(entries)
+    }
+
+    /// Writes v2 property search results: metadata into `properties` and
+    /// events into `property_events`, like
+    /// [`write_event_history`](Self::write_event_history).
+    pub fn write_properties_v2(&mut self, properties: &[PropertyV2]) -> Result<usize> {
+        let entries = properties
+            .iter()
+            .map(|p| (p.parcl_property_id, &p.property_metadata, &p.events));
+        self.write_with_events(entries)
+    }
+
+    fn write_with_events<'a, M, E>(
+        &mut self,
+        entries: impl Iterator<Item = (i64, &'a Option<M>, &'a Option<Vec<E>>)>,
+    ) -> Result<usize>
+    where
+        M: Serialize + 'a,
+        E: Serialize + 'a,
+    {
+        let mut properties = Vec::new();
+        let mut events = Vec::new();
+        let mut ids = Vec::new();
+        for (id, metadata, property_events) in entries {
+            ids.push(id);
+            let mut row = match metadata {
+                Some(metadata) => flatten(metadata)?,
+                None => Map::new(),
+            };
+            row.insert("parcl_property_id".into(), id.into());
+            properties.push(row);
+            for event in property_events.iter().flatten() {
+                let mut row = Map::new();
+                row.insert("parcl_property_id".into(), id.into());
+                row.extend(flatten(event)?);
+                events.push(row);
+            }
+        }
+
+        let tx = self.conn.transaction()?;
+        write_rows(&tx, PROPERTIES, &["parcl_property_id"], &properties)?;
+        if table_exists(&tx, PROPERTY_EVENTS)? {
+            let mut delete = tx.prepare(&format!(
+                "DELETE FROM {} WHERE parcl_property_id = ?1",
+                quote(PROPERTY_EVENTS)
+            ))?;
+            for id in ids {
+                delete.execute([id])?;
+            }
+        }
+        let written = write_rows(&tx, PROPERTY_EVENTS, &[], &events)?;
+        tx.commit()?;
+        Ok(written)
+    }
+
+    fn upsert(
+        &mut self,
+        table: &str,
+        key: &[&str],
+        rows: Vec<Map<String, Value>>,
+    ) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let written = write_rows(&tx, table, key, &rows)?;
+        tx.commit()?;
+        Ok(written)
+    }
+}
+
+fn sql_type(value: &Value) -> &'static str {
+    match value {
+        Value::Bool(_) => "INTEGER",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "INTEGER",
+        Value::Number(_) => "REAL",
+        Value::String(_) | Value::Array(_) | Value::Object(_) => "TEXT",
+        Value::Null => "",
+    }
+}
+
+fn sql_value(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        // Arrays are kept as JSON text.
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn table_exists(tx: &Transaction<'_>, table: &str) -> Result<bool> {
+    let count: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+fn columns(tx: &Transaction<'_>, table: &str) -> Result<Vec<String>> {
+    let mut stmt = tx.prepare(&format!("PRAGMA table_info({})", quote(table)))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    Ok(names.collect::<std::result::Result<_, _>>()?)
+}
+
+/// Creates or widens `table` to fit `rows`, then inserts them, replacing
+/// rows with the same `key`.
+fn write_rows(
+    tx: &Transaction<'_>,
+    table: &str,
+    key: &[&str],
+    rows: &[Map<String, Value>],
+) -> Result<usize> {
+    let wanted = column_names(rows);
+    if wanted.is_empty() {
+        return Ok(0);
+    }
+
+    let existing = columns(tx, table)?;
+    if existing.is_empty() {
+        let defs: Vec<String> = wanted
+            .iter()
+            .map(|c| {
+                format!("{} {}", quote(c), sql_type(sample(rows, c)))
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        let key: Vec<String> = key
+            .iter()
+            .filter(|k| wanted.contains(k))
+            .map(|k| quote(k))
+            .collect();
+        let primary_key = if key.is_empty() {
+            String::new()
+        } else {
+            format!(", PRIMARY KEY ({})", key.join(", "))
+        };
+        tx.execute_batch(&format!(
+            "CREATE TABLE {} ({}{primary_key})",
+            quote(table),
+            defs.join(", ")
+        ))?;
+    } else {
+        for column in wanted.iter().filter(|c| !existing.iter().any(|e| e == *c)) {
+            tx.execute_batch(&format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                quote(table),
+                quote(column),
+                sql_type(sample(rows, column))
+            ))?;
+        }
+    }
+
+    let mut stmt = tx.prepare(&format!(
+        "INSERT OR REPLACE INTO {} ({}) VALUES ({})",
+        quote(table),
+        wanted
+            .iter()
+            .map(|c| quote(c))
+            .collect::<Vec<_>>()
+            .join(", "),
+        (1..=wanted.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))?;
+    for row in rows {
+        let values = wanted
+            .iter()
+            .map(|c| row.get(*c).map_or(SqlValue::Null, sql_value));
+        stmt.execute(rusqlite::params_from_iter(values))?;
+    }
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{HousingEventPrices, HousingStock};
+
+    fn response<T: serde::de::DeserializeOwned>(items: Value) -> MetricsResponse<T> {
+        serde_json::from_value(serde_json::json!({
+            "parcl_id": 7, "items": items, "total": 2, "limit": 2, "offset": 0, "links": {}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn metrics_get_one_table_with_flattened_columns() {
+        let mut db = SqliteExporter::in_memory().unwrap();
+        let prices: MetricsResponse<HousingEventPrices> = response(serde_json::json!([
+            {"date": "2024-02-01", "price": {"median": {"sales": 410000.5}}},
+            {"date": "2024-01-01", "price": {"median": {"sales": 400000.0}}}
+        ]));
+        assert_eq!(
+            db.write_metrics("housing_event_prices", &prices).unwrap(),
+            2
+        );
+        // Rewriting the same dates replaces rather than duplicates.
+        db.write_metrics("housing_event_prices", &prices).unwrap();
+
+        let (count, median): (i64, f64) = db
+            .connection()
+            .query_row(
+                "SELECT COUNT(*), MAX(price_median_sales) FROM housing_event_prices WHERE parcl_id = 7",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((count, median), (2, 410000.5));
+
+        let stock: MetricsResponse<HousingStock> =
+            response(serde_json::json!([{"date": "2024-01-01", "single_family": 90}]));
+        db.write_metrics("housing_stock", &stock).unwrap();
+        let joined: i64 = db
+            .connection()
+            .query_row(
+                "SELECT s.single_family FROM housing_stock s \
+                 JOIN housing_event_prices p USING (parcl_id, date)",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(joined, 90);
+    }
+
+    #[test]
+    fn properties_and_events_are_replaced_per_property() {
+        let mut db = SqliteExporter::in_memory().unwrap();
+        let history = |events: Value| -> Vec<PropertyWithEvents> {
+            serde_json::from_value(serde_json::json!([{
+                "parcl_property_id": 11,
+                "property_metadata": {"city": "Austin", "bedrooms": 3},
+                "events": events
+            }]))
+            .unwrap()
+        };
+        let written = db
+            .write_event_history(&history(serde_json::json!([
+                {"event_type": "SALE", "price": 300000},
+                {"event_type": "LISTING", "price": 310000}
+            ])))
+            .unwrap();
+        assert_eq!(written, 2);
+        db.write_event_history(&history(
+            serde_json::json!([{"event_type": "SALE", "price": 350000}]),
+        ))
+        .unwrap();
+
+        let conn = db.connection();
+        let events: i64 = conn
+            .query_row("SELECT COUNT(*) FROM property_events", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(events, 1);
+        let city: String = conn
+            .query_row(
+                "SELECT city FROM properties WHERE parcl_property_id = 11",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(city, "Austin");
+
+        // v2 metadata adds its own columns to the same table.
+        let v2: Vec<PropertyV2> = serde_json::from_value(serde_json::json!([{
+            "parcl_property_id": 12, "property_metadata": {"zip5": "78701"}
+        }]))
+        .unwrap();
+        db.write_properties_v2(&v2).unwrap();
+        let zip: String = db
+            .connection()
+            .query_row(
+                "SELECT zip5 FROM properties WHERE parcl_property_id = 12",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(zip, "78701");
+    }
+}
@@ -0,0 +1,403 @@
+//! Transports for testing code built on the client.
+//!
+//! Enabled with the `test-util` feature. [`CannedTransport`] answers
+//! requests from a closure instead of the network. [`FaultyTransport`] wraps
+//! another [`Transport`] and, at a configurable rate, answers with the
+//! failures the SDK meets in production instead of forwarding the request:
+//! 429s, 503s, dropped connections and truncated bodies. Every request can
+//! also be delayed, through a [`Timer`] so tests can run on virtual time.
+
+use crate::error::{ParclError, Result};
+use crate::protocol::{HttpRequest, HttpResponse, Transport, TransportFuture};
+use crate::runtime::{self, Timer};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A response with `status` and `body` as JSON.
+pub fn json_response(status: u16, body: &serde_json::Value) -> HttpResponse {
+    http::Response::builder()
+        .status(status)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::to_vec(body).expect("JSON values serialize"))
+        .expect("valid status code")
+}
+
+fn copy_request(request: &HttpRequest) -> HttpRequest {
+    let mut copy = http::Request::new(request.body().clone());
+    *copy.method_mut() = request.method().clone();
+    *copy.uri_mut() = request.uri().clone();
+    *copy.version_mut() = request.version();
+    *copy.headers_mut() = request.headers().clone();
+    copy
+}
+
+type Responder = dyn Fn(&HttpRequest) -> HttpResponse + Send + Sync;
+
+/// A [`Transport`] that answers every request from a closure and records
+/// the requests it served.
+///
+/// Clones share the record, so keep one to inspect after handing another
+/// to the client.
+///
+/// # Example
+///
+/// ```
+/// use parcllabs::test_util::CannedTransport;
+/// use parcllabs::ParclClient;
+///
+/// # async fn example() -> parcllabs::Result<()> {
+/// let transport = CannedTransport::json(
+///     200,
+///     serde_json::json!({
+///         "parcl_id": 7, "items": [{"date": "2024-01-01", "sales": 3}],
+///         "total": 1, "limit": 1, "offset": 0, "links": {}
+///     }),
+/// );
+/// let client = ParclClient::with_api_key("key").with_transport(transport.clone());
+/// let counts = client.market_metrics().housing_event_counts(7, None).await?;
+/// assert_eq!(counts.items[0].sales, Some(3));
+/// assert_eq!(transport.request_count(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CannedTransport {
+    respond: Arc<Responder>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl CannedTransport {
+    /// Answers each request with `respond(&request)`.
+    pub fn new(respond: impl Fn(&HttpRequest) -> HttpResponse + Send + Sync + 'static) -> Self {
+        Self {
+            respond: Arc::new(respond),
+            requests: Arc::default(),
+        }
+    }
+
+    /// Answers every request with `status` and the JSON `body`.
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        Self::new(move |_| json_response(status, &body))
+    }
+
+    /// Answers with `responses` in order, repeating the last once they run
+    /// out.
+    pub fn sequence(responses: Vec<HttpResponse>) -> Self {
+        assert!(!responses.is_empty(), "a sequence needs a response");
+        let next = std::sync::atomic::AtomicUsize::new(0);
+        Self::new(move |_| {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            let response = &responses[i.min(responses.len() - 1)];
+            let mut builder = http::Response::builder().status(response.status());
+            for (name, value) in response.headers() {
+                builder = builder.header(name, value);
+            }
+            builder
+                .body(response.body().clone())
+                .expect("copied from a valid response")
+        })
+    }
+
+    /// Copies of the requests served so far, in order.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.lock().iter().map(copy_request).collect()
+    }
+
+    /// URIs requested so far, in order.
+    pub fn uris(&self) -> Vec<String> {
+        self.lock().iter().map(|r| r.uri().to_string()).collect()
+    }
+
+    /// Requests served so far.
+    pub fn request_count(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<HttpRequest>> {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl std::fmt::Debug for CannedTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CannedTransport")
+            .field("requests", &self.request_count())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Transport for CannedTransport {
+    fn execute(&self, request: HttpRequest) -> TransportFuture<'_> {
+        let response = (self.respond)(&request);
+        self.lock().push(request);
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// A failure [`FaultyTransport`] can inject.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// A 429 response, which the client retries with backoff.
+    RateLimited,
+    /// A 503 response, surfaced as `ParclError::ApiError`.
+    ServiceUnavailable,
+    /// The connection drops before a response arrives, surfaced as
+    /// `ParclError::Io`.
+    ConnectionReset,
+    /// A 200 response whose JSON body is cut off, surfaced as
+    /// `ParclError::Decode`.
+    TruncatedBody,
+}
+
+impl Fault {
+    pub const ALL: [Fault; 4] = [
+        Fault::RateLimited,
+        Fault::ServiceUnavailable,
+        Fault::ConnectionReset,
+        Fault::TruncatedBody,
+    ];
+
+    fn respond(self) -> Result<HttpResponse> {
+        let (status, body): (u16, &[u8]) = match self {
+            Fault::RateLimited => (429, br#"{"detail":"Rate limit exceeded (injected)"}"#),
+            Fault::ServiceUnavailable => (503, br#"{"detail":"Service unavailable (injected)"}"#),
+            Fault::TruncatedBody => (200, br#"{"items":[{"date":"2024-01-01","#),
+            Fault::ConnectionReset => {
+                return Err(ParclError::Io(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "connection reset (injected)",
+                )))
+            }
+        };
+        Ok(http::Response::builder()
+            .status(status)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(body.to_vec())?)
+    }
+}
+
+/// A [`Transport`] wrapper that delays requests and fails a share of them.
+///
+/// Faults are drawn from a seeded generator, so a fixed
+/// [`seed`](Self::seed) replays the same sequence of failures.
+///
+/// # Example
+///
+/// ```no_run
+/// use parcllabs::test_util::FaultyTransport;
+/// use parcllabs::{ParclClient, ReqwestTransport};
+/// use std::time::Duration;
+///
+/// let transport = FaultyTransport::new(ReqwestTransport::default())
+///     .fail_rate(0.1)
+///     .latency(Duration::from_millis(200));
+/// let client = ParclClient::with_api_key("key").with_transport(transport);
+/// ```
+pub struct FaultyTransport {
+    inner: Arc<dyn Transport>,
+    fail_rate: f64,
+    latency: Duration,
+    faults: Vec<Fault>,
+    timer: Arc<dyn Timer>,
+    rng: AtomicU64,
+    injected: AtomicU64,
+}
+
+impl FaultyTransport {
+    /// Forwards every request to `inner` until faults or latency are set.
+    pub fn new(inner: impl Transport + 'static) -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self {
+            inner: Arc::new(inner),
+            fail_rate: 0.0,
+            latency: Duration::ZERO,
+            faults: Fault::ALL.to_vec(),
+            timer: runtime::default_timer(),
+            rng: AtomicU64::new(seed),
+            injected: AtomicU64::new(0),
+        }
+    }
+
+    /// Share of requests, from 0 to 1, answered with a fault.
+    pub fn fail_rate(mut self, rate: f64) -> Self {
+        self.fail_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Delay added before every request, faulty or not.
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Restricts injected failures to `faults`, picked uniformly. Defaults
+    /// to [`Fault::ALL`]; an empty list disables faults.
+    pub fn faults(mut self, faults: &[Fault]) -> Self {
+        self.faults = faults.to_vec();
+        self
+    }
+
+    /// Fixes the generator seed for a reproducible failure sequence.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = AtomicU64::new(seed);
+        self
+    }
+
+    /// Sleeps through `timer` instead of the default, e.g. a
+    /// [`MockClock`](crate::runtime::MockClock).
+    pub fn timer(mut self, timer: impl Timer + 'static) -> Self {
+        self.timer = Arc::new(timer);
+        self
+    }
+
+    /// Faults injected so far.
+    pub fn injected(&self) -> u64 {
+        self.injected.load(Ordering::Relaxed)
+    }
+
+    /// Next value from a splitmix64 sequence.
+    fn next_u64(&self) -> u64 {
+        let mut z = self
+            .rng
+            .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+            .wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn pick_fault(&self) -> Option<Fault> {
+        if self.faults.is_empty() || self.fail_rate == 0.0 {
+            return None;
+        }
+        let roll = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        if roll >= self.fail_rate {
+            return None;
+        }
+        self.injected.fetch_add(1, Ordering::Relaxed);
+        Some(self.faults[(self.next_u64() % self.faults.len() as u64) as usize])
+    }
+}
+
+impl Transport for FaultyTransport {
+    fn execute(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            if !self.latency.is_zero() {
+                self.timer.sleep(self.latency).await;
+            }
+            match self.pick_fault() {
+                Some(fault) => fault.respond(),
+                None => self.inner.execute(request).await,
+            }
+        })
+    }
+}
+
+/// Model builders shared by the crate's unit tests.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::models::{HousingEventCounts, PriceFeedEntry};
+
+    /// Event counts with only `sales` reported.
+    pub(crate) fn event_counts(
+        parcl_id: impl Into<Option<i64>>,
+        date: &str,
+        sales: impl Into<Option<i64>>,
+    ) -> HousingEventCounts {
+        HousingEventCounts {
+            parcl_id: parcl_id.into(),
+            date: date.into(),
+            sales: sales.into(),
+            new_listings_for_sale: None,
+            new_rental_listings: None,
+        }
+    }
+
+    /// A price feed observation.
+    pub(crate) fn price_entry(parcl_id: i64, date: &str, price: f64) -> PriceFeedEntry {
+        PriceFeedEntry {
+            parcl_id: Some(parcl_id),
+            date: date.into(),
+            price,
+            price_feed_type: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::MockClock;
+    use crate::{MetricsParams, ParclClient};
+
+    fn ok200() -> CannedTransport {
+        CannedTransport::json(
+            200,
+            serde_json::json!({
+                "parcl_id": 7, "items": [{"date": "2024-01-01", "sales": 3}],
+                "total": 1, "limit": 1, "offset": 0, "links": {}
+            }),
+        )
+    }
+
+    async fn fetch(transport: FaultyTransport) -> Result<u64> {
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(transport)
+            .with_timer(MockClock::new());
+        let response = client
+            .market_metrics()
+            .housing_event_counts(7, Some(MetricsParams::new()))
+            .await?;
+        Ok(response.meta.retries as u64)
+    }
+
+    #[tokio::test]
+    async fn each_fault_surfaces_as_the_sdk_error() {
+        let faulty = |fault| {
+            FaultyTransport::new(ok200())
+                .fail_rate(1.0)
+                .faults(&[fault])
+        };
+        assert!(matches!(
+            fetch(faulty(Fault::RateLimited)).await,
+            Err(ParclError::RateLimited { .. })
+        ));
+        assert!(matches!(
+            fetch(faulty(Fault::ServiceUnavailable)).await,
+            Err(ParclError::ApiError { status: 503, .. })
+        ));
+        assert!(matches!(
+            fetch(faulty(Fault::ConnectionReset)).await,
+            Err(ParclError::Io(_))
+        ));
+        assert!(matches!(
+            fetch(faulty(Fault::TruncatedBody)).await,
+            Err(ParclError::Decode { .. })
+        ));
+        assert_eq!(fetch(FaultyTransport::new(ok200())).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn seeded_rate_and_latency() {
+        let clock = MockClock::new();
+        let transport = FaultyTransport::new(ok200())
+            .fail_rate(0.25)
+            .latency(Duration::from_millis(200))
+            .timer(clock.clone())
+            .seed(42);
+        for _ in 0..400 {
+            let _ = transport.execute(HttpRequest::default()).await;
+        }
+        assert!((60..140).contains(&transport.injected()));
+        assert_eq!(clock.elapsed(), Duration::from_millis(200 * 400));
+
+        let replay = |seed| {
+            let t = FaultyTransport::new(ok200()).fail_rate(0.5).seed(seed);
+            (0..32).map(|_| t.pick_fault()).collect::<Vec<_>>()
+        };
+        assert_eq!(replay(7), replay(7));
+    }
+}
@@ -0,0 +1,323 @@
+//! Record and replay HTTP interactions ("cassettes") for deterministic,
+//! credit-free tests.
+//!
+//! Enabled with the `vcr` feature. [`VcrTransport`] wraps another
+//! [`Transport`]: in [`VcrMode::Record`] it forwards every request and saves
+//! the exchange to a JSON cassette, in [`VcrMode::Replay`] it answers from the
+//! cassette without touching the network, and in [`VcrMode::Auto`] it replays
+//! known requests and records new ones.
+//!
+//! Requests are matched on method, URI and body. Headers are not recorded, so
+//! the API key never ends up in a cassette.
+
+use crate::error::{ParclError, Result};
+use crate::protocol::{HttpRequest, HttpResponse, ReqwestTransport, Transport, TransportFuture};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Environment variable selecting the mode for [`VcrTransport::from_env`].
+pub const VCR_MODE_ENV: &str = "PARCL_LABS_VCR";
+
+/// How a [`VcrTransport`] treats requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VcrMode {
+    /// Forward every request and record it, replacing any existing cassette.
+    Record,
+    /// Answer only from the cassette; unknown requests fail.
+    Replay,
+    /// Replay known requests and record unknown ones.
+    Auto,
+}
+
+impl VcrMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Record => "record",
+            Self::Replay => "replay",
+            Self::Auto => "auto",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "record" => Some(Self::Record),
+            "replay" => Some(Self::Replay),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for VcrMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One recorded request/response pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub request_body: String,
+    pub status: u16,
+    pub response_body: String,
+}
+
+impl Interaction {
+    fn matches(&self, request: &HttpRequest) -> bool {
+        self.method == request.method().as_str()
+            && self.uri == request.uri().to_string()
+            && self.request_body.as_bytes() == request.body().as_slice()
+    }
+
+    fn to_response(&self) -> Result<HttpResponse> {
+        Ok(http::Response::builder()
+            .status(self.status)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(self.response_body.clone().into_bytes())?)
+    }
+}
+
+/// A file of recorded interactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Loads a cassette, or returns an empty one if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(cassette_error(path, e)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| cassette_error(path, e))?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json + "\n").map_err(|e| cassette_error(path, e))
+    }
+}
+
+fn cassette_error(path: &Path, err: impl std::fmt::Display) -> ParclError {
+    ParclError::Cassette(format!("{}: {err}", path.display()))
+}
+
+struct State {
+    cassette: Cassette,
+    /// Replay position per interaction, so repeated requests play back in order.
+    used: Vec<bool>,
+}
+
+/// A [`Transport`] that records to and replays from a cassette file.
+///
+/// # Example
+///
+/// ```no_run
+/// use parcllabs::vcr::{VcrMode, VcrTransport};
+/// use parcllabs::ParclClient;
+///
+/// # fn example() -> parcllabs::Result<()> {
+/// let transport = VcrTransport::new("tests/cassettes/austin.json", VcrMode::Auto)?;
+/// let client = ParclClient::with_api_key("unused-in-replay").with_transport(transport);
+/// # Ok(())
+/// # }
+/// ```
+pub struct VcrTransport {
+    inner: Arc<dyn Transport>,
+    path: PathBuf,
+    mode: VcrMode,
+    state: Mutex<State>,
+}
+
+impl VcrTransport {
+    /// Uses the cassette at `path`, recording through the default reqwest transport.
+    pub fn new(path: impl Into<PathBuf>, mode: VcrMode) -> Result<Self> {
+        Self::with_inner(path, mode, ReqwestTransport::default())
+    }
+
+    /// Like [`new`](Self::new), reading the mode from `PARCL_LABS_VCR`
+    /// (`record`, `replay` or `auto`) and defaulting to replay.
+    pub fn from_env(path: impl Into<PathBuf>) -> Result<Self> {
+        let mode = match std::env::var(VCR_MODE_ENV) {
+            Ok(value) => VcrMode::parse(&value).ok_or_else(|| {
+                ParclError::Cassette(format!("{VCR_MODE_ENV} must be record, replay or auto"))
+            })?,
+            Err(_) => VcrMode::Replay,
+        };
+        Self::new(path, mode)
+    }
+
+    /// Records through `inner` instead of the default transport.
+    pub fn with_inner(
+        path: impl Into<PathBuf>,
+        mode: VcrMode,
+        inner: impl Transport + 'static,
+    ) -> Result<Self> {
+        let path = path.into();
+        let cassette = match mode {
+            VcrMode::Record => Cassette::default(),
+            VcrMode::Replay | VcrMode::Auto => Cassette::load(&path)?,
+        };
+        let used = vec![false; cassette.interactions.len()];
+        Ok(Self {
+            inner: Arc::new(inner),
+            path,
+            mode,
+            state: Mutex::new(State { cassette, used }),
+        })
+    }
+
+    pub fn mode(&self) -> VcrMode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the interaction for `request`: the first unplayed match, or
+    /// the last match once all have been played.
+    fn lookup(&self, request: &HttpRequest) -> Option<Interaction> {
+        let mut state = self.state.lock().unwrap();
+        let State { cassette, used } = &mut *state;
+        let matches: Vec<usize> = cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| i.matches(request))
+            .map(|(idx, _)| idx)
+            .collect();
+        let idx = matches
+            .iter()
+            .copied()
+            .find(|&idx| !used[idx])
+            .or(matches.last().copied())?;
+        used[idx] = true;
+        Some(cassette.interactions[idx].clone())
+    }
+
+    fn record(&self, interaction: Interaction) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.cassette.interactions.push(interaction);
+        state.used.push(true);
+        state.cassette.save(&self.path)
+    }
+}
+
+impl Transport for VcrTransport {
+    fn execute(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            if self.mode != VcrMode::Record {
+                if let Some(interaction) = self.lookup(&request) {
+                    return interaction.to_response();
+                }
+                if self.mode == VcrMode::Replay {
+                    return Err(ParclError::Cassette(format!(
+                        "{}: no recorded response for {} {}",
+                        self.path.display(),
+                        request.method(),
+                        request.uri()
+                    )));
+                }
+            }
+
+            let method = request.method().to_string();
+            let uri = request.uri().to_string();
+            let request_body = String::from_utf8_lossy(request.body()).into_owned();
+            let response = self.inner.execute(request).await?;
+            self.record(Interaction {
+                method,
+                uri,
+                request_body,
+                status: response.status().as_u16(),
+                response_body: String::from_utf8_lossy(response.body()).into_owned(),
+            })?;
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::CannedTransport;
+
+    /// Answers every request with a fixed body.
+    fn fixed() -> CannedTransport {
+        CannedTransport::json(
+            200,
+            serde_json::json!({
+                "parcl_id": 7, "items": [{"date": "2024-01-01", "sales": 3}],
+                "total": 1, "limit": 1, "offset": 0, "links": {}
+            }),
+        )
+    }
+
+    fn cassette_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("parcllabs-vcr-{}-{name}.json", std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn records_then_replays_without_network() {
+        let path = cassette_path("roundtrip");
+        let inner = fixed();
+        let recorder = VcrTransport::with_inner(&path, VcrMode::Record, inner.clone()).unwrap();
+        let client = crate::ParclClient::with_config("secret-key", "https://example.com")
+            .with_transport(recorder);
+        client
+            .market_metrics()
+            .housing_event_counts(7, None)
+            .await
+            .unwrap();
+        assert_eq!(inner.request_count(), 1);
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("secret-key"));
+
+        let client = crate::ParclClient::with_config("other-key", "https://example.com")
+            .with_transport(VcrTransport::new(&path, VcrMode::Replay).unwrap());
+        let resp = client
+            .market_metrics()
+            .housing_event_counts(7, None)
+            .await
+            .unwrap();
+        assert_eq!(resp.items[0].sales, Some(3));
+
+        let err = client
+            .market_metrics()
+            .housing_event_counts(8, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ParclError::Cassette(_)));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn auto_mode_records_only_new_requests() {
+        let path = cassette_path("auto");
+        let inner = fixed();
+        let transport = VcrTransport::with_inner(&path, VcrMode::Auto, inner.clone()).unwrap();
+        let request = |uri: &str| http::Request::get(uri).body(Vec::new()).unwrap();
+        transport.execute(request("https://x/a")).await.unwrap();
+        transport.execute(request("https://x/a")).await.unwrap();
+        transport.execute(request("https://x/b")).await.unwrap();
+        assert_eq!(inner.request_count(), 2);
+        assert_eq!(Cassette::load(&path).unwrap().interactions.len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn missing_cassette_loads_empty() {
+        let cassette = Cassette::load(Path::new("/nonexistent/cassette.json")).unwrap();
+        assert!(cassette.interactions.is_empty());
+        assert_eq!(VcrMode::parse(" Replay "), Some(VcrMode::Replay));
+        assert_eq!(VcrMode::parse("live"), None);
+    }
+}
@@ -0,0 +1,351 @@
+//! Watching a basket for surges in new for-sale listings.
+//!
+//! A [`SupplyWatchdog`] polls the 30-day rolling count of new listings for
+//! every market in a [`Basket`] and raises a [`SurgeAlert`] when a
+//! market's latest weekly count jumps past a threshold over the week
+//! before: the usual "is supply breaking out?" check.
+//!
+//! ```no_run
+//! use parcllabs::{Basket, ParclClient, SupplyWatchdog};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> parcllabs::Result<()> {
+//! let client = Arc::new(ParclClient::new()?);
+//! let basket = Basket::load("sun_belt.json")?;
+//! let watchdog = SupplyWatchdog::new(client, basket, Duration::from_secs(24 * 60 * 60))
+//!     .threshold(15.0)
+//!     .on_surge(|alert| println!("{}: new listings {:+.1}%", alert.parcl_id, alert.change_pct))
+//!     .on_error(|err| eprintln!("check failed: {err}"));
+//! tokio::spawn(async move {
+//!     let err = watchdog.run().await;
+//!     eprintln!("watchdog stopped: {err}");
+//! });
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::basket::Basket;
+use crate::error::{ParclError, Result};
+use crate::models::NewListingsRollingCounts;
+use crate::{ForSaleMetricsParams, ParclClient};
+use chrono::{DateTime, Days, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Receives each surge the watchdog detects.
+pub type SurgeHandler = Arc<dyn Fn(&SurgeAlert) + Send + Sync>;
+
+/// Receives each failed check.
+pub type CheckErrorHandler = Arc<dyn Fn(&ParclError) + Send + Sync>;
+
+/// Default week-over-week rise, in percent, that counts as a surge.
+const DEFAULT_THRESHOLD: f64 = 10.0;
+
+/// How far back each check fetches when no start date is set: enough
+/// weekly observations to find the previous week's despite publishing lag.
+const LOOKBACK_DAYS: u64 = 35;
+
+/// A market whose new-listing count rose past the threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SurgeAlert {
+    pub parcl_id: i64,
+    /// Date of the latest observation.
+    pub date: String,
+    pub previous_date: String,
+    /// 30-day rolling count of new listings at `date`.
+    pub rolling_30_day: i64,
+    /// The same count at `previous_date`.
+    pub previous: i64,
+    /// `(rolling_30_day - previous) / previous`, in percent.
+    pub change_pct: f64,
+}
+
+/// Compares each market's latest 30-day rolling count with the observation
+/// before it and returns the markets that rose by at least `threshold_pct`.
+///
+/// Items need a `parcl_id`, as batch responses have. Markets with fewer
+/// than two observations, or no listings the week before, are skipped.
+/// Output is ordered by market.
+pub fn detect_surges(items: &[NewListingsRollingCounts], threshold_pct: f64) -> Vec<SurgeAlert> {
+    let mut by_market: BTreeMap<i64, Vec<(&str, i64)>> = BTreeMap::new();
+    for item in items {
+        if let (Some(parcl_id), Some(count)) = (item.parcl_id, item.rolling_30_day_count) {
+            by_market
+                .entry(parcl_id)
+                .or_default()
+                .push((&item.date, count));
+        }
+    }
+    by_market
+        .into_iter()
+        .filter_map(|(parcl_id, mut observations)| {
+            observations.sort_unstable();
+            let [.., (previous_date, previous), (date, latest)] = observations[..] else {
+                return None;
+            };
+            let change_pct = (latest - previous) as f64 / previous as f64 * 100.0;
+            (previous > 0 && change_pct >= threshold_pct).then(|| SurgeAlert {
+                parcl_id,
+                date: date.to_string(),
+                previous_date: previous_date.to_string(),
+                rolling_30_day: latest,
+                previous,
+                change_pct,
+            })
+        })
+        .collect()
+}
+
+/// Polls a basket's new-listing counts and reports surges.
+///
+/// Each surge is reported once: a later check over the same data stays
+/// quiet until a market publishes a newer observation that also surges.
+pub struct SupplyWatchdog {
+    client: Arc<ParclClient>,
+    basket: Basket,
+    interval: Duration,
+    threshold: f64,
+    params: ForSaleMetricsParams,
+    handler: Option<SurgeHandler>,
+    error_handler: Option<CheckErrorHandler>,
+    /// Latest alerted date per market.
+    alerted: Mutex<HashMap<i64, String>>,
+}
+
+impl SupplyWatchdog {
+    /// Creates a watchdog that checks every `interval` once
+    /// [`run`](Self::run) is started, alerting on a 10% weekly rise.
+    pub fn new(client: Arc<ParclClient>, basket: Basket, interval: Duration) -> Self {
+        Self {
+            client,
+            basket,
+            interval,
+            threshold: DEFAULT_THRESHOLD,
+            params: ForSaleMetricsParams::new().auto_paginate(true),
+            handler: None,
+            error_handler: None,
+            alerted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Week-over-week rise, in percent, that counts as a surge.
+    pub fn threshold(mut self, pct: f64) -> Self {
+        self.threshold = pct;
+        self
+    }
+
+    /// Query parameters for each check, e.g. a property type.
+    ///
+    /// Without a `start_date`, each check fetches the last five weeks.
+    pub fn params(mut self, params: ForSaleMetricsParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Calls `handler` for every new surge.
+    pub fn on_surge(mut self, handler: impl Fn(&SurgeAlert) + Send + Sync + 'static) -> Self {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Calls `handler` for every failed check in [`run`](Self::run).
+    pub fn on_error(mut self, handler: impl Fn(&ParclError) + Send + Sync + 'static) -> Self {
+        self.error_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Fetches the basket's counts once and returns the surges not reported
+    /// before, after passing each to the handler.
+    pub async fn check(&self) -> Result<Vec<SurgeAlert>> {
+        let mut params = self.params.clone();
+        if params.start_date.is_none() {
+            let today = DateTime::<Utc>::from(self.client.timer.system_now()).date_naive();
+            let since = today - Days::new(LOOKBACK_DAYS);
+            params.start_date = Some(since.to_string());
+        }
+        let counts = self
+            .client
+            .for_sale_metrics()
+            .batch_new_listings_rolling_counts(self.basket.parcl_ids(), Some(params))
+            .await?;
+
+        let mut alerted = self.alerted.lock().unwrap_or_else(|e| e.into_inner());
+        let fresh: Vec<SurgeAlert> = detect_surges(&counts.items, self.threshold)
+            .into_iter()
+            .filter(|alert| {
+                alerted
+                    .get(&alert.parcl_id)
+                    .is_none_or(|seen| seen.as_str() < alert.date.as_str())
+            })
+            .collect();
+        for alert in &fresh {
+            alerted.insert(alert.parcl_id, alert.date.clone());
+        }
+        drop(alerted);
+
+        if let Some(handler) = &self.handler {
+            fresh.iter().for_each(|alert| handler(alert));
+        }
+        Ok(fresh)
+    }
+
+    /// Checks every interval until a check fails with an error retrying
+    /// won't fix, such as a revoked key or a plan denial, and returns it.
+    ///
+    /// Spawn it on the runtime; it sleeps through the client's
+    /// [`Timer`](crate::Timer). Every failed check goes to the
+    /// [`on_error`](Self::on_error) handler; retryable ones are retried at
+    /// the next interval.
+    pub async fn run(&self) -> ParclError {
+        loop {
+            if let Err(err) = self.check().await {
+                if let Some(handler) = &self.error_handler {
+                    handler(&err);
+                }
+                if !err.is_retryable() {
+                    return err;
+                }
+            }
+            self.client.timer.sleep(self.interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{json_response, CannedTransport};
+
+    fn counts(parcl_id: i64, date: &str, rolling_30_day: i64) -> NewListingsRollingCounts {
+        NewListingsRollingCounts {
+            parcl_id: Some(parcl_id),
+            date: date.into(),
+            rolling_7_day_count: None,
+            rolling_30_day_count: Some(rolling_30_day),
+            rolling_60_day_count: None,
+            rolling_90_day_count: None,
+        }
+    }
+
+    #[test]
+    fn surges_compare_latest_two_weeks() {
+        // Newest first, as the API returns it.
+        let items = vec![
+            counts(1, "2024-06-10", 1150),
+            counts(1, "2024-06-03", 1000),
+            counts(1, "2024-05-27", 500),
+            counts(2, "2024-06-10", 1050),
+            counts(2, "2024-06-03", 1000),
+            counts(3, "2024-06-10", 40),
+            counts(3, "2024-06-03", 0),
+            counts(4, "2024-06-10", 900),
+        ];
+        let surges = detect_surges(&items, 10.0);
+        assert_eq!(surges.len(), 1);
+        assert_eq!(surges[0].parcl_id, 1);
+        assert_eq!(surges[0].previous_date, "2024-06-03");
+        assert!((surges[0].change_pct - 15.0).abs() < 1e-9);
+    }
+
+    /// Serves a batch where market 1 surges and market 2 is flat.
+    fn batch() -> CannedTransport {
+        CannedTransport::new(|request| {
+            assert!(request
+                .uri()
+                .path()
+                .ends_with("/v1/for_sale_market_metrics/new_listings_rolling_counts"));
+            let item = |parcl_id: i64, date: &str, count: i64| serde_json::json!({"parcl_id": parcl_id, "date": date, "rolling_30_day": count});
+            json_response(
+                200,
+                &serde_json::json!({
+                    "items": [
+                        item(1, "2024-06-10", 1200), item(1, "2024-06-03", 1000),
+                        item(2, "2024-06-10", 1000), item(2, "2024-06-03", 1000)
+                    ],
+                    "total": 4, "limit": 4, "offset": 0, "links": {}
+                }),
+            )
+        })
+    }
+
+    #[tokio::test]
+    async fn check_reports_each_surge_once() {
+        let client = ParclClient::with_config("key", "https://example.com").with_transport(batch());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let watchdog = SupplyWatchdog::new(
+            Arc::new(client),
+            Basket::new("pair").members([1, 2]),
+            Duration::from_secs(60),
+        )
+        .on_surge({
+            let seen = Arc::clone(&seen);
+            move |alert| seen.lock().unwrap().push(alert.parcl_id)
+        });
+
+        let first = watchdog.check().await.unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].rolling_30_day, 1200);
+        assert!(watchdog.check().await.unwrap().is_empty());
+        assert_eq!(*seen.lock().unwrap(), [1]);
+    }
+
+    #[tokio::test]
+    async fn lookback_follows_the_client_clock() {
+        let transport = batch();
+        let june_30 = std::time::UNIX_EPOCH + Duration::from_secs(1_719_705_600);
+        let clock = crate::runtime::MockClock::new().starting_at(june_30);
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_transport(transport.clone())
+            .with_timer(clock.clone());
+        let watchdog = SupplyWatchdog::new(
+            Arc::new(client),
+            Basket::new("pair").members([1, 2]),
+            Duration::from_secs(60),
+        );
+
+        let start_date = |request: &crate::protocol::HttpRequest| {
+            let body: serde_json::Value = serde_json::from_slice(request.body()).unwrap();
+            body["start_date"].as_str().unwrap().to_string()
+        };
+        watchdog.check().await.unwrap();
+        clock.advance(Duration::from_secs(7 * 24 * 60 * 60));
+        watchdog.check().await.unwrap();
+        let requests = transport.requests();
+        assert_eq!(start_date(&requests[0]), "2024-05-26");
+        assert_eq!(start_date(&requests[1]), "2024-06-02");
+    }
+
+    #[tokio::test]
+    async fn run_reports_errors_and_stops_on_permanent_ones() {
+        let transport = CannedTransport::sequence(vec![
+            json_response(503, &serde_json::json!({"detail": "Unavailable"})),
+            json_response(401, &serde_json::json!({"detail": "Invalid API key"})),
+        ]);
+        let clock = crate::runtime::MockClock::new();
+        let client = ParclClient::with_config("key", "https://example.com")
+            .with_retry_config(crate::RetryConfig {
+                max_retries: 0,
+                initial_backoff_ms: 0,
+            })
+            .with_transport(transport.clone())
+            .with_timer(clock.clone());
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let watchdog = SupplyWatchdog::new(
+            Arc::new(client),
+            Basket::new("pair").members([1, 2]),
+            Duration::from_secs(60),
+        )
+        .on_error({
+            let errors = Arc::clone(&errors);
+            move |err| errors.lock().unwrap().push(err.status())
+        });
+
+        let err = watchdog.run().await;
+        assert_eq!(err.status(), Some(401));
+        assert_eq!(*errors.lock().unwrap(), [Some(503), Some(401)]);
+        assert_eq!(clock.sleeps(), [Duration::from_secs(60)]);
+    }
+}
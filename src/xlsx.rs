@@ -0,0 +1,371 @@
+//! Excel workbooks of metric responses.
+//!
+//! Enabled with the `xlsx` feature. [`XlsxExporter`] writes each metric
+//! series to its own worksheet, named after the metric and market, with one
+//! row per item and nested objects flattened into `price_median_sales`-style
+//! columns like the SQLite exporter. Dates are written as Excel dates, price
+//! and income columns as currency and `pct_*`, yield and concentration
+//! columns as percentages, so the sheets sort and chart without cleanup.
+//!
+//! ```no_run
+//! use parcllabs::xlsx::XlsxExporter;
+//! use parcllabs::{Metric, ParclClient};
+//!
+//! # async fn example() -> parcllabs::Result<()> {
+//! let client = ParclClient::new()?;
+//! let mut workbook = XlsxExporter::new();
+//! for parcl_id in [2900078, 2900187] {
+//!     for metric in [Metric::HousingEventPrices, Metric::GrossYield] {
+//!         workbook.write_series(&client.fetch_metric(metric, parcl_id, None).await?)?;
+//!     }
+//! }
+//! workbook.save("markets.xlsx")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::metric::MetricSeries;
+use crate::models::{BatchMetricsResponse, MetricsResponse};
+use crate::sink::rows::{column_names, flatten, METRIC_KEY};
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook, Worksheet};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Excel's limit on worksheet name length.
+const MAX_SHEET_NAME: usize = 31;
+
+/// Writes metric responses into an Excel workbook, one sheet per
+/// metric and market.
+pub struct XlsxExporter {
+    workbook: Workbook,
+    sheets: Vec<String>,
+    formats: Formats,
+}
+
+struct Formats {
+    header: Format,
+    date: Format,
+    currency: Format,
+    percent: Format,
+}
+
+impl std::fmt::Debug for XlsxExporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("XlsxExporter")
+            .field("sheets", &self.sheets)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for XlsxExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XlsxExporter {
+    pub fn new() -> Self {
+        Self {
+            workbook: Workbook::new(),
+            sheets: Vec::new(),
+            formats: Formats {
+                header: Format::new().set_bold(),
+                date: Format::new().set_num_format("yyyy-mm-dd"),
+                currency: Format::new().set_num_format("$#,##0"),
+                // The API reports percentages as points (28.5, not 0.285),
+                // so the sign is appended rather than scaling by 100.
+                percent: Format::new().set_num_format("0.00\"%\""),
+            },
+        }
+    }
+
+    /// Worksheet names in the order they were added.
+    pub fn sheet_names(&self) -> &[String] {
+        &self.sheets
+    }
+
+    /// Writes a single market's series to a new sheet named
+    /// `"{metric} {parcl_id}"`. Returns the number of rows written.
+    pub fn write_metrics<T: Serialize>(
+        &mut self,
+        metric: &str,
+        response: &MetricsResponse<T>,
+    ) -> Result<usize> {
+        let rows = response
+            .items
+            .iter()
+            .map(|item| {
+                let mut row = flatten(item)?;
+                if row.get("parcl_id").is_none_or(Value::is_null) {
+                    row.insert("parcl_id".into(), response.parcl_id.into());
+                }
+                Ok(row)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.add_sheet(&format!("{metric} {}", response.parcl_id), &rows)?;
+        Ok(rows.len())
+    }
+
+    /// Writes a batch response, one sheet per market in the order the
+    /// markets first appear.
+    pub fn write_batch_metrics<T: Serialize>(
+        &mut self,
+        metric: &str,
+        response: &BatchMetricsResponse<T>,
+    ) -> Result<usize> {
+        type Rows = Vec<Map<String, Value>>;
+        let mut markets: Vec<(Option<i64>, Rows)> = Vec::new();
+        for item in &response.items {
+            let row = flatten(item)?;
+            let parcl_id = row.get("parcl_id").and_then(Value::as_i64);
+            match markets.iter_mut().find(|(id, _)| *id == parcl_id) {
+                Some((_, rows)) => rows.push(row),
+                None => markets.push((parcl_id, vec![row])),
+            }
+        }
+        for (parcl_id, rows) in &markets {
+            let name = match parcl_id {
+                Some(id) => format!("{metric} {id}"),
+                None => metric.to_string(),
+            };
+            self.add_sheet(&name, rows)?;
+        }
+        Ok(response.items.len())
+    }
+
+    /// Writes a series from [`ParclClient::fetch_metric`](crate::ParclClient::fetch_metric),
+    /// named after its [`Metric`](crate::Metric).
+    pub fn write_series(&mut self, series: &MetricSeries) -> Result<usize> {
+        let rows = series
+            .items_json()?
+            .iter()
+            .map(|item| {
+                let mut row = flatten(item)?;
+                if row.get("parcl_id").is_none_or(Value::is_null) {
+                    row.insert("parcl_id".into(), series.parcl_id().into());
+                }
+                Ok(row)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.add_sheet(&format!("{} {}", series.metric(), series.parcl_id()), &rows)?;
+        Ok(rows.len())
+    }
+
+    /// Writes the workbook to `path`.
+    pub fn save(mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.workbook.save(path.as_ref())?;
+        Ok(())
+    }
+
+    /// The workbook as `.xlsx` bytes, e.g. for an HTTP response.
+    pub fn to_bytes(mut self) -> Result<Vec<u8>> {
+        Ok(self.workbook.save_to_buffer()?)
+    }
+
+    fn add_sheet(&mut self, name: &str, rows: &[Map<String, Value>]) -> Result<()> {
+        let name = unique_name(&sheet_name(name), &self.sheets);
+        let sheet = self.workbook.add_worksheet();
+        sheet.set_name(&name)?;
+        self.sheets.push(name);
+
+        let mut columns = column_names(rows);
+        // Key columns first; the rest stay in flattened (sorted) order.
+        columns.sort_by_key(|c| METRIC_KEY.iter().position(|k| k == c).unwrap_or(usize::MAX));
+        for (col, name) in columns.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, *name, &self.formats.header)?;
+        }
+        for (row, values) in rows.iter().enumerate() {
+            for (col, name) in columns.iter().enumerate() {
+                if let Some(value) = values.get(*name) {
+                    write_cell(
+                        sheet,
+                        &self.formats,
+                        row as u32 + 1,
+                        col as u16,
+                        name,
+                        value,
+                    )?;
+                }
+            }
+        }
+        sheet.set_freeze_panes(1, 0)?;
+        sheet.autofit();
+        Ok(())
+    }
+}
+
+fn write_cell(
+    sheet: &mut Worksheet,
+    formats: &Formats,
+    row: u32,
+    col: u16,
+    column: &str,
+    value: &Value,
+) -> Result<()> {
+    match value {
+        Value::Null => {}
+        Value::Bool(b) => {
+            sheet.write_boolean(row, col, *b)?;
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or(f64::NAN);
+            match column_kind(column) {
+                ColumnKind::Currency => {
+                    sheet.write_number_with_format(row, col, n, &formats.currency)?
+                }
+                ColumnKind::Percent => {
+                    sheet.write_number_with_format(row, col, n, &formats.percent)?
+                }
+                ColumnKind::Number => sheet.write_number(row, col, n)?,
+            };
+        }
+        Value::String(s) => match ExcelDateTime::parse_from_str(s) {
+            Ok(date) if column == "date" => {
+                sheet.write_datetime_with_format(row, col, &date, &formats.date)?;
+            }
+            _ => {
+                sheet.write_string(row, col, s)?;
+            }
+        },
+        // Arrays are kept as JSON text.
+        other => {
+            sheet.write_string(row, col, other.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Currency,
+    Percent,
+    Number,
+}
+
+/// Picks a number format from a flattened column name.
+fn column_kind(column: &str) -> ColumnKind {
+    if column.contains("pct") || column.ends_with("yield") || column.ends_with("concentration") {
+        ColumnKind::Percent
+    } else if (column.contains("price") || column.contains("income"))
+        && !["count", "days", "pricefeed"]
+            .iter()
+            .any(|word| column.contains(word))
+    {
+        ColumnKind::Currency
+    } else {
+        ColumnKind::Number
+    }
+}
+
+/// Replaces characters Excel rejects in sheet names and trims to length,
+/// keeping the trailing market ID.
+fn sheet_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+            c => c,
+        })
+        .collect();
+    if name.chars().count() <= MAX_SHEET_NAME {
+        return name;
+    }
+    match name.rsplit_once(' ') {
+        Some((metric, id)) if id.len() < MAX_SHEET_NAME - 1 => {
+            let keep = MAX_SHEET_NAME - 1 - id.chars().count();
+            format!("{} {id}", metric.chars().take(keep).collect::<String>())
+        }
+        _ => name.chars().take(MAX_SHEET_NAME).collect(),
+    }
+}
+
+/// Suffixes ` (2)`, ` (3)`… when `name` is taken (Excel compares names
+/// case-insensitively).
+fn unique_name(name: &str, taken: &[String]) -> String {
+    let is_taken = |candidate: &str| taken.iter().any(|t| t.eq_ignore_ascii_case(candidate));
+    if !is_taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| {
+            let suffix = format!(" ({n})");
+            let keep = MAX_SHEET_NAME - suffix.len();
+            format!("{}{suffix}", name.chars().take(keep).collect::<String>())
+        })
+        .find(|candidate| !is_taken(candidate))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ForSaleInventoryPriceChanges, HousingEventPrices};
+
+    #[test]
+    fn one_sheet_per_metric_and_market() {
+        let mut workbook = XlsxExporter::new();
+        let prices: MetricsResponse<HousingEventPrices> =
+            serde_json::from_value(serde_json::json!({
+                "parcl_id": 7, "total": 1, "limit": 1, "offset": 0, "links": {},
+                "items": [{"date": "2024-01-01", "price": {"median": {"sales": 400000.0}}}]
+            }))
+            .unwrap();
+        assert_eq!(
+            workbook
+                .write_metrics("housing_event_prices", &prices)
+                .unwrap(),
+            1
+        );
+        workbook
+            .write_metrics("housing_event_prices", &prices)
+            .unwrap();
+
+        let batch: BatchMetricsResponse<ForSaleInventoryPriceChanges> =
+            serde_json::from_value(serde_json::json!({
+                "total": 3, "limit": 3, "offset": 0, "links": {},
+                "items": [
+                    {"parcl_id": 1, "date": "2024-01-01", "pct_inventory_price_change": 12.5},
+                    {"parcl_id": 2, "date": "2024-01-01", "pct_inventory_price_change": 9.0},
+                    {"parcl_id": 1, "date": "2024-01-08", "pct_inventory_price_change": 13.0}
+                ]
+            }))
+            .unwrap();
+        assert_eq!(
+            workbook
+                .write_batch_metrics("for_sale_inventory_price_changes", &batch)
+                .unwrap(),
+            3
+        );
+
+        assert_eq!(
+            workbook.sheet_names(),
+            [
+                "housing_event_prices 7",
+                "housing_event_prices 7 (2)",
+                "for_sale_inventory_price_chan 1",
+                "for_sale_inventory_price_chan 2",
+            ]
+        );
+        assert!(workbook.to_bytes().unwrap().starts_with(b"PK"));
+    }
+
+    #[test]
+    fn columns_get_typed_formats() {
+        assert_eq!(column_kind("price_median_sales"), ColumnKind::Currency);
+        assert_eq!(column_kind("median_income"), ColumnKind::Currency);
+        assert_eq!(
+            column_kind("pct_inventory_price_change"),
+            ColumnKind::Percent
+        );
+        assert_eq!(column_kind("gross_yield"), ColumnKind::Percent);
+        assert_eq!(column_kind("count_price_drop"), ColumnKind::Number);
+        assert_eq!(
+            column_kind("median_days_bt_price_change"),
+            ColumnKind::Number
+        );
+        assert_eq!(column_kind("sales"), ColumnKind::Number);
+        assert_eq!(sheet_name("a/b [1]"), "a_b _1_");
+    }
+}
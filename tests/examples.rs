@@ -0,0 +1,553 @@
+//! The analytical examples' workflows, replayed from cassettes.
+//!
+//! Each test walks the same calls as an example in `examples/` against a
+//! cassette in `tests/cassettes/`, so the workflows keep compiling and
+//! producing the expected results as the API surface grows. `axum_proxy`
+//! is left out: it serves requests rather than making a fixed set of them.
+//!
+//! ```bash
+//! cargo test --features vcr --test examples
+//! ```
+
+use parcllabs::vcr::{VcrMode, VcrTransport};
+use parcllabs::{
+    AddressSearchRequest, EventHistoryParams, EventType, ForSaleMetricsParams,
+    InvestorMetricsParams, LocationType, MetricsParams, NewConstructionMetricsParams, ParclClient,
+    PortfolioMetricsParams, PortfolioSize, PriceEvent, PriceStat, PropertyFilters,
+    PropertySearchParams, PropertyType, PropertyV2SearchRequest, SearchParams, SortBy, SortOrder,
+    USRegion, V2EventFilters,
+};
+
+fn client(cassette: &str) -> ParclClient {
+    let path = format!("{}/tests/cassettes/{cassette}", env!("CARGO_MANIFEST_DIR"));
+    let transport = VcrTransport::new(path, VcrMode::Replay).unwrap();
+    ParclClient::with_config("unused-in-replay", "https://api.parcllabs.com")
+        .with_transport(transport)
+}
+
+async fn market_id(client: &ParclClient, city: &str, state: &str) -> i64 {
+    let params = SearchParams::new().query(city).state(state).limit(1);
+    client.search().markets(params).await.unwrap().items[0].parcl_id
+}
+
+/// `for_sale_market_analysis`: ranks metros by share of listings with a
+/// price drop.
+#[tokio::test]
+async fn for_sale_screening_ranks_by_price_drops() {
+    let client = client("for_sale_screening.json");
+    let latest_sf = || {
+        Some(
+            ForSaleMetricsParams::new()
+                .property_type(PropertyType::SingleFamily)
+                .limit(1),
+        )
+    };
+
+    let mut snapshots = Vec::new();
+    for (city, state) in [("Austin", "TX"), ("Phoenix", "AZ")] {
+        let parcl_id = market_id(&client, city, state).await;
+        let for_sale = client.for_sale_metrics();
+        let inventory = for_sale
+            .for_sale_inventory(parcl_id, latest_sf())
+            .await
+            .unwrap();
+        let changes = for_sale
+            .for_sale_inventory_price_changes(parcl_id, latest_sf())
+            .await
+            .unwrap();
+        let rolling = for_sale
+            .new_listings_rolling_counts(parcl_id, latest_sf())
+            .await
+            .unwrap();
+        snapshots.push((
+            city,
+            inventory.items[0].for_sale_inventory.unwrap(),
+            changes.items[0].pct_inventory_price_drop().unwrap(),
+            changes.items[0].median_days_bt_change().unwrap(),
+            rolling.items[0].rolling_30_day_count.unwrap(),
+        ));
+    }
+    snapshots.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    assert_eq!(
+        snapshots,
+        [
+            ("Phoenix", 9_820, 41.3, 16.0, 2_114),
+            ("Austin", 7_415, 38.9, 19.0, 1_502),
+        ]
+    );
+}
+
+/// `institutional_ownership`: ranks metros by investor-owned share, then
+/// follows the leader's ownership trend.
+#[tokio::test]
+async fn ownership_analysis_ranks_and_trends() {
+    let client = client("institutional_ownership.json");
+
+    let mut ownership = Vec::new();
+    for (city, state) in [("Jacksonville", "FL"), ("Denver", "CO")] {
+        let parcl_id = market_id(&client, city, state).await;
+        let latest = client
+            .investor_metrics()
+            .housing_stock_ownership_latest(parcl_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let prices = client
+            .market_metrics()
+            .housing_event_prices(
+                parcl_id,
+                Some(
+                    MetricsParams::new()
+                        .limit(1)
+                        .property_type(PropertyType::SingleFamily),
+                ),
+            )
+            .await
+            .unwrap();
+        let median_sale = prices.items[0]
+            .price
+            .as_ref()
+            .and_then(|p| p.get(PriceStat::Median, PriceEvent::Sales))
+            .unwrap();
+        ownership.push((parcl_id, latest.investor_owned_pct.unwrap(), median_sale));
+    }
+    ownership.sort_by(|a, b| b.1.total_cmp(&a.1));
+    assert_eq!(
+        ownership,
+        [(2900091, 14.2, 385_000.0), (2899845, 8.7, 610_000.0)]
+    );
+
+    let mut history = client
+        .investor_metrics()
+        .housing_stock_ownership(
+            ownership[0].0,
+            Some(InvestorMetricsParams::new().limit(12).auto_paginate(true)),
+        )
+        .await
+        .unwrap();
+    history.sort_ascending();
+    let trend: Vec<f64> = history
+        .items
+        .iter()
+        .filter_map(|i| i.investor_owned_pct)
+        .collect();
+    assert_eq!(trend, [13.6, 13.9, 14.2]);
+}
+
+/// `search_markets`: free-text, filtered and region searches, then one
+/// search fetched a page at a time.
+#[tokio::test]
+async fn market_search_filters_sorts_and_pages() {
+    let client = client("search_markets.json");
+
+    let la = client
+        .search()
+        .markets(SearchParams::new().query("Los Angeles").limit(10))
+        .await
+        .unwrap();
+    let feeds: Vec<(&str, bool)> = la
+        .items
+        .iter()
+        .map(|m| (m.location_type.as_str(), m.has_price_feed()))
+        .collect();
+    assert_eq!(feeds, [("CITY", true), ("COUNTY", true), ("CBSA", false)]);
+
+    let ca_cities = client
+        .search()
+        .markets(
+            SearchParams::new()
+                .query("San")
+                .state("CA")
+                .location_type(LocationType::City)
+                .sort_by(SortBy::TotalPopulation)
+                .sort_order(SortOrder::Desc)
+                .limit(5),
+        )
+        .await
+        .unwrap();
+    let names: Vec<&str> = ca_cities.items.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "San Diego",
+            "San Jose",
+            "San Francisco",
+            "Santa Ana",
+            "San Bernardino"
+        ]
+    );
+
+    let pacific = client
+        .search()
+        .markets(
+            SearchParams::new()
+                .query("port")
+                .region(USRegion::Pacific)
+                .limit(5),
+        )
+        .await
+        .unwrap();
+    let states: Vec<Option<&str>> = pacific
+        .items
+        .iter()
+        .map(|m| m.state_abbreviation.as_deref())
+        .collect();
+    assert_eq!(states, [Some("OR"), Some("WA")]);
+
+    let san_cities = || {
+        SearchParams::new()
+            .query("San")
+            .state("CA")
+            .location_type(LocationType::City)
+            .limit(5)
+    };
+    let first_page = client.search().markets(san_cities()).await.unwrap();
+    assert_eq!((first_page.items.len(), first_page.total), (5, 7));
+    let all = client
+        .search()
+        .markets(san_cities().auto_paginate(true))
+        .await
+        .unwrap();
+    assert_eq!(all.items.len(), 7);
+    assert_eq!(all.items[6].name, "San Mateo");
+}
+
+/// `market_metrics`: event counts, housing stock and median prices by
+/// property type for one market.
+#[tokio::test]
+async fn market_metrics_cover_counts_stock_and_prices() {
+    let client = client("market_metrics.json");
+    let la = market_id(&client, "Los Angeles", "CA").await;
+    let metrics = client.market_metrics();
+
+    let events = metrics
+        .housing_event_counts(
+            la,
+            Some(MetricsParams::new().limit(6).start_date("2024-01-01")),
+        )
+        .await
+        .unwrap();
+    let sales: i64 = events.items.iter().filter_map(|e| e.sales).sum();
+    assert_eq!((events.items.len(), sales), (6, 10_318));
+
+    let stock = metrics
+        .housing_stock(la, Some(MetricsParams::new().limit(1)))
+        .await
+        .unwrap();
+    let latest = &stock.items[0];
+    assert_eq!(latest.single_family, Some(489_512));
+    assert_eq!(latest.all_properties, Some(1_027_834));
+
+    let median_sale = |property_type: Option<PropertyType>| {
+        let mut params = MetricsParams::new().limit(1);
+        if let Some(property_type) = property_type {
+            params = params.property_type(property_type);
+        }
+        let metrics = client.market_metrics();
+        async move {
+            let prices = metrics
+                .housing_event_prices(la, Some(params))
+                .await
+                .unwrap();
+            prices.items[0]
+                .price
+                .as_ref()
+                .and_then(|p| p.get(PriceStat::Median, PriceEvent::Sales))
+                .unwrap()
+        }
+    };
+    let medians = [
+        median_sale(None).await,
+        median_sale(Some(PropertyType::SingleFamily)).await,
+        median_sale(Some(PropertyType::Condo)).await,
+    ];
+    assert_eq!(medians, [975_000.0, 1_150_000.0, 705_000.0]);
+
+    let by_five = || MetricsParams::new().limit(5).start_date("2024-01-01");
+    let first_page = metrics
+        .housing_event_counts(la, Some(by_five()))
+        .await
+        .unwrap();
+    assert_eq!((first_page.items.len(), first_page.total), (5, 6));
+    let all = metrics
+        .housing_event_counts(la, Some(by_five().auto_paginate(true)))
+        .await
+        .unwrap();
+    assert_eq!(all.items.len(), 6);
+}
+
+/// `investor_activity`: ranks metros by investor purchase-to-sale ratio,
+/// then follows the leader's listings and ratio trend.
+#[tokio::test]
+async fn investor_activity_ranks_buyers_and_trends() {
+    let client = client("investor_activity.json");
+    let investors = client.investor_metrics();
+
+    let mut ranked = Vec::new();
+    for (city, state) in [("Phoenix", "AZ"), ("Atlanta", "GA")] {
+        let parcl_id = market_id(&client, city, state).await;
+        let ratios = investors
+            .purchase_to_sale_ratio(parcl_id, Some(InvestorMetricsParams::new().limit(6)))
+            .await
+            .unwrap();
+        let counts = investors
+            .housing_event_counts(parcl_id, Some(InvestorMetricsParams::new().limit(1)))
+            .await
+            .unwrap();
+        let history: Vec<f64> = ratios
+            .items
+            .iter()
+            .filter_map(|r| r.purchase_to_sale_ratio)
+            .collect();
+        let average = history.iter().sum::<f64>() / history.len() as f64;
+        ranked.push((
+            parcl_id,
+            history[0],
+            (average * 100.0).round() / 100.0,
+            counts.items[0].acquisitions.unwrap(),
+            counts.items[0].dispositions.unwrap(),
+        ));
+    }
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    assert_eq!(
+        ranked,
+        [
+            (2900098, 1.31, 1.21, 1_142, 872),
+            (2900245, 0.94, 1.03, 1_388, 1_477),
+        ]
+    );
+
+    let top = ranked[0].0;
+    let rolling = investors
+        .new_listings_for_sale_rolling_counts(top, Some(InvestorMetricsParams::new().limit(4)))
+        .await
+        .unwrap();
+    let monthly: Vec<(Option<i64>, Option<f64>)> = rolling
+        .items
+        .iter()
+        .map(|i| {
+            (
+                i.count.as_ref().and_then(|c| c.rolling_30_day),
+                i.pct_for_sale_market
+                    .as_ref()
+                    .and_then(|p| p.rolling_30_day),
+            )
+        })
+        .collect();
+    assert_eq!(monthly.len(), 4);
+    assert_eq!(monthly[0], (Some(312), Some(18.4)));
+
+    let mut trend = investors
+        .purchase_to_sale_ratio(
+            top,
+            Some(InvestorMetricsParams::new().limit(12).auto_paginate(true)),
+        )
+        .await
+        .unwrap();
+    trend.sort_ascending();
+    let dates: Vec<&str> = trend.items.iter().map(|i| i.date.as_str()).collect();
+    assert_eq!(dates.first(), Some(&"2023-07-01"));
+    assert_eq!(dates.last(), Some(&"2024-06-01"));
+    assert_eq!(trend.items.len(), 12);
+}
+
+/// `batch_metrics`: one POST for two markets' event counts.
+#[tokio::test]
+async fn batch_metrics_return_every_market() {
+    let client = client("batch_metrics.json");
+    let resp = client
+        .market_metrics()
+        .batch_housing_event_counts(vec![2900187, 2900078], Some(MetricsParams::new().limit(2)))
+        .await
+        .unwrap();
+    assert_eq!((resp.items.len(), resp.total), (4, 4));
+    let per_market: Vec<(Option<i64>, Option<i64>)> =
+        resp.items.iter().map(|i| (i.parcl_id, i.sales)).collect();
+    assert_eq!(
+        per_market,
+        [
+            (Some(2900187), Some(1_742)),
+            (Some(2900187), Some(1_655)),
+            (Some(2900078), Some(2_310)),
+            (Some(2900078), Some(2_198)),
+        ]
+    );
+}
+
+/// `new_construction`: new-build event counts and median prices.
+#[tokio::test]
+async fn new_construction_counts_and_prices() {
+    let client = client("new_construction.json");
+    let austin = market_id(&client, "Austin", "TX").await;
+    let params = || {
+        Some(
+            NewConstructionMetricsParams::new()
+                .limit(3)
+                .property_type(PropertyType::SingleFamily),
+        )
+    };
+    let new_builds = client.new_construction_metrics();
+
+    let counts = new_builds
+        .housing_event_counts(austin, params())
+        .await
+        .unwrap();
+    let listings: Vec<Option<i64>> = counts
+        .items
+        .iter()
+        .map(|i| i.new_listings_for_sale)
+        .collect();
+    assert_eq!(listings, [Some(418), Some(396), Some(441)]);
+
+    let prices = new_builds
+        .housing_event_prices(austin, params())
+        .await
+        .unwrap();
+    let medians: Vec<(Option<f64>, Option<f64>)> = prices
+        .items
+        .iter()
+        .map(|i| {
+            let median = i.price.as_ref().and_then(|p| p.median.as_ref());
+            (
+                median.and_then(|m| m.sales),
+                median.and_then(|m| m.new_listings_for_sale),
+            )
+        })
+        .collect();
+    assert_eq!(
+        medians,
+        [
+            (Some(489_990.0), Some(509_900.0)),
+            (Some(495_000.0), None),
+            (Some(502_450.0), Some(519_990.0)),
+        ]
+    );
+}
+
+/// `portfolio_metrics`: single-family ownership and activity by portfolio
+/// size.
+#[tokio::test]
+async fn portfolio_metrics_break_down_by_size() {
+    let client = client("portfolio_metrics.json");
+    let atlanta = market_id(&client, "Atlanta", "GA").await;
+    let portfolios = client.portfolio_metrics();
+
+    let ownership = portfolios
+        .sf_housing_stock_ownership(atlanta, Some(PortfolioMetricsParams::new().limit(1)))
+        .await
+        .unwrap();
+    let latest = &ownership.items[0];
+    let count = latest.count.as_ref().unwrap();
+    let pct = latest.pct_sf_housing_stock.as_ref().unwrap();
+    assert_eq!(count.portfolio_1000_plus, Some(6_204));
+    assert_eq!(count.all_portfolios, Some(24_571));
+    assert_eq!(pct.all_portfolios, Some(18.9));
+
+    let counts = portfolios
+        .sf_housing_event_counts(
+            atlanta,
+            Some(
+                PortfolioMetricsParams::new()
+                    .limit(3)
+                    .portfolio_size(PortfolioSize::Portfolio1000Plus),
+            ),
+        )
+        .await
+        .unwrap();
+    let net: i64 = counts
+        .items
+        .iter()
+        .map(|i| i.acquisitions.unwrap() - i.dispositions.unwrap())
+        .sum();
+    assert_eq!(net, -87);
+
+    let rolling = portfolios
+        .sf_new_listings_for_sale_rolling_counts(
+            atlanta,
+            Some(
+                PortfolioMetricsParams::new()
+                    .limit(3)
+                    .portfolio_size(PortfolioSize::AllPortfolios),
+            ),
+        )
+        .await
+        .unwrap();
+    let share: Vec<Option<f64>> = rolling
+        .items
+        .iter()
+        .map(|i| {
+            i.pct_sf_for_sale_market
+                .as_ref()
+                .and_then(|p| p.rolling_30_day)
+        })
+        .collect();
+    assert_eq!(share, [Some(21.7), Some(20.9), Some(22.3)]);
+}
+
+/// `property_search`: search, address lookup, event history and the v2
+/// search, chained on the first property found.
+#[tokio::test]
+async fn property_search_chains_lookups() {
+    let client = client("property_search.json");
+    let property = client.property();
+
+    let results = property
+        .search(
+            PropertySearchParams::new(5387853, PropertyType::SingleFamily)
+                .limit(3)
+                .bedrooms_min(3)
+                .event_history_sale_flag(true),
+        )
+        .await
+        .unwrap();
+    let beds: Vec<Option<i32>> = results.items.iter().map(|p| p.bedrooms).collect();
+    assert_eq!(beds, [Some(3), Some(4), Some(3)]);
+    let property_id = results.items[0].parcl_property_id;
+
+    let found = property
+        .search_by_address(vec![AddressSearchRequest {
+            address: "1225 W SCHOOL ST".into(),
+            city: "CHICAGO".into(),
+            state_abbreviation: "IL".into(),
+            zip_code: "60657".into(),
+        }])
+        .await
+        .unwrap();
+    assert_eq!(found.items[0].parcl_property_id, 63325076);
+
+    let history = property
+        .event_history(EventHistoryParams::new(vec![property_id]).event_type(EventType::All))
+        .await
+        .unwrap();
+    let events = history.properties[0].events.as_deref().unwrap();
+    let sales: Vec<Option<i64>> = events
+        .iter()
+        .filter(|e| e.event_name.as_deref() == Some("SOLD"))
+        .map(|e| e.price)
+        .collect();
+    assert_eq!(sales, [Some(612_000), Some(455_000)]);
+
+    let request = PropertyV2SearchRequest {
+        parcl_ids: Some(vec![5387853]),
+        property_filters: Some(PropertyFilters {
+            include_property_details: Some(true),
+            property_types: Some(vec!["SINGLE_FAMILY".into()]),
+            min_beds: Some(4),
+            ..Default::default()
+        }),
+        event_filters: Some(V2EventFilters {
+            include_events: Some(true),
+            event_names: Some(vec!["SOLD".into()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let v2 = property.search_v2(request, Some(3), None).await.unwrap();
+    let built: Vec<Option<i32>> = v2
+        .properties
+        .iter()
+        .map(|p| p.property_metadata.as_ref().and_then(|m| m.year_built))
+        .collect();
+    assert_eq!(built, [Some(1912), Some(1998)]);
+}
@@ -0,0 +1,361 @@
+//! Rust model generation from an OpenAPI 3 spec or a JSON Schema bundle.
+//!
+//! Every object schema under `components.schemas` (OpenAPI) or `$defs`
+//! (JSON Schema, such as `schemas/responses.json`) becomes a serde struct.
+//! Optional or nullable properties become `Option<T>`, inline objects become
+//! their own structs named after the parent and property, and string enums
+//! stay `String` so the hand-written enums in `models.rs` remain the typed
+//! layer. Names follow [`NamingRules`].
+
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Renames and exclusions applied on top of the default case conversion.
+#[derive(Debug, Default)]
+pub struct NamingRules {
+    /// Spec schema name to Rust type name.
+    pub types: BTreeMap<String, String>,
+    /// `Schema.property` to Rust field name.
+    pub fields: BTreeMap<String, String>,
+    /// Suffixes removed from schema names, e.g. `Schema` or `Out`.
+    pub strip_suffixes: Vec<String>,
+    /// Schemas not generated at all (e.g. FastAPI validation errors).
+    pub skip: BTreeSet<String>,
+}
+
+impl NamingRules {
+    /// Reads rules from JSON with optional `types`, `fields`,
+    /// `strip_suffixes` and `skip` keys.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let strings = |key: &str| -> Vec<String> {
+            value[key]
+                .as_array()
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|v| v.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let map = |key: &str| -> BTreeMap<String, String> {
+            value[key]
+                .as_object()
+                .map(|o| {
+                    o.iter()
+                        .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        if !value.is_object() {
+            return Err("naming rules must be a JSON object".into());
+        }
+        Ok(Self {
+            types: map("types"),
+            fields: map("fields"),
+            strip_suffixes: strings("strip_suffixes"),
+            skip: strings("skip").into_iter().collect(),
+        })
+    }
+
+    fn type_name(&self, schema: &str) -> String {
+        if let Some(name) = self.types.get(schema) {
+            return name.clone();
+        }
+        let base = self
+            .strip_suffixes
+            .iter()
+            .find_map(|s| schema.strip_suffix(s.as_str()).filter(|b| !b.is_empty()))
+            .unwrap_or(schema);
+        pascal_case(base)
+    }
+
+    fn field_name(&self, schema: &str, property: &str) -> String {
+        match self.fields.get(&format!("{schema}.{property}")) {
+            Some(name) => name.clone(),
+            None => snake_case(property),
+        }
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while",
+];
+
+pub fn pascal_case(s: &str) -> String {
+    s.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            std::iter::once(first).chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+pub fn snake_case(s: &str) -> String {
+    let mut out = String::new();
+    let mut prev_lower = false;
+    for c in s.chars() {
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            if !out.ends_with('_') && !out.is_empty() {
+                out.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+    let out = out.trim_end_matches('_').to_string();
+    if out.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{out}")
+    } else {
+        out
+    }
+}
+
+struct Generator<'a> {
+    rules: &'a NamingRules,
+    /// Generated structs by Rust name, so output order is stable.
+    structs: BTreeMap<String, String>,
+}
+
+/// Generates the Rust source for all object schemas in `spec`.
+pub fn generate(spec: &Value, rules: &NamingRules) -> Result<String, String> {
+    let schemas = spec
+        .pointer("/components/schemas")
+        .or_else(|| spec.get("$defs"))
+        .and_then(Value::as_object)
+        .ok_or("spec has no components.schemas or $defs")?;
+    let mut gen = Generator {
+        rules,
+        structs: BTreeMap::new(),
+    };
+    for (name, schema) in schemas {
+        if rules.skip.contains(name) || !is_object(schema) {
+            continue;
+        }
+        gen.object(name, &rules.type_name(name), schema)?;
+    }
+
+    let mut out = String::from(
+        "//! Response and request types generated from the Parcl Labs API schemas.\n\
+         //!\n\
+         //! @generated by `cargo xtask codegen`. Do not edit by hand; change the\n\
+         //! naming rules in `xtask/naming.json` and regenerate instead.\n\n\
+         use serde::{Deserialize, Serialize};\n",
+    );
+    for def in gen.structs.values() {
+        out.push('\n');
+        out.push_str(def);
+    }
+    Ok(out)
+}
+
+fn is_object(schema: &Value) -> bool {
+    schema["type"] == "object" || schema.get("properties").is_some()
+}
+
+impl Generator<'_> {
+    fn object(&mut self, schema_name: &str, rust_name: &str, schema: &Value) -> Result<(), String> {
+        let empty = Map::new();
+        let properties = schema["properties"].as_object().unwrap_or(&empty);
+        let required: BTreeSet<&str> = schema["required"]
+            .as_array()
+            .map(|r| r.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut def = String::new();
+        doc(&mut def, "", schema);
+        def.push_str("#[derive(Debug, Clone, Deserialize, Serialize)]\n");
+        writeln!(def, "pub struct {rust_name} {{").unwrap();
+        for (property, prop_schema) in properties {
+            let field = self.rules.field_name(schema_name, property);
+            let (ty, nullable) = self.field_type(
+                &format!("{rust_name}{}", pascal_case(property)),
+                prop_schema,
+            )?;
+            let ty = if nullable || !required.contains(property.as_str()) {
+                format!("Option<{ty}>")
+            } else {
+                ty
+            };
+            doc(&mut def, "    ", prop_schema);
+            let ident = if KEYWORDS.contains(&field.as_str()) {
+                format!("r#{field}")
+            } else {
+                field.clone()
+            };
+            if field != *property {
+                writeln!(def, "    #[serde(rename = \"{property}\")]").unwrap();
+            }
+            writeln!(def, "    pub {ident}: {ty},").unwrap();
+        }
+        def.push_str("}\n");
+        self.structs.insert(rust_name.to_string(), def);
+        Ok(())
+    }
+
+    /// Returns the Rust type for `schema` and whether it is nullable.
+    fn field_type(&mut self, inline_name: &str, schema: &Value) -> Result<(String, bool), String> {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.rsplit('/').next().unwrap_or(reference);
+            return Ok((self.rules.type_name(name), false));
+        }
+        for key in ["anyOf", "oneOf"] {
+            if let Some(variants) = schema[key].as_array() {
+                let non_null: Vec<&Value> =
+                    variants.iter().filter(|v| v["type"] != "null").collect();
+                let nullable = non_null.len() < variants.len();
+                return match non_null.as_slice() {
+                    [single] => {
+                        let (ty, inner_nullable) = self.field_type(inline_name, single)?;
+                        Ok((ty, nullable || inner_nullable))
+                    }
+                    _ => Ok(("serde_json::Value".into(), nullable)),
+                };
+            }
+        }
+        let mut nullable = schema["nullable"] == true;
+        // OpenAPI 3.1 and JSON Schema spell nullable types as `["integer", "null"]`.
+        let type_name = match &schema["type"] {
+            Value::Array(types) => {
+                nullable |= types.iter().any(|t| t == "null");
+                match types.iter().filter(|t| *t != "null").collect::<Vec<_>>()[..] {
+                    [single] => single.as_str(),
+                    _ => None,
+                }
+            }
+            other => other.as_str(),
+        };
+        let ty = match type_name {
+            Some("string") => "String".to_string(),
+            Some("integer") => "i64".to_string(),
+            Some("number") => "f64".to_string(),
+            Some("boolean") => "bool".to_string(),
+            Some("array") => {
+                let (item, _) = self.field_type(inline_name, &schema["items"])?;
+                format!("Vec<{item}>")
+            }
+            _ if is_object(schema) => {
+                self.object(inline_name, inline_name, schema)?;
+                inline_name.to_string()
+            }
+            _ => "serde_json::Value".to_string(),
+        };
+        Ok((ty, nullable))
+    }
+}
+
+fn doc(out: &mut String, indent: &str, schema: &Value) {
+    if let Some(text) = schema["description"].as_str() {
+        for line in text.trim().lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                writeln!(out, "{indent}///").unwrap();
+            } else {
+                writeln!(out, "{indent}/// {line}").unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> Value {
+        json!({
+            "openapi": "3.1.0",
+            "components": {"schemas": {
+                "HousingStockOut": {
+                    "type": "object",
+                    "description": "Housing stock for a month.",
+                    "required": ["date"],
+                    "properties": {
+                        "date": {"type": "string", "format": "date"},
+                        "singleFamily": {"anyOf": [{"type": "integer"}, {"type": "null"}]},
+                        "type": {"type": "string"},
+                        "links": {"type": "object", "properties": {"next": {"type": "string"}}}
+                    }
+                },
+                "HousingStockPage": {
+                    "type": "object",
+                    "required": ["items"],
+                    "properties": {
+                        "items": {"type": "array", "items": {"$ref": "#/components/schemas/HousingStockOut"}}
+                    }
+                },
+                "HTTPValidationError": {"type": "object", "properties": {}},
+                "PropertyType": {"type": "string", "enum": ["CONDO"]}
+            }}
+        })
+    }
+
+    #[test]
+    fn generates_structs_with_naming_rules() {
+        let rules = NamingRules::from_json(&json!({
+            "strip_suffixes": ["Out"],
+            "types": {"HousingStockPage": "HousingStockResponse"},
+            "fields": {"HousingStockOut.type": "kind"},
+            "skip": ["HTTPValidationError"]
+        }))
+        .unwrap();
+        let code = generate(&spec(), &rules).unwrap();
+
+        assert!(code.contains("/// Housing stock for a month.\n#[derive(Debug, Clone, Deserialize, Serialize)]\npub struct HousingStock {"));
+        assert!(code.contains("    pub date: String,\n"));
+        assert!(code.contains(
+            "    #[serde(rename = \"singleFamily\")]\n    pub single_family: Option<i64>,\n"
+        ));
+        assert!(code.contains("    #[serde(rename = \"type\")]\n    pub kind: Option<String>,\n"));
+        assert!(code.contains("    pub links: Option<HousingStockLinks>,\n"));
+        assert!(code.contains("pub struct HousingStockLinks {\n    pub next: Option<String>,\n}"));
+        assert!(code
+            .contains("pub struct HousingStockResponse {\n    pub items: Vec<HousingStock>,\n}"));
+        assert!(!code.contains("HTTPValidationError"));
+        assert!(!code.contains("PropertyType"));
+    }
+
+    #[test]
+    fn keywords_become_raw_identifiers() {
+        let code = generate(&spec(), &NamingRules::default()).unwrap();
+        assert!(code.contains("    pub r#type: Option<String>,\n"));
+        assert!(code.contains("pub struct HousingStockOut {"));
+    }
+
+    #[test]
+    fn reads_json_schema_defs() {
+        let spec = json!({"$defs": {
+            "Page": {"type": "object", "properties": {"items": {"type": "array", "items": {"$ref": "#/$defs/Item"}}}},
+            "Item": {"type": "object", "required": ["date", "sales"], "properties": {
+                "date": {"type": "string"},
+                "sales": {"type": ["integer", "null"]}
+            }}
+        }});
+        let code = generate(&spec, &NamingRules::default()).unwrap();
+        assert!(code
+            .contains("pub struct Item {\n    pub date: String,\n    pub sales: Option<i64>,\n}"));
+        assert!(code.contains("pub struct Page {\n    pub items: Option<Vec<Item>>,\n}"));
+    }
+
+    #[test]
+    fn case_conversion() {
+        assert_eq!(snake_case("pricePerSqFt"), "price_per_sq_ft");
+        assert_eq!(snake_case("percentile_20th"), "percentile_20th");
+        assert_eq!(snake_case("20th-pct"), "_20th_pct");
+        assert_eq!(pascal_case("housing_event_counts"), "HousingEventCounts");
+    }
+}
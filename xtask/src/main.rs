@@ -0,0 +1,102 @@
+//! Maintenance tasks for the parcllabs crate, run with `cargo xtask <task>`.
+
+mod codegen;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: cargo xtask codegen [<spec.json>] [--rules <naming.json>] [--out <file>] [--check]
+
+  Generates Rust model types from an OpenAPI 3 spec or a JSON Schema bundle
+  (default: schemas/responses.json).
+  --rules  naming rules (default: xtask/naming.json)
+  --out    output file (default: src/generated.rs)
+  --check  fail if the output file is out of date instead of writing it";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("codegen") => run_codegen(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives in the workspace root")
+        .to_path_buf()
+}
+
+fn run_codegen(args: &[String]) -> Result<(), String> {
+    let root = workspace_root();
+    let mut spec = None;
+    let default_spec = root.join("schemas/responses.json");
+    let mut rules = root.join("xtask/naming.json");
+    let mut out = root.join("src/generated.rs");
+    let mut check = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => rules = args.next().ok_or(USAGE)?.into(),
+            "--out" => out = args.next().ok_or(USAGE)?.into(),
+            "--check" => check = true,
+            path if spec.is_none() && !path.starts_with("--") => spec = Some(PathBuf::from(path)),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let spec = read_json(&spec.unwrap_or(default_spec))?;
+    let rules = codegen::NamingRules::from_json(&read_json(&rules)?)?;
+    let code = rustfmt(codegen::generate(&spec, &rules)?);
+
+    if check {
+        let current = std::fs::read_to_string(&out).unwrap_or_default();
+        if current != code {
+            return Err(format!(
+                "{} is out of date; run `cargo xtask codegen` to regenerate",
+                out.display()
+            ));
+        }
+        return Ok(());
+    }
+    std::fs::write(&out, code).map_err(|e| format!("{}: {e}", out.display()))?;
+    eprintln!("wrote {}", out.display());
+    Ok(())
+}
+
+/// Formats `code` with rustfmt, returning it unchanged if rustfmt fails.
+fn rustfmt(code: String) -> String {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let child = Command::new("rustfmt")
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return code;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(code.as_bytes());
+    }
+    match child.wait_with_output() {
+        Ok(output) if output.status.success() => String::from_utf8(output.stdout).unwrap_or(code),
+        _ => code,
+    }
+}
+
+fn read_json(path: &Path) -> Result<serde_json::Value, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+}